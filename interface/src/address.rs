@@ -22,6 +22,10 @@ mod inline_spl_token {
     solana_pubkey::declare_id!("TokenkegQfeZyiNwAJbNbGKPFXCWuBvf9Ss623VQ5DA");
 }
 
+mod inline_spl_token_2022 {
+    solana_pubkey::declare_id!("TokenzQdBNbLqP5VEhdkAS6EPFLC1PHnBqCXEpPxuEb");
+}
+
 /// Derives the associated token account address for the given wallet address
 /// and token mint
 #[deprecated(
@@ -56,6 +60,29 @@ pub fn get_associated_token_address_with_program_id(
     .0
 }
 
+/// Derives the associated token account addresses of `wallet_address` for
+/// `token_mint_address` under both the SPL Token and the Token-2022 program,
+/// as `(address, token_program_id)` pairs in that order
+///
+/// A mint address only exists under one token program, but callers that do
+/// not know which one, or that track assets migrated between them, should
+/// look up both accounts before reporting a missing balance.
+pub fn get_all_possible_atas(
+    wallet_address: &Pubkey,
+    token_mint_address: &Pubkey,
+) -> [(Pubkey, Pubkey); 2] {
+    [inline_spl_token::ID, inline_spl_token_2022::ID].map(|token_program_id| {
+        (
+            get_associated_token_address_with_program_id(
+                wallet_address,
+                token_mint_address,
+                &token_program_id,
+            ),
+            token_program_id,
+        )
+    })
+}
+
 /// For internal use only.
 #[doc(hidden)]
 pub fn get_associated_token_address_and_bump_seed_internal(
@@ -73,3 +100,130 @@ pub fn get_associated_token_address_and_bump_seed_internal(
         program_id,
     )
 }
+
+/// Whether `candidate` is the associated token account address of
+/// `wallet_address` for `token_mint_address` under `token_program_id`
+///
+/// Use this to validate user-supplied destination addresses. An address that
+/// is a valid ATA for a different wallet, mint or token program returns
+/// `false`.
+pub fn is_associated_token_address(
+    candidate: &Pubkey,
+    wallet_address: &Pubkey,
+    token_mint_address: &Pubkey,
+    token_program_id: &Pubkey,
+) -> bool {
+    *candidate
+        == get_associated_token_address_with_program_id(
+            wallet_address,
+            token_mint_address,
+            token_program_id,
+        )
+}
+
+/// Batched [`is_associated_token_address`] over `(candidate, wallet, mint)`
+/// triples sharing `token_program_id`, returning one result per triple
+pub fn are_associated_token_addresses(
+    candidates: &[(Pubkey, Pubkey, Pubkey)],
+    token_program_id: &Pubkey,
+) -> Vec<bool> {
+    candidates
+        .iter()
+        .map(|(candidate, wallet_address, token_mint_address)| {
+            is_associated_token_address(
+                candidate,
+                wallet_address,
+                token_mint_address,
+                token_program_id,
+            )
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const WALLET: Pubkey = Pubkey::new_from_array([1; 32]);
+    const MINT: Pubkey = Pubkey::new_from_array([2; 32]);
+    const OTHER: Pubkey = Pubkey::new_from_array([3; 32]);
+    const TOKEN_PROGRAM_ID: Pubkey = Pubkey::new_from_array([4; 32]);
+
+    #[test]
+    fn checks_candidate_against_derivation() {
+        let ata = get_associated_token_address_with_program_id(&WALLET, &MINT, &TOKEN_PROGRAM_ID);
+
+        assert!(is_associated_token_address(
+            &ata,
+            &WALLET,
+            &MINT,
+            &TOKEN_PROGRAM_ID
+        ));
+        // Swapped wallet and mint
+        assert!(!is_associated_token_address(
+            &ata,
+            &MINT,
+            &WALLET,
+            &TOKEN_PROGRAM_ID
+        ));
+        assert!(!is_associated_token_address(
+            &ata,
+            &OTHER,
+            &MINT,
+            &TOKEN_PROGRAM_ID
+        ));
+        assert!(!is_associated_token_address(
+            &ata,
+            &WALLET,
+            &OTHER,
+            &TOKEN_PROGRAM_ID
+        ));
+        assert!(!is_associated_token_address(&ata, &WALLET, &MINT, &OTHER));
+        assert!(!is_associated_token_address(
+            &WALLET,
+            &WALLET,
+            &MINT,
+            &TOKEN_PROGRAM_ID
+        ));
+    }
+
+    #[test]
+    fn batch_returns_one_result_per_candidate() {
+        let ata = get_associated_token_address_with_program_id(&WALLET, &MINT, &TOKEN_PROGRAM_ID);
+        let other_ata =
+            get_associated_token_address_with_program_id(&OTHER, &MINT, &TOKEN_PROGRAM_ID);
+
+        assert_eq!(
+            are_associated_token_addresses(
+                &[
+                    (ata, WALLET, MINT),
+                    (ata, OTHER, MINT),
+                    (other_ata, OTHER, MINT)
+                ],
+                &TOKEN_PROGRAM_ID,
+            ),
+            [true, false, true]
+        );
+        assert!(are_associated_token_addresses(&[], &TOKEN_PROGRAM_ID).is_empty());
+    }
+
+    #[test]
+    fn all_possible_atas_cover_both_token_programs() {
+        let [
+            (token_ata, token_program_id),
+            (token_2022_ata, token_2022_program_id),
+        ] = get_all_possible_atas(&WALLET, &MINT);
+
+        assert_eq!(token_program_id, inline_spl_token::ID);
+        assert_eq!(token_2022_program_id, inline_spl_token_2022::ID);
+        assert_eq!(
+            token_ata,
+            get_associated_token_address_with_program_id(&WALLET, &MINT, &token_program_id)
+        );
+        assert_eq!(
+            token_2022_ata,
+            get_associated_token_address_with_program_id(&WALLET, &MINT, &token_2022_program_id)
+        );
+        assert_ne!(token_ata, token_2022_ata);
+    }
+}
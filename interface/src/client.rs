@@ -0,0 +1,577 @@
+//! A single entry point for applications managing one associated token
+//! account
+//!
+//! [`AtaClient`] ties a wallet, mint and token program to an RPC connection
+//! and combines the address derivation, validation and instruction builders
+//! of this crate. It is generic over [`RpcLike`], so it works with the
+//! blocking and nonblocking RPC clients, banks clients or test fixtures
+//! alike, without this crate depending on any of them.
+//!
+//! [`check_atas_exist`] looks up many associated token accounts at once, for
+//! pipelines deciding which create instructions to include in a batch.
+
+use {
+    crate::{
+        address::get_associated_token_address_with_program_id,
+        instruction::{create_associated_token_account_idempotent, recover_nested},
+        validation::assert_is_ata_of,
+    },
+    solana_instruction::{AccountMeta, Instruction},
+    solana_pubkey::Pubkey,
+};
+
+const MINT_END: usize = 32;
+const AMOUNT_OFFSET: usize = 64;
+const AMOUNT_END: usize = 72;
+const CLOSE_AUTHORITY_OFFSET: usize = 129;
+const CLOSE_AUTHORITY_KEY_OFFSET: usize = 133;
+const CLOSE_AUTHORITY_END: usize = 165;
+const STATE_OFFSET: usize = 108;
+
+/// Most addresses a single `getMultipleAccounts` request accepts
+pub const MAX_MULTIPLE_ACCOUNTS: usize = 100;
+
+/// `TokenInstruction::CloseAccount` discriminator, shared by SPL Token and
+/// Token-2022
+const CLOSE_ACCOUNT: u8 = 9;
+
+/// An account as returned by an RPC connection
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct RpcAccount {
+    /// Program owning the account
+    pub owner: Pubkey,
+    /// Account data
+    pub data: Vec<u8>,
+}
+
+/// The RPC queries [`AtaClient`] needs
+pub trait RpcLike {
+    /// Error returned by a failed query
+    type Error;
+
+    /// Returns the account at `address`, or `None` if it does not exist
+    fn get_account(&self, address: &Pubkey) -> Result<Option<RpcAccount>, Self::Error>;
+
+    /// Returns the accounts at `addresses`, in order, as `getMultipleAccounts`
+    /// does
+    ///
+    /// Callers pass at most [`MAX_MULTIPLE_ACCOUNTS`] addresses. The default
+    /// implementation queries each address with [`Self::get_account`].
+    fn get_multiple_accounts(
+        &self,
+        addresses: &[Pubkey],
+    ) -> Result<Vec<Option<RpcAccount>>, Self::Error> {
+        addresses
+            .iter()
+            .map(|address| self.get_account(address))
+            .collect()
+    }
+
+    /// Returns every token account of `token_program_id` whose token owner is
+    /// `owner`, as `getTokenAccountsByOwner` does with a `programId` filter
+    fn get_token_accounts_by_owner(
+        &self,
+        owner: &Pubkey,
+        token_program_id: &Pubkey,
+    ) -> Result<Vec<(Pubkey, RpcAccount)>, Self::Error>;
+}
+
+/// State of an associated token account address, as found by
+/// [`check_atas_exist`]
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum AtaStatus {
+    /// No account exists at the address, so a create instruction is needed
+    Missing,
+    /// The initialized associated token account of the wallet and mint exists
+    ExistsValid,
+    /// An account exists at the address but is not the initialized associated
+    /// token account, for instance because another program owns it or its
+    /// token owner was changed. Creating it fails.
+    ExistsForeignOwner,
+    /// The wallet is itself a token account of the token program, so its
+    /// associated token account is nested. Tokens sent there can only be
+    /// moved out with `RecoverNested`.
+    Nested,
+}
+
+/// Returns the status of the associated token account of each `(wallet, mint,
+/// token program)` triple, in order
+///
+/// Wallets and associated token accounts are fetched together with
+/// `getMultipleAccounts`, in requests of at most [`MAX_MULTIPLE_ACCOUNTS`]
+/// addresses. A wallet that is a token account makes its entry
+/// [`AtaStatus::Nested`], whether the associated token account exists or not.
+pub fn check_atas_exist<R: RpcLike>(
+    rpc: &R,
+    atas: &[(Pubkey, Pubkey, Pubkey)],
+) -> Result<Vec<AtaStatus>, R::Error> {
+    let mut statuses = Vec::with_capacity(atas.len());
+    for chunk in atas.chunks(MAX_MULTIPLE_ACCOUNTS / 2) {
+        let addresses = chunk
+            .iter()
+            .map(|(wallet_address, token_mint_address, token_program_id)| {
+                get_associated_token_address_with_program_id(
+                    wallet_address,
+                    token_mint_address,
+                    token_program_id,
+                )
+            })
+            .collect::<Vec<_>>();
+        let queried = chunk
+            .iter()
+            .map(|(wallet_address, _, _)| *wallet_address)
+            .chain(addresses.iter().copied())
+            .collect::<Vec<_>>();
+        let accounts = rpc.get_multiple_accounts(&queried)?;
+        let (wallets, associated) = accounts.split_at(chunk.len());
+        for (((wallet_address, token_mint_address, token_program_id), address), (wallet, ata)) in
+            chunk
+                .iter()
+                .zip(&addresses)
+                .zip(wallets.iter().zip(associated))
+        {
+            // `AccountState::Uninitialized` is zero, and so is that byte in
+            // Token-2022 mints padded to the account length
+            let is_token_account = |account: &RpcAccount| {
+                account.owner == *token_program_id
+                    && account.data.len() >= CLOSE_AUTHORITY_END
+                    && account.data[STATE_OFFSET] != 0
+            };
+            statuses.push(if wallet.as_ref().is_some_and(is_token_account) {
+                AtaStatus::Nested
+            } else {
+                match ata {
+                    None => AtaStatus::Missing,
+                    Some(account)
+                        if assert_is_ata_of(
+                            address,
+                            &account.owner,
+                            &account.data,
+                            wallet_address,
+                            token_mint_address,
+                            token_program_id,
+                        )
+                        .is_ok() =>
+                    {
+                        AtaStatus::ExistsValid
+                    }
+                    Some(_) => AtaStatus::ExistsForeignOwner,
+                }
+            });
+        }
+    }
+    Ok(statuses)
+}
+
+/// The associated token account of a wallet for one mint, queried through
+/// `rpc`
+#[derive(Debug)]
+pub struct AtaClient<'a, R: RpcLike> {
+    rpc: &'a R,
+    wallet_address: Pubkey,
+    token_mint_address: Pubkey,
+    token_program_id: Pubkey,
+}
+
+impl<'a, R: RpcLike> AtaClient<'a, R> {
+    /// Creates a client for the associated token account of `wallet_address`
+    /// for `token_mint_address` under `token_program_id`
+    pub fn new(
+        rpc: &'a R,
+        wallet_address: Pubkey,
+        token_mint_address: Pubkey,
+        token_program_id: Pubkey,
+    ) -> Self {
+        Self {
+            rpc,
+            wallet_address,
+            token_mint_address,
+            token_program_id,
+        }
+    }
+
+    /// The associated token account address
+    pub fn address(&self) -> Pubkey {
+        get_associated_token_address_with_program_id(
+            &self.wallet_address,
+            &self.token_mint_address,
+            &self.token_program_id,
+        )
+    }
+
+    /// Whether the associated token account exists and is initialized for the
+    /// wallet and mint, as checked by [`assert_is_ata_of`]
+    pub fn exists(&self) -> Result<bool, R::Error> {
+        Ok(self.fetch()?.is_some())
+    }
+
+    /// Creates a `CreateIdempotent` instruction for the associated token
+    /// account, funded by `funding_address`
+    pub fn create_ix(&self, funding_address: &Pubkey) -> Instruction {
+        create_associated_token_account_idempotent(
+            funding_address,
+            &self.wallet_address,
+            &self.token_mint_address,
+            &self.token_program_id,
+        )
+    }
+
+    /// Returns [`Self::create_ix`] if the associated token account does not
+    /// exist yet, or `None` if it does
+    ///
+    /// The instruction is idempotent, so a transaction racing another creation
+    /// of the same account still succeeds.
+    pub fn create_if_missing(
+        &self,
+        funding_address: &Pubkey,
+    ) -> Result<Option<Instruction>, R::Error> {
+        Ok((!self.exists()?).then(|| self.create_ix(funding_address)))
+    }
+
+    /// Returns the instructions recovering every nested associated token
+    /// account held by the associated token account, each `RecoverNested`
+    /// preceded by a `CreateIdempotent` of its destination funded by the wallet
+    ///
+    /// Only nested accounts under the same token program are found, since
+    /// `RecoverNested` takes a single token program. Token accounts held by the
+    /// associated token account at other addresses cannot be recovered and are
+    /// skipped.
+    pub fn recover_all_nested(&self) -> Result<Vec<Instruction>, R::Error> {
+        let owner_address = self.address();
+        let mut instructions = vec![];
+        for (address, account) in self
+            .rpc
+            .get_token_accounts_by_owner(&owner_address, &self.token_program_id)?
+        {
+            let Some(nested_mint) = account
+                .data
+                .get(..MINT_END)
+                .map(|mint| Pubkey::new_from_array(mint.try_into().unwrap()))
+            else {
+                continue;
+            };
+            if address
+                != get_associated_token_address_with_program_id(
+                    &owner_address,
+                    &nested_mint,
+                    &self.token_program_id,
+                )
+            {
+                continue;
+            }
+            instructions.push(create_associated_token_account_idempotent(
+                &self.wallet_address,
+                &self.wallet_address,
+                &nested_mint,
+                &self.token_program_id,
+            ));
+            instructions.push(recover_nested(
+                &self.wallet_address,
+                &self.token_mint_address,
+                &nested_mint,
+                &self.token_program_id,
+            ));
+        }
+        Ok(instructions)
+    }
+
+    /// Returns a `CloseAccount` instruction sending the associated token
+    /// account's lamports to `destination`, or `None` if the account does not
+    /// exist or still holds tokens
+    ///
+    /// The instruction must be signed by the account's close authority if it
+    /// has one, or by the wallet otherwise.
+    pub fn close_empty(&self, destination: &Pubkey) -> Result<Option<Instruction>, R::Error> {
+        let Some(account) = self.fetch()? else {
+            return Ok(None);
+        };
+        if account.data[AMOUNT_OFFSET..AMOUNT_END] != [0; 8] {
+            return Ok(None);
+        }
+        // `COption` tag, then the key
+        let authority = if account.data[CLOSE_AUTHORITY_OFFSET] == 1 {
+            Pubkey::new_from_array(
+                account.data[CLOSE_AUTHORITY_KEY_OFFSET..CLOSE_AUTHORITY_END]
+                    .try_into()
+                    .unwrap(),
+            )
+        } else {
+            self.wallet_address
+        };
+        Ok(Some(Instruction {
+            program_id: self.token_program_id,
+            accounts: vec![
+                AccountMeta::new(self.address(), false),
+                AccountMeta::new(*destination, false),
+                AccountMeta::new_readonly(authority, true),
+            ],
+            data: vec![CLOSE_ACCOUNT],
+        }))
+    }
+
+    /// The associated token account, if it exists and is initialized for the
+    /// wallet and mint
+    fn fetch(&self) -> Result<Option<RpcAccount>, R::Error> {
+        let address = self.address();
+        Ok(self.rpc.get_account(&address)?.filter(|account| {
+            assert_is_ata_of(
+                &address,
+                &account.owner,
+                &account.data,
+                &self.wallet_address,
+                &self.token_mint_address,
+                &self.token_program_id,
+            )
+            .is_ok()
+        }))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use {
+        super::*,
+        std::{cell::Cell, collections::HashMap},
+    };
+
+    const TOKEN_PROGRAM_ID: Pubkey =
+        Pubkey::from_str_const("TokenkegQfeZyiNwAJbNbGKPFXCWuBvf9Ss623VQ5DA");
+
+    #[derive(Default)]
+    struct MockRpc(HashMap<Pubkey, RpcAccount>, Cell<usize>);
+
+    impl MockRpc {
+        fn add_token_account(
+            &mut self,
+            address: Pubkey,
+            mint: &Pubkey,
+            owner: &Pubkey,
+            amount: u64,
+        ) {
+            let mut data = vec![0; CLOSE_AUTHORITY_END];
+            data[..MINT_END].copy_from_slice(mint.as_ref());
+            data[MINT_END..AMOUNT_OFFSET].copy_from_slice(owner.as_ref());
+            data[AMOUNT_OFFSET..AMOUNT_END].copy_from_slice(&amount.to_le_bytes());
+            // `AccountState::Initialized`
+            data[STATE_OFFSET] = 1;
+            self.0.insert(
+                address,
+                RpcAccount {
+                    owner: TOKEN_PROGRAM_ID,
+                    data,
+                },
+            );
+        }
+    }
+
+    impl RpcLike for MockRpc {
+        type Error = ();
+
+        fn get_account(&self, address: &Pubkey) -> Result<Option<RpcAccount>, ()> {
+            Ok(self.0.get(address).cloned())
+        }
+
+        fn get_multiple_accounts(
+            &self,
+            addresses: &[Pubkey],
+        ) -> Result<Vec<Option<RpcAccount>>, ()> {
+            assert!(addresses.len() <= MAX_MULTIPLE_ACCOUNTS);
+            self.1.set(self.1.get() + 1);
+            Ok(addresses
+                .iter()
+                .map(|address| self.0.get(address).cloned())
+                .collect())
+        }
+
+        fn get_token_accounts_by_owner(
+            &self,
+            owner: &Pubkey,
+            token_program_id: &Pubkey,
+        ) -> Result<Vec<(Pubkey, RpcAccount)>, ()> {
+            Ok(self
+                .0
+                .iter()
+                .filter(|(_, account)| {
+                    account.owner == *token_program_id
+                        && account.data[MINT_END..AMOUNT_OFFSET] == owner.to_bytes()
+                })
+                .map(|(address, account)| (*address, account.clone()))
+                .collect())
+        }
+    }
+
+    #[test]
+    fn create_if_missing() {
+        let mut rpc = MockRpc::default();
+        let (funding, wallet, mint) = (
+            Pubkey::new_unique(),
+            Pubkey::new_unique(),
+            Pubkey::new_unique(),
+        );
+        let address =
+            get_associated_token_address_with_program_id(&wallet, &mint, &TOKEN_PROGRAM_ID);
+
+        let client = AtaClient::new(&rpc, wallet, mint, TOKEN_PROGRAM_ID);
+        assert_eq!(client.exists(), Ok(false));
+        assert_eq!(
+            client.create_if_missing(&funding),
+            Ok(Some(create_associated_token_account_idempotent(
+                &funding,
+                &wallet,
+                &mint,
+                &TOKEN_PROGRAM_ID
+            )))
+        );
+
+        rpc.add_token_account(address, &mint, &wallet, 0);
+        let client = AtaClient::new(&rpc, wallet, mint, TOKEN_PROGRAM_ID);
+        assert_eq!(client.exists(), Ok(true));
+        assert_eq!(client.create_if_missing(&funding), Ok(None));
+    }
+
+    #[test]
+    fn recover_all_nested_skips_non_associated_accounts() {
+        let mut rpc = MockRpc::default();
+        let (wallet, mint, nested_mint) = (
+            Pubkey::new_unique(),
+            Pubkey::new_unique(),
+            Pubkey::new_unique(),
+        );
+        let owner_address =
+            get_associated_token_address_with_program_id(&wallet, &mint, &TOKEN_PROGRAM_ID);
+        rpc.add_token_account(owner_address, &mint, &wallet, 0);
+        rpc.add_token_account(
+            get_associated_token_address_with_program_id(
+                &owner_address,
+                &nested_mint,
+                &TOKEN_PROGRAM_ID,
+            ),
+            &nested_mint,
+            &owner_address,
+            100,
+        );
+        rpc.add_token_account(Pubkey::new_unique(), &nested_mint, &owner_address, 100);
+
+        let client = AtaClient::new(&rpc, wallet, mint, TOKEN_PROGRAM_ID);
+        assert_eq!(
+            client.recover_all_nested(),
+            Ok(vec![
+                create_associated_token_account_idempotent(
+                    &wallet,
+                    &wallet,
+                    &nested_mint,
+                    &TOKEN_PROGRAM_ID
+                ),
+                recover_nested(&wallet, &mint, &nested_mint, &TOKEN_PROGRAM_ID),
+            ])
+        );
+    }
+
+    #[test]
+    fn close_empty_requires_zero_balance() {
+        let mut rpc = MockRpc::default();
+        let (wallet, mint, destination) = (
+            Pubkey::new_unique(),
+            Pubkey::new_unique(),
+            Pubkey::new_unique(),
+        );
+        let address =
+            get_associated_token_address_with_program_id(&wallet, &mint, &TOKEN_PROGRAM_ID);
+
+        let client = AtaClient::new(&rpc, wallet, mint, TOKEN_PROGRAM_ID);
+        assert_eq!(client.close_empty(&destination), Ok(None));
+
+        rpc.add_token_account(address, &mint, &wallet, 1);
+        let client = AtaClient::new(&rpc, wallet, mint, TOKEN_PROGRAM_ID);
+        assert_eq!(client.close_empty(&destination), Ok(None));
+
+        rpc.add_token_account(address, &mint, &wallet, 0);
+        let client = AtaClient::new(&rpc, wallet, mint, TOKEN_PROGRAM_ID);
+        assert_eq!(
+            client.close_empty(&destination),
+            Ok(Some(Instruction {
+                program_id: TOKEN_PROGRAM_ID,
+                accounts: vec![
+                    AccountMeta::new(address, false),
+                    AccountMeta::new(destination, false),
+                    AccountMeta::new_readonly(wallet, true),
+                ],
+                data: vec![CLOSE_ACCOUNT],
+            }))
+        );
+    }
+
+    #[test]
+    fn check_atas_exist_classifies_addresses() {
+        let mut rpc = MockRpc::default();
+        let (wallet, other_wallet, mint) = (
+            Pubkey::new_unique(),
+            Pubkey::new_unique(),
+            Pubkey::new_unique(),
+        );
+        let valid = (Pubkey::new_unique(), mint, TOKEN_PROGRAM_ID);
+        rpc.add_token_account(
+            get_associated_token_address_with_program_id(&valid.0, &mint, &TOKEN_PROGRAM_ID),
+            &mint,
+            &valid.0,
+            0,
+        );
+        let foreign = (Pubkey::new_unique(), mint, TOKEN_PROGRAM_ID);
+        rpc.0.insert(
+            get_associated_token_address_with_program_id(&foreign.0, &mint, &TOKEN_PROGRAM_ID),
+            RpcAccount {
+                owner: Pubkey::new_unique(),
+                data: vec![],
+            },
+        );
+        let reassigned = (other_wallet, mint, TOKEN_PROGRAM_ID);
+        rpc.add_token_account(
+            get_associated_token_address_with_program_id(&other_wallet, &mint, &TOKEN_PROGRAM_ID),
+            &mint,
+            &wallet,
+            0,
+        );
+        let owner_address =
+            get_associated_token_address_with_program_id(&wallet, &mint, &TOKEN_PROGRAM_ID);
+        rpc.add_token_account(owner_address, &mint, &wallet, 0);
+        let nested = (owner_address, Pubkey::new_unique(), TOKEN_PROGRAM_ID);
+
+        assert_eq!(
+            check_atas_exist(
+                &rpc,
+                &[
+                    (wallet, Pubkey::new_unique(), TOKEN_PROGRAM_ID),
+                    valid,
+                    foreign,
+                    reassigned,
+                    nested,
+                ]
+            ),
+            Ok(vec![
+                AtaStatus::Missing,
+                AtaStatus::ExistsValid,
+                AtaStatus::ExistsForeignOwner,
+                AtaStatus::ExistsForeignOwner,
+                AtaStatus::Nested,
+            ])
+        );
+        assert_eq!(rpc.1.get(), 1);
+    }
+
+    #[test]
+    fn check_atas_exist_batches_requests() {
+        let rpc = MockRpc::default();
+        let atas = (0..MAX_MULTIPLE_ACCOUNTS)
+            .map(|_| (Pubkey::new_unique(), Pubkey::new_unique(), TOKEN_PROGRAM_ID))
+            .collect::<Vec<_>>();
+
+        assert_eq!(
+            check_atas_exist(&rpc, &atas),
+            Ok(vec![AtaStatus::Missing; MAX_MULTIPLE_ACCOUNTS])
+        );
+        // A wallet and its associated token account per entry
+        assert_eq!(rpc.1.get(), 2);
+        assert_eq!(check_atas_exist(&rpc, &[]), Ok(vec![]));
+        assert_eq!(rpc.1.get(), 2);
+    }
+}
@@ -0,0 +1,215 @@
+//! Builders for clusters where the token and associated token account
+//! programs are deployed at non-canonical addresses
+//!
+//! Forks and local test clusters often deploy the programs under locally
+//! generated keypairs. [`ClusterConfig`] carries the three program ids and
+//! exposes the same builders as [`crate::instruction`], deriving every address
+//! under `ata_program` instead of the canonical program id.
+
+use {
+    crate::{address::get_associated_token_address_and_bump_seed, instruction},
+    solana_instruction::Instruction,
+    solana_pubkey::Pubkey,
+};
+
+/// Token program an associated token account belongs to
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum TokenProgram {
+    /// SPL Token
+    Token,
+    /// Token-2022
+    Token2022,
+}
+
+/// Program ids of a cluster
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct ClusterConfig {
+    /// SPL Token program id
+    pub token_program: Pubkey,
+    /// Token-2022 program id
+    pub token_2022_program: Pubkey,
+    /// Associated token account program id
+    pub ata_program: Pubkey,
+}
+
+impl Default for ClusterConfig {
+    /// The canonical program ids, as deployed on mainnet, testnet and devnet
+    fn default() -> Self {
+        Self {
+            token_program: Pubkey::from_str_const("TokenkegQfeZyiNwAJbNbGKPFXCWuBvf9Ss623VQ5DA"),
+            token_2022_program: Pubkey::from_str_const(
+                "TokenzQdBNbLqP5VEhdkAS6EPFLC1PHnBqCXEpPxuEb",
+            ),
+            ata_program: crate::program::id(),
+        }
+    }
+}
+
+impl ClusterConfig {
+    /// Program id of `token_program` on this cluster
+    pub fn token_program_id(&self, token_program: TokenProgram) -> Pubkey {
+        match token_program {
+            TokenProgram::Token => self.token_program,
+            TokenProgram::Token2022 => self.token_2022_program,
+        }
+    }
+
+    /// Derives the associated token account address for the given wallet
+    /// address and token mint
+    pub fn get_associated_token_address(
+        &self,
+        wallet_address: &Pubkey,
+        token_mint_address: &Pubkey,
+        token_program: TokenProgram,
+    ) -> Pubkey {
+        get_associated_token_address_and_bump_seed(
+            wallet_address,
+            token_mint_address,
+            &self.ata_program,
+            &self.token_program_id(token_program),
+        )
+        .0
+    }
+
+    /// Creates a `Create` instruction
+    pub fn create_associated_token_account(
+        &self,
+        funding_address: &Pubkey,
+        wallet_address: &Pubkey,
+        token_mint_address: &Pubkey,
+        token_program: TokenProgram,
+    ) -> Instruction {
+        instruction::build_associated_token_account_instruction(
+            &self.ata_program,
+            funding_address,
+            wallet_address,
+            token_mint_address,
+            &self.token_program_id(token_program),
+            0, // AssociatedTokenAccountInstruction::Create
+        )
+    }
+
+    /// Creates a `CreateIdempotent` instruction
+    pub fn create_associated_token_account_idempotent(
+        &self,
+        funding_address: &Pubkey,
+        wallet_address: &Pubkey,
+        token_mint_address: &Pubkey,
+        token_program: TokenProgram,
+    ) -> Instruction {
+        instruction::build_associated_token_account_instruction(
+            &self.ata_program,
+            funding_address,
+            wallet_address,
+            token_mint_address,
+            &self.token_program_id(token_program),
+            1, // AssociatedTokenAccountInstruction::CreateIdempotent
+        )
+    }
+
+    /// Creates a `RecoverNested` instruction
+    pub fn recover_nested(
+        &self,
+        wallet_address: &Pubkey,
+        owner_token_mint_address: &Pubkey,
+        nested_token_mint_address: &Pubkey,
+        token_program: TokenProgram,
+    ) -> Instruction {
+        instruction::build_recover_nested_instruction(
+            &self.ata_program,
+            wallet_address,
+            owner_token_mint_address,
+            nested_token_mint_address,
+            &self.token_program_id(token_program),
+        )
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const FUNDING: Pubkey = Pubkey::new_from_array([1; 32]);
+    const WALLET: Pubkey = Pubkey::new_from_array([2; 32]);
+    const MINT: Pubkey = Pubkey::new_from_array([3; 32]);
+    const NESTED_MINT: Pubkey = Pubkey::new_from_array([4; 32]);
+
+    #[test]
+    fn default_matches_canonical_builders() {
+        let cluster = ClusterConfig::default();
+
+        for token_program in [TokenProgram::Token, TokenProgram::Token2022] {
+            let token_program_id = cluster.token_program_id(token_program);
+            assert_eq!(
+                cluster.create_associated_token_account(&FUNDING, &WALLET, &MINT, token_program),
+                instruction::create_associated_token_account(
+                    &FUNDING,
+                    &WALLET,
+                    &MINT,
+                    &token_program_id
+                )
+            );
+            assert_eq!(
+                cluster.create_associated_token_account_idempotent(
+                    &FUNDING,
+                    &WALLET,
+                    &MINT,
+                    token_program
+                ),
+                instruction::create_associated_token_account_idempotent(
+                    &FUNDING,
+                    &WALLET,
+                    &MINT,
+                    &token_program_id
+                )
+            );
+            assert_eq!(
+                cluster.recover_nested(&WALLET, &MINT, &NESTED_MINT, token_program),
+                instruction::recover_nested(&WALLET, &MINT, &NESTED_MINT, &token_program_id)
+            );
+        }
+    }
+
+    #[test]
+    fn custom_program_ids_are_used_throughout() {
+        let cluster = ClusterConfig {
+            token_program: Pubkey::new_from_array([5; 32]),
+            token_2022_program: Pubkey::new_from_array([6; 32]),
+            ata_program: Pubkey::new_from_array([7; 32]),
+        };
+        let address = |wallet: &Pubkey, mint: &Pubkey| {
+            Pubkey::find_program_address(
+                &[
+                    wallet.as_ref(),
+                    cluster.token_2022_program.as_ref(),
+                    mint.as_ref(),
+                ],
+                &cluster.ata_program,
+            )
+            .0
+        };
+        let ata = address(&WALLET, &MINT);
+
+        assert_eq!(
+            cluster.get_associated_token_address(&WALLET, &MINT, TokenProgram::Token2022),
+            ata
+        );
+
+        let create = cluster.create_associated_token_account(
+            &FUNDING,
+            &WALLET,
+            &MINT,
+            TokenProgram::Token2022,
+        );
+        assert_eq!(create.program_id, cluster.ata_program);
+        assert_eq!(create.accounts[1].pubkey, ata);
+        assert_eq!(create.accounts[5].pubkey, cluster.token_2022_program);
+
+        let recover = cluster.recover_nested(&WALLET, &MINT, &NESTED_MINT, TokenProgram::Token2022);
+        assert_eq!(recover.program_id, cluster.ata_program);
+        assert_eq!(recover.accounts[0].pubkey, address(&ata, &NESTED_MINT));
+        assert_eq!(recover.accounts[2].pubkey, address(&WALLET, &NESTED_MINT));
+        assert_eq!(recover.accounts[3].pubkey, ata);
+        assert_eq!(recover.accounts[6].pubkey, cluster.token_2022_program);
+    }
+}
@@ -0,0 +1,201 @@
+//! Compute unit limits to request for each instruction
+//!
+//! Taken from `pinocchio/program/benches/compute_units.md` at release time:
+//! the most expensive benchmarked case of each instruction, rounded up with
+//! headroom for mints with more extensions than the benchmarks use. Refresh
+//! them with `make bench-pinocchio-program` when the program changes.
+//!
+//! The `*_with_compute_budget` builders put the matching limit ahead of the
+//! instruction so callers get a correctly budgeted transaction by default.
+
+use {
+    crate::{instruction, program},
+    solana_instruction::Instruction,
+    solana_program_error::ProgramError,
+    solana_pubkey::Pubkey,
+    solana_sdk_ids::compute_budget,
+};
+
+/// Recommended compute unit limit for a `Create` instruction
+pub const RECOMMENDED_CU_CREATE: u32 = 10_000;
+
+/// Recommended compute unit limit for a `CreateIdempotent` instruction,
+/// whether or not the account already exists
+pub const RECOMMENDED_CU_CREATE_IDEMPOTENT: u32 = 10_000;
+
+/// Recommended compute unit limit for a `RecoverNested` instruction, up to
+/// a multisig wallet with the maximum number of signers
+pub const RECOMMENDED_CU_RECOVER_NESTED: u32 = 20_000;
+
+/// `ComputeBudgetInstruction::SetComputeUnitLimit` discriminator
+const SET_COMPUTE_UNIT_LIMIT: u8 = 2;
+
+/// Creates a `SetComputeUnitLimit` compute budget instruction
+pub fn set_compute_unit_limit(units: u32) -> Instruction {
+    let mut data = vec![SET_COMPUTE_UNIT_LIMIT];
+    data.extend_from_slice(&units.to_le_bytes());
+    Instruction {
+        program_id: compute_budget::ID,
+        accounts: vec![],
+        data,
+    }
+}
+
+/// Recommended compute unit limit for an ATA program instruction
+///
+/// Fails with `IncorrectProgramId` for any other program and
+/// `InvalidInstructionData` for any instruction without a recommended limit.
+pub fn recommended_compute_units(instruction: &Instruction) -> Result<u32, ProgramError> {
+    if instruction.program_id != program::id() {
+        return Err(ProgramError::IncorrectProgramId);
+    }
+    match instruction.data.as_slice() {
+        [] | [0] => Ok(RECOMMENDED_CU_CREATE),
+        [1] => Ok(RECOMMENDED_CU_CREATE_IDEMPOTENT),
+        [2] => Ok(RECOMMENDED_CU_RECOVER_NESTED),
+        _ => Err(ProgramError::InvalidInstructionData),
+    }
+}
+
+/// Creates a `Create` instruction preceded by a compute unit limit of
+/// [`RECOMMENDED_CU_CREATE`]
+pub fn create_associated_token_account_with_compute_budget(
+    funding_address: &Pubkey,
+    wallet_address: &Pubkey,
+    token_mint_address: &Pubkey,
+    token_program_id: &Pubkey,
+) -> Vec<Instruction> {
+    vec![
+        set_compute_unit_limit(RECOMMENDED_CU_CREATE),
+        instruction::create_associated_token_account(
+            funding_address,
+            wallet_address,
+            token_mint_address,
+            token_program_id,
+        ),
+    ]
+}
+
+/// Creates a `CreateIdempotent` instruction preceded by a compute unit limit
+/// of [`RECOMMENDED_CU_CREATE_IDEMPOTENT`]
+pub fn create_associated_token_account_idempotent_with_compute_budget(
+    funding_address: &Pubkey,
+    wallet_address: &Pubkey,
+    token_mint_address: &Pubkey,
+    token_program_id: &Pubkey,
+) -> Vec<Instruction> {
+    vec![
+        set_compute_unit_limit(RECOMMENDED_CU_CREATE_IDEMPOTENT),
+        instruction::create_associated_token_account_idempotent(
+            funding_address,
+            wallet_address,
+            token_mint_address,
+            token_program_id,
+        ),
+    ]
+}
+
+/// Creates a `RecoverNested` instruction preceded by a compute unit limit of
+/// [`RECOMMENDED_CU_RECOVER_NESTED`]
+pub fn recover_nested_with_compute_budget(
+    wallet_address: &Pubkey,
+    owner_token_mint_address: &Pubkey,
+    nested_token_mint_address: &Pubkey,
+    token_program_id: &Pubkey,
+) -> Vec<Instruction> {
+    vec![
+        set_compute_unit_limit(RECOMMENDED_CU_RECOVER_NESTED),
+        instruction::recover_nested(
+            wallet_address,
+            owner_token_mint_address,
+            nested_token_mint_address,
+            token_program_id,
+        ),
+    ]
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const TOKEN_PROGRAM_ID: Pubkey =
+        Pubkey::from_str_const("TokenkegQfeZyiNwAJbNbGKPFXCWuBvf9Ss623VQ5DA");
+
+    #[test]
+    fn set_compute_unit_limit_encoding() {
+        let instruction = set_compute_unit_limit(0x0102_0304);
+
+        assert_eq!(instruction.program_id, compute_budget::ID);
+        assert!(instruction.accounts.is_empty());
+        assert_eq!(instruction.data, [2, 4, 3, 2, 1]);
+    }
+
+    #[test]
+    fn limit_precedes_ata_instruction() {
+        let funding = Pubkey::new_unique();
+        let wallet = Pubkey::new_unique();
+        let mint = Pubkey::new_unique();
+        let nested_mint = Pubkey::new_unique();
+
+        let cases = [
+            (
+                create_associated_token_account_with_compute_budget(
+                    &funding,
+                    &wallet,
+                    &mint,
+                    &TOKEN_PROGRAM_ID,
+                ),
+                RECOMMENDED_CU_CREATE,
+                instruction::create_associated_token_account(
+                    &funding,
+                    &wallet,
+                    &mint,
+                    &TOKEN_PROGRAM_ID,
+                ),
+            ),
+            (
+                create_associated_token_account_idempotent_with_compute_budget(
+                    &funding,
+                    &wallet,
+                    &mint,
+                    &TOKEN_PROGRAM_ID,
+                ),
+                RECOMMENDED_CU_CREATE_IDEMPOTENT,
+                instruction::create_associated_token_account_idempotent(
+                    &funding,
+                    &wallet,
+                    &mint,
+                    &TOKEN_PROGRAM_ID,
+                ),
+            ),
+            (
+                recover_nested_with_compute_budget(&wallet, &mint, &nested_mint, &TOKEN_PROGRAM_ID),
+                RECOMMENDED_CU_RECOVER_NESTED,
+                instruction::recover_nested(&wallet, &mint, &nested_mint, &TOKEN_PROGRAM_ID),
+            ),
+        ];
+        for (instructions, units, expected) in cases {
+            assert_eq!(recommended_compute_units(&expected), Ok(units));
+            assert_eq!(instructions, [set_compute_unit_limit(units), expected]);
+        }
+    }
+
+    #[test]
+    fn no_recommendation_outside_create_and_recover_nested() {
+        let mut instruction = instruction::create_associated_token_account(
+            &Pubkey::new_unique(),
+            &Pubkey::new_unique(),
+            &Pubkey::new_unique(),
+            &TOKEN_PROGRAM_ID,
+        );
+        instruction.data = vec![3];
+        assert_eq!(
+            recommended_compute_units(&instruction),
+            Err(ProgramError::InvalidInstructionData)
+        );
+        assert_eq!(
+            recommended_compute_units(&set_compute_unit_limit(1)),
+            Err(ProgramError::IncorrectProgramId)
+        );
+    }
+}
@@ -1,6 +1,6 @@
 //! Instruction creators for the program
 use {
-    crate::{address::get_associated_token_address_with_program_id, program::id},
+    crate::{address::get_associated_token_address_and_bump_seed, program::id},
     solana_instruction::{AccountMeta, Instruction},
     solana_pubkey::Pubkey,
 };
@@ -60,22 +60,24 @@ pub enum AssociatedTokenAccountInstruction {
     RecoverNested,
 }
 
-fn build_associated_token_account_instruction(
+pub(crate) fn build_associated_token_account_instruction(
+    program_id: &Pubkey,
     funding_address: &Pubkey,
     wallet_address: &Pubkey,
     token_mint_address: &Pubkey,
     token_program_id: &Pubkey,
     instruction: u8,
 ) -> Instruction {
-    let associated_account_address = get_associated_token_address_with_program_id(
+    let (associated_account_address, _) = get_associated_token_address_and_bump_seed(
         wallet_address,
         token_mint_address,
+        program_id,
         token_program_id,
     );
     // safety check, assert if not a creation instruction, which is only 0 or 1
     assert!(instruction <= 1);
     Instruction {
-        program_id: id(),
+        program_id: *program_id,
         accounts: vec![
             AccountMeta::new(*funding_address, true),
             AccountMeta::new(associated_account_address, false),
@@ -96,6 +98,7 @@ pub fn create_associated_token_account(
     token_program_id: &Pubkey,
 ) -> Instruction {
     build_associated_token_account_instruction(
+        &id(),
         funding_address,
         wallet_address,
         token_mint_address,
@@ -112,6 +115,7 @@ pub fn create_associated_token_account_idempotent(
     token_program_id: &Pubkey,
 ) -> Instruction {
     build_associated_token_account_instruction(
+        &id(),
         funding_address,
         wallet_address,
         token_mint_address,
@@ -127,24 +131,40 @@ pub fn recover_nested(
     nested_token_mint_address: &Pubkey,
     token_program_id: &Pubkey,
 ) -> Instruction {
-    let owner_associated_account_address = get_associated_token_address_with_program_id(
+    build_recover_nested_instruction(
+        &id(),
         wallet_address,
         owner_token_mint_address,
-        token_program_id,
-    );
-    let destination_associated_account_address = get_associated_token_address_with_program_id(
-        wallet_address,
         nested_token_mint_address,
         token_program_id,
-    );
-    let nested_associated_account_address = get_associated_token_address_with_program_id(
+    )
+}
+
+pub(crate) fn build_recover_nested_instruction(
+    program_id: &Pubkey,
+    wallet_address: &Pubkey,
+    owner_token_mint_address: &Pubkey,
+    nested_token_mint_address: &Pubkey,
+    token_program_id: &Pubkey,
+) -> Instruction {
+    let address = |wallet_address: &Pubkey, token_mint_address: &Pubkey| {
+        get_associated_token_address_and_bump_seed(
+            wallet_address,
+            token_mint_address,
+            program_id,
+            token_program_id,
+        )
+        .0
+    };
+    let owner_associated_account_address = address(wallet_address, owner_token_mint_address);
+    let destination_associated_account_address = address(wallet_address, nested_token_mint_address);
+    let nested_associated_account_address = address(
         &owner_associated_account_address, // ATA is wrongly used as a wallet_address
         nested_token_mint_address,
-        token_program_id,
     );
 
     Instruction {
-        program_id: id(),
+        program_id: *program_id,
         accounts: vec![
             AccountMeta::new(nested_associated_account_address, false),
             AccountMeta::new_readonly(*nested_token_mint_address, false),
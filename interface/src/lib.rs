@@ -3,8 +3,20 @@
 #![forbid(unsafe_code)]
 
 pub mod address;
+pub mod client;
+pub mod cluster;
+pub mod compute_budget;
 pub mod error;
 pub mod instruction;
+pub mod migration;
+pub mod planner;
+pub mod smart_wallet;
+pub mod sponsored;
+#[cfg(feature = "token-state")]
+pub mod token_state;
+pub mod transaction_meta;
+pub mod typed;
+pub mod validation;
 
 /// Module defining the program id
 pub mod program {
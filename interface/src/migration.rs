@@ -0,0 +1,274 @@
+//! Drop-in replacement for the deprecated `spl-associated-token-account-client`
+//! crate
+//!
+//! The submodules mirror that crate's `address`, `instruction` and `program`
+//! modules, with the same function names and signatures, implemented over
+//! this crate. Code still written against the old crate can switch its
+//! dependency first and rename the import:
+//!
+//! ```ignore
+//! use spl_associated_token_account_interface::migration as spl_associated_token_account_client;
+//! ```
+//!
+//! Every function is deprecated with a note naming its replacement in this
+//! crate, so the compiler lists the remaining call sites to move over:
+//!
+//! | `spl-associated-token-account-client` | `spl-associated-token-account-interface` |
+//! |---|---|
+//! | `address::get_associated_token_address` | [`address::get_associated_token_address_with_program_id`](crate::address::get_associated_token_address_with_program_id) |
+//! | `address::get_associated_token_address_with_program_id` | [`address::get_associated_token_address_with_program_id`](crate::address::get_associated_token_address_with_program_id) |
+//! | `address::get_associated_token_address_and_bump_seed_internal` | [`address::get_associated_token_address_and_bump_seed`](crate::address::get_associated_token_address_and_bump_seed) |
+//! | `instruction::create_associated_token_account` | [`instruction::create_associated_token_account`](crate::instruction::create_associated_token_account) |
+//! | `instruction::create_associated_token_account_idempotent` | [`instruction::create_associated_token_account_idempotent`](crate::instruction::create_associated_token_account_idempotent) |
+//! | `instruction::recover_nested` | [`instruction::recover_nested`](crate::instruction::recover_nested) |
+//! | `program::id`, `program::ID`, `program::check_id` | [`program`](crate::program) |
+
+/// Address derivation, as in `spl_associated_token_account_client::address`
+pub mod address {
+    use solana_pubkey::Pubkey;
+
+    /// Derives the associated token account address for the given wallet
+    /// address and token mint, assuming the SPL Token program
+    #[deprecated(
+        since = "2.1.0",
+        note = "Use `spl_associated_token_account_interface::address::\
+                get_associated_token_address_with_program_id` instead. This function assumes the \
+                SPL Token program, which does not work for Token-2022 mints."
+    )]
+    pub fn get_associated_token_address(
+        wallet_address: &Pubkey,
+        token_mint_address: &Pubkey,
+    ) -> Pubkey {
+        #[allow(deprecated)]
+        crate::address::get_associated_token_address(wallet_address, token_mint_address)
+    }
+
+    /// Derives the associated token account address for the given wallet
+    /// address, token mint and token program id
+    #[deprecated(
+        since = "2.1.0",
+        note = "Use `spl_associated_token_account_interface::address::\
+                get_associated_token_address_with_program_id` instead"
+    )]
+    pub fn get_associated_token_address_with_program_id(
+        wallet_address: &Pubkey,
+        token_mint_address: &Pubkey,
+        token_program_id: &Pubkey,
+    ) -> Pubkey {
+        crate::address::get_associated_token_address_with_program_id(
+            wallet_address,
+            token_mint_address,
+            token_program_id,
+        )
+    }
+
+    /// Derives the associated token account address and bump seed under
+    /// `program_id`
+    #[deprecated(
+        since = "2.1.0",
+        note = "Use `spl_associated_token_account_interface::address::\
+                get_associated_token_address_and_bump_seed` instead"
+    )]
+    pub fn get_associated_token_address_and_bump_seed_internal(
+        wallet_address: &Pubkey,
+        token_mint_address: &Pubkey,
+        program_id: &Pubkey,
+        token_program_id: &Pubkey,
+    ) -> (Pubkey, u8) {
+        crate::address::get_associated_token_address_and_bump_seed(
+            wallet_address,
+            token_mint_address,
+            program_id,
+            token_program_id,
+        )
+    }
+}
+
+/// Instruction builders, as in
+/// `spl_associated_token_account_client::instruction`
+pub mod instruction {
+    use {solana_instruction::Instruction, solana_pubkey::Pubkey};
+
+    /// Creates `Create` instruction
+    #[deprecated(
+        since = "2.1.0",
+        note = "Use `spl_associated_token_account_interface::instruction::\
+                create_associated_token_account` instead"
+    )]
+    pub fn create_associated_token_account(
+        funding_address: &Pubkey,
+        wallet_address: &Pubkey,
+        token_mint_address: &Pubkey,
+        token_program_id: &Pubkey,
+    ) -> Instruction {
+        crate::instruction::create_associated_token_account(
+            funding_address,
+            wallet_address,
+            token_mint_address,
+            token_program_id,
+        )
+    }
+
+    /// Creates `CreateIdempotent` instruction
+    #[deprecated(
+        since = "2.1.0",
+        note = "Use `spl_associated_token_account_interface::instruction::\
+                create_associated_token_account_idempotent` instead"
+    )]
+    pub fn create_associated_token_account_idempotent(
+        funding_address: &Pubkey,
+        wallet_address: &Pubkey,
+        token_mint_address: &Pubkey,
+        token_program_id: &Pubkey,
+    ) -> Instruction {
+        crate::instruction::create_associated_token_account_idempotent(
+            funding_address,
+            wallet_address,
+            token_mint_address,
+            token_program_id,
+        )
+    }
+
+    /// Creates a `RecoverNested` instruction
+    #[deprecated(
+        since = "2.1.0",
+        note = "Use `spl_associated_token_account_interface::instruction::recover_nested` instead"
+    )]
+    pub fn recover_nested(
+        wallet_address: &Pubkey,
+        owner_token_mint_address: &Pubkey,
+        nested_token_mint_address: &Pubkey,
+        token_program_id: &Pubkey,
+    ) -> Instruction {
+        crate::instruction::recover_nested(
+            wallet_address,
+            owner_token_mint_address,
+            nested_token_mint_address,
+            token_program_id,
+        )
+    }
+}
+
+/// Program id, as in `spl_associated_token_account_client::program`
+pub mod program {
+    use solana_pubkey::Pubkey;
+
+    /// The associated token account program id
+    #[deprecated(
+        since = "2.1.0",
+        note = "Use `spl_associated_token_account_interface::program::ID` instead"
+    )]
+    pub const ID: Pubkey = crate::program::ID;
+
+    /// Returns the associated token account program id
+    #[deprecated(
+        since = "2.1.0",
+        note = "Use `spl_associated_token_account_interface::program::id` instead"
+    )]
+    pub const fn id() -> Pubkey {
+        crate::program::ID
+    }
+
+    /// Whether `id` is the associated token account program id
+    #[deprecated(
+        since = "2.1.0",
+        note = "Use `spl_associated_token_account_interface::program::check_id` instead"
+    )]
+    pub fn check_id(id: &Pubkey) -> bool {
+        crate::program::check_id(id)
+    }
+}
+
+#[cfg(test)]
+#[allow(deprecated)]
+mod tests {
+    use {super::*, solana_pubkey::Pubkey};
+
+    const TOKEN_PROGRAM_ID: Pubkey =
+        Pubkey::from_str_const("TokenkegQfeZyiNwAJbNbGKPFXCWuBvf9Ss623VQ5DA");
+    const TOKEN_2022_PROGRAM_ID: Pubkey =
+        Pubkey::from_str_const("TokenzQdBNbLqP5VEhdkAS6EPFLC1PHnBqCXEpPxuEb");
+
+    #[test]
+    fn shims_match_interface() {
+        let (funding, wallet, mint, nested_mint) = (
+            Pubkey::new_unique(),
+            Pubkey::new_unique(),
+            Pubkey::new_unique(),
+            Pubkey::new_unique(),
+        );
+
+        assert_eq!(
+            address::get_associated_token_address(&wallet, &mint),
+            crate::address::get_associated_token_address_with_program_id(
+                &wallet,
+                &mint,
+                &TOKEN_PROGRAM_ID
+            )
+        );
+        assert_eq!(
+            address::get_associated_token_address_and_bump_seed_internal(
+                &wallet,
+                &mint,
+                &program::id(),
+                &TOKEN_2022_PROGRAM_ID
+            ),
+            crate::address::get_associated_token_address_and_bump_seed(
+                &wallet,
+                &mint,
+                &crate::program::id(),
+                &TOKEN_2022_PROGRAM_ID
+            )
+        );
+
+        for token_program_id in [TOKEN_PROGRAM_ID, TOKEN_2022_PROGRAM_ID] {
+            assert_eq!(
+                address::get_associated_token_address_with_program_id(
+                    &wallet,
+                    &mint,
+                    &token_program_id
+                ),
+                crate::address::get_associated_token_address_with_program_id(
+                    &wallet,
+                    &mint,
+                    &token_program_id
+                )
+            );
+            assert_eq!(
+                instruction::create_associated_token_account(
+                    &funding,
+                    &wallet,
+                    &mint,
+                    &token_program_id
+                ),
+                crate::instruction::create_associated_token_account(
+                    &funding,
+                    &wallet,
+                    &mint,
+                    &token_program_id
+                )
+            );
+            assert_eq!(
+                instruction::create_associated_token_account_idempotent(
+                    &funding,
+                    &wallet,
+                    &mint,
+                    &token_program_id
+                ),
+                crate::instruction::create_associated_token_account_idempotent(
+                    &funding,
+                    &wallet,
+                    &mint,
+                    &token_program_id
+                )
+            );
+            assert_eq!(
+                instruction::recover_nested(&wallet, &mint, &nested_mint, &token_program_id),
+                crate::instruction::recover_nested(&wallet, &mint, &nested_mint, &token_program_id)
+            );
+        }
+
+        assert_eq!(program::ID, crate::program::ID);
+        assert!(program::check_id(&program::id()));
+    }
+}
@@ -0,0 +1,321 @@
+//! Packing of instructions into transactions
+
+use {
+    crate::compute_budget::{recommended_compute_units, set_compute_unit_limit},
+    solana_hash::Hash,
+    solana_instruction::Instruction,
+    solana_message::Message,
+    solana_program_error::ProgramError,
+    solana_pubkey::Pubkey,
+    solana_sdk_ids::compute_budget,
+};
+
+/// Maximum serialized size of a transaction, in bytes
+pub const PACKET_DATA_SIZE: usize = 1232;
+
+/// Maximum compute unit limit a transaction can request
+pub const MAX_COMPUTE_UNIT_LIMIT: u32 = 1_400_000;
+
+/// `SetComputeUnitLimit` data: a one-byte discriminator followed by a `u32` limit
+const SET_COMPUTE_UNIT_LIMIT_DATA_LEN: usize = 5;
+
+const SIGNATURE_LEN: usize = 64;
+const MESSAGE_HEADER_LEN: usize = 3;
+const BLOCKHASH_LEN: usize = 32;
+
+/// Packs `instructions`, in order, into legacy messages paid for by `payer`
+/// and ready to sign, each starting with a `SetComputeUnitLimit` instruction
+/// for the sum of the [`recommended_compute_units`] of its instructions.
+///
+/// Every message fits in a [`PACKET_DATA_SIZE`] transaction and requests at
+/// most `max_compute_units`. An instruction that exceeds either limit on its
+/// own is placed in a message by itself.
+///
+/// Fails like [`recommended_compute_units`] for an instruction without a
+/// recommended limit.
+pub fn pack_instructions(
+    payer: &Pubkey,
+    instructions: &[Instruction],
+    max_compute_units: u32,
+    recent_blockhash: &Hash,
+) -> Result<Vec<Message>, ProgramError> {
+    let mut messages = Vec::new();
+    let mut start = 0;
+    let mut group_compute_units = 0u32;
+
+    for (end, instruction) in instructions.iter().enumerate() {
+        let instruction_compute_units = recommended_compute_units(instruction)?;
+        let fits = group_compute_units
+            .checked_add(instruction_compute_units)
+            .is_some_and(|total| total <= max_compute_units)
+            && transaction_size(payer, &instructions[start..=end]) <= PACKET_DATA_SIZE;
+
+        if !fits && start < end {
+            messages.push(budgeted_message(
+                payer,
+                &instructions[start..end],
+                group_compute_units,
+                recent_blockhash,
+            ));
+            start = end;
+            group_compute_units = 0;
+        }
+        group_compute_units = group_compute_units.saturating_add(instruction_compute_units);
+    }
+    if start < instructions.len() {
+        messages.push(budgeted_message(
+            payer,
+            &instructions[start..],
+            group_compute_units,
+            recent_blockhash,
+        ));
+    }
+
+    Ok(messages)
+}
+
+/// Message carrying `instructions` after a `SetComputeUnitLimit` instruction
+/// for `compute_units`
+fn budgeted_message(
+    payer: &Pubkey,
+    instructions: &[Instruction],
+    compute_units: u32,
+    recent_blockhash: &Hash,
+) -> Message {
+    let budgeted_instructions: Vec<Instruction> =
+        core::iter::once(set_compute_unit_limit(compute_units))
+            .chain(instructions.iter().cloned())
+            .collect();
+    Message::new_with_blockhash(&budgeted_instructions, Some(payer), recent_blockhash)
+}
+
+/// Serialized size of a legacy transaction carrying `instructions` and a
+/// `SetComputeUnitLimit` instruction
+fn transaction_size(payer: &Pubkey, instructions: &[Instruction]) -> usize {
+    let mut signers = vec![*payer];
+    let mut keys = vec![*payer, compute_budget::ID];
+    let mut instructions_len = compact_u16_len(instructions.len().saturating_add(1))
+        .saturating_add(compiled_instruction_len(0, SET_COMPUTE_UNIT_LIMIT_DATA_LEN));
+
+    for instruction in instructions {
+        for meta in &instruction.accounts {
+            if meta.is_signer && !signers.contains(&meta.pubkey) {
+                signers.push(meta.pubkey);
+            }
+            if !keys.contains(&meta.pubkey) {
+                keys.push(meta.pubkey);
+            }
+        }
+        if !keys.contains(&instruction.program_id) {
+            keys.push(instruction.program_id);
+        }
+        instructions_len = instructions_len.saturating_add(compiled_instruction_len(
+            instruction.accounts.len(),
+            instruction.data.len(),
+        ));
+    }
+
+    compact_u16_len(signers.len())
+        .saturating_add(signers.len().saturating_mul(SIGNATURE_LEN))
+        .saturating_add(MESSAGE_HEADER_LEN)
+        .saturating_add(compact_u16_len(keys.len()))
+        .saturating_add(keys.len().saturating_mul(core::mem::size_of::<Pubkey>()))
+        .saturating_add(BLOCKHASH_LEN)
+        .saturating_add(instructions_len)
+}
+
+/// Program id index, then the account indices and data, each prefixed by its length
+fn compiled_instruction_len(accounts_len: usize, data_len: usize) -> usize {
+    1usize
+        .saturating_add(compact_u16_len(accounts_len))
+        .saturating_add(accounts_len)
+        .saturating_add(compact_u16_len(data_len))
+        .saturating_add(data_len)
+}
+
+fn compact_u16_len(len: usize) -> usize {
+    match len {
+        0..=0x7f => 1,
+        0x80..=0x3fff => 2,
+        _ => 3,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use {
+        super::*,
+        crate::{
+            compute_budget::RECOMMENDED_CU_CREATE_IDEMPOTENT,
+            instruction::create_associated_token_account_idempotent,
+        },
+    };
+
+    const PAYER: Pubkey = Pubkey::new_from_array([u8::MAX; 32]);
+    const BLOCKHASH: Hash = Hash::new_from_array([7; 32]);
+
+    fn creates(count: u8) -> Vec<Instruction> {
+        let mint = Pubkey::new_from_array([254; 32]);
+        let token_program_id = Pubkey::new_from_array([253; 32]);
+        (0..count)
+            .map(|wallet| {
+                create_associated_token_account_idempotent(
+                    &PAYER,
+                    &Pubkey::new_from_array([wallet; 32]),
+                    &mint,
+                    &token_program_id,
+                )
+            })
+            .collect()
+    }
+
+    /// Program id, account addresses and data of each instruction of
+    /// `message`, after its `SetComputeUnitLimit` instruction
+    fn packed_instructions(message: &Message) -> Vec<(Pubkey, Vec<Pubkey>, Vec<u8>)> {
+        message.instructions[1..]
+            .iter()
+            .map(|instruction| {
+                (
+                    message.account_keys[usize::from(instruction.program_id_index)],
+                    instruction
+                        .accounts
+                        .iter()
+                        .map(|index| message.account_keys[usize::from(*index)])
+                        .collect(),
+                    instruction.data.clone(),
+                )
+            })
+            .collect()
+    }
+
+    /// Serialized size of a transaction signing `message`
+    fn signed_transaction_size(message: &Message) -> usize {
+        let signers = usize::from(message.header.num_required_signatures);
+        let instructions_len = message
+            .instructions
+            .iter()
+            .map(|instruction| {
+                compiled_instruction_len(instruction.accounts.len(), instruction.data.len())
+            })
+            .sum::<usize>();
+        compact_u16_len(signers)
+            .saturating_add(signers.saturating_mul(SIGNATURE_LEN))
+            .saturating_add(MESSAGE_HEADER_LEN)
+            .saturating_add(compact_u16_len(message.account_keys.len()))
+            .saturating_add(
+                message
+                    .account_keys
+                    .len()
+                    .saturating_mul(core::mem::size_of::<Pubkey>()),
+            )
+            .saturating_add(BLOCKHASH_LEN)
+            .saturating_add(compact_u16_len(message.instructions.len()))
+            .saturating_add(instructions_len)
+    }
+
+    #[test]
+    fn packs_within_packet_size_and_preserves_order() {
+        let instructions = creates(40);
+
+        let messages =
+            pack_instructions(&PAYER, &instructions, MAX_COMPUTE_UNIT_LIMIT, &BLOCKHASH).unwrap();
+
+        assert!(messages.len() > 1);
+        let expected = instructions
+            .iter()
+            .map(|instruction| {
+                (
+                    instruction.program_id,
+                    instruction
+                        .accounts
+                        .iter()
+                        .map(|meta| meta.pubkey)
+                        .collect(),
+                    instruction.data.clone(),
+                )
+            })
+            .collect::<Vec<_>>();
+        assert_eq!(
+            messages
+                .iter()
+                .flat_map(packed_instructions)
+                .collect::<Vec<_>>(),
+            expected
+        );
+        let mut remaining = instructions.as_slice();
+        for message in &messages {
+            assert_eq!(message.account_keys[0], PAYER);
+            assert_eq!(message.recent_blockhash, BLOCKHASH);
+            assert!(signed_transaction_size(message) <= PACKET_DATA_SIZE);
+            // Each message is as large as possible
+            let (packed, rest) = remaining.split_at(packed_instructions(message).len());
+            if let Some(next) = rest.first() {
+                let mut extended = packed.to_vec();
+                extended.push(next.clone());
+                assert!(transaction_size(&PAYER, &extended) > PACKET_DATA_SIZE);
+            }
+            remaining = rest;
+        }
+    }
+
+    #[test]
+    fn sets_recommended_compute_unit_limit() {
+        let instructions = creates(5);
+
+        let messages = pack_instructions(&PAYER, &instructions, 25_000, &BLOCKHASH).unwrap();
+
+        let limits = [20_000, 20_000, RECOMMENDED_CU_CREATE_IDEMPOTENT];
+        assert_eq!(messages.len(), limits.len());
+        for (message, limit) in messages.iter().zip(limits) {
+            let set_limit = set_compute_unit_limit(limit);
+            let compiled = &message.instructions[0];
+            assert_eq!(
+                message.account_keys[usize::from(compiled.program_id_index)],
+                set_limit.program_id
+            );
+            assert_eq!(compiled.data, set_limit.data);
+        }
+        assert_eq!(
+            messages
+                .iter()
+                .map(|message| packed_instructions(message).len())
+                .collect::<Vec<_>>(),
+            [2, 2, 1]
+        );
+    }
+
+    #[test]
+    fn instruction_over_compute_budget_gets_its_own_message() {
+        let instructions = creates(3);
+
+        let messages = pack_instructions(&PAYER, &instructions, 5_000, &BLOCKHASH).unwrap();
+
+        assert_eq!(
+            messages
+                .iter()
+                .map(|message| packed_instructions(message).len())
+                .collect::<Vec<_>>(),
+            [1, 1, 1]
+        );
+    }
+
+    #[test]
+    fn fails_for_instruction_without_recommended_limit() {
+        let mut instructions = creates(2);
+        instructions.push(set_compute_unit_limit(1));
+
+        assert_eq!(
+            pack_instructions(&PAYER, &instructions, MAX_COMPUTE_UNIT_LIMIT, &BLOCKHASH),
+            Err(ProgramError::IncorrectProgramId)
+        );
+    }
+
+    #[test]
+    fn empty_input() {
+        assert!(
+            pack_instructions(&PAYER, &[], MAX_COMPUTE_UNIT_LIMIT, &BLOCKHASH)
+                .unwrap()
+                .is_empty()
+        );
+    }
+}
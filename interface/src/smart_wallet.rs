@@ -0,0 +1,140 @@
+//! Associated token account derivation for smart-wallet authorities
+//!
+//! Smart wallets hold tokens under a program derived address rather than the
+//! account that created them. The ATA has to be derived for that PDA: using
+//! the multisig account, the create key or a member key instead yields a
+//! valid-looking address that the wallet cannot sign for.
+
+use {crate::address::get_associated_token_address_with_program_id, solana_pubkey::Pubkey};
+
+/// Squads v4 program id
+pub const SQUADS_V4_PROGRAM_ID: Pubkey =
+    Pubkey::from_str_const("SQDS4ep65T869zMMBKyuUq6aD6EgTu8psMjkvj52pCf");
+
+const SQUADS_V4_SEED_PREFIX: &[u8] = b"multisig";
+const SQUADS_V4_SEED_MULTISIG: &[u8] = b"multisig";
+const SQUADS_V4_SEED_VAULT: &[u8] = b"vault";
+
+/// Derives the authority PDA from `seeds` under `smart_wallet_program_id`,
+/// and returns it with its associated token account for `token_mint_address`
+pub fn get_associated_token_address_for_pda(
+    smart_wallet_program_id: &Pubkey,
+    seeds: &[&[u8]],
+    token_mint_address: &Pubkey,
+    token_program_id: &Pubkey,
+) -> (Pubkey, Pubkey) {
+    let (authority, _) = Pubkey::find_program_address(seeds, smart_wallet_program_id);
+    let associated_token_address = get_associated_token_address_with_program_id(
+        &authority,
+        token_mint_address,
+        token_program_id,
+    );
+    (authority, associated_token_address)
+}
+
+/// Derives the Squads v4 multisig account for `create_key`
+///
+/// The multisig account stores configuration and never holds tokens; pass it
+/// to [`get_squads_v4_vault_associated_token_address`] to find the vault.
+pub fn get_squads_v4_multisig_address(program_id: &Pubkey, create_key: &Pubkey) -> Pubkey {
+    Pubkey::find_program_address(
+        &[
+            SQUADS_V4_SEED_PREFIX,
+            SQUADS_V4_SEED_MULTISIG,
+            create_key.as_ref(),
+        ],
+        program_id,
+    )
+    .0
+}
+
+/// Derives the Squads v4 vault at `vault_index` of `multisig`, and returns it
+/// with its associated token account for `token_mint_address`
+///
+/// Pass [`SQUADS_V4_PROGRAM_ID`] as `program_id` for the mainnet deployment.
+pub fn get_squads_v4_vault_associated_token_address(
+    program_id: &Pubkey,
+    multisig: &Pubkey,
+    vault_index: u8,
+    token_mint_address: &Pubkey,
+    token_program_id: &Pubkey,
+) -> (Pubkey, Pubkey) {
+    get_associated_token_address_for_pda(
+        program_id,
+        &[
+            SQUADS_V4_SEED_PREFIX,
+            multisig.as_ref(),
+            SQUADS_V4_SEED_VAULT,
+            &[vault_index],
+        ],
+        token_mint_address,
+        token_program_id,
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const CREATE_KEY: Pubkey = Pubkey::new_from_array([1; 32]);
+    const MINT: Pubkey = Pubkey::new_from_array([2; 32]);
+    const TOKEN_PROGRAM_ID: Pubkey = Pubkey::new_from_array([3; 32]);
+
+    #[test]
+    fn pda_helper_derives_for_the_authority() {
+        let seeds: &[&[u8]] = &[b"wallet", CREATE_KEY.as_ref()];
+        let program_id = Pubkey::new_from_array([4; 32]);
+
+        let (authority, ata) =
+            get_associated_token_address_for_pda(&program_id, seeds, &MINT, &TOKEN_PROGRAM_ID);
+
+        assert_eq!(
+            authority,
+            Pubkey::find_program_address(seeds, &program_id).0
+        );
+        assert_eq!(
+            ata,
+            get_associated_token_address_with_program_id(&authority, &MINT, &TOKEN_PROGRAM_ID)
+        );
+    }
+
+    #[test]
+    fn squads_v4_vault_is_not_the_multisig() {
+        let multisig = get_squads_v4_multisig_address(&SQUADS_V4_PROGRAM_ID, &CREATE_KEY);
+        let (vault, ata) = get_squads_v4_vault_associated_token_address(
+            &SQUADS_V4_PROGRAM_ID,
+            &multisig,
+            0,
+            &MINT,
+            &TOKEN_PROGRAM_ID,
+        );
+
+        assert_eq!(
+            vault,
+            Pubkey::find_program_address(
+                &[b"multisig", multisig.as_ref(), b"vault", &[0]],
+                &SQUADS_V4_PROGRAM_ID
+            )
+            .0
+        );
+        for wrong_authority in [CREATE_KEY, multisig] {
+            assert_ne!(
+                ata,
+                get_associated_token_address_with_program_id(
+                    &wrong_authority,
+                    &MINT,
+                    &TOKEN_PROGRAM_ID
+                )
+            );
+        }
+        // Each vault index is a distinct authority
+        let (next_vault, _) = get_squads_v4_vault_associated_token_address(
+            &SQUADS_V4_PROGRAM_ID,
+            &multisig,
+            1,
+            &MINT,
+            &TOKEN_PROGRAM_ID,
+        );
+        assert_ne!(vault, next_vault);
+    }
+}
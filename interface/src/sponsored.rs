@@ -0,0 +1,253 @@
+//! Associated token account creation paid for by a relayer
+//!
+//! A relayer (or paymaster) pays the transaction fee and the rent of the new
+//! account on behalf of a wallet that holds no SOL. The builders here make the
+//! relayer both fee payer and funding account, so the wallet does not sign.
+//! Before signing a transaction it did not build, the relayer should run
+//! [`verify_sponsored_create`] over its instructions.
+
+use {
+    crate::{address::get_associated_token_address_with_program_id, instruction, program},
+    solana_instruction::Instruction,
+    solana_program_error::ProgramError,
+    solana_pubkey::Pubkey,
+    solana_sdk_ids::{
+        compute_budget::ID as COMPUTE_BUDGET_PROGRAM_ID, system_program::ID as SYSTEM_PROGRAM_ID,
+    },
+};
+
+const TOKEN_PROGRAM_ID: Pubkey =
+    Pubkey::from_str_const("TokenkegQfeZyiNwAJbNbGKPFXCWuBvf9Ss623VQ5DA");
+const TOKEN_2022_PROGRAM_ID: Pubkey =
+    Pubkey::from_str_const("TokenzQdBNbLqP5VEhdkAS6EPFLC1PHnBqCXEpPxuEb");
+
+/// Instructions of a sponsored transaction and the accounts that must sign it
+#[derive(Clone, Debug, PartialEq)]
+pub struct SponsoredTransaction {
+    /// Fee payer, first signer of the message
+    pub fee_payer: Pubkey,
+    /// Instructions to compile into a message paid for by `fee_payer`
+    pub instructions: Vec<Instruction>,
+    /// Every account that must sign the message, fee payer first
+    pub required_signers: Vec<Pubkey>,
+}
+
+/// Builds a transaction creating the associated token account of
+/// `wallet_address` for each mint in `token_mint_addresses`, with
+/// `fee_payer` paying both the fee and the rent
+///
+/// Uses `CreateIdempotent` so that a relayer retrying a landed transaction
+/// is not charged for a failed one.
+pub fn sponsored_create_associated_token_accounts(
+    fee_payer: &Pubkey,
+    wallet_address: &Pubkey,
+    token_mint_addresses: &[Pubkey],
+    token_program_id: &Pubkey,
+) -> SponsoredTransaction {
+    SponsoredTransaction {
+        fee_payer: *fee_payer,
+        instructions: token_mint_addresses
+            .iter()
+            .map(|token_mint_address| {
+                instruction::create_associated_token_account_idempotent(
+                    fee_payer,
+                    wallet_address,
+                    token_mint_address,
+                    token_program_id,
+                )
+            })
+            .collect(),
+        required_signers: vec![*fee_payer],
+    }
+}
+
+/// Checks that `instructions` only create associated token accounts funded
+/// by `fee_payer`, so that signing as fee payer cannot spend its lamports on
+/// anything else
+///
+/// Every instruction must be a compute budget instruction or a `Create` /
+/// `CreateIdempotent` instruction funded by `fee_payer`, for the canonical
+/// address, through the system program and the SPL Token or Token-2022
+/// program. `fee_payer` must not appear in any other account position.
+///
+/// Fails with `IncorrectProgramId` for any other program,
+/// `InvalidInstructionData` for any other ATA instruction,
+/// `NotEnoughAccountKeys` for a short account list, `InvalidSeeds` for a
+/// non-canonical address and `InvalidArgument` for a misplaced fee payer.
+pub fn verify_sponsored_create(
+    fee_payer: &Pubkey,
+    instructions: &[Instruction],
+) -> Result<(), ProgramError> {
+    for instruction in instructions {
+        if instruction.program_id == COMPUTE_BUDGET_PROGRAM_ID {
+            if instruction
+                .accounts
+                .iter()
+                .any(|meta| meta.pubkey == *fee_payer)
+            {
+                return Err(ProgramError::InvalidArgument);
+            }
+            continue;
+        }
+        if instruction.program_id != program::id() {
+            return Err(ProgramError::IncorrectProgramId);
+        }
+        if !matches!(instruction.data.as_slice(), [] | [0] | [1]) {
+            return Err(ProgramError::InvalidInstructionData);
+        }
+        let [
+            funder,
+            associated_account,
+            wallet,
+            mint,
+            system_program,
+            token_program,
+            remaining @ ..,
+        ] = instruction.accounts.as_slice()
+        else {
+            return Err(ProgramError::NotEnoughAccountKeys);
+        };
+        if funder.pubkey != *fee_payer
+            || [
+                associated_account,
+                wallet,
+                mint,
+                system_program,
+                token_program,
+            ]
+            .into_iter()
+            .chain(remaining)
+            .any(|meta| meta.pubkey == *fee_payer || meta.is_signer)
+        {
+            return Err(ProgramError::InvalidArgument);
+        }
+        if system_program.pubkey != SYSTEM_PROGRAM_ID
+            || (token_program.pubkey != TOKEN_PROGRAM_ID
+                && token_program.pubkey != TOKEN_2022_PROGRAM_ID)
+        {
+            return Err(ProgramError::IncorrectProgramId);
+        }
+        let expected = get_associated_token_address_with_program_id(
+            &wallet.pubkey,
+            &mint.pubkey,
+            &token_program.pubkey,
+        );
+        if associated_account.pubkey != expected {
+            return Err(ProgramError::InvalidSeeds);
+        }
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use {super::*, solana_instruction::AccountMeta};
+
+    const FEE_PAYER: Pubkey = Pubkey::new_from_array([1; 32]);
+    const WALLET: Pubkey = Pubkey::new_from_array([2; 32]);
+    const MINTS: [Pubkey; 2] = [
+        Pubkey::new_from_array([3; 32]),
+        Pubkey::new_from_array([4; 32]),
+    ];
+
+    fn sponsored() -> SponsoredTransaction {
+        sponsored_create_associated_token_accounts(
+            &FEE_PAYER,
+            &WALLET,
+            &MINTS,
+            &TOKEN_2022_PROGRAM_ID,
+        )
+    }
+
+    #[test]
+    fn wallet_does_not_sign() {
+        let transaction = sponsored();
+
+        assert_eq!(transaction.required_signers, [FEE_PAYER]);
+        assert_eq!(transaction.instructions.len(), MINTS.len());
+        for instruction in &transaction.instructions {
+            let signers: Vec<_> = instruction
+                .accounts
+                .iter()
+                .filter(|meta| meta.is_signer)
+                .map(|meta| meta.pubkey)
+                .collect();
+            assert_eq!(signers, [FEE_PAYER]);
+        }
+        assert_eq!(
+            verify_sponsored_create(&FEE_PAYER, &transaction.instructions),
+            Ok(())
+        );
+    }
+
+    #[test]
+    fn accepts_compute_budget_and_legacy_create() {
+        let mut instructions = vec![Instruction {
+            program_id: COMPUTE_BUDGET_PROGRAM_ID,
+            accounts: vec![],
+            data: vec![2, 0, 0, 1, 0],
+        }];
+        let mut create = instruction::create_associated_token_account(
+            &FEE_PAYER,
+            &WALLET,
+            &MINTS[0],
+            &TOKEN_PROGRAM_ID,
+        );
+        create.data.clear();
+        instructions.push(create);
+
+        assert_eq!(verify_sponsored_create(&FEE_PAYER, &instructions), Ok(()));
+    }
+
+    #[test]
+    fn rejects_non_create_instructions() {
+        let mut transfer = sponsored().instructions[0].clone();
+        transfer.program_id = SYSTEM_PROGRAM_ID;
+        let recover =
+            instruction::recover_nested(&FEE_PAYER, &MINTS[0], &MINTS[1], &TOKEN_PROGRAM_ID);
+        let mut short = sponsored().instructions[0].clone();
+        short.accounts.truncate(5);
+
+        let cases = [
+            (transfer, ProgramError::IncorrectProgramId),
+            (recover, ProgramError::InvalidInstructionData),
+            (short, ProgramError::NotEnoughAccountKeys),
+        ];
+        for (instruction, expected) in cases {
+            assert_eq!(
+                verify_sponsored_create(&FEE_PAYER, &[instruction]),
+                Err(expected)
+            );
+        }
+    }
+
+    #[test]
+    fn rejects_misused_fee_payer_and_accounts() {
+        let create = sponsored().instructions[0].clone();
+
+        let mut other_funder = create.clone();
+        other_funder.accounts[0].pubkey = WALLET;
+        let mut fee_payer_as_wallet = create.clone();
+        fee_payer_as_wallet.accounts[2].pubkey = FEE_PAYER;
+        let mut extra_signer = create.clone();
+        extra_signer.accounts.push(AccountMeta::new(WALLET, true));
+        let mut wrong_token_program = create.clone();
+        wrong_token_program.accounts[5].pubkey = WALLET;
+        let mut wrong_address = create.clone();
+        wrong_address.accounts[1].pubkey = WALLET;
+
+        let cases = [
+            (other_funder, ProgramError::InvalidArgument),
+            (fee_payer_as_wallet, ProgramError::InvalidArgument),
+            (extra_signer, ProgramError::InvalidArgument),
+            (wrong_token_program, ProgramError::IncorrectProgramId),
+            (wrong_address, ProgramError::InvalidSeeds),
+        ];
+        for (instruction, expected) in cases {
+            assert_eq!(
+                verify_sponsored_create(&FEE_PAYER, &[instruction]),
+                Err(expected)
+            );
+        }
+    }
+}
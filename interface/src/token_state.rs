@@ -0,0 +1,80 @@
+//! State of fetched associated token accounts
+//!
+//! Re-exports the account and mint state types of `spl-token-2022-interface`,
+//! which also read SPL Token accounts since both programs share the base
+//! layout, so a client can read the balance of an associated token account
+//! without depending on either token crate itself.
+
+use solana_program_error::ProgramError;
+pub use spl_token_2022_interface::{
+    extension::{BaseStateWithExtensions, ExtensionType, StateWithExtensions},
+    state::{Account as TokenAccountState, AccountState, Mint as MintState},
+};
+
+/// Unpacks the data of a token account owned by SPL Token or Token-2022
+///
+/// Extensions of Token-2022 accounts are read with the
+/// [`BaseStateWithExtensions`] getters of the result. Fails if the data is not
+/// an initialized token account.
+pub fn unpack_token_account(
+    data: &[u8],
+) -> Result<StateWithExtensions<'_, TokenAccountState>, ProgramError> {
+    StateWithExtensions::<TokenAccountState>::unpack(data)
+}
+
+#[cfg(test)]
+mod tests {
+    use {super::*, solana_pubkey::Pubkey};
+
+    const ACCOUNT_LEN: usize = 165;
+
+    /// Initialized base account state of `owner` holding `amount` of `mint`
+    fn account_data(mint: &Pubkey, owner: &Pubkey, amount: u64) -> Vec<u8> {
+        let mut data = vec![0; ACCOUNT_LEN];
+        data[..32].copy_from_slice(mint.as_ref());
+        data[32..64].copy_from_slice(owner.as_ref());
+        data[64..72].copy_from_slice(&amount.to_le_bytes());
+        data[108] = AccountState::Initialized as u8;
+        data
+    }
+
+    #[test]
+    fn unpacks_spl_token_account() {
+        let (mint, owner) = (Pubkey::new_unique(), Pubkey::new_unique());
+        let data = account_data(&mint, &owner, 42);
+
+        let account = unpack_token_account(&data).unwrap();
+        assert_eq!(account.base.mint, mint);
+        assert_eq!(account.base.owner, owner);
+        assert_eq!(account.base.amount, 42);
+        assert_eq!(account.get_extension_types().unwrap(), vec![]);
+    }
+
+    #[test]
+    fn unpacks_token_2022_account_with_extensions() {
+        let (mint, owner) = (Pubkey::new_unique(), Pubkey::new_unique());
+        let mut data = account_data(&mint, &owner, 7);
+        // `AccountType::Account`, then an empty `ImmutableOwner` entry
+        data.push(2);
+        data.extend_from_slice(&(ExtensionType::ImmutableOwner as u16).to_le_bytes());
+        data.extend_from_slice(&0u16.to_le_bytes());
+
+        let account = unpack_token_account(&data).unwrap();
+        assert_eq!(account.base.amount, 7);
+        assert_eq!(
+            account.get_extension_types().unwrap(),
+            vec![ExtensionType::ImmutableOwner]
+        );
+    }
+
+    #[test]
+    fn rejects_uninitialized_account() {
+        let mut data = account_data(&Pubkey::new_unique(), &Pubkey::new_unique(), 0);
+        data[108] = AccountState::Uninitialized as u8;
+
+        assert_eq!(
+            unpack_token_account(&data).unwrap_err(),
+            ProgramError::UninitializedAccount
+        );
+    }
+}
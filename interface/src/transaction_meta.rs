@@ -0,0 +1,389 @@
+//! Associated token account creations recorded in confirmed transactions
+//!
+//! Indexers that only look at top-level instructions miss accounts created
+//! through CPI, such as a program creating the recipient's account before a
+//! transfer. [`parse_ata_creations`] recognizes a single compiled instruction;
+//! with the `transaction-status` feature, [`ata_creations_in_transaction`]
+//! walks every top-level and inner instruction of an RPC transaction.
+
+use {crate::program, solana_pubkey::Pubkey};
+
+/// An associated token account creation found in a transaction
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct AtaCreation {
+    /// Account that paid the rent
+    pub funder: Pubkey,
+    /// The associated token account
+    pub associated_account: Pubkey,
+    /// Wallet owning the associated token account
+    pub wallet: Pubkey,
+    /// Token mint of the associated token account
+    pub mint: Pubkey,
+    /// Token program owning the associated token account
+    pub token_program: Pubkey,
+    /// Whether the instruction succeeds when the account already exists, in
+    /// which case it may not have created anything
+    pub idempotent: bool,
+}
+
+/// Accounts of `CreateMany` before its first group: the funder and the system
+/// program
+const CREATE_MANY_FIRST_GROUP: usize = 2;
+/// Accounts in each `CreateMany` group: the associated token account, the
+/// wallet, the mint and the token program
+const CREATE_MANY_GROUP_LEN: usize = 4;
+/// Most groups the program accepts in one `CreateMany`
+const MAX_CREATE_MANY_GROUPS: usize = 16;
+
+/// Returns the creations performed by an instruction of `program_id` with the
+/// given `accounts` and `data`, empty if it is not an associated token account
+/// creation
+///
+/// Recognizes `Create` and `CreateIdempotent` in every encoding the program
+/// accepts, including the p-ATA `CreateWithArgs`, `CreateCompact` and
+/// `CreateWithBumpLimit` extensions, and the p-ATA instructions that create an
+/// account along the way: `CreateWrapped`, `CreateWithDeposit`,
+/// `CreateConfidential` and `CreateMany`, which yields one creation per group.
+pub fn parse_ata_creations(
+    program_id: &Pubkey,
+    accounts: &[Pubkey],
+    data: &[u8],
+) -> Vec<AtaCreation> {
+    if *program_id != program::id() {
+        return vec![];
+    }
+    let (idempotent, token_program_index) = match data {
+        [] | [0] => (false, 5),
+        [1] => (true, 5),
+        [3 | 6 | 13 | 14 | 15, mode @ (0 | 1), ..] => (*mode == 1, 5),
+        [4, mode @ (0 | 1), ..] => (*mode == 1, 4),
+        [8, mode @ (0 | 1)] => return parse_create_many(accounts, *mode == 1),
+        _ => return vec![],
+    };
+    parse_create(accounts, token_program_index, idempotent)
+        .into_iter()
+        .collect()
+}
+
+fn parse_create(
+    accounts: &[Pubkey],
+    token_program_index: usize,
+    idempotent: bool,
+) -> Option<AtaCreation> {
+    let [funder, associated_account, wallet, mint, ..] = accounts else {
+        return None;
+    };
+    Some(AtaCreation {
+        funder: *funder,
+        associated_account: *associated_account,
+        wallet: *wallet,
+        mint: *mint,
+        token_program: *accounts.get(token_program_index)?,
+        idempotent,
+    })
+}
+
+/// The program fails a `CreateMany` without a group, with a trailing partial
+/// one or with too many, so none of those created anything
+fn parse_create_many(accounts: &[Pubkey], idempotent: bool) -> Vec<AtaCreation> {
+    let (Some(funder), Some(groups)) = (accounts.first(), accounts.get(CREATE_MANY_FIRST_GROUP..))
+    else {
+        return vec![];
+    };
+    let groups = groups.chunks_exact(CREATE_MANY_GROUP_LEN);
+    if groups.len() == 0 || groups.len() > MAX_CREATE_MANY_GROUPS || !groups.remainder().is_empty()
+    {
+        return vec![];
+    }
+    groups
+        .map(|group| AtaCreation {
+            funder: *funder,
+            associated_account: group[0],
+            wallet: group[1],
+            mint: group[2],
+            token_program: group[3],
+            idempotent,
+        })
+        .collect()
+}
+
+#[cfg(feature = "transaction-status")]
+pub use self::transaction_status::ata_creations_in_transaction;
+
+#[cfg(feature = "transaction-status")]
+mod transaction_status {
+    use {
+        super::{AtaCreation, parse_ata_creations},
+        solana_pubkey::Pubkey,
+        solana_transaction_status_client_types::{
+            EncodedConfirmedTransactionWithStatusMeta, EncodedTransaction, UiCompiledInstruction,
+            UiInstruction, UiMessage,
+        },
+        std::str::FromStr,
+    };
+
+    struct CompiledInstruction {
+        program_id_index: u8,
+        accounts: Vec<u8>,
+        data: Vec<u8>,
+    }
+
+    impl CompiledInstruction {
+        fn resolve(&self, keys: &[Pubkey]) -> Vec<AtaCreation> {
+            let Some(program_id) = keys.get(usize::from(self.program_id_index)) else {
+                return vec![];
+            };
+            let Some(accounts) = self
+                .accounts
+                .iter()
+                .map(|index| keys.get(usize::from(*index)).copied())
+                .collect::<Option<Vec<_>>>()
+            else {
+                return vec![];
+            };
+            parse_ata_creations(program_id, &accounts, &self.data)
+        }
+    }
+
+    /// Returns every associated token account creation in `transaction`, in
+    /// execution order: each top-level instruction followed by the inner
+    /// instructions it invoked
+    ///
+    /// Returns nothing for a failed transaction, since its creations were
+    /// rolled back, or for a transaction whose instructions cannot be read:
+    /// it must be fetched with a binary or `json` encoding, not `jsonParsed`,
+    /// and with its status metadata to see inner instructions.
+    pub fn ata_creations_in_transaction(
+        transaction: &EncodedConfirmedTransactionWithStatusMeta,
+    ) -> Vec<AtaCreation> {
+        let meta = transaction.transaction.meta.as_ref();
+        if meta.is_some_and(|meta| meta.err.is_some()) {
+            return vec![];
+        }
+        let Some((mut keys, instructions)) = top_level(&transaction.transaction.transaction) else {
+            return vec![];
+        };
+        let mut invoked: Vec<Vec<CompiledInstruction>> =
+            instructions.iter().map(|_| vec![]).collect();
+        if let Some(meta) = meta {
+            if let Some(loaded) = Option::from(meta.loaded_addresses.as_ref()) {
+                let Some(loaded) = loaded
+                    .writable
+                    .iter()
+                    .chain(&loaded.readonly)
+                    .map(|key| Pubkey::from_str(key).ok())
+                    .collect::<Option<Vec<_>>>()
+                else {
+                    return vec![];
+                };
+                keys.extend(loaded);
+            }
+            if let Some(inner_instructions) =
+                Option::<&Vec<_>>::from(meta.inner_instructions.as_ref())
+            {
+                for inner in inner_instructions {
+                    let Some(invoked) = invoked.get_mut(usize::from(inner.index)) else {
+                        continue;
+                    };
+                    invoked.extend(inner.instructions.iter().filter_map(|instruction| {
+                        match instruction {
+                            UiInstruction::Compiled(instruction) => compiled(instruction),
+                            UiInstruction::Parsed(_) => None,
+                        }
+                    }));
+                }
+            }
+        }
+        instructions
+            .iter()
+            .zip(&invoked)
+            .flat_map(|(instruction, invoked)| std::iter::once(instruction).chain(invoked))
+            .flat_map(|instruction| instruction.resolve(&keys))
+            .collect()
+    }
+
+    fn top_level(
+        transaction: &EncodedTransaction,
+    ) -> Option<(Vec<Pubkey>, Vec<CompiledInstruction>)> {
+        if let EncodedTransaction::Json(transaction) = transaction {
+            let UiMessage::Raw(message) = &transaction.message else {
+                return None;
+            };
+            let keys = message
+                .account_keys
+                .iter()
+                .map(|key| Pubkey::from_str(key).ok())
+                .collect::<Option<Vec<_>>>()?;
+            let instructions = message
+                .instructions
+                .iter()
+                .map(compiled)
+                .collect::<Option<Vec<_>>>()?;
+            return Some((keys, instructions));
+        }
+        let transaction = transaction.decode()?;
+        let keys = transaction.message.static_account_keys().to_vec();
+        let instructions = transaction
+            .message
+            .instructions()
+            .iter()
+            .map(|instruction| CompiledInstruction {
+                program_id_index: instruction.program_id_index,
+                accounts: instruction.accounts.clone(),
+                data: instruction.data.clone(),
+            })
+            .collect();
+        Some((keys, instructions))
+    }
+
+    fn compiled(instruction: &UiCompiledInstruction) -> Option<CompiledInstruction> {
+        Some(CompiledInstruction {
+            program_id_index: instruction.program_id_index,
+            accounts: instruction.accounts.clone(),
+            data: bs58::decode(&instruction.data).into_vec().ok()?,
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const KEYS: [Pubkey; 7] = [
+        Pubkey::new_from_array([1; 32]),
+        Pubkey::new_from_array([2; 32]),
+        Pubkey::new_from_array([3; 32]),
+        Pubkey::new_from_array([4; 32]),
+        Pubkey::new_from_array([5; 32]),
+        Pubkey::new_from_array([6; 32]),
+        Pubkey::new_from_array([7; 32]),
+    ];
+
+    fn creation(token_program: Pubkey, idempotent: bool) -> AtaCreation {
+        AtaCreation {
+            funder: KEYS[0],
+            associated_account: KEYS[1],
+            wallet: KEYS[2],
+            mint: KEYS[3],
+            token_program,
+            idempotent,
+        }
+    }
+
+    fn parse(accounts: &[Pubkey], data: &[u8]) -> Vec<AtaCreation> {
+        parse_ata_creations(&program::id(), accounts, data)
+    }
+
+    #[test]
+    fn parses_every_create_encoding() {
+        let cases: [(&[u8], AtaCreation); 9] = [
+            (&[], creation(KEYS[5], false)),
+            (&[0], creation(KEYS[5], false)),
+            (&[1], creation(KEYS[5], true)),
+            (&[3, 0, 255, 0, 0, 0, 0], creation(KEYS[5], false)),
+            (&[3, 1, 255, 165, 0, 0, 0], creation(KEYS[5], true)),
+            (&[4, 0, 0, 0, 0, 0, 0], creation(KEYS[4], false)),
+            (&[4, 1, 254, 0, 0, 0, 0], creation(KEYS[4], true)),
+            (&[6, 0, 250, 0, 0, 0, 0], creation(KEYS[5], false)),
+            (&[6, 1, 250, 0, 0, 0, 0], creation(KEYS[5], true)),
+        ];
+        for (data, expected) in cases {
+            assert_eq!(parse(&KEYS, data), vec![expected], "{data:?}");
+        }
+    }
+
+    #[test]
+    fn parses_create_wrapped() {
+        let mut data = vec![13, 1];
+        data.extend_from_slice(&1_000_000_u64.to_le_bytes());
+        assert_eq!(parse(&KEYS, &data), vec![creation(KEYS[5], true)]);
+        data[1] = 0;
+        assert_eq!(parse(&KEYS, &data), vec![creation(KEYS[5], false)]);
+    }
+
+    #[test]
+    fn parses_create_with_deposit() {
+        let mut data = vec![14, 0];
+        data.extend_from_slice(&500_u64.to_le_bytes());
+        data.push(6);
+        // The deposit source follows the token program
+        assert_eq!(parse(&KEYS, &data), vec![creation(KEYS[5], false)]);
+        data[1] = 1;
+        assert_eq!(parse(&KEYS, &data), vec![creation(KEYS[5], true)]);
+    }
+
+    #[test]
+    fn parses_create_confidential() {
+        let mut data = vec![15, 1];
+        data.extend_from_slice(&[0; 36]);
+        data.extend_from_slice(&65_536_u64.to_le_bytes());
+        data.push(1);
+        // The proof account follows the token program
+        assert_eq!(parse(&KEYS, &data), vec![creation(KEYS[5], true)]);
+        data[1] = 0;
+        assert_eq!(parse(&KEYS, &data), vec![creation(KEYS[5], false)]);
+    }
+
+    #[test]
+    fn parses_every_create_many_group() {
+        let keys: Vec<Pubkey> = (1..=10).map(|i| Pubkey::new_from_array([i; 32])).collect();
+        let group = |accounts: &[Pubkey], idempotent: bool| AtaCreation {
+            funder: keys[0],
+            associated_account: accounts[0],
+            wallet: accounts[1],
+            mint: accounts[2],
+            token_program: accounts[3],
+            idempotent,
+        };
+
+        assert_eq!(parse(&keys[..6], &[8, 0]), vec![group(&keys[2..6], false)]);
+        assert_eq!(
+            parse(&keys, &[8, 1]),
+            vec![group(&keys[2..6], true), group(&keys[6..10], true)]
+        );
+    }
+
+    #[test]
+    fn ignores_create_many_the_program_rejects() {
+        // The funder, the system program and 17 groups
+        let keys: Vec<Pubkey> = (0..70).map(|i| Pubkey::new_from_array([i; 32])).collect();
+        // No group, a trailing partial group and one group too many
+        for accounts in [&keys[..2], &keys[..9], &keys[..]] {
+            assert!(
+                parse(accounts, &[8, 1]).is_empty(),
+                "{} accounts",
+                accounts.len()
+            );
+        }
+        // Trailing data and an invalid mode
+        assert!(parse(&keys[..6], &[8, 1, 0]).is_empty());
+        assert!(parse(&keys[..6], &[8, 2]).is_empty());
+    }
+
+    #[test]
+    fn ignores_other_instructions() {
+        let other_program = Pubkey::new_from_array([9; 32]);
+        assert!(parse_ata_creations(&other_program, &KEYS, &[1]).is_empty());
+
+        for data in [
+            &[2][..],
+            &[5],
+            &[7],
+            &[3, 2, 0, 0, 0, 0, 0],
+            &[13, 2, 0, 0, 0, 0, 0, 0, 0, 0],
+            &[16, 0, 0],
+        ] {
+            assert!(parse(&KEYS, data).is_empty(), "{data:?}");
+        }
+    }
+
+    #[test]
+    fn requires_token_program_account() {
+        assert!(parse(&KEYS[..5], &[1]).is_empty());
+        assert_eq!(
+            parse(&KEYS[..5], &[4, 1, 0, 0, 0, 0, 0]),
+            vec![creation(KEYS[4], true)]
+        );
+        assert!(parse(&KEYS[..4], &[4, 1, 0, 0, 0, 0, 0]).is_empty());
+    }
+}
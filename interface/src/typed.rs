@@ -0,0 +1,155 @@
+//! Address newtypes and builders that keep wallet, mint and associated token
+//! account arguments apart at the type level
+//!
+//! The raw-`Pubkey` functions in [`crate::address`] and [`crate::instruction`]
+//! take the wallet and mint as adjacent arguments of the same type, so
+//! swapping them compiles and silently derives the wrong address. The
+//! builders here take distinct types instead; convert with `From<Pubkey>`.
+
+use {
+    crate::{address::get_associated_token_address_with_program_id, instruction},
+    solana_instruction::Instruction,
+    solana_pubkey::Pubkey,
+};
+
+macro_rules! address_newtype {
+    ($(#[$meta:meta])* $name:ident) => {
+        $(#[$meta])*
+        #[derive(Clone, Copy, Debug, PartialEq, Eq, Hash, PartialOrd, Ord)]
+        pub struct $name(pub Pubkey);
+
+        impl From<Pubkey> for $name {
+            fn from(address: Pubkey) -> Self {
+                Self(address)
+            }
+        }
+
+        impl From<$name> for Pubkey {
+            fn from(address: $name) -> Self {
+                address.0
+            }
+        }
+
+        impl AsRef<Pubkey> for $name {
+            fn as_ref(&self) -> &Pubkey {
+                &self.0
+            }
+        }
+    };
+}
+
+address_newtype!(
+    /// Address of the wallet that owns an associated token account
+    WalletAddress
+);
+address_newtype!(
+    /// Address of a token mint
+    MintAddress
+);
+address_newtype!(
+    /// Address of an associated token account
+    AtaAddress
+);
+
+/// Derives the associated token account address for `wallet` and `mint`
+/// under `token_program_id`
+pub fn get_associated_token_address(
+    wallet: &WalletAddress,
+    mint: &MintAddress,
+    token_program_id: &Pubkey,
+) -> AtaAddress {
+    AtaAddress(get_associated_token_address_with_program_id(
+        &wallet.0,
+        &mint.0,
+        token_program_id,
+    ))
+}
+
+/// Creates a `Create` instruction
+pub fn create_associated_token_account(
+    funding_address: &Pubkey,
+    wallet: &WalletAddress,
+    mint: &MintAddress,
+    token_program_id: &Pubkey,
+) -> Instruction {
+    instruction::create_associated_token_account(
+        funding_address,
+        &wallet.0,
+        &mint.0,
+        token_program_id,
+    )
+}
+
+/// Creates a `CreateIdempotent` instruction
+pub fn create_associated_token_account_idempotent(
+    funding_address: &Pubkey,
+    wallet: &WalletAddress,
+    mint: &MintAddress,
+    token_program_id: &Pubkey,
+) -> Instruction {
+    instruction::create_associated_token_account_idempotent(
+        funding_address,
+        &wallet.0,
+        &mint.0,
+        token_program_id,
+    )
+}
+
+/// Creates a `RecoverNested` instruction
+pub fn recover_nested(
+    wallet: &WalletAddress,
+    owner_mint: &MintAddress,
+    nested_mint: &MintAddress,
+    token_program_id: &Pubkey,
+) -> Instruction {
+    instruction::recover_nested(&wallet.0, &owner_mint.0, &nested_mint.0, token_program_id)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const FUNDER: Pubkey = Pubkey::new_from_array([1; 32]);
+    const WALLET: Pubkey = Pubkey::new_from_array([2; 32]);
+    const MINT: Pubkey = Pubkey::new_from_array([3; 32]);
+    const NESTED_MINT: Pubkey = Pubkey::new_from_array([4; 32]);
+    const TOKEN_PROGRAM_ID: Pubkey = Pubkey::new_from_array([5; 32]);
+
+    #[test]
+    fn typed_builders_match_raw_builders() {
+        let wallet = WalletAddress::from(WALLET);
+        let mint = MintAddress::from(MINT);
+        let nested_mint = MintAddress::from(NESTED_MINT);
+
+        assert_eq!(
+            Pubkey::from(get_associated_token_address(
+                &wallet,
+                &mint,
+                &TOKEN_PROGRAM_ID
+            )),
+            get_associated_token_address_with_program_id(&WALLET, &MINT, &TOKEN_PROGRAM_ID)
+        );
+        assert_eq!(
+            create_associated_token_account(&FUNDER, &wallet, &mint, &TOKEN_PROGRAM_ID),
+            instruction::create_associated_token_account(
+                &FUNDER,
+                &WALLET,
+                &MINT,
+                &TOKEN_PROGRAM_ID
+            )
+        );
+        assert_eq!(
+            create_associated_token_account_idempotent(&FUNDER, &wallet, &mint, &TOKEN_PROGRAM_ID),
+            instruction::create_associated_token_account_idempotent(
+                &FUNDER,
+                &WALLET,
+                &MINT,
+                &TOKEN_PROGRAM_ID
+            )
+        );
+        assert_eq!(
+            recover_nested(&wallet, &mint, &nested_mint, &TOKEN_PROGRAM_ID),
+            instruction::recover_nested(&WALLET, &MINT, &NESTED_MINT, &TOKEN_PROGRAM_ID)
+        );
+    }
+}
@@ -0,0 +1,120 @@
+//! Validation helpers for clients and programs accepting associated token
+//! accounts
+
+use {
+    crate::{
+        address::get_associated_token_address_with_program_id, error::AssociatedTokenAccountError,
+    },
+    solana_program_error::ProgramError,
+    solana_pubkey::Pubkey,
+};
+
+/// Length of the base token account layout shared by SPL Token and Token-2022
+const TOKEN_ACCOUNT_BASE_LEN: usize = 165;
+const MINT_OFFSET: usize = 0;
+const OWNER_OFFSET: usize = 32;
+const OWNER_END: usize = 64;
+const STATE_OFFSET: usize = 108;
+
+/// Asserts that the account at `address`, owned by `account_owner` and
+/// holding `account_data`, is the initialized associated token account of
+/// `wallet_address` for `token_mint_address` under `token_program_id`
+///
+/// Checks, in order, the address derivation, the owning program, and the
+/// token account `mint` and `owner` fields. Works for SPL Token and
+/// Token-2022 accounts alike.
+pub fn assert_is_ata_of(
+    address: &Pubkey,
+    account_owner: &Pubkey,
+    account_data: &[u8],
+    wallet_address: &Pubkey,
+    token_mint_address: &Pubkey,
+    token_program_id: &Pubkey,
+) -> Result<(), ProgramError> {
+    let expected = get_associated_token_address_with_program_id(
+        wallet_address,
+        token_mint_address,
+        token_program_id,
+    );
+    if *address != expected {
+        return Err(ProgramError::InvalidSeeds);
+    }
+    if account_owner != token_program_id {
+        return Err(ProgramError::IllegalOwner);
+    }
+    if account_data.len() < TOKEN_ACCOUNT_BASE_LEN {
+        return Err(ProgramError::InvalidAccountData);
+    }
+    // `AccountState::Uninitialized` is zero
+    if account_data[STATE_OFFSET] == 0 {
+        return Err(ProgramError::UninitializedAccount);
+    }
+    if account_data[MINT_OFFSET..OWNER_OFFSET] != token_mint_address.to_bytes() {
+        return Err(ProgramError::InvalidAccountData);
+    }
+    if account_data[OWNER_OFFSET..OWNER_END] != wallet_address.to_bytes() {
+        return Err(AssociatedTokenAccountError::InvalidOwner.into());
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const WALLET: Pubkey = Pubkey::new_from_array([1; 32]);
+    const MINT: Pubkey = Pubkey::new_from_array([2; 32]);
+    const TOKEN_PROGRAM: Pubkey = Pubkey::new_from_array([3; 32]);
+
+    fn token_account_data(mint: &Pubkey, owner: &Pubkey, state: u8) -> Vec<u8> {
+        let mut data = vec![0; TOKEN_ACCOUNT_BASE_LEN];
+        data[MINT_OFFSET..OWNER_OFFSET].copy_from_slice(mint.as_ref());
+        data[OWNER_OFFSET..OWNER_END].copy_from_slice(owner.as_ref());
+        data[STATE_OFFSET] = state;
+        data
+    }
+
+    fn check(address: &Pubkey, owner: &Pubkey, data: &[u8]) -> Result<(), ProgramError> {
+        assert_is_ata_of(address, owner, data, &WALLET, &MINT, &TOKEN_PROGRAM)
+    }
+
+    #[test]
+    fn accepts_ata() {
+        let ata = get_associated_token_address_with_program_id(&WALLET, &MINT, &TOKEN_PROGRAM);
+        let data = token_account_data(&MINT, &WALLET, 1);
+        assert_eq!(check(&ata, &TOKEN_PROGRAM, &data), Ok(()));
+    }
+
+    #[test]
+    fn rejects_invalid_accounts() {
+        let ata = get_associated_token_address_with_program_id(&WALLET, &MINT, &TOKEN_PROGRAM);
+        let other = Pubkey::new_from_array([4; 32]);
+        let data = token_account_data(&MINT, &WALLET, 1);
+
+        assert_eq!(
+            check(&other, &TOKEN_PROGRAM, &data),
+            Err(ProgramError::InvalidSeeds)
+        );
+        assert_eq!(check(&ata, &other, &data), Err(ProgramError::IllegalOwner));
+        assert_eq!(
+            check(&ata, &TOKEN_PROGRAM, &data[..100]),
+            Err(ProgramError::InvalidAccountData)
+        );
+        assert_eq!(
+            check(&ata, &TOKEN_PROGRAM, &token_account_data(&MINT, &WALLET, 0)),
+            Err(ProgramError::UninitializedAccount)
+        );
+        assert_eq!(
+            check(
+                &ata,
+                &TOKEN_PROGRAM,
+                &token_account_data(&other, &WALLET, 1)
+            ),
+            Err(ProgramError::InvalidAccountData)
+        );
+        assert_eq!(
+            check(&ata, &TOKEN_PROGRAM, &token_account_data(&MINT, &other, 1)),
+            Err(AssociatedTokenAccountError::InvalidOwner.into())
+        );
+    }
+}
@@ -1,7 +1,17 @@
 use {
-    mollusk_svm::{Mollusk, MolluskContext, result::Check},
-    pinocchio_associated_token_account_interface::instruction::{
-        AccountLenHint, AssociatedTokenAccountInstruction, BumpSeedHint, CreateMode,
+    mollusk_svm::{
+        Mollusk, MolluskContext,
+        account_store::AccountStore,
+        result::{Check, InstructionResult},
+    },
+    pinocchio_associated_token_account_interface::{
+        account_indices::{
+            CREATE_ASSOCIATED_TOKEN_ACCOUNT, CREATE_COMPACT_TOKEN_PROGRAM, CREATE_FUNDER,
+            CREATE_TOKEN_PROGRAM,
+        },
+        instruction::{
+            AccountLenHint, AssociatedTokenAccountInstruction, BumpSeedHint, CreateMode,
+        },
     },
     solana_account::Account,
     solana_instruction::{AccountMeta, Instruction},
@@ -11,10 +21,24 @@ use {
     solana_pubkey::Pubkey,
     solana_rent::Rent,
     solana_system_interface::program as system_program,
-    spl_associated_token_account_interface::address::get_associated_token_address_with_program_id,
-    spl_token_2022_interface::{extension::ExtensionType, state::Account as Token2022Account},
-    spl_token_interface::state::{Account as TokenAccount, AccountState, Mint},
-    std::{collections::HashMap, path::PathBuf, vec::Vec},
+    spl_associated_token_account_interface::address::{
+        get_associated_token_address_and_bump_seed, get_associated_token_address_with_program_id,
+    },
+    spl_token_2022_interface::{
+        extension::{
+            BaseStateWithExtensions, BaseStateWithExtensionsMut, Extension, ExtensionType,
+            StateWithExtensions, StateWithExtensionsMut,
+        },
+        state::{Account as Token2022Account, Mint as Token2022Mint},
+    },
+    spl_token_interface::state::{Account as TokenAccount, AccountState, Mint, Multisig},
+    std::{
+        collections::{BTreeMap, HashMap},
+        fmt::Write,
+        ops::{Deref, DerefMut},
+        path::PathBuf,
+        vec::Vec,
+    },
 };
 
 const PINOCCHIO_TOKEN_PROGRAM_NAME: &str = "pinocchio_token_program";
@@ -25,12 +49,24 @@ const SPL_TOKEN_2022_PROGRAM_NAME: &str = "spl_token_2022";
 pub enum AtaProgram {
     Legacy,
     Pinocchio,
+    /// A pinned p-ATA release ELF stored as `program/tests/fixtures/<name>.so`
+    PinocchioRelease(&'static str),
 }
 
-fn ata_program_name(ata_program: AtaProgram) -> &'static str {
+fn add_ata_program(mollusk: &mut Mollusk, program_id: &Pubkey, ata_program: AtaProgram) {
     match ata_program {
-        AtaProgram::Legacy => "spl_associated_token_account",
-        AtaProgram::Pinocchio => "pinocchio_associated_token_account_program",
+        AtaProgram::Legacy => mollusk.add_program(program_id, "spl_associated_token_account"),
+        AtaProgram::Pinocchio => {
+            mollusk.add_program(program_id, "pinocchio_associated_token_account_program")
+        }
+        AtaProgram::PinocchioRelease(name) => {
+            let elf = mollusk_svm::file::read_file(fixture_path(name));
+            mollusk.add_program_with_loader_and_elf(
+                program_id,
+                &mollusk_svm::program::loader_keys::LOADER_V3,
+                &elf,
+            );
+        }
     }
 }
 
@@ -74,6 +110,13 @@ pub enum CreateAtaInstructionType {
         account_len: Option<u32>,
         rent_sysvar: bool,
     },
+    /// The p-ATA-only `CreateCompact` instruction, which omits the system program account.
+    CreateCompact {
+        mode: CreateMode,
+        bump: Option<u8>,
+        account_len: Option<u32>,
+        rent_sysvar: bool,
+    },
 }
 
 /// Calculate the expected account length for a Token-2022 account with `ImmutableOwner` extension
@@ -92,30 +135,255 @@ pub fn token_account_rent_exempt_balance() -> u64 {
     Rent::default().minimum_balance(TokenAccount::LEN)
 }
 
+/// Calculate the expected ATA length for `mint_account`: the base token account
+/// for SPL Token, or for Token-2022 the account with `ImmutableOwner` plus every
+/// account extension required by the mint's extensions
+pub fn expected_ata_len_for_mint(mint_account: &Account) -> usize {
+    if mint_account.owner != spl_token_2022_interface::id() {
+        return TokenAccount::LEN;
+    }
+    let mint = StateWithExtensions::<Token2022Mint>::unpack(&mint_account.data)
+        .expect("Failed to unpack Token-2022 mint");
+    let mut account_extensions = ExtensionType::get_required_init_account_extensions(
+        &mint
+            .get_extension_types()
+            .expect("Failed to read mint extension types"),
+    );
+    if !account_extensions.contains(&ExtensionType::ImmutableOwner) {
+        account_extensions.push(ExtensionType::ImmutableOwner);
+    }
+    ExtensionType::try_calculate_account_len::<Token2022Account>(&account_extensions)
+        .expect("Failed to calculate Token-2022 account length")
+}
+
+/// Calculate the rent-exempt balance for an ATA of `mint_account` under `Rent::default()`
+pub fn expected_rent_for_mint(mint_account: &Account) -> u64 {
+    Rent::default().minimum_balance(expected_ata_len_for_mint(mint_account))
+}
+
+/// Token-level expectations for a single token account, checked against the
+/// account store after mollusk's raw `Check`s have passed.
+#[derive(Debug)]
+pub struct TokenChecks {
+    address: Pubkey,
+    mint: Option<Pubkey>,
+    owner: Option<Pubkey>,
+    amount: Option<u64>,
+    state: Option<AccountState>,
+    extensions: Vec<ExtensionType>,
+}
+
+impl TokenChecks {
+    /// Start a set of expectations for the token account at `address`
+    pub fn account(address: Pubkey) -> Self {
+        Self {
+            address,
+            mint: None,
+            owner: None,
+            amount: None,
+            state: None,
+            extensions: Vec::new(),
+        }
+    }
+
+    /// Expect the token account's `amount` field
+    pub fn balance(mut self, amount: u64) -> Self {
+        self.amount = Some(amount);
+        self
+    }
+
+    /// Expect the token account's `mint` field
+    pub fn mint_field(mut self, mint: Pubkey) -> Self {
+        self.mint = Some(mint);
+        self
+    }
+
+    /// Expect the token account's `owner` field
+    pub fn owner_field(mut self, owner: Pubkey) -> Self {
+        self.owner = Some(owner);
+        self
+    }
+
+    /// Expect the token account's `state` field
+    pub fn state(mut self, state: AccountState) -> Self {
+        self.state = Some(state);
+        self
+    }
+
+    /// Expect a Token-2022 account extension to be initialized
+    pub fn extension_initialized<E: Extension>(mut self) -> Self {
+        self.extensions.push(E::TYPE);
+        self
+    }
+
+    /// Assert every expectation against the account as stored in `harness`
+    pub fn assert<S: HarnessStore>(&self, harness: &AtaTestHarness<S>) {
+        let account = harness.get_account(self.address);
+        let address = self.address;
+
+        // Both token programs share the base account layout
+        let (mint, owner, amount, state, extensions) =
+            if account.owner == spl_token_2022_interface::id() {
+                let token_account = StateWithExtensions::<Token2022Account>::unpack(&account.data)
+                    .unwrap_or_else(|_| panic!("{address} is not a Token-2022 account"));
+                (
+                    token_account.base.mint,
+                    token_account.base.owner,
+                    token_account.base.amount,
+                    token_account.base.state as u8,
+                    token_account.get_extension_types().unwrap(),
+                )
+            } else {
+                let token_account = TokenAccount::unpack(&account.data)
+                    .unwrap_or_else(|_| panic!("{address} is not an SPL Token account"));
+                (
+                    token_account.mint,
+                    token_account.owner,
+                    token_account.amount,
+                    token_account.state as u8,
+                    Vec::new(),
+                )
+            };
+
+        if let Some(expected) = self.mint {
+            assert_eq!(mint, expected, "mint field mismatch for {address}");
+        }
+        if let Some(expected) = self.owner {
+            assert_eq!(owner, expected, "owner field mismatch for {address}");
+        }
+        if let Some(expected) = self.amount {
+            assert_eq!(amount, expected, "balance mismatch for {address}");
+        }
+        if let Some(expected) = self.state {
+            assert_eq!(state, expected as u8, "state mismatch for {address}");
+        }
+        for extension in &self.extensions {
+            assert!(
+                extensions.contains(extension),
+                "{extension:?} not initialized for {address}"
+            );
+        }
+    }
+}
+
+/// Account store backing an [`AtaTestHarness`].
+///
+/// Implement this for persistent or instrumented stores; `Clone` is used to
+/// snapshot and restore the store around multi-run assertions.
+pub trait HarnessStore: AccountStore + Clone {
+    /// Every account in the store
+    fn accounts(&self) -> Vec<(Pubkey, Account)>;
+}
+
+impl HarnessStore for HashMap<Pubkey, Account> {
+    fn accounts(&self) -> Vec<(Pubkey, Account)> {
+        self.iter()
+            .map(|(address, account)| (*address, account.clone()))
+            .collect()
+    }
+}
+
+/// Account store with deterministic, address-ordered iteration
+#[derive(Clone, Debug, Default)]
+pub struct OrderedAccountStore(pub BTreeMap<Pubkey, Account>);
+
+impl Deref for OrderedAccountStore {
+    type Target = BTreeMap<Pubkey, Account>;
+
+    fn deref(&self) -> &Self::Target {
+        &self.0
+    }
+}
+
+impl DerefMut for OrderedAccountStore {
+    fn deref_mut(&mut self) -> &mut Self::Target {
+        &mut self.0
+    }
+}
+
+impl AccountStore for OrderedAccountStore {
+    fn get_account(&self, pubkey: &Pubkey) -> Option<Account> {
+        self.0.get(pubkey).cloned()
+    }
+
+    fn store_account(&mut self, pubkey: Pubkey, account: Account) {
+        self.0.insert(pubkey, account);
+    }
+}
+
+impl HarnessStore for OrderedAccountStore {
+    fn accounts(&self) -> Vec<(Pubkey, Account)> {
+        self.0
+            .iter()
+            .map(|(address, account)| (*address, account.clone()))
+            .collect()
+    }
+}
+
 /// Test harness for ATA testing scenarios
-pub struct AtaTestHarness {
-    pub ctx: MolluskContext<HashMap<Pubkey, Account>>,
+pub struct AtaTestHarness<S: HarnessStore = HashMap<Pubkey, Account>> {
+    pub ctx: MolluskContext<S>,
+    pub ata_program: AtaProgram,
+    /// Address the ATA program is loaded under, canonical unless
+    /// [`AtaTestHarness::with_sandboxed_program_id`] was used
+    pub ata_program_id: Pubkey,
     pub token_program_id: Pubkey,
     pub payer: Pubkey,
     pub wallet: Option<Pubkey>,
     pub mint: Option<Pubkey>,
     pub mint_authority: Option<Pubkey>,
+    pub freeze_authority: Option<Pubkey>,
     pub ata_address: Option<Pubkey>,
 }
 
 impl AtaTestHarness {
+    /// Create a new test harness with the specified token program
+    pub fn new(token_program_id: &Pubkey) -> Self {
+        Self::new_with_ata_program(token_program_id, AtaProgram::Legacy)
+    }
+
+    /// Create a new test harness with the selected ATA program implementation
+    pub fn new_with_ata_program(token_program_id: &Pubkey, ata_program: AtaProgram) -> Self {
+        Self::new_with_store(token_program_id, ata_program, HashMap::new())
+    }
+
+    /// Create a new test harness using a custom token program ELF name under the
+    /// provided token program id.
+    pub fn new_with_token_program_name(
+        token_program_id: &Pubkey,
+        token_program_name: &str,
+    ) -> Self {
+        let mut mollusk = Mollusk::default();
+        add_ata_program(
+            &mut mollusk,
+            &spl_associated_token_account_interface::program::id(),
+            AtaProgram::Legacy,
+        );
+        add_token_program_by_name(&mut mollusk, token_program_id, token_program_name);
+        Self::new_with_mollusk(
+            token_program_id,
+            AtaProgram::Legacy,
+            mollusk,
+            HashMap::new(),
+        )
+    }
+}
+
+impl<S: HarnessStore> AtaTestHarness<S> {
     /// Ensure an account exists in the context store with the given lamports.
     /// If the account does not exist, it will be created as a system account.
     /// However, this can be called on a non-system account (to be used for
     /// example when testing accidental nested owners).
     pub fn ensure_account_exists_with_lamports(&self, address: Pubkey, lamports: u64) {
         let mut store = self.ctx.account_store.borrow_mut();
-        if let Some(existing) = store.get_mut(&address) {
-            if existing.lamports < lamports {
-                existing.lamports = lamports;
+        match store.get_account(&address) {
+            Some(mut existing) => {
+                if existing.lamports < lamports {
+                    existing.lamports = lamports;
+                    store.store_account(address, existing);
+                }
             }
-        } else {
-            store.insert(address, AccountBuilder::system_account(lamports));
+            None => store.store_account(address, AccountBuilder::system_account(lamports)),
         }
     }
 
@@ -141,16 +409,14 @@ impl AtaTestHarness {
             .process_and_validate_instruction(&create_mint_ix, &[Check::success()]);
     }
 
-    /// Create a new test harness with the specified token program
-    pub fn new(token_program_id: &Pubkey) -> Self {
-        Self::new_with_ata_program(token_program_id, AtaProgram::Legacy)
-    }
-
-    /// Create a new test harness with the selected ATA program implementation
-    pub fn new_with_ata_program(token_program_id: &Pubkey, ata_program: AtaProgram) -> Self {
-        let mut mollusk = Mollusk::new(
+    /// Create a new test harness with the selected ATA program implementation,
+    /// backed by a custom account store
+    pub fn new_with_store(token_program_id: &Pubkey, ata_program: AtaProgram, store: S) -> Self {
+        let mut mollusk = Mollusk::default();
+        add_ata_program(
+            &mut mollusk,
             &spl_associated_token_account_interface::program::id(),
-            ata_program_name(ata_program),
+            ata_program,
         );
         add_token_program_by_name(
             &mut mollusk,
@@ -162,41 +428,67 @@ impl AtaTestHarness {
             &spl_token_2022_interface::id(),
             SPL_TOKEN_2022_PROGRAM_NAME,
         );
-        Self::new_with_mollusk(token_program_id, mollusk)
+        Self::new_with_mollusk(token_program_id, ata_program, mollusk, store)
     }
 
-    /// Create a new test harness using a custom token program ELF name under the
-    /// provided token program id.
-    pub fn new_with_token_program_name(
+    /// Create a new test harness from a pre-configured Mollusk instance.
+    fn new_with_mollusk(
         token_program_id: &Pubkey,
-        token_program_name: &str,
+        ata_program: AtaProgram,
+        mollusk: Mollusk,
+        store: S,
     ) -> Self {
-        let mut mollusk = Mollusk::new(
-            &spl_associated_token_account_interface::program::id(),
-            ata_program_name(AtaProgram::Legacy),
-        );
-        add_token_program_by_name(&mut mollusk, token_program_id, token_program_name);
-        Self::new_with_mollusk(token_program_id, mollusk)
-    }
-
-    /// Create a new test harness from a pre-configured Mollusk instance.
-    fn new_with_mollusk(token_program_id: &Pubkey, mollusk: Mollusk) -> Self {
         let payer = Pubkey::new_unique();
-        let ctx = mollusk.with_context(HashMap::new());
+        let ctx = mollusk.with_context(store);
 
         let harness = Self {
             ctx,
+            ata_program,
+            ata_program_id: spl_associated_token_account_interface::program::id(),
             token_program_id: *token_program_id,
             payer,
             wallet: None,
             mint: None,
             mint_authority: None,
+            freeze_authority: None,
             ata_address: None,
         };
         harness.ensure_account_exists_with_lamports(payer, 10_000_000_000);
         harness
     }
 
+    /// Load the ATA program under [`sandboxed_program_id`] of `test_name`
+    /// instead of the canonical id, for every instruction and address the
+    /// harness builds from now on.
+    ///
+    /// Addresses derived outside the harness, with the interface functions,
+    /// still use the canonical id.
+    pub fn with_sandboxed_program_id(mut self, test_name: &str) -> Self {
+        self.ata_program_id = sandboxed_program_id(test_name);
+        add_ata_program(
+            &mut self.ctx.mollusk,
+            &self.ata_program_id,
+            self.ata_program,
+        );
+        self
+    }
+
+    /// ATA address of `wallet` and `mint` under the loaded ATA program id
+    pub fn derive_ata_address(
+        &self,
+        wallet: &Pubkey,
+        mint: &Pubkey,
+        token_program_id: &Pubkey,
+    ) -> Pubkey {
+        get_associated_token_address_and_bump_seed(
+            wallet,
+            mint,
+            &self.ata_program_id,
+            token_program_id,
+        )
+        .0
+    }
+
     /// Add a wallet with the specified lamports
     pub fn with_wallet(mut self, lamports: u64) -> Self {
         let wallet = Pubkey::new_unique();
@@ -212,6 +504,13 @@ impl AtaTestHarness {
         (self, additional_wallet)
     }
 
+    /// Use a dedicated freeze authority for the next initialized mint instead
+    /// of the mint authority (must be called before the mint is initialized)
+    pub fn with_freeze_authority(mut self, freeze_authority: Pubkey) -> Self {
+        self.freeze_authority = Some(freeze_authority);
+        self
+    }
+
     /// Create and initialize a mint with the specified decimals
     pub fn with_mint(mut self, decimals: u8) -> Self {
         let [mint_authority, mint_account] = [Pubkey::new_unique(); 2];
@@ -228,12 +527,13 @@ impl AtaTestHarness {
     pub fn with_raw_mint(mut self, owner: Pubkey, lamports: u64, data: Vec<u8>) -> Self {
         let mint = Pubkey::new_unique();
         self.ensure_account_exists_with_lamports(mint, lamports);
-        {
-            let mut store = self.ctx.account_store.borrow_mut();
-            let mint_account = store.get_mut(&mint).expect("mint account must exist");
-            mint_account.owner = owner;
-            mint_account.data = data;
-        }
+        let mut mint_account = self.get_account(mint);
+        mint_account.owner = owner;
+        mint_account.data = data;
+        self.ctx
+            .account_store
+            .borrow_mut()
+            .store_account(mint, mint_account);
         self.mint = Some(mint);
         self
     }
@@ -280,16 +580,94 @@ impl AtaTestHarness {
         self
     }
 
+    /// Initialize the `ConfidentialTransferMint` extension on the current mint, approving new accounts
+    /// automatically and without an auditor (requires Token-2022 mint with `ConfidentialTransferMint` extension)
+    pub fn initialize_confidential_transfer_mint(self) -> Self {
+        let mint = self.mint.expect("Mint must be set");
+        let mint_authority = self.mint_authority.expect("Mint authority must be set");
+
+        let init_confidential_ix =
+            spl_token_2022_interface::extension::confidential_transfer::instruction::initialize_mint(
+                &spl_token_2022_interface::id(),
+                &mint,
+                Some(mint_authority),
+                true,
+                None,
+            )
+            .expect("Failed to create initialize confidential transfer mint instruction");
+
+        self.ctx
+            .process_and_validate_instruction(&init_confidential_ix, &[Check::success()]);
+        self
+    }
+
+    /// Initialize the `GroupPointer` extension on the current mint (requires Token-2022 mint with `GroupPointer` extension)
+    pub fn initialize_group_pointer(self, group_address: Pubkey) -> Self {
+        let mint = self.mint.expect("Mint must be set");
+        let mint_authority = self.mint_authority.expect("Mint authority must be set");
+
+        let init_pointer_ix =
+            spl_token_2022_interface::extension::group_pointer::instruction::initialize(
+                &spl_token_2022_interface::id(),
+                &mint,
+                Some(mint_authority),
+                Some(group_address),
+            )
+            .expect("Failed to create initialize group pointer instruction");
+
+        self.ctx
+            .process_and_validate_instruction(&init_pointer_ix, &[Check::success()]);
+        self
+    }
+
+    /// Initialize the `GroupMemberPointer` extension on the current mint (requires Token-2022 mint with `GroupMemberPointer` extension)
+    pub fn initialize_group_member_pointer(self, member_address: Pubkey) -> Self {
+        let mint = self.mint.expect("Mint must be set");
+        let mint_authority = self.mint_authority.expect("Mint authority must be set");
+
+        let init_pointer_ix =
+            spl_token_2022_interface::extension::group_member_pointer::instruction::initialize(
+                &spl_token_2022_interface::id(),
+                &mint,
+                Some(mint_authority),
+                Some(member_address),
+            )
+            .expect("Failed to create initialize group member pointer instruction");
+
+        self.ctx
+            .process_and_validate_instruction(&init_pointer_ix, &[Check::success()]);
+        self
+    }
+
+    /// Create and initialize a collection-style group mint whose `GroupPointer` points at the mint itself
+    pub fn with_group_mint(self, decimals: u8) -> Self {
+        let harness = self.with_mint_with_extensions(&[ExtensionType::GroupPointer]);
+        let mint = harness.mint.expect("Mint must be set");
+        harness
+            .initialize_group_pointer(mint)
+            .initialize_mint(decimals)
+    }
+
+    /// Create and initialize a member mint whose `GroupMemberPointer` points at the mint itself
+    pub fn with_group_member_mint(self, decimals: u8) -> Self {
+        let harness = self.with_mint_with_extensions(&[ExtensionType::GroupMemberPointer]);
+        let mint = harness.mint.expect("Mint must be set");
+        harness
+            .initialize_group_member_pointer(mint)
+            .initialize_mint(decimals)
+    }
+
     /// Initialize mint (must be called after extensions are initialized)
-    pub fn initialize_mint(self, decimals: u8) -> Self {
+    pub fn initialize_mint(mut self, decimals: u8) -> Self {
         let mint = self.mint.expect("Mint must be set");
         let mint_authority = self.mint_authority.expect("Mint authority must be set");
+        let freeze_authority = *self.freeze_authority.get_or_insert(mint_authority);
 
         let init_mint_ix = spl_token_2022_interface::instruction::initialize_mint(
             &self.token_program_id,
             &mint,
             &mint_authority,
-            Some(&mint_authority),
+            Some(&freeze_authority),
             decimals,
         )
         .expect("Failed to create initialize_mint instruction");
@@ -304,11 +682,10 @@ impl AtaTestHarness {
         let wallet = self.wallet.expect("Wallet must be set before creating ATA");
         let mint = self.mint.expect("Mint must be set before creating ATA");
 
-        let ata_address =
-            get_associated_token_address_with_program_id(&wallet, &mint, &self.token_program_id);
+        let ata_address = self.derive_ata_address(&wallet, &mint, &self.token_program_id);
 
         let instruction = build_create_ata_instruction(
-            spl_associated_token_account_interface::program::id(),
+            self.ata_program_id,
             self.payer,
             ata_address,
             wallet,
@@ -329,9 +706,8 @@ impl AtaTestHarness {
         self.ctx
             .account_store
             .borrow()
-            .get(&pubkey)
+            .get_account(&pubkey)
             .expect("account not found")
-            .clone()
     }
 
     /// Mint tokens to the ATA (requires `mint`, `mint_authority` and `ata_address` to be set)
@@ -376,6 +752,41 @@ impl AtaTestHarness {
             .process_and_validate_instruction(&mint_to_ix, &[Check::success()]);
     }
 
+    /// Freeze the ATA with the mint's freeze authority (requires `freeze_authority`
+    /// and `ata_address` to be set)
+    pub fn freeze_ata(&mut self) {
+        let ata_address = self.ata_address.expect("ATA must be set");
+        let mint = self.mint.expect("Mint must be set");
+        let freeze_authority = self.freeze_authority.expect("Freeze authority must be set");
+        self.freeze_account_with_token_program(
+            ata_address,
+            mint,
+            freeze_authority,
+            self.token_program_id,
+        );
+    }
+
+    /// Freeze any token account using an arbitrary token program
+    pub fn freeze_account_with_token_program(
+        &mut self,
+        account: Pubkey,
+        mint: Pubkey,
+        freeze_authority: Pubkey,
+        token_program_id: Pubkey,
+    ) {
+        let freeze_ix = spl_token_2022_interface::instruction::freeze_account(
+            &token_program_id,
+            &account,
+            &mint,
+            &freeze_authority,
+            &[],
+        )
+        .unwrap();
+
+        self.ctx
+            .process_and_validate_instruction(&freeze_ix, &[Check::success()]);
+    }
+
     /// Create and initialize a mint under an arbitrary token program
     pub fn create_mint_with_token_program(
         &mut self,
@@ -411,11 +822,10 @@ impl AtaTestHarness {
     ) -> Pubkey {
         self.ensure_accounts_with_lamports(&[(owner, owner_lamports)]);
 
-        let ata_address =
-            get_associated_token_address_with_program_id(&owner, &mint, &token_program_id);
+        let ata_address = self.derive_ata_address(&owner, &mint, &token_program_id);
 
         let instruction = build_create_ata_instruction(
-            spl_associated_token_account_interface::program::id(),
+            self.ata_program_id,
             self.payer,
             ata_address,
             owner,
@@ -430,6 +840,31 @@ impl AtaTestHarness {
         ata_address
     }
 
+    /// Store a Token-2022 account without `ImmutableOwner` at the ATA address
+    /// of `owner` and `mint`, shaped like the ATAs created before the
+    /// extension was required, which still exist on mainnet
+    pub fn insert_legacy_token_2022_ata_for_owner(&self, owner: Pubkey, mint: Pubkey) -> Pubkey {
+        let token_program_id = spl_token_2022_interface::id();
+        self.ensure_accounts_with_lamports(&[(owner, 1_000_000)]);
+        let ata_address = self.derive_ata_address(&owner, &mint, &token_program_id);
+        let token_account =
+            AccountBuilder::legacy_token_2022_account(&mint, &self.get_account(mint), &owner, 0);
+        self.ctx
+            .account_store
+            .borrow_mut()
+            .store_account(ata_address, token_account);
+        ata_address
+    }
+
+    /// Store a legacy Token-2022 ATA for the wallet and mint, see
+    /// [`Self::insert_legacy_token_2022_ata_for_owner`]
+    pub fn with_legacy_token_2022_ata(mut self) -> Self {
+        let wallet = self.wallet.expect("Wallet must be set before creating ATA");
+        let mint = self.mint.expect("Mint must be set before creating ATA");
+        self.ata_address = Some(self.insert_legacy_token_2022_ata_for_owner(wallet, mint));
+        self
+    }
+
     /// Build a create ATA instruction for the current wallet and mint
     pub fn build_create_ata_instruction(
         &mut self,
@@ -437,13 +872,12 @@ impl AtaTestHarness {
     ) -> solana_instruction::Instruction {
         let wallet = self.wallet.expect("Wallet must be set");
         let mint = self.mint.expect("Mint must be set");
-        let ata_address =
-            get_associated_token_address_with_program_id(&wallet, &mint, &self.token_program_id);
+        let ata_address = self.derive_ata_address(&wallet, &mint, &self.token_program_id);
 
         self.ata_address = Some(ata_address);
 
         build_create_ata_instruction(
-            spl_associated_token_account_interface::program::id(),
+            self.ata_program_id,
             self.payer,
             ata_address,
             wallet,
@@ -472,7 +906,8 @@ impl AtaTestHarness {
         nested_mint: Pubkey,
     ) -> solana_instruction::Instruction {
         let wallet = self.wallet.as_ref().expect("Wallet must be set");
-        build_recover_nested_instruction(
+        recover_nested_instruction(
+            &self.ata_program_id,
             wallet,
             &owner_mint,
             &nested_mint,
@@ -491,11 +926,10 @@ impl AtaTestHarness {
     pub fn create_ata(&mut self, instruction_type: CreateAtaInstructionType) -> Pubkey {
         let wallet = self.wallet.expect("Wallet must be set");
         let mint = self.mint.expect("Mint must be set");
-        let ata_address =
-            get_associated_token_address_with_program_id(&wallet, &mint, &self.token_program_id);
+        let ata_address = self.derive_ata_address(&wallet, &mint, &self.token_program_id);
 
         let instruction = build_create_ata_instruction(
-            spl_associated_token_account_interface::program::id(),
+            self.ata_program_id,
             self.payer,
             ata_address,
             wallet,
@@ -504,17 +938,9 @@ impl AtaTestHarness {
             instruction_type,
         );
 
-        let expected_len = if self.token_program_id == spl_token_2022_interface::id() {
-            token_2022_immutable_owner_account_len()
-        } else {
-            TokenAccount::LEN
-        };
-
-        let expected_balance = if self.token_program_id == spl_token_2022_interface::id() {
-            token_2022_immutable_owner_rent_exempt_balance()
-        } else {
-            token_account_rent_exempt_balance()
-        };
+        let mint_account = self.get_account(mint);
+        let expected_len = expected_ata_len_for_mint(&mint_account);
+        let expected_balance = self.rent_exempt_minimum(expected_len);
 
         self.ctx.process_and_validate_instruction(
             &instruction,
@@ -528,23 +954,42 @@ impl AtaTestHarness {
             ],
         );
 
+        self.assert_rent_exempt(ata_address);
+
         self.ata_address = Some(ata_address);
         ata_address
     }
 
+    /// Rent-exempt minimum balance for `data_len` bytes under the rent mollusk
+    /// serves to programs, which tests may configure away from `Rent::default()`
+    pub fn rent_exempt_minimum(&self, data_len: usize) -> u64 {
+        self.ctx.mollusk.sysvars.rent.minimum_balance(data_len)
+    }
+
+    /// Assert that the account at `pubkey` holds at least the rent-exempt
+    /// minimum for its data length under the harness's configured rent
+    pub fn assert_rent_exempt(&self, pubkey: Pubkey) {
+        let account = self.get_account(pubkey);
+        let minimum = self.rent_exempt_minimum(account.data.len());
+        assert!(
+            account.lamports >= minimum,
+            "{pubkey} holds {} lamports, below the rent-exempt minimum of {minimum}",
+            account.lamports
+        );
+    }
+
     /// Insert a token account directly at the canonical ATA address.
     pub fn insert_token_account_at_ata_address(&self, owner: Pubkey) -> Pubkey {
         let wallet = self.wallet.as_ref().expect("Wallet must be set");
         let mint = self.mint.expect("Mint must be set");
         self.ensure_accounts_with_lamports(&[(owner, 1_000_000)]);
-        let ata_address =
-            get_associated_token_address_with_program_id(wallet, &mint, &self.token_program_id);
+        let ata_address = self.derive_ata_address(wallet, &mint, &self.token_program_id);
         // Create token account with wrong owner at the ATA address
         let token_account = AccountBuilder::token_account(&mint, &owner, 0, &self.token_program_id);
         self.ctx
             .account_store
             .borrow_mut()
-            .insert(ata_address, token_account);
+            .store_account(ata_address, token_account);
         ata_address
     }
 
@@ -558,15 +1003,15 @@ impl AtaTestHarness {
         let mint = self.mint.expect("Mint must be set");
 
         // Create a token account at the wrong address
-        self.ctx.account_store.borrow_mut().insert(
+        self.ctx.account_store.borrow_mut().store_account(
             wrong_account,
             AccountBuilder::token_account(&mint, &wallet, 0, &self.token_program_id),
         );
 
         let mut instruction = build_create_ata_instruction(
-            spl_associated_token_account_interface::program::id(),
+            self.ata_program_id,
             self.payer,
-            get_associated_token_address_with_program_id(&wallet, &mint, &self.token_program_id),
+            self.derive_ata_address(&wallet, &mint, &self.token_program_id),
             wallet,
             mint,
             self.token_program_id,
@@ -574,10 +1019,40 @@ impl AtaTestHarness {
         );
 
         // Replace the ATA address with the wrong account address
-        instruction.accounts[1] = AccountMeta::new(wrong_account, false);
+        instruction.accounts[CREATE_ASSOCIATED_TOKEN_ACCOUNT] =
+            AccountMeta::new(wrong_account, false);
 
-        self.ctx
-            .process_and_validate_instruction(&instruction, &[Check::err(expected_error)]);
+        self.process_and_validate_failed_create(&instruction, expected_error);
+    }
+
+    /// Process a create instruction that is expected to fail with
+    /// `expected_error`, and check that the payer and the target address are
+    /// left with exactly the lamports they had before
+    pub fn process_and_validate_failed_create(
+        &self,
+        instruction: &Instruction,
+        expected_error: ProgramError,
+    ) {
+        let payer = instruction.accounts[CREATE_FUNDER].pubkey;
+        let target = instruction.accounts[CREATE_ASSOCIATED_TOKEN_ACCOUNT].pubkey;
+        let lamports_before = |address: &Pubkey| {
+            self.ctx
+                .account_store
+                .borrow()
+                .get_account(address)
+                .map_or(0, |account| account.lamports)
+        };
+        let payer_lamports = lamports_before(&payer);
+        let target_lamports = lamports_before(&target);
+
+        self.ctx.process_and_validate_instruction(
+            instruction,
+            &[
+                Check::err(expected_error),
+                Check::account(&payer).lamports(payer_lamports).build(),
+                Check::account(&target).lamports(target_lamports).build(),
+            ],
+        );
     }
 
     /// Create ATA instruction with custom modifications (for special cases like legacy empty data)
@@ -591,11 +1066,10 @@ impl AtaTestHarness {
     {
         let wallet = self.wallet.expect("Wallet must be set");
         let mint = self.mint.expect("Mint must be set");
-        let ata_address =
-            get_associated_token_address_with_program_id(&wallet, &mint, &self.token_program_id);
+        let ata_address = self.derive_ata_address(&wallet, &mint, &self.token_program_id);
 
         let mut instruction = build_create_ata_instruction(
-            spl_associated_token_account_interface::program::id(),
+            self.ata_program_id,
             self.payer,
             ata_address,
             wallet,
@@ -607,17 +1081,9 @@ impl AtaTestHarness {
         // Apply custom modification
         modify_instruction(&mut instruction);
 
-        let expected_len = if self.token_program_id == spl_token_2022_interface::id() {
-            token_2022_immutable_owner_account_len()
-        } else {
-            TokenAccount::LEN
-        };
-
-        let expected_balance = if self.token_program_id == spl_token_2022_interface::id() {
-            token_2022_immutable_owner_rent_exempt_balance()
-        } else {
-            token_account_rent_exempt_balance()
-        };
+        let mint_account = self.get_account(mint);
+        let expected_len = expected_ata_len_for_mint(&mint_account);
+        let expected_balance = self.rent_exempt_minimum(expected_len);
 
         self.ctx.process_and_validate_instruction(
             &instruction,
@@ -631,9 +1097,866 @@ impl AtaTestHarness {
             ],
         );
 
+        self.assert_rent_exempt(ata_address);
+
         self.ata_address = Some(ata_address);
         ata_address
     }
+
+    /// Process an instruction, validate it with mollusk's `Check`s, then assert
+    /// the token-level expectations against the resulting account state
+    pub fn process_and_validate_with_token_checks(
+        &self,
+        instruction: &Instruction,
+        checks: &[Check],
+        token_checks: &[TokenChecks],
+    ) {
+        self.ctx
+            .process_and_validate_instruction(instruction, checks);
+        for token_check in token_checks {
+            token_check.assert(self);
+        }
+    }
+
+    /// Process an instruction, validate it with mollusk's `Check`s, then assert
+    /// that every account in `untouched` is identical to its state before the
+    /// instruction: lamports, data, owner and executable flag
+    ///
+    /// Only accounts held in the store are compared. Programs and sysvars that
+    /// mollusk supplies itself cannot be written by the program under test.
+    pub fn process_and_assert_untouched(
+        &self,
+        instruction: &Instruction,
+        checks: &[Check],
+        untouched: &[Pubkey],
+    ) {
+        let snapshot = |addresses: &[Pubkey]| -> Vec<Option<Account>> {
+            let store = self.ctx.account_store.borrow();
+            addresses
+                .iter()
+                .map(|address| store.get_account(address))
+                .collect()
+        };
+        let before = snapshot(untouched);
+
+        self.ctx
+            .process_and_validate_instruction(instruction, checks);
+
+        for ((address, before), after) in untouched.iter().zip(before).zip(snapshot(untouched)) {
+            assert_eq!(after, before, "account {address} was modified");
+        }
+    }
+
+    /// Process a multi-instruction flow in order, validating each instruction
+    /// against its checks, and assert that the flow's total compute units fit
+    /// within `compute_unit_budget`. Returns the total compute units consumed.
+    pub fn process_and_validate_flow(
+        &self,
+        steps: &[(&Instruction, &[Check])],
+        compute_unit_budget: u64,
+    ) -> u64 {
+        let mut total_compute_units = 0u64;
+        for (step, (instruction, checks)) in steps.iter().enumerate() {
+            let result = self
+                .ctx
+                .process_and_validate_instruction(instruction, checks);
+            total_compute_units = total_compute_units.saturating_add(result.compute_units_consumed);
+            assert!(
+                total_compute_units <= compute_unit_budget,
+                "flow exceeded {compute_unit_budget} CUs at step {step} with \
+                 {total_compute_units} CUs"
+            );
+        }
+        total_compute_units
+    }
+
+    /// Run `run` once against the harness and return the compute units consumed
+    /// by the instruction it processes, for CU checks that live next to the
+    /// functional tests rather than in the bench binaries.
+    pub fn measure_cu<F>(&mut self, run: F) -> u64
+    where
+        F: FnOnce(&mut Self) -> InstructionResult,
+    {
+        run(self).compute_units_consumed
+    }
+
+    /// Process `instruction` from the current account state under both the loaded
+    /// ATA program and `baseline`, asserting identical results and that the
+    /// loaded program uses no more compute units. The account store and the
+    /// loaded program are restored afterwards.
+    pub fn assert_no_regression_against(
+        &mut self,
+        instruction: &Instruction,
+        baseline: AtaProgram,
+    ) {
+        let snapshot = self.ctx.account_store.borrow().clone();
+        let current = self.ctx.process_instruction(instruction);
+
+        *self.ctx.account_store.borrow_mut() = snapshot.clone();
+        add_ata_program(&mut self.ctx.mollusk, &self.ata_program_id, baseline);
+        let previous = self.ctx.process_instruction(instruction);
+        add_ata_program(
+            &mut self.ctx.mollusk,
+            &self.ata_program_id,
+            self.ata_program,
+        );
+        *self.ctx.account_store.borrow_mut() = snapshot;
+
+        assert_eq!(
+            current.program_result, previous.program_result,
+            "program result differs from {baseline:?}"
+        );
+        assert_eq!(
+            current.resulting_accounts, previous.resulting_accounts,
+            "resulting accounts differ from {baseline:?}"
+        );
+        assert!(
+            current.compute_units_consumed <= previous.compute_units_consumed,
+            "{} CUs regressed from {} under {baseline:?}",
+            current.compute_units_consumed,
+            previous.compute_units_consumed
+        );
+    }
+
+    /// Process `instruction` under the reference SPL ATA program, then under
+    /// the loaded ATA program from the same account state, asserting identical
+    /// program results and resulting accounts.
+    ///
+    /// Unlike [`Self::assert_no_regression_against`], the store keeps the state
+    /// left by the loaded program, so a test can assert parity at each step of
+    /// its own flow. Returns the result under the loaded program.
+    pub fn assert_matches_reference(&mut self, instruction: &Instruction) -> InstructionResult {
+        let snapshot = self.ctx.account_store.borrow().clone();
+        add_ata_program(
+            &mut self.ctx.mollusk,
+            &self.ata_program_id,
+            AtaProgram::Legacy,
+        );
+        let reference = self.ctx.process_instruction(instruction);
+        add_ata_program(
+            &mut self.ctx.mollusk,
+            &self.ata_program_id,
+            self.ata_program,
+        );
+        *self.ctx.account_store.borrow_mut() = snapshot;
+
+        let current = self.ctx.process_instruction(instruction);
+        assert_eq!(
+            current.program_result, reference.program_result,
+            "program result differs from the reference program"
+        );
+        assert_eq!(
+            current.resulting_accounts, reference.resulting_accounts,
+            "resulting accounts differ from the reference program"
+        );
+        current
+    }
+
+    /// Process every interleaving of two actors' instruction sequences, each
+    /// starting from the current account state. Individual instructions may
+    /// fail, but a failed one must leave every account as it was, and after
+    /// every step the total lamports and the total token amount of each mint
+    /// must be unchanged, with no lamports stranded at the given ATA
+    /// addresses. The account store is restored afterwards.
+    pub fn assert_interleavings_are_clean(
+        &self,
+        actor_a: &[Instruction],
+        actor_b: &[Instruction],
+        ata_addresses: &[Pubkey],
+    ) {
+        let snapshot = self.ctx.account_store.borrow().clone();
+        let expected_lamports = total_lamports(&snapshot);
+        let expected_token_amounts = token_amounts_by_mint(&snapshot);
+
+        for (index, ordering) in interleavings(actor_a, actor_b).into_iter().enumerate() {
+            *self.ctx.account_store.borrow_mut() = snapshot.clone();
+
+            for (step, instruction) in ordering.iter().enumerate() {
+                let before = sorted_accounts(&*self.ctx.account_store.borrow());
+                // Failures are expected for some orderings, only the resulting state matters
+                let result = self.ctx.process_instruction(instruction);
+
+                let store = self.ctx.account_store.borrow();
+                if result.program_result.is_err() {
+                    assert_eq!(
+                        sorted_accounts(&*store),
+                        before,
+                        "failed step {step} of ordering {index} modified accounts"
+                    );
+                }
+                assert_eq!(
+                    total_lamports(&*store),
+                    expected_lamports,
+                    "lamports not conserved at step {step} of ordering {index}"
+                );
+                assert_eq!(
+                    token_amounts_by_mint(&*store),
+                    expected_token_amounts,
+                    "token amounts not conserved at step {step} of ordering {index}"
+                );
+                for address in ata_addresses {
+                    assert_no_stranded_lamports(&*store, address);
+                }
+            }
+        }
+
+        *self.ctx.account_store.borrow_mut() = snapshot;
+    }
+
+    /// Render the labelled accounts as they are stored, one block per
+    /// account: owner, lamports and data length, plus the base fields and
+    /// extension types of token accounts. Missing accounts are rendered as
+    /// such, so a dump also records which accounts a flow closed.
+    pub fn dump_accounts(&self, accounts: &[(&str, Pubkey)]) -> String {
+        let store = self.ctx.account_store.borrow();
+        let mut dump = String::new();
+        for (label, address) in accounts {
+            let _ = writeln!(dump, "{label}: {address}");
+            let Some(account) = store.get_account(address) else {
+                let _ = writeln!(dump, "  (no account)");
+                continue;
+            };
+            let _ = writeln!(dump, "  owner: {}", account.owner);
+            let _ = writeln!(dump, "  lamports: {}", account.lamports);
+            let _ = writeln!(dump, "  data length: {}", account.data.len());
+
+            // Both token programs share the base account layout
+            let token_fields = if account.owner == spl_token_2022_interface::id() {
+                StateWithExtensions::<Token2022Account>::unpack(&account.data)
+                    .ok()
+                    .map(|state| {
+                        (
+                            state.base.mint,
+                            state.base.owner,
+                            state.base.amount,
+                            format!("{:?}", state.base.state),
+                            state.get_extension_types().unwrap_or_default(),
+                        )
+                    })
+            } else if account.owner == spl_token_interface::id() {
+                TokenAccount::unpack(&account.data).ok().map(|base| {
+                    (
+                        base.mint,
+                        base.owner,
+                        base.amount,
+                        format!("{:?}", base.state),
+                        Vec::new(),
+                    )
+                })
+            } else {
+                None
+            };
+            if let Some((mint, owner, amount, state, extensions)) = token_fields {
+                let _ = writeln!(dump, "  mint: {mint}");
+                let _ = writeln!(dump, "  token owner: {owner}");
+                let _ = writeln!(dump, "  amount: {amount}");
+                let _ = writeln!(dump, "  state: {state}");
+                if !extensions.is_empty() {
+                    let _ = writeln!(dump, "  extensions: {extensions:?}");
+                }
+            }
+        }
+        dump
+    }
+}
+
+fn total_lamports<S: HarnessStore>(store: &S) -> u128 {
+    store
+        .accounts()
+        .iter()
+        .map(|(_, account)| u128::from(account.lamports))
+        .sum()
+}
+
+/// Every account in `store`, ordered by address so that two stores compare equal
+/// whatever their iteration order.
+fn sorted_accounts<S: HarnessStore>(store: &S) -> BTreeMap<Pubkey, Account> {
+    store.accounts().into_iter().collect()
+}
+
+/// Total amount held in token accounts of either token program, per mint.
+fn token_amounts_by_mint<S: HarnessStore>(store: &S) -> BTreeMap<Pubkey, u128> {
+    let mut amounts = BTreeMap::new();
+    for (_, account) in store.accounts() {
+        if account.owner != spl_token_interface::id()
+            && account.owner != spl_token_2022_interface::id()
+        {
+            continue;
+        }
+        // Both token programs share the base account layout, and mints fail to unpack
+        let Ok(state) = StateWithExtensions::<Token2022Account>::unpack(&account.data) else {
+            continue;
+        };
+        let amount: &mut u128 = amounts.entry(state.base.mint).or_default();
+        *amount = amount.saturating_add(u128::from(state.base.amount));
+    }
+    amounts
+}
+
+/// Lamports at an ATA address are stranded unless a token program owns the account.
+fn assert_no_stranded_lamports<S: HarnessStore>(store: &S, address: &Pubkey) {
+    if let Some(account) = store.get_account(address) {
+        assert!(
+            account.lamports == 0
+                || account.owner == spl_token_interface::id()
+                || account.owner == spl_token_2022_interface::id(),
+            "{} lamports stranded at {address} owned by {}",
+            account.lamports,
+            account.owner
+        );
+    }
+}
+
+/// Program id derived from `test_name`, unique per test and stable across
+/// runs, for [`AtaTestHarness::with_sandboxed_program_id`]
+///
+/// Panics if `test_name` is longer than 512 bytes.
+pub fn sandboxed_program_id(test_name: &str) -> Pubkey {
+    let seeds: Vec<&[u8]> = test_name.as_bytes().chunks(32).collect();
+    Pubkey::find_program_address(
+        &seeds,
+        &spl_associated_token_account_interface::program::id(),
+    )
+    .0
+}
+
+/// Run the whole life of an ATA under `token_program_id` and `ata_program`,
+/// asserting at every step: create the wallet's ATA, fund a nested ATA
+/// mistakenly owned by it, recover the nested tokens into the wallet's ATA
+/// for that mint, then burn them and close that ATA back into the wallet.
+///
+/// Intended as a smoke test for any build of the program, including forks.
+pub fn run_lifecycle_test(token_program_id: &Pubkey, ata_program: AtaProgram) {
+    const AMOUNT: u64 = 100;
+
+    let mut harness =
+        AtaTestHarness::new_with_ata_program(token_program_id, ata_program).with_wallet(1_000_000);
+    let wallet = harness.wallet.unwrap();
+
+    // Create
+    let (owner_mint, _) = harness.create_mint_with_token_program(*token_program_id, 0);
+    let owner_ata = harness.create_ata_for_owner_with_token_program(
+        wallet,
+        1_000_000,
+        owner_mint,
+        *token_program_id,
+    );
+    let assert_ata = |harness: &AtaTestHarness, ata: Pubkey, owner: Pubkey, mint: Pubkey| {
+        assert_eq!(
+            ata,
+            get_associated_token_address_with_program_id(&owner, &mint, token_program_id)
+        );
+        let mint_account = harness.get_account(mint);
+        let account = harness.get_account(ata);
+        assert_eq!(account.owner, *token_program_id, "{ata} owner");
+        assert_eq!(account.data.len(), expected_ata_len_for_mint(&mint_account));
+        assert_eq!(account.lamports, expected_rent_for_mint(&mint_account));
+        TokenChecks::account(ata)
+            .mint_field(mint)
+            .owner_field(owner)
+            .balance(0)
+            .state(AccountState::Initialized)
+            .assert(harness);
+    };
+    assert_ata(&harness, owner_ata, wallet, owner_mint);
+
+    // Fund a nested ATA, owned by the wallet's ATA instead of the wallet
+    let (nested_mint, nested_mint_authority) =
+        harness.create_mint_with_token_program(*token_program_id, 0);
+    let nested_ata = harness.create_ata_for_owner_with_token_program(
+        owner_ata,
+        0,
+        nested_mint,
+        *token_program_id,
+    );
+    assert_ata(&harness, nested_ata, owner_ata, nested_mint);
+    harness.mint_tokens_to_with_token_program(
+        nested_mint,
+        nested_mint_authority,
+        nested_ata,
+        *token_program_id,
+        AMOUNT,
+    );
+    TokenChecks::account(nested_ata)
+        .balance(AMOUNT)
+        .assert(&harness);
+
+    // Recover
+    let destination_ata =
+        harness.create_ata_for_owner_with_token_program(wallet, 0, nested_mint, *token_program_id);
+    assert_ata(&harness, destination_ata, wallet, nested_mint);
+    let wallet_lamports = harness.get_account(wallet).lamports;
+    let nested_lamports = harness.get_account(nested_ata).lamports;
+    let recover_instruction = harness.build_recover_nested_instruction(owner_mint, nested_mint);
+    harness.process_and_validate_with_token_checks(
+        &recover_instruction,
+        &[
+            Check::success(),
+            Check::account(&nested_ata).closed().build(),
+            Check::account(&wallet)
+                .lamports(wallet_lamports.saturating_add(nested_lamports))
+                .build(),
+        ],
+        &[TokenChecks::account(destination_ata).balance(AMOUNT)],
+    );
+
+    // Close
+    let burn_instruction = spl_token_2022_interface::instruction::burn(
+        token_program_id,
+        &destination_ata,
+        &nested_mint,
+        &wallet,
+        &[],
+        AMOUNT,
+    )
+    .unwrap();
+    harness
+        .ctx
+        .process_and_validate_instruction(&burn_instruction, &[Check::success()]);
+    let wallet_lamports = harness.get_account(wallet).lamports;
+    let destination_lamports = harness.get_account(destination_ata).lamports;
+    let close_instruction = spl_token_2022_interface::instruction::close_account(
+        token_program_id,
+        &destination_ata,
+        &wallet,
+        &wallet,
+        &[],
+    )
+    .unwrap();
+    harness.ctx.process_and_validate_instruction(
+        &close_instruction,
+        &[
+            Check::success(),
+            Check::account(&destination_ata).closed().build(),
+            Check::account(&wallet)
+                .lamports(wallet_lamports.saturating_add(destination_lamports))
+                .build(),
+        ],
+    );
+}
+
+/// Adversarial Token-2022 mint account data, each entry named after the
+/// corruption it carries. Every entry starts from an initialized mint and is
+/// rejected by the Token-2022 extension parser, so callers can feed it to
+/// anything that walks mint extensions and expect a clean error.
+pub fn malformed_mint_tlv_corpus() -> Vec<(&'static str, Vec<u8>)> {
+    const TLV_START: usize = TokenAccount::LEN + 1;
+    const MINT_CLOSE_AUTHORITY: u16 = 3;
+
+    let base = |tlv: &[u8]| {
+        let mut data = vec![0; Token2022Mint::LEN];
+        Token2022Mint::pack(
+            Token2022Mint {
+                mint_authority: COption::Some(Pubkey::new_from_array([1; 32])),
+                supply: 0,
+                decimals: 6,
+                is_initialized: true,
+                freeze_authority: COption::None,
+            },
+            &mut data,
+        )
+        .unwrap();
+        data.resize(TLV_START, 0);
+        data[TokenAccount::LEN] = 1; // `AccountType::Mint`
+        data.extend_from_slice(tlv);
+        data
+    };
+    let header = |extension_type: u16, len: u16| {
+        let mut header = extension_type.to_le_bytes().to_vec();
+        header.extend_from_slice(&len.to_le_bytes());
+        header
+    };
+
+    let mut value_past_end = header(MINT_CLOSE_AUTHORITY, 32);
+    value_past_end.extend_from_slice(&[7; 10]);
+    let mut giant_length = header(MINT_CLOSE_AUTHORITY, u16::MAX);
+    giant_length.extend_from_slice(&[7; 32]);
+    // A short declared length makes the next header start inside the value
+    let mut overlapping = header(MINT_CLOSE_AUTHORITY, 4);
+    overlapping.extend_from_slice(&[u8::MAX; 32]);
+    let mut wrong_account_type = base(&header(MINT_CLOSE_AUTHORITY, 32));
+    wrong_account_type[TokenAccount::LEN] = 2; // `AccountType::Account`
+    wrong_account_type.resize(wrong_account_type.len().saturating_add(32), 0);
+    let mut uninitialized = base(&header(MINT_CLOSE_AUTHORITY, 0));
+    uninitialized[45] = 0; // `is_initialized`
+    let mut multisig_len = base(&[]);
+    multisig_len.resize(Multisig::LEN, 0);
+
+    vec![
+        (
+            "truncated header",
+            base(&header(MINT_CLOSE_AUTHORITY, 32)[..3]),
+        ),
+        ("value past end", base(&value_past_end)),
+        ("giant declared length", base(&giant_length)),
+        ("unknown extension type", base(&header(u16::MAX, 0))),
+        ("overlapping entries", base(&overlapping)),
+        ("wrong account type", wrong_account_type),
+        ("uninitialized mint", uninitialized),
+        ("multisig length", multisig_len),
+    ]
+}
+
+/// Owner of the ATA a [`HarnessScenario`] creates
+#[cfg_attr(feature = "fuzz", derive(arbitrary::Arbitrary))]
+#[derive(Clone, Copy, Debug)]
+pub enum ScenarioWallet {
+    /// A funded system account
+    System,
+    /// An off-curve address with no account, like a PDA
+    OffCurve,
+    /// Another wallet's ATA for the same mint, making the new ATA nested
+    TokenAccount,
+}
+
+/// Mint a [`HarnessScenario`] creates the ATA for, which selects the token program
+#[cfg_attr(feature = "fuzz", derive(arbitrary::Arbitrary))]
+#[derive(Clone, Copy, Debug)]
+pub enum ScenarioMint {
+    Token,
+    Token2022,
+    Token2022TransferFee,
+    Token2022Group,
+    Token2022GroupMember,
+}
+
+/// Create instruction a [`HarnessScenario`] sends. Hints, when set, carry the
+/// canonical bump and the expected account length.
+#[cfg_attr(feature = "fuzz", derive(arbitrary::Arbitrary))]
+#[derive(Clone, Copy, Debug)]
+pub enum ScenarioInstruction {
+    Create,
+    CreateIdempotent,
+    CreateWithArgs {
+        idempotent: bool,
+        bump: bool,
+        account_len: bool,
+        rent_sysvar: bool,
+    },
+    CreateCompact {
+        idempotent: bool,
+        bump: bool,
+        account_len: bool,
+        rent_sysvar: bool,
+    },
+}
+
+/// Change to an otherwise valid instruction that must make it fail
+#[cfg_attr(feature = "fuzz", derive(arbitrary::Arbitrary))]
+#[derive(Clone, Copy, Debug)]
+pub enum ScenarioCorruption {
+    None,
+    ReadOnlyPayer,
+    ReadOnlyAta,
+    WrongAtaAddress,
+    WrongTokenProgram,
+    MissingAccounts,
+}
+
+/// A create scenario built from the same harness setup as the unit tests.
+///
+/// With the `fuzz` feature it implements `arbitrary::Arbitrary`, so fuzz
+/// targets can pass decoded inputs to [`run_scenario`], and any input they
+/// find can be written out as a test case from its `Debug` output.
+#[cfg_attr(feature = "fuzz", derive(arbitrary::Arbitrary))]
+#[derive(Clone, Copy, Debug)]
+pub struct HarnessScenario {
+    pub wallet: ScenarioWallet,
+    pub mint: ScenarioMint,
+    /// Create the ATA before sending the instruction
+    pub existing: bool,
+    pub instruction: ScenarioInstruction,
+    pub corruption: ScenarioCorruption,
+}
+
+/// Set up and process `scenario` under `ata_program`, asserting that it
+/// succeeds exactly when it should, that a created ATA is initialized for the
+/// wallet and mint with the expected size, and that a failure leaves the
+/// payer's lamports unchanged.
+///
+/// Expectations follow the p-ATA program, which rejects read-only accounts
+/// even for a `CreateIdempotent` no-op, so `ata_program` should be a p-ATA build.
+pub fn run_scenario(ata_program: AtaProgram, scenario: &HarnessScenario) {
+    let token_program_id = match scenario.mint {
+        ScenarioMint::Token => spl_token_interface::id(),
+        _ => spl_token_2022_interface::id(),
+    };
+    let harness = AtaTestHarness::new_with_ata_program(&token_program_id, ata_program);
+    let mut harness = match scenario.mint {
+        ScenarioMint::Token | ScenarioMint::Token2022 => harness.with_mint(6),
+        ScenarioMint::Token2022TransferFee => harness
+            .with_mint_with_extensions(&[ExtensionType::TransferFeeConfig])
+            .initialize_transfer_fee(1_000, 100)
+            .initialize_mint(6),
+        ScenarioMint::Token2022Group => harness.with_group_mint(6),
+        ScenarioMint::Token2022GroupMember => harness.with_group_member_mint(6),
+    };
+    let wallet = match scenario.wallet {
+        ScenarioWallet::System => {
+            harness = harness.with_wallet(1_000_000);
+            harness.wallet.unwrap()
+        }
+        ScenarioWallet::OffCurve => {
+            let wallet = Pubkey::find_program_address(&[b"scenario"], &Pubkey::new_unique()).0;
+            harness.ensure_account_exists_with_lamports(wallet, 0);
+            wallet
+        }
+        ScenarioWallet::TokenAccount => {
+            harness.create_ata_for_owner(Pubkey::new_unique(), 1_000_000)
+        }
+    };
+    harness.wallet = Some(wallet);
+    if scenario.existing {
+        harness = harness.with_ata();
+    }
+
+    let mint_account = harness.get_account(harness.mint.unwrap());
+    let account_len = expected_ata_len_for_mint(&mint_account);
+    let bump_hint = |bump: bool| {
+        bump.then(|| {
+            spl_associated_token_account_interface::address::get_associated_token_address_and_bump_seed(
+                &wallet,
+                &harness.mint.unwrap(),
+                &spl_associated_token_account_interface::program::id(),
+                &token_program_id,
+            )
+            .1
+        })
+    };
+    let mode = |idempotent: bool| {
+        if idempotent {
+            CreateMode::Idempotent
+        } else {
+            CreateMode::Always
+        }
+    };
+    let (instruction_type, idempotent) = match scenario.instruction {
+        ScenarioInstruction::Create => (CreateAtaInstructionType::Create, false),
+        ScenarioInstruction::CreateIdempotent => (CreateAtaInstructionType::CreateIdempotent, true),
+        ScenarioInstruction::CreateWithArgs {
+            idempotent,
+            bump,
+            account_len: len,
+            rent_sysvar,
+        } => (
+            CreateAtaInstructionType::CreateWithArgs {
+                mode: mode(idempotent),
+                bump: bump_hint(bump),
+                account_len: len.then_some(account_len as u32),
+                rent_sysvar,
+            },
+            idempotent,
+        ),
+        ScenarioInstruction::CreateCompact {
+            idempotent,
+            bump,
+            account_len: len,
+            rent_sysvar,
+        } => (
+            CreateAtaInstructionType::CreateCompact {
+                mode: mode(idempotent),
+                bump: bump_hint(bump),
+                account_len: len.then_some(account_len as u32),
+                rent_sysvar,
+            },
+            idempotent,
+        ),
+    };
+    let is_compact = matches!(
+        instruction_type,
+        CreateAtaInstructionType::CreateCompact { .. }
+    );
+    let mut instruction = harness.build_create_ata_instruction(instruction_type);
+    let ata_address = harness.ata_address.unwrap();
+
+    let token_program_index = if is_compact {
+        CREATE_COMPACT_TOKEN_PROGRAM
+    } else {
+        CREATE_TOKEN_PROGRAM
+    };
+    match scenario.corruption {
+        ScenarioCorruption::None => {}
+        ScenarioCorruption::ReadOnlyPayer => {
+            instruction.accounts[CREATE_FUNDER].is_writable = false
+        }
+        ScenarioCorruption::ReadOnlyAta => {
+            instruction.accounts[CREATE_ASSOCIATED_TOKEN_ACCOUNT].is_writable = false
+        }
+        ScenarioCorruption::WrongAtaAddress => {
+            instruction.accounts[CREATE_ASSOCIATED_TOKEN_ACCOUNT].pubkey = Pubkey::new_unique()
+        }
+        ScenarioCorruption::WrongTokenProgram => {
+            instruction.accounts[token_program_index].pubkey = system_program::id()
+        }
+        ScenarioCorruption::MissingAccounts => instruction.accounts.truncate(token_program_index),
+    }
+
+    let payer_lamports = harness.get_account(harness.payer).lamports;
+    let result = harness.ctx.process_instruction(&instruction);
+
+    let should_succeed = matches!(scenario.corruption, ScenarioCorruption::None)
+        && (idempotent || !scenario.existing);
+    assert_eq!(
+        result.program_result.is_ok(),
+        should_succeed,
+        "{scenario:?}: {:?}",
+        result.program_result
+    );
+    if should_succeed {
+        let account = harness.get_account(ata_address);
+        assert_eq!(account.owner, token_program_id, "{scenario:?}");
+        assert_eq!(account.data.len(), account_len, "{scenario:?}");
+        TokenChecks::account(ata_address)
+            .mint_field(harness.mint.unwrap())
+            .owner_field(wallet)
+            .state(AccountState::Initialized)
+            .assert(&harness);
+    } else {
+        assert_eq!(
+            harness.get_account(harness.payer).lamports,
+            payer_lamports,
+            "{scenario:?}"
+        );
+    }
+}
+
+/// Assert that `compute_units` is within `tolerance` of `expected`, so small
+/// changes pass while regressions and unexpected savings still need the
+/// expectation updated.
+pub fn assert_cu_close_to(compute_units: u64, expected: u64, tolerance: u64) {
+    assert!(
+        compute_units.abs_diff(expected) <= tolerance,
+        "{compute_units} CUs is not within {tolerance} of {expected}"
+    );
+}
+
+/// Every ordering of `actor_a` and `actor_b` that preserves each actor's own order.
+pub fn interleavings<'a, T>(actor_a: &'a [T], actor_b: &'a [T]) -> Vec<Vec<&'a T>> {
+    match (actor_a.split_first(), actor_b.split_first()) {
+        (None, None) => vec![Vec::new()],
+        (Some(_), None) => vec![actor_a.iter().collect()],
+        (None, Some(_)) => vec![actor_b.iter().collect()],
+        (Some((first_a, rest_a)), Some((first_b, rest_b))) => {
+            let mut orderings = Vec::new();
+            for mut ordering in interleavings(rest_a, actor_b) {
+                ordering.insert(0, first_a);
+                orderings.push(ordering);
+            }
+            for mut ordering in interleavings(actor_a, rest_b) {
+                ordering.insert(0, first_b);
+                orderings.push(ordering);
+            }
+            orderings
+        }
+    }
+}
+
+/// An instruction and the accounts passed to it, as shrunk by [`minimize_failure`]
+#[derive(Clone, Debug)]
+pub struct MinimizedFailure {
+    pub instruction: Instruction,
+    pub accounts: Vec<(Pubkey, Account)>,
+}
+
+impl MinimizedFailure {
+    fn drop_unreferenced_accounts(&mut self) {
+        let metas = &self.instruction.accounts;
+        self.accounts
+            .retain(|(address, _)| metas.iter().any(|meta| meta.pubkey == *address));
+    }
+
+    /// Every candidate one step simpler than `self`
+    fn simplifications(&self) -> Vec<Self> {
+        let mut candidates = Vec::new();
+        let mut push = |simplify: &dyn Fn(&mut Self)| {
+            let mut candidate = self.clone();
+            simplify(&mut candidate);
+            candidate.drop_unreferenced_accounts();
+            candidates.push(candidate);
+        };
+
+        for (index, meta) in self.instruction.accounts.iter().enumerate() {
+            push(&|candidate| {
+                candidate.instruction.accounts.remove(index);
+            });
+            if meta.is_signer {
+                push(&|candidate| candidate.instruction.accounts[index].is_signer = false);
+            }
+            if meta.is_writable {
+                push(&|candidate| candidate.instruction.accounts[index].is_writable = false);
+            }
+        }
+        for len in 0..self.instruction.data.len() {
+            push(&|candidate| candidate.instruction.data.truncate(len));
+        }
+        for (index, byte) in self.instruction.data.iter().enumerate() {
+            if *byte != 0 {
+                push(&|candidate| candidate.instruction.data[index] = 0);
+            }
+        }
+        for (index, (_, account)) in self.accounts.iter().enumerate() {
+            if *account != Account::default() {
+                push(&|candidate| candidate.accounts[index].1 = Account::default());
+            }
+            if account.lamports != 0 {
+                push(&|candidate| candidate.accounts[index].1.lamports = 0);
+            }
+            if !account.data.is_empty() {
+                let len = account.data.len().saturating_div(2);
+                push(&|candidate| candidate.accounts[index].1.data.truncate(len));
+            }
+        }
+        candidates
+    }
+}
+
+/// Shrink a failing `instruction` and its `accounts` for as long as `mollusk`
+/// keeps failing them with the same result, turning a randomized or
+/// fuzz-derived failure into a small regression test.
+///
+/// Each step drops an account meta, clears a signer or writable flag,
+/// truncates or zeroes instruction data, or empties, defunds or truncates an
+/// account, until no single step preserves the failure. Accounts no meta
+/// refers to are dropped along the way. The result is processed once more with
+/// `process_and_validate_instruction`, so a run with the `mollusk-svm/fuzz`
+/// feature and `EJECT_FUZZ_FIXTURES` set ejects it as a fixture.
+///
+/// Panics if `instruction` succeeds.
+pub fn minimize_failure(
+    mollusk: &Mollusk,
+    instruction: &Instruction,
+    accounts: &[(Pubkey, Account)],
+) -> MinimizedFailure {
+    let expected = mollusk
+        .process_instruction(instruction, accounts)
+        .program_result;
+    assert!(
+        !expected.is_ok(),
+        "only a failing instruction can be minimized"
+    );
+    let fails_the_same = |candidate: &MinimizedFailure| {
+        mollusk
+            .process_instruction(&candidate.instruction, &candidate.accounts)
+            .program_result
+            == expected
+    };
+
+    let mut minimized = MinimizedFailure {
+        instruction: instruction.clone(),
+        accounts: accounts.to_vec(),
+    };
+    minimized.drop_unreferenced_accounts();
+    // Every step strictly simplifies, so this terminates
+    while let Some(simpler) = minimized
+        .simplifications()
+        .into_iter()
+        .find(|candidate| fails_the_same(candidate))
+    {
+        minimized = simpler;
+    }
+
+    mollusk.process_and_validate_instruction(&minimized.instruction, &minimized.accounts, &[]);
+    minimized
 }
 
 /// Encodes the instruction data payload for ATA creation-related instructions.
@@ -659,6 +1982,22 @@ pub fn encode_create_ata_instruction_data(instruction_type: &CreateAtaInstructio
                 .map(Into::into)
                 .unwrap_or_default(),
         },
+        CreateAtaInstructionType::CreateCompact {
+            mode,
+            bump,
+            account_len,
+            ..
+        } => AssociatedTokenAccountInstruction::CreateCompact {
+            mode: *mode,
+            bump: bump
+                .and_then(BumpSeedHint::new)
+                .map(Into::into)
+                .unwrap_or_default(),
+            account_len: account_len
+                .and_then(AccountLenHint::new)
+                .map(Into::into)
+                .unwrap_or_default(),
+        },
     };
 
     wincode::serialize(&instruction).unwrap()
@@ -682,11 +2021,21 @@ pub fn build_create_ata_instruction(
         AccountMeta::new_readonly(system_program::id(), false),
         AccountMeta::new_readonly(token_program, false),
     ];
+    // `CreateCompact` omits the system program account
+    if matches!(
+        instruction_type,
+        CreateAtaInstructionType::CreateCompact { .. }
+    ) {
+        accounts.remove(4);
+    }
     if matches!(
         &instruction_type,
         CreateAtaInstructionType::CreateWithArgs {
             rent_sysvar: true,
             ..
+        } | CreateAtaInstructionType::CreateCompact {
+            rent_sysvar: true,
+            ..
         }
     ) {
         accounts.push(AccountMeta::new_readonly(
@@ -710,15 +2059,32 @@ pub fn build_recover_nested_instruction(
     nested_token_program_id: &Pubkey,
     multisig_signers: &[&Pubkey],
 ) -> Instruction {
-    let owner_ata =
-        get_associated_token_address_with_program_id(wallet, owner_mint, owner_token_program_id);
-    let destination_ata =
-        get_associated_token_address_with_program_id(wallet, nested_mint, nested_token_program_id);
-    let nested_ata = get_associated_token_address_with_program_id(
-        &owner_ata,
+    recover_nested_instruction(
+        &spl_associated_token_account_interface::program::id(),
+        wallet,
+        owner_mint,
         nested_mint,
+        owner_token_program_id,
         nested_token_program_id,
-    );
+        multisig_signers,
+    )
+}
+
+fn recover_nested_instruction(
+    ata_program_id: &Pubkey,
+    wallet: &Pubkey,
+    owner_mint: &Pubkey,
+    nested_mint: &Pubkey,
+    owner_token_program_id: &Pubkey,
+    nested_token_program_id: &Pubkey,
+    multisig_signers: &[&Pubkey],
+) -> Instruction {
+    let ata = |wallet: &Pubkey, mint: &Pubkey, token_program_id: &Pubkey| {
+        get_associated_token_address_and_bump_seed(wallet, mint, ata_program_id, token_program_id).0
+    };
+    let owner_ata = ata(wallet, owner_mint, owner_token_program_id);
+    let destination_ata = ata(wallet, nested_mint, nested_token_program_id);
+    let nested_ata = ata(&owner_ata, nested_mint, nested_token_program_id);
 
     let mut accounts = vec![
         AccountMeta::new(nested_ata, false),
@@ -743,7 +2109,7 @@ pub fn build_recover_nested_instruction(
     );
 
     Instruction {
-        program_id: spl_associated_token_account_interface::program::id(),
+        program_id: *ata_program_id,
         accounts,
         data: wincode::serialize(&AssociatedTokenAccountInstruction::RecoverNested).unwrap(),
     }
@@ -795,4 +2161,62 @@ impl AccountBuilder {
             mollusk_svm_programs_token::token::create_account_for_token_account(account_data)
         }
     }
+
+    /// Token-2022 account for `mint_account` with the account extensions its
+    /// mint requires, but without `ImmutableOwner`
+    pub fn legacy_token_2022_account(
+        mint: &Pubkey,
+        mint_account: &Account,
+        owner: &Pubkey,
+        amount: u64,
+    ) -> Account {
+        let base = Token2022Account {
+            mint: *mint,
+            owner: *owner,
+            amount,
+            delegate: COption::None,
+            state: AccountState::Initialized,
+            is_native: COption::None,
+            delegated_amount: 0,
+            close_authority: COption::None,
+        };
+        let mint_state = StateWithExtensions::<Token2022Mint>::unpack(&mint_account.data)
+            .expect("Failed to unpack Token-2022 mint");
+        let account_extensions = ExtensionType::get_required_init_account_extensions(
+            &mint_state
+                .get_extension_types()
+                .expect("Failed to read mint extension types"),
+        );
+
+        let data = if account_extensions.is_empty() {
+            let mut data = vec![0; Token2022Account::LEN];
+            Token2022Account::pack(base, &mut data).unwrap();
+            data
+        } else {
+            let account_len =
+                ExtensionType::try_calculate_account_len::<Token2022Account>(&account_extensions)
+                    .expect("Failed to calculate Token-2022 account length");
+            let mut data = vec![0; account_len];
+            let mut state =
+                StateWithExtensionsMut::<Token2022Account>::unpack_uninitialized(&mut data)
+                    .unwrap();
+            for extension_type in account_extensions {
+                state
+                    .init_account_extension_from_type(extension_type)
+                    .unwrap();
+            }
+            state.base = base;
+            state.pack_base();
+            state.init_account_type().unwrap();
+            data
+        };
+
+        Account {
+            lamports: Rent::default().minimum_balance(data.len()),
+            data,
+            owner: spl_token_2022_interface::id(),
+            executable: false,
+            rent_epoch: 0,
+        }
+    }
 }
@@ -0,0 +1,234 @@
+//! Position of each account in the account list of every instruction.
+//!
+//! Optional accounts follow the required ones, so their index is only valid
+//! when the account list is long enough to include them.
+
+/// `Create` and `CreateIdempotent`: account paying for the new account.
+pub const CREATE_FUNDER: usize = 0;
+/// `Create` and `CreateIdempotent`: associated token account to create.
+pub const CREATE_ASSOCIATED_TOKEN_ACCOUNT: usize = 1;
+/// `Create` and `CreateIdempotent`: wallet owning the new account.
+pub const CREATE_WALLET: usize = 2;
+/// `Create` and `CreateIdempotent`: token mint of the new account.
+pub const CREATE_MINT: usize = 3;
+/// `Create` and `CreateIdempotent`: system program.
+pub const CREATE_SYSTEM_PROGRAM: usize = 4;
+/// `Create` and `CreateIdempotent`: token program owning the mint.
+pub const CREATE_TOKEN_PROGRAM: usize = 5;
+
+/// `CreateWithArgs` and `CreateWithBumpLimit`: account paying for the new
+/// account.
+pub const CREATE_WITH_ARGS_FUNDER: usize = CREATE_FUNDER;
+/// `CreateWithArgs` and `CreateWithBumpLimit`: associated token account to
+/// create.
+pub const CREATE_WITH_ARGS_ASSOCIATED_TOKEN_ACCOUNT: usize = CREATE_ASSOCIATED_TOKEN_ACCOUNT;
+/// `CreateWithArgs` and `CreateWithBumpLimit`: wallet owning the new account.
+pub const CREATE_WITH_ARGS_WALLET: usize = CREATE_WALLET;
+/// `CreateWithArgs` and `CreateWithBumpLimit`: token mint of the new account.
+pub const CREATE_WITH_ARGS_MINT: usize = CREATE_MINT;
+/// `CreateWithArgs` and `CreateWithBumpLimit`: system program.
+pub const CREATE_WITH_ARGS_SYSTEM_PROGRAM: usize = CREATE_SYSTEM_PROGRAM;
+/// `CreateWithArgs` and `CreateWithBumpLimit`: token program owning the mint.
+pub const CREATE_WITH_ARGS_TOKEN_PROGRAM: usize = CREATE_TOKEN_PROGRAM;
+/// `CreateWithArgs` and `CreateWithBumpLimit`: optional rent sysvar.
+pub const CREATE_WITH_ARGS_RENT_SYSVAR: usize = 6;
+
+/// `CreateCompact`: account paying for the new account.
+pub const CREATE_COMPACT_FUNDER: usize = CREATE_FUNDER;
+/// `CreateCompact`: associated token account to create.
+pub const CREATE_COMPACT_ASSOCIATED_TOKEN_ACCOUNT: usize = CREATE_ASSOCIATED_TOKEN_ACCOUNT;
+/// `CreateCompact`: wallet owning the new account.
+pub const CREATE_COMPACT_WALLET: usize = CREATE_WALLET;
+/// `CreateCompact`: token mint of the new account.
+pub const CREATE_COMPACT_MINT: usize = CREATE_MINT;
+/// `CreateCompact`: token program owning the mint, in place of the system
+/// program.
+pub const CREATE_COMPACT_TOKEN_PROGRAM: usize = 4;
+/// `CreateCompact`: optional rent sysvar.
+pub const CREATE_COMPACT_RENT_SYSVAR: usize = 5;
+
+/// `RecoverNested`, `RecoverNestedWithArgs` and `RecoverNestedIdempotent`:
+/// nested associated token account to drain and close.
+pub const RECOVER_NESTED_NESTED_ASSOCIATED_TOKEN_ACCOUNT: usize = 0;
+/// `RecoverNested`, `RecoverNestedWithArgs` and `RecoverNestedIdempotent`:
+/// token mint of the nested account.
+pub const RECOVER_NESTED_NESTED_MINT: usize = 1;
+/// `RecoverNested`, `RecoverNestedWithArgs` and `RecoverNestedIdempotent`:
+/// wallet's associated token account for the nested mint.
+pub const RECOVER_NESTED_DESTINATION_ASSOCIATED_TOKEN_ACCOUNT: usize = 2;
+/// `RecoverNested`, `RecoverNestedWithArgs` and `RecoverNestedIdempotent`:
+/// wallet's associated token account owning the nested one.
+pub const RECOVER_NESTED_OWNER_ASSOCIATED_TOKEN_ACCOUNT: usize = 3;
+/// `RecoverNested`, `RecoverNestedWithArgs` and `RecoverNestedIdempotent`:
+/// token mint of the owner account.
+pub const RECOVER_NESTED_OWNER_MINT: usize = 4;
+/// `RecoverNested`, `RecoverNestedWithArgs` and `RecoverNestedIdempotent`:
+/// wallet, a signer unless it is a multisig.
+pub const RECOVER_NESTED_WALLET: usize = 5;
+/// `RecoverNested`, `RecoverNestedWithArgs` and `RecoverNestedIdempotent`:
+/// token program of the owner account.
+pub const RECOVER_NESTED_OWNER_TOKEN_PROGRAM: usize = 6;
+/// `RecoverNested`, `RecoverNestedWithArgs` and `RecoverNestedIdempotent`:
+/// token program of the nested account, optional unless it differs from the
+/// owner token program or the wallet is a multisig.
+pub const RECOVER_NESTED_NESTED_TOKEN_PROGRAM: usize = 7;
+/// `RecoverNested`, `RecoverNestedWithArgs` and `RecoverNestedIdempotent`:
+/// first signer of a multisig wallet, the others following.
+pub const RECOVER_NESTED_MULTISIG_SIGNERS: usize = 8;
+/// `RecoverNested`, `RecoverNestedWithArgs` and `RecoverNestedIdempotent`:
+/// first extra account of the nested mint's transfer hook, the others
+/// following. Comes after the signers of a multisig wallet.
+pub const RECOVER_NESTED_TRANSFER_HOOK_ACCOUNTS: usize = 8;
+
+/// `AssertAtaExists`: associated token account that must exist.
+pub const ASSERT_ATA_EXISTS_ASSOCIATED_TOKEN_ACCOUNT: usize = 0;
+/// `AssertAtaExists`: wallet owning the account.
+pub const ASSERT_ATA_EXISTS_WALLET: usize = 1;
+/// `AssertAtaExists`: token mint of the account.
+pub const ASSERT_ATA_EXISTS_MINT: usize = 2;
+/// `AssertAtaExists`: token program owning the account.
+pub const ASSERT_ATA_EXISTS_TOKEN_PROGRAM: usize = 3;
+
+/// `CreateMany`: account paying for every new account.
+pub const CREATE_MANY_FUNDER: usize = 0;
+/// `CreateMany`: system program.
+pub const CREATE_MANY_SYSTEM_PROGRAM: usize = 1;
+/// `CreateMany`: first account of the first group, one group per account to
+/// create.
+pub const CREATE_MANY_FIRST_GROUP: usize = 2;
+/// `CreateMany`: number of accounts in each group.
+pub const CREATE_MANY_GROUP_LEN: usize = 4;
+/// `CreateMany`: most groups one instruction may pass, so a single
+/// instruction cannot run past a transaction's compute budget whatever the
+/// mints' extensions. More fail with `GroupLimitExceeded`.
+pub const MAX_CREATE_MANY_GROUPS: usize = 16;
+/// `CreateMany`: associated token account to create, within its group.
+pub const CREATE_MANY_GROUP_ASSOCIATED_TOKEN_ACCOUNT: usize = 0;
+/// `CreateMany`: wallet owning the new account, within its group.
+pub const CREATE_MANY_GROUP_WALLET: usize = 1;
+/// `CreateMany`: token mint of the new account, within its group.
+pub const CREATE_MANY_GROUP_MINT: usize = 2;
+/// `CreateMany`: token program owning the mint, within its group.
+pub const CREATE_MANY_GROUP_TOKEN_PROGRAM: usize = 3;
+
+/// `Close`: associated token account to close.
+pub const CLOSE_ASSOCIATED_TOKEN_ACCOUNT: usize = 0;
+/// `Close`: wallet owning the account, receiving its lamports.
+pub const CLOSE_WALLET: usize = 1;
+/// `Close`: token mint of the account.
+pub const CLOSE_MINT: usize = 2;
+/// `Close`: token program owning the account.
+pub const CLOSE_TOKEN_PROGRAM: usize = 3;
+/// `Close`: optional destination of the lamports, the wallet if omitted.
+pub const CLOSE_DESTINATION: usize = 4;
+
+/// `CloseEmpty`: wallet owning every account, receiving their lamports.
+pub const CLOSE_EMPTY_WALLET: usize = 0;
+/// `CloseEmpty`: first account of the first group, one group per account to
+/// close.
+pub const CLOSE_EMPTY_FIRST_GROUP: usize = 1;
+/// `CloseEmpty`: number of accounts in each group.
+pub const CLOSE_EMPTY_GROUP_LEN: usize = 3;
+/// `CloseEmpty`: most groups one instruction may pass, so a single
+/// instruction cannot run past a transaction's compute budget. More fail with
+/// `GroupLimitExceeded`.
+pub const MAX_CLOSE_EMPTY_GROUPS: usize = 16;
+/// `CloseEmpty`: associated token account to close, within its group.
+pub const CLOSE_EMPTY_GROUP_ASSOCIATED_TOKEN_ACCOUNT: usize = 0;
+/// `CloseEmpty`: token mint of the account, within its group.
+pub const CLOSE_EMPTY_GROUP_MINT: usize = 1;
+/// `CloseEmpty`: token program owning the account, within its group.
+pub const CLOSE_EMPTY_GROUP_TOKEN_PROGRAM: usize = 2;
+
+/// `CreateWrapped`: account paying for the new account and the wrapped SOL.
+pub const CREATE_WRAPPED_FUNDER: usize = CREATE_FUNDER;
+/// `CreateWrapped`: associated token account to create and fund.
+pub const CREATE_WRAPPED_ASSOCIATED_TOKEN_ACCOUNT: usize = CREATE_ASSOCIATED_TOKEN_ACCOUNT;
+/// `CreateWrapped`: wallet owning the new account.
+pub const CREATE_WRAPPED_WALLET: usize = CREATE_WALLET;
+/// `CreateWrapped`: native mint of the token program.
+pub const CREATE_WRAPPED_MINT: usize = CREATE_MINT;
+/// `CreateWrapped`: system program.
+pub const CREATE_WRAPPED_SYSTEM_PROGRAM: usize = CREATE_SYSTEM_PROGRAM;
+/// `CreateWrapped`: token program owning the native mint.
+pub const CREATE_WRAPPED_TOKEN_PROGRAM: usize = CREATE_TOKEN_PROGRAM;
+
+/// `CreateWithDeposit`: account paying for the new account, owner of the
+/// source token account.
+pub const CREATE_WITH_DEPOSIT_FUNDER: usize = CREATE_FUNDER;
+/// `CreateWithDeposit`: associated token account to create and deposit into.
+pub const CREATE_WITH_DEPOSIT_ASSOCIATED_TOKEN_ACCOUNT: usize = CREATE_ASSOCIATED_TOKEN_ACCOUNT;
+/// `CreateWithDeposit`: wallet owning the new account.
+pub const CREATE_WITH_DEPOSIT_WALLET: usize = CREATE_WALLET;
+/// `CreateWithDeposit`: token mint of the new account.
+pub const CREATE_WITH_DEPOSIT_MINT: usize = CREATE_MINT;
+/// `CreateWithDeposit`: system program.
+pub const CREATE_WITH_DEPOSIT_SYSTEM_PROGRAM: usize = CREATE_SYSTEM_PROGRAM;
+/// `CreateWithDeposit`: token program owning the mint.
+pub const CREATE_WITH_DEPOSIT_TOKEN_PROGRAM: usize = CREATE_TOKEN_PROGRAM;
+/// `CreateWithDeposit`: funder's associated token account the deposit is
+/// taken from, followed by any transfer hook extra accounts.
+pub const CREATE_WITH_DEPOSIT_SOURCE: usize = 6;
+
+/// `CreateConfidential`: account paying for the new account.
+pub const CREATE_CONFIDENTIAL_FUNDER: usize = CREATE_FUNDER;
+/// `CreateConfidential`: associated token account to create and configure.
+pub const CREATE_CONFIDENTIAL_ASSOCIATED_TOKEN_ACCOUNT: usize = CREATE_ASSOCIATED_TOKEN_ACCOUNT;
+/// `CreateConfidential`: wallet owning the new account, signing the
+/// `ConfigureAccount`.
+pub const CREATE_CONFIDENTIAL_WALLET: usize = CREATE_WALLET;
+/// `CreateConfidential`: Token-2022 mint with the `ConfidentialTransferMint`
+/// extension.
+pub const CREATE_CONFIDENTIAL_MINT: usize = CREATE_MINT;
+/// `CreateConfidential`: system program.
+pub const CREATE_CONFIDENTIAL_SYSTEM_PROGRAM: usize = CREATE_SYSTEM_PROGRAM;
+/// `CreateConfidential`: Token-2022 program.
+pub const CREATE_CONFIDENTIAL_TOKEN_PROGRAM: usize = CREATE_TOKEN_PROGRAM;
+/// `CreateConfidential`: instructions sysvar, or the `PubkeyValidity` proof
+/// context state account.
+pub const CREATE_CONFIDENTIAL_PROOF: usize = 6;
+
+/// `RecoverNestedWithMemo`: nested associated token account to drain and
+/// close.
+pub const RECOVER_NESTED_WITH_MEMO_NESTED_ASSOCIATED_TOKEN_ACCOUNT: usize =
+    RECOVER_NESTED_NESTED_ASSOCIATED_TOKEN_ACCOUNT;
+/// `RecoverNestedWithMemo`: token mint of the nested account.
+pub const RECOVER_NESTED_WITH_MEMO_NESTED_MINT: usize = RECOVER_NESTED_NESTED_MINT;
+/// `RecoverNestedWithMemo`: wallet's associated token account for the nested
+/// mint, which may require transfer memos.
+pub const RECOVER_NESTED_WITH_MEMO_DESTINATION_ASSOCIATED_TOKEN_ACCOUNT: usize =
+    RECOVER_NESTED_DESTINATION_ASSOCIATED_TOKEN_ACCOUNT;
+/// `RecoverNestedWithMemo`: wallet's associated token account owning the
+/// nested one.
+pub const RECOVER_NESTED_WITH_MEMO_OWNER_ASSOCIATED_TOKEN_ACCOUNT: usize =
+    RECOVER_NESTED_OWNER_ASSOCIATED_TOKEN_ACCOUNT;
+/// `RecoverNestedWithMemo`: token mint of the owner account.
+pub const RECOVER_NESTED_WITH_MEMO_OWNER_MINT: usize = RECOVER_NESTED_OWNER_MINT;
+/// `RecoverNestedWithMemo`: wallet, a signer unless it is a multisig.
+pub const RECOVER_NESTED_WITH_MEMO_WALLET: usize = RECOVER_NESTED_WALLET;
+/// `RecoverNestedWithMemo`: token program of the owner account.
+pub const RECOVER_NESTED_WITH_MEMO_OWNER_TOKEN_PROGRAM: usize = RECOVER_NESTED_OWNER_TOKEN_PROGRAM;
+/// `RecoverNestedWithMemo`: token program of the nested account, required
+/// even if it is the owner token program.
+pub const RECOVER_NESTED_WITH_MEMO_NESTED_TOKEN_PROGRAM: usize =
+    RECOVER_NESTED_NESTED_TOKEN_PROGRAM;
+/// `RecoverNestedWithMemo`: SPL Memo program.
+pub const RECOVER_NESTED_WITH_MEMO_MEMO_PROGRAM: usize = 8;
+/// `RecoverNestedWithMemo`: first signer of a multisig wallet, the others
+/// following.
+pub const RECOVER_NESTED_WITH_MEMO_MULTISIG_SIGNERS: usize = 9;
+/// `RecoverNestedWithMemo`: first extra account of the nested mint's transfer
+/// hook, the others following. Comes after the signers of a multisig wallet.
+pub const RECOVER_NESTED_WITH_MEMO_TRANSFER_HOOK_ACCOUNTS: usize = 9;
+
+/// `ReclaimUninitialized`: associated token account address holding the
+/// lamports.
+pub const RECLAIM_UNINITIALIZED_ASSOCIATED_TOKEN_ACCOUNT: usize = CLOSE_ASSOCIATED_TOKEN_ACCOUNT;
+/// `ReclaimUninitialized`: wallet of the address, receiving its lamports.
+pub const RECLAIM_UNINITIALIZED_WALLET: usize = CLOSE_WALLET;
+/// `ReclaimUninitialized`: token mint of the address.
+pub const RECLAIM_UNINITIALIZED_MINT: usize = CLOSE_MINT;
+/// `ReclaimUninitialized`: token program of the address.
+pub const RECLAIM_UNINITIALIZED_TOKEN_PROGRAM: usize = CLOSE_TOKEN_PROGRAM;
+/// `ReclaimUninitialized`: system program.
+pub const RECLAIM_UNINITIALIZED_SYSTEM_PROGRAM: usize = 4;
@@ -0,0 +1,345 @@
+//! Prints a JSON description of the program id, instruction discriminators
+//! and account orderings, for consumption by non-Rust client generators.
+//!
+//! Run with `make generate-client-constants-pinocchio-interface`.
+
+use {
+    pinocchio_associated_token_account_interface::{
+        ID,
+        instruction::{
+            AccountLenHint, AssociatedTokenAccountInstruction, BumpSeedHint, CreateMode,
+            DECRYPTABLE_BALANCE_LEN,
+        },
+    },
+    solana_nullable::{MaybeNull, Nullable},
+    std::fmt::Write,
+};
+
+struct AccountSpec {
+    name: &'static str,
+    writable: bool,
+    signer: bool,
+    optional: bool,
+}
+
+const fn account(name: &'static str, writable: bool, signer: bool) -> AccountSpec {
+    AccountSpec {
+        name,
+        writable,
+        signer,
+        optional: false,
+    }
+}
+
+const fn optional(name: &'static str) -> AccountSpec {
+    AccountSpec {
+        name,
+        writable: false,
+        signer: false,
+        optional: true,
+    }
+}
+
+const CREATE_ACCOUNTS: &[AccountSpec] = &[
+    account("funder", true, true),
+    account("associated_token_account", true, false),
+    account("wallet", false, false),
+    account("mint", false, false),
+    account("system_program", false, false),
+    account("token_program", false, false),
+];
+
+const CREATE_WITH_ARGS_ACCOUNTS: &[AccountSpec] = &[
+    account("funder", true, true),
+    account("associated_token_account", true, false),
+    account("wallet", false, false),
+    account("mint", false, false),
+    account("system_program", false, false),
+    account("token_program", false, false),
+    optional("rent_sysvar"),
+];
+
+const CREATE_COMPACT_ACCOUNTS: &[AccountSpec] = &[
+    account("funder", true, true),
+    account("associated_token_account", true, false),
+    account("wallet", false, false),
+    account("mint", false, false),
+    account("token_program", false, false),
+    optional("rent_sysvar"),
+];
+
+const CREATE_WITH_DEPOSIT_ACCOUNTS: &[AccountSpec] = &[
+    account("funder", true, true),
+    account("associated_token_account", true, false),
+    account("wallet", false, false),
+    account("mint", false, false),
+    account("system_program", false, false),
+    account("token_program", false, false),
+    account("source", true, false),
+];
+
+const CREATE_CONFIDENTIAL_ACCOUNTS: &[AccountSpec] = &[
+    account("funder", true, true),
+    account("associated_token_account", true, false),
+    account("wallet", false, true),
+    account("mint", false, false),
+    account("system_program", false, false),
+    account("token_program", false, false),
+    account("proof", false, false),
+];
+
+const RECOVER_NESTED_ACCOUNTS: &[AccountSpec] = &[
+    account("nested_associated_token_account", true, false),
+    account("nested_mint", false, false),
+    account("destination_associated_token_account", true, false),
+    account("owner_associated_token_account", false, false),
+    account("owner_mint", false, false),
+    // Not a signer when the wallet is a multisig
+    account("wallet", true, true),
+    account("owner_token_program", false, false),
+    optional("nested_token_program"),
+];
+
+const RECOVER_NESTED_WITH_MEMO_ACCOUNTS: &[AccountSpec] = &[
+    account("nested_associated_token_account", true, false),
+    account("nested_mint", false, false),
+    account("destination_associated_token_account", true, false),
+    account("owner_associated_token_account", false, false),
+    account("owner_mint", false, false),
+    // Not a signer when the wallet is a multisig
+    account("wallet", true, true),
+    account("owner_token_program", false, false),
+    account("nested_token_program", false, false),
+    account("memo_program", false, false),
+];
+
+const ASSERT_ATA_EXISTS_ACCOUNTS: &[AccountSpec] = &[
+    account("associated_token_account", false, false),
+    account("wallet", false, false),
+    account("mint", false, false),
+    account("token_program", false, false),
+];
+
+const CLOSE_ACCOUNTS: &[AccountSpec] = &[
+    account("associated_token_account", true, false),
+    account("wallet", true, true),
+    account("mint", false, false),
+    account("token_program", false, false),
+    AccountSpec {
+        optional: true,
+        ..account("destination", true, false)
+    },
+];
+
+const RECLAIM_UNINITIALIZED_ACCOUNTS: &[AccountSpec] = &[
+    account("associated_token_account", true, false),
+    account("wallet", true, true),
+    account("mint", false, false),
+    account("token_program", false, false),
+    account("system_program", false, false),
+];
+
+const CLOSE_EMPTY_ACCOUNTS: &[AccountSpec] = &[account("wallet", true, true)];
+
+/// Repeated after `CLOSE_EMPTY_ACCOUNTS` once per account to close
+const CLOSE_EMPTY_GROUP_ACCOUNTS: &[AccountSpec] = &[
+    account("associated_token_account", true, false),
+    account("mint", false, false),
+    account("token_program", false, false),
+];
+
+const CREATE_MANY_ACCOUNTS: &[AccountSpec] = &[
+    account("funder", true, true),
+    account("system_program", false, false),
+];
+
+/// Repeated after `CREATE_MANY_ACCOUNTS` once per account to create
+const CREATE_MANY_GROUP_ACCOUNTS: &[AccountSpec] = &[
+    account("associated_token_account", true, false),
+    account("wallet", false, false),
+    account("mint", false, false),
+    account("token_program", false, false),
+];
+
+/// One representative of each instruction variant
+fn instructions() -> [AssociatedTokenAccountInstruction; 18] {
+    let bump = MaybeNull::from(BumpSeedHint::NONE);
+    let account_len = MaybeNull::from(AccountLenHint::NONE);
+    [
+        AssociatedTokenAccountInstruction::Create,
+        AssociatedTokenAccountInstruction::CreateIdempotent,
+        AssociatedTokenAccountInstruction::RecoverNested,
+        AssociatedTokenAccountInstruction::CreateWithArgs {
+            mode: CreateMode::Always,
+            bump,
+            account_len,
+        },
+        AssociatedTokenAccountInstruction::CreateCompact {
+            mode: CreateMode::Always,
+            bump,
+            account_len,
+        },
+        AssociatedTokenAccountInstruction::CreateWithBumpLimit {
+            mode: CreateMode::Always,
+            min_bump: 0,
+            account_len,
+        },
+        AssociatedTokenAccountInstruction::AssertAtaExists,
+        AssociatedTokenAccountInstruction::CreateMany {
+            mode: CreateMode::Always,
+        },
+        AssociatedTokenAccountInstruction::RecoverNestedWithArgs {
+            owner_bump: bump,
+            nested_bump: bump,
+            destination_bump: bump,
+        },
+        AssociatedTokenAccountInstruction::RecoverNestedIdempotent,
+        AssociatedTokenAccountInstruction::Close,
+        AssociatedTokenAccountInstruction::CloseEmpty,
+        AssociatedTokenAccountInstruction::CreateWrapped {
+            mode: CreateMode::Always,
+            lamports: 0,
+        },
+        AssociatedTokenAccountInstruction::CreateWithDeposit {
+            mode: CreateMode::Always,
+            amount: 0,
+            decimals: 0,
+        },
+        AssociatedTokenAccountInstruction::CreateConfidential {
+            mode: CreateMode::Always,
+            decryptable_zero_balance: [0; DECRYPTABLE_BALANCE_LEN],
+            maximum_pending_balance_credit_counter: 0,
+            proof_instruction_offset: 0,
+        },
+        AssociatedTokenAccountInstruction::RecoverNestedWithMemo { memo_len: 0 },
+        AssociatedTokenAccountInstruction::ReclaimUninitialized,
+        AssociatedTokenAccountInstruction::GetVersion,
+    ]
+}
+
+/// Name and accounts of `instruction`. The match is exhaustive, so a new
+/// variant does not compile until it is described here.
+fn describe(
+    instruction: &AssociatedTokenAccountInstruction,
+) -> (&'static str, &'static [AccountSpec]) {
+    match instruction {
+        AssociatedTokenAccountInstruction::Create => ("create", CREATE_ACCOUNTS),
+        AssociatedTokenAccountInstruction::CreateIdempotent => {
+            ("create_idempotent", CREATE_ACCOUNTS)
+        }
+        AssociatedTokenAccountInstruction::RecoverNested => {
+            ("recover_nested", RECOVER_NESTED_ACCOUNTS)
+        }
+        AssociatedTokenAccountInstruction::CreateWithArgs { .. } => {
+            ("create_with_args", CREATE_WITH_ARGS_ACCOUNTS)
+        }
+        AssociatedTokenAccountInstruction::CreateCompact { .. } => {
+            ("create_compact", CREATE_COMPACT_ACCOUNTS)
+        }
+        AssociatedTokenAccountInstruction::GetVersion => ("get_version", &[]),
+        AssociatedTokenAccountInstruction::CreateWithBumpLimit { .. } => {
+            ("create_with_bump_limit", CREATE_WITH_ARGS_ACCOUNTS)
+        }
+        AssociatedTokenAccountInstruction::AssertAtaExists => {
+            ("assert_ata_exists", ASSERT_ATA_EXISTS_ACCOUNTS)
+        }
+        AssociatedTokenAccountInstruction::CreateMany { .. } => {
+            ("create_many", CREATE_MANY_ACCOUNTS)
+        }
+        AssociatedTokenAccountInstruction::RecoverNestedWithArgs { .. } => {
+            ("recover_nested_with_args", RECOVER_NESTED_ACCOUNTS)
+        }
+        AssociatedTokenAccountInstruction::RecoverNestedIdempotent => {
+            ("recover_nested_idempotent", RECOVER_NESTED_ACCOUNTS)
+        }
+        AssociatedTokenAccountInstruction::Close => ("close", CLOSE_ACCOUNTS),
+        AssociatedTokenAccountInstruction::CloseEmpty => ("close_empty", CLOSE_EMPTY_ACCOUNTS),
+        AssociatedTokenAccountInstruction::CreateWrapped { .. } => {
+            ("create_wrapped", CREATE_ACCOUNTS)
+        }
+        AssociatedTokenAccountInstruction::CreateWithDeposit { .. } => {
+            ("create_with_deposit", CREATE_WITH_DEPOSIT_ACCOUNTS)
+        }
+        AssociatedTokenAccountInstruction::CreateConfidential { .. } => {
+            ("create_confidential", CREATE_CONFIDENTIAL_ACCOUNTS)
+        }
+        AssociatedTokenAccountInstruction::RecoverNestedWithMemo { .. } => (
+            "recover_nested_with_memo",
+            RECOVER_NESTED_WITH_MEMO_ACCOUNTS,
+        ),
+        AssociatedTokenAccountInstruction::ReclaimUninitialized => {
+            ("reclaim_uninitialized", RECLAIM_UNINITIALIZED_ACCOUNTS)
+        }
+    }
+}
+
+/// Accounts repeated after those of [`describe`], once per item, or none for
+/// instructions with a fixed account list.
+fn repeated_accounts(instruction: &AssociatedTokenAccountInstruction) -> &'static [AccountSpec] {
+    match instruction {
+        AssociatedTokenAccountInstruction::CreateMany { .. } => CREATE_MANY_GROUP_ACCOUNTS,
+        AssociatedTokenAccountInstruction::CloseEmpty => CLOSE_EMPTY_GROUP_ACCOUNTS,
+        _ => &[],
+    }
+}
+
+fn render_accounts(json: &mut String, key: &str, accounts: &[AccountSpec], last: bool) {
+    writeln!(json, "      \"{key}\": [").unwrap();
+    for (j, account) in accounts.iter().enumerate() {
+        let separator = if j.saturating_add(1) < accounts.len() {
+            ","
+        } else {
+            ""
+        };
+        writeln!(
+            json,
+            "        {{ \"name\": \"{}\", \"writable\": {}, \"signer\": {}, \"optional\": {} \
+             }}{separator}",
+            account.name, account.writable, account.signer, account.optional
+        )
+        .unwrap();
+    }
+    writeln!(json, "      ]{}", if last { "" } else { "," }).unwrap();
+}
+
+fn render() -> String {
+    let mut json = String::new();
+    writeln!(json, "{{").unwrap();
+    writeln!(json, "  \"programId\": \"{ID}\",").unwrap();
+    writeln!(json, "  \"instructions\": [").unwrap();
+
+    let instructions = instructions();
+    for (i, instruction) in instructions.iter().enumerate() {
+        let (name, accounts) = describe(instruction);
+        writeln!(json, "    {{").unwrap();
+        writeln!(json, "      \"name\": \"{name}\",").unwrap();
+        writeln!(
+            json,
+            "      \"discriminator\": {},",
+            instruction.discriminator()
+        )
+        .unwrap();
+        writeln!(json, "      \"dataLen\": {},", instruction.data_len()).unwrap();
+        render_accounts(&mut json, "accounts", accounts, false);
+        render_accounts(
+            &mut json,
+            "repeatedAccounts",
+            repeated_accounts(instruction),
+            true,
+        );
+        let separator = if i.saturating_add(1) < instructions.len() {
+            ","
+        } else {
+            ""
+        };
+        writeln!(json, "    }}{separator}").unwrap();
+    }
+
+    writeln!(json, "  ]").unwrap();
+    writeln!(json, "}}").unwrap();
+    json
+}
+
+fn main() {
+    print!("{}", render());
+}
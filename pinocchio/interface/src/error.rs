@@ -16,6 +16,40 @@ pub enum AssociatedTokenAccountError {
         ))
     )]
     InvalidOwner,
+    /// Mint has an extension that this deployment does not create associated
+    /// token accounts for.
+    #[cfg_attr(
+        feature = "codama",
+        codama(error(message = "Mint is rejected by the deployment's mint policy"))
+    )]
+    MintRejectedByPolicy,
+    /// Instruction data is empty, and this deployment does not treat empty
+    /// data as `Create`.
+    #[cfg_attr(
+        feature = "codama",
+        codama(error(message = "Instruction data is empty"))
+    )]
+    EmptyInstructionData,
+    /// No off-curve bump seed at or above the instruction's minimum bump; the
+    /// caller must supply the bump.
+    #[cfg_attr(
+        feature = "codama",
+        codama(error(message = "Bump search limit exceeded, supply the bump instead"))
+    )]
+    BumpSearchLimitExceeded,
+    /// The new account's data length is more than the runtime lets an inner
+    /// instruction allocate.
+    #[cfg_attr(
+        feature = "codama",
+        codama(error(message = "Account data length exceeds the runtime allocation limit"))
+    )]
+    AccountDataLengthExceeded,
+    /// More account groups than a batch instruction accepts.
+    #[cfg_attr(
+        feature = "codama",
+        codama(error(message = "Too many account groups for one instruction"))
+    )]
+    GroupLimitExceeded,
 }
 
 impl From<AssociatedTokenAccountError> for pinocchio::error::ProgramError {
@@ -0,0 +1,211 @@
+//! Lamports a funder needs to create an associated token account.
+//!
+//! Every create instruction funds the new account the same way: it tops the
+//! address up to the rent-exempt minimum for the account length, so lamports
+//! already sent to the address, as with a prefunded account, are deducted.
+
+use pinocchio::{Address, error::ProgramError, sysvars::rent::Rent};
+
+const TOKEN_PROGRAM_ID: Address =
+    Address::from_str_const("TokenkegQfeZyiNwAJbNbGKPFXCWuBvf9Ss623VQ5DA");
+const TOKEN_2022_PROGRAM_ID: Address =
+    Address::from_str_const("TokenzQdBNbLqP5VEhdkAS6EPFLC1PHnBqCXEpPxuEb");
+
+/// Length of an SPL Token or Token-2022 mint without extensions.
+const MINT_BASE_LEN: usize = 82;
+/// Length of an SPL Token or Token-2022 account without extensions.
+const ACCOUNT_BASE_LEN: usize = 165;
+/// Offset of the account type byte in extended Token-2022 accounts.
+const ACCOUNT_TYPE_OFFSET: usize = ACCOUNT_BASE_LEN;
+/// `AccountType::Mint`
+const ACCOUNT_TYPE_MINT: u8 = 1;
+const ACCOUNT_TYPE_LEN: usize = 1;
+const TLV_START: usize = ACCOUNT_TYPE_OFFSET + ACCOUNT_TYPE_LEN;
+const TLV_HEADER_LEN: usize = 4;
+
+/// `ExtensionType` discriminators, see `spl_token_2022_interface::extension::ExtensionType`.
+const EXTENSION_UNINITIALIZED: u16 = 0;
+const EXTENSION_TRANSFER_FEE_CONFIG: u16 = 1;
+const EXTENSION_NON_TRANSFERABLE: u16 = 9;
+const EXTENSION_TRANSFER_HOOK: u16 = 14;
+const EXTENSION_PAUSABLE: u16 = 26;
+
+/// `TransferFeeAmount` value: the withheld amount.
+const TRANSFER_FEE_AMOUNT_LEN: usize = 8;
+/// `TransferHookAccount` value: the transferring flag.
+const TRANSFER_HOOK_ACCOUNT_LEN: usize = 1;
+
+/// Token-2022 account with only the `ImmutableOwner` extension, which every
+/// associated token account has and whose value is empty.
+const TOKEN_2022_BASE_ACCOUNT_LEN: usize = TLV_START + TLV_HEADER_LEN;
+
+/// Data length of the associated token account that a create instruction
+/// without an account length hint allocates for the mint.
+///
+/// SPL Token accounts have a fixed length. Token-2022 accounts add the
+/// account extensions that the mint's extensions require, as
+/// `spl_token_2022_interface::extension::ExtensionType::get_required_init_account_extensions`
+/// lists them.
+///
+/// Fails with `IncorrectProgramId` for any other token program, or with
+/// `InvalidAccountData` if the Token-2022 mint data is not a mint or an
+/// extension entry runs past the end of the data.
+pub fn account_len_for_mint(
+    token_program_id: &Address,
+    mint_data: &[u8],
+) -> Result<usize, ProgramError> {
+    if *token_program_id == TOKEN_PROGRAM_ID {
+        return Ok(ACCOUNT_BASE_LEN);
+    }
+    if *token_program_id != TOKEN_2022_PROGRAM_ID {
+        return Err(ProgramError::IncorrectProgramId);
+    }
+    if mint_data.len() == MINT_BASE_LEN {
+        return Ok(TOKEN_2022_BASE_ACCOUNT_LEN);
+    }
+    if mint_data.get(ACCOUNT_TYPE_OFFSET) != Some(&ACCOUNT_TYPE_MINT) {
+        return Err(ProgramError::InvalidAccountData);
+    }
+
+    let mut account_len = TOKEN_2022_BASE_ACCOUNT_LEN;
+    let mut offset = TLV_START;
+    while let Some(header) = mint_data.get(offset..offset.saturating_add(TLV_HEADER_LEN)) {
+        let extension_type = u16::from_le_bytes([header[0], header[1]]);
+        if extension_type == EXTENSION_UNINITIALIZED {
+            break;
+        }
+        let value_len = u16::from_le_bytes([header[2], header[3]]) as usize;
+        let value_end = offset
+            .saturating_add(TLV_HEADER_LEN)
+            .saturating_add(value_len);
+        if value_end > mint_data.len() {
+            return Err(ProgramError::InvalidAccountData);
+        }
+
+        let account_extension_len = match extension_type {
+            EXTENSION_TRANSFER_FEE_CONFIG => Some(TRANSFER_FEE_AMOUNT_LEN),
+            EXTENSION_NON_TRANSFERABLE | EXTENSION_PAUSABLE => Some(0),
+            EXTENSION_TRANSFER_HOOK => Some(TRANSFER_HOOK_ACCOUNT_LEN),
+            _ => None,
+        };
+        if let Some(len) = account_extension_len {
+            account_len = account_len
+                .saturating_add(TLV_HEADER_LEN)
+                .saturating_add(len);
+        }
+        offset = value_end;
+    }
+    Ok(account_len)
+}
+
+/// Lamports the funder transfers to create the associated token account of
+/// the mint with any create instruction, given the lamports the account
+/// address already holds.
+///
+/// The instruction tops `ata_lamports` up to the rent-exempt minimum of
+/// [`account_len_for_mint`], so this is that minimum less what the address
+/// already holds, and `0` once it holds enough. The funder's balance must
+/// cover this amount on top of the transaction fee. A `CreateIdempotent` of
+/// an existing account transfers nothing, and a caller-supplied account
+/// length hint replaces the derived length.
+pub fn lamports_required_for_create(
+    token_program_id: &Address,
+    mint_data: &[u8],
+    rent: &Rent,
+    ata_lamports: u64,
+) -> Result<u64, ProgramError> {
+    let account_len = account_len_for_mint(token_program_id, mint_data)?;
+    Ok(rent
+        .minimum_balance(account_len)
+        .saturating_sub(ata_lamports))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const DATA_LEN: usize = 512;
+
+    /// Default cluster rent: 3480 lamports per byte-year, twice as much for
+    /// exemption, 50% burned.
+    fn default_rent_bytes() -> [u8; 17] {
+        let mut bytes = [0; 17];
+        bytes[..8].copy_from_slice(&3480u64.to_le_bytes());
+        bytes[8..16].copy_from_slice(&2.0f64.to_le_bytes());
+        bytes[16] = 50;
+        bytes
+    }
+
+    /// Extended mint data with `(extension_type, value_len)` TLV entries.
+    fn mint_data(extensions: &[(u16, u16)]) -> ([u8; DATA_LEN], usize) {
+        let mut data = [0; DATA_LEN];
+        data[ACCOUNT_TYPE_OFFSET] = ACCOUNT_TYPE_MINT;
+        let mut offset = TLV_START;
+        for (extension_type, value_len) in extensions {
+            let len_offset = offset.saturating_add(2);
+            let value_start = offset.saturating_add(TLV_HEADER_LEN);
+            data[offset..len_offset].copy_from_slice(&extension_type.to_le_bytes());
+            data[len_offset..value_start].copy_from_slice(&value_len.to_le_bytes());
+            offset = value_start.saturating_add(*value_len as usize);
+        }
+        (data, offset)
+    }
+
+    #[test]
+    fn base_mint_account_lens() {
+        let mint = [0; MINT_BASE_LEN];
+        assert_eq!(account_len_for_mint(&TOKEN_PROGRAM_ID, &mint), Ok(165));
+        assert_eq!(account_len_for_mint(&TOKEN_2022_PROGRAM_ID, &mint), Ok(170));
+        assert_eq!(
+            account_len_for_mint(&Address::new_from_array([7; 32]), &mint),
+            Err(ProgramError::IncorrectProgramId)
+        );
+    }
+
+    #[test]
+    fn adds_required_account_extensions() {
+        // TransferFeeConfig (1), MetadataPointer (18), TransferHook (14),
+        // NonTransferable (9), Pausable (26)
+        let (data, len) = mint_data(&[(1, 108), (18, 64), (14, 64), (9, 0), (26, 33)]);
+
+        // 170 with ImmutableOwner, plus the TransferFeeAmount (4 + 8),
+        // TransferHookAccount (4 + 1), NonTransferableAccount (4) and
+        // PausableAccount (4) entries
+        assert_eq!(
+            account_len_for_mint(&TOKEN_2022_PROGRAM_ID, &data[..len]),
+            Ok(195)
+        );
+        // Trailing zeroed space reads as an uninitialized entry
+        assert_eq!(account_len_for_mint(&TOKEN_2022_PROGRAM_ID, &data), Ok(195));
+    }
+
+    #[test]
+    fn rejects_malformed_mint_data() {
+        let (mut data, len) = mint_data(&[(1, 108)]);
+        assert_eq!(
+            account_len_for_mint(&TOKEN_2022_PROGRAM_ID, &data[..len.saturating_sub(1)]),
+            Err(ProgramError::InvalidAccountData)
+        );
+        data[ACCOUNT_TYPE_OFFSET] = 2;
+        assert_eq!(
+            account_len_for_mint(&TOKEN_2022_PROGRAM_ID, &data[..len]),
+            Err(ProgramError::InvalidAccountData)
+        );
+    }
+
+    #[test]
+    fn deducts_lamports_already_at_the_address() {
+        let rent_bytes = default_rent_bytes();
+        let rent = Rent::from_bytes(&rent_bytes).unwrap();
+        let mint = [0; MINT_BASE_LEN];
+        let required = |token_program_id, ata_lamports| {
+            lamports_required_for_create(token_program_id, &mint, rent, ata_lamports).unwrap()
+        };
+
+        assert_eq!(required(&TOKEN_PROGRAM_ID, 0), 2_039_280);
+        assert_eq!(required(&TOKEN_2022_PROGRAM_ID, 0), 2_074_080);
+        assert_eq!(required(&TOKEN_PROGRAM_ID, 1_000_000), 1_039_280);
+        assert_eq!(required(&TOKEN_PROGRAM_ID, 2_039_280), 0);
+        assert_eq!(required(&TOKEN_PROGRAM_ID, u64::MAX), 0);
+    }
+}
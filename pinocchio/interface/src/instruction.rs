@@ -3,6 +3,9 @@
 #[cfg(feature = "codama")]
 use codama_macros::{CodamaInstructions, CodamaType};
 use {
+    crate::account_indices::{
+        RECOVER_NESTED_WITH_MEMO_MULTISIG_SIGNERS, RECOVER_NESTED_WITH_MEMO_NESTED_TOKEN_PROGRAM,
+    },
     pinocchio::error::ProgramError,
     solana_nullable::{MaybeNull, Nullable},
     solana_zero_copy::unaligned::U32,
@@ -10,6 +13,13 @@ use {
 };
 
 /// Instructions supported by the `AssociatedTokenAccount` program
+///
+/// Each variant's wincode tag is its discriminator, given explicitly since
+/// `GetVersion` sits apart from the others.
+///
+/// Discriminator 5 is reserved and fails to decode. `GetVersion` used it
+/// before moving to 255, so it is never reassigned: data encoded for that
+/// layout is rejected instead of running another instruction.
 #[repr(u8)]
 #[derive(Clone, Copy, Debug, PartialEq, SchemaRead, SchemaWrite)]
 #[wincode(tag_encoding = "u8")]
@@ -21,7 +31,570 @@ pub enum AssociatedTokenAccountInstruction {
     ///   0. `[writeable,signer]` Funding account (must be a system account)
     ///   1. `[writeable]` Associated token account address to be created
     ///   2. `[]` Wallet address for the new associated token account
-    ///   3. `[]` The token mint for the new associated token account
+    ///   3. `[]` The token mint for the new associated token account
+    ///   4. `[]` System program
+    ///   5. `[]` SPL Token program
+    #[cfg_attr(
+        feature = "codama",
+        codama(account(
+            name = "funder",
+            signer,
+            writable,
+            docs = "Funding account (must be a system account)"
+        )),
+        codama(account(
+            name = "associated_token_account",
+            writable,
+            docs = "Associated token account address to be created"
+        )),
+        codama(account(name = "wallet", docs = "Wallet address for the new associated token account")),
+        codama(account(name = "mint", docs = "The token mint for the new associated token account")),
+        codama(account(
+            name = "system_program",
+            docs = "System program",
+            default_value = program("system")
+        )),
+        codama(account(name = "token_program", docs = "SPL Token program"))
+    )]
+    #[wincode(tag = 0)]
+    Create,
+    /// Creates an associated token account for the given wallet address and
+    /// token mint, if it doesn't already exist.  Returns an error if the
+    /// account exists, but with a different owner.
+    ///
+    ///   0. `[writeable,signer]` Funding account (must be a system account)
+    ///   1. `[writeable]` Associated token account address to be created
+    ///   2. `[]` Wallet address for the new associated token account
+    ///   3. `[]` The token mint for the new associated token account
+    ///   4. `[]` System program
+    ///   5. `[]` SPL Token program
+    #[cfg_attr(
+        feature = "codama",
+        codama(account(
+            name = "funder",
+            signer,
+            writable,
+            docs = "Funding account (must be a system account)"
+        )),
+        codama(account(
+            name = "associated_token_account",
+            writable,
+            docs = "Associated token account address to be created"
+        )),
+        codama(account(name = "wallet", docs = "Wallet address for the new associated token account")),
+        codama(account(name = "mint", docs = "The token mint for the new associated token account")),
+        codama(account(
+            name = "system_program",
+            docs = "System program",
+            default_value = program("system")
+        )),
+        codama(account(name = "token_program", docs = "SPL Token program"))
+    )]
+    #[wincode(tag = 1)]
+    CreateIdempotent,
+    /// Transfers from and closes a nested associated token account: an
+    /// associated token account owned by an associated token account.
+    ///
+    /// The tokens are moved from the nested associated token account to the
+    /// wallet's associated token account, and the nested account lamports are
+    /// moved to the wallet.
+    ///
+    /// Note: Nested token accounts are an anti-pattern, and almost always
+    /// created unintentionally, so this instruction should only be used to
+    /// recover from errors.
+    ///
+    /// Accounts expected by this instruction:
+    ///
+    ///   * Single owner
+    ///   0. `[writeable]` Nested associated token account, must be owned by `3`
+    ///   1. `[]` Token mint for the nested associated token account
+    ///   2. `[writeable]` Wallet's associated token account
+    ///   3. `[]` Owner associated token account address, must be owned by `5`
+    ///   4. `[]` Token mint for the owner associated token account
+    ///   5. `[writeable, signer]` Wallet address for the owner associated token
+    ///      account
+    ///   6. `[]` Token program for the owner mint
+    ///   7. `[]` Optional token program for the nested mint, if different from
+    ///      the owner mint's token program. Required when passing `8..`
+    ///   8. `..` Extra accounts of the nested mint's transfer hook, if it has
+    ///      one
+    ///
+    ///   * Multisignature owner
+    ///   0. `[writeable]` Nested associated token account, must be owned by `3`
+    ///   1. `[]` Token mint for the nested associated token account
+    ///   2. `[writeable]` Wallet's associated token account
+    ///   3. `[]` Owner associated token account address, must be owned by `5`
+    ///   4. `[]` Token mint for the owner associated token account
+    ///   5. `[writeable]` Multisignature wallet address for the owner associated
+    ///      token account
+    ///   6. `[]` Token program for the owner mint
+    ///   7. `[]` Token program for the nested mint
+    ///   8. `..+M` `[signer]` M multisig signer accounts that authorize the
+    ///      wallet
+    ///   8+M. `..` Extra accounts of the nested mint's transfer hook, if it has
+    ///      one
+    ///
+    /// Every account after `7` is passed on to the nested token program's
+    /// `TransferChecked`, without signer privileges, for the transfer hook to
+    /// pick its extra accounts from.
+    #[cfg_attr(
+        feature = "codama",
+        codama(optional_account_strategy = omitted),
+        codama(account(
+            name = "nested_associated_token_account",
+            writable,
+            docs = "Nested associated token account, must be owned by \
+                    `owner_associated_token_account`"
+        )),
+        codama(account(
+            name = "nested_mint",
+            docs = "Token mint for the nested associated token account"
+        )),
+        codama(account(
+            name = "destination_associated_token_account",
+            writable,
+            docs = "Wallet's associated token account"
+        )),
+        codama(account(
+            name = "owner_associated_token_account",
+            docs = "Owner associated token account address, must be owned by `wallet`"
+        )),
+        codama(account(
+            name = "owner_mint",
+            docs = "Token mint for the owner associated token account"
+        )),
+        codama(account(
+            name = "wallet",
+            signer = "either",
+            writable,
+            docs = "Wallet address for the owner associated token account. If multisig, not a signer."
+        )),
+        codama(account(
+            name = "owner_token_program",
+            docs = "Token program for the owner mint"
+        )),
+        codama(account(
+            name = "nested_token_program",
+            optional,
+            docs = "Optional token program for the nested mint, if different from the owner \
+                    mint's token program. Required when the wallet is a multisig or transfer \
+                    hook accounts follow."
+        ))
+    )]
+    #[wincode(tag = 2)]
+    RecoverNested,
+    /// Creates an associated token account for the given wallet address and
+    /// token mint. Accepts optional optimization arguments to lower CU usage.
+    ///
+    ///   0. `[writeable,signer]` Funding account (must be a system account)
+    ///   1. `[writeable]` Associated token account address to be created
+    ///   2. `[]` Wallet address for the new associated token account
+    ///   3. `[]` The token mint for the new associated token account
+    ///   4. `[]` System program
+    ///   5. `[]` SPL Token program
+    ///   6. `[]` Optional rent sysvar
+    #[cfg_attr(
+        feature = "codama",
+        codama(optional_account_strategy = omitted),
+        codama(account(
+            name = "funder",
+            signer,
+            writable,
+            docs = "Funding account (must be a system account)"
+        )),
+        codama(account(
+            name = "associated_token_account",
+            writable,
+            docs = "Associated token account address to be created"
+        )),
+        codama(account(name = "wallet", docs = "Wallet address for the new associated token account")),
+        codama(account(name = "mint", docs = "The token mint for the new associated token account")),
+        codama(account(
+            name = "system_program",
+            docs = "System program",
+            default_value = program("system")
+        )),
+        codama(account(name = "token_program", docs = "SPL Token program")),
+        codama(account(
+            name = "rent_sysvar",
+            optional,
+            default_value = sysvar("rent"),
+            docs = "Optional rent sysvar"
+        ))
+    )]
+    #[wincode(tag = 3)]
+    CreateWithArgs {
+        /// Selects whether behaves like `Create` or `CreateIdempotent`.
+        mode: CreateMode,
+        /// The ATA PDA bump seed.
+        #[cfg_attr(feature = "codama", codama(type = number(u8)))]
+        bump: MaybeNull<BumpSeedHint>,
+        /// The account data length for the new ATA.
+        #[cfg_attr(feature = "codama", codama(type = number(u32)))]
+        account_len: MaybeNull<AccountLenHint>,
+    },
+    /// Creates an associated token account for the given wallet address and
+    /// token mint, like `CreateWithArgs`, but without the system program in the
+    /// account list.
+    ///
+    /// The runtime resolves the system program from the transaction account
+    /// keys, so it must still be referenced elsewhere in the transaction. Batch
+    /// creators that already include it save one account index per
+    /// instruction.
+    ///
+    ///   0. `[writeable,signer]` Funding account (must be a system account)
+    ///   1. `[writeable]` Associated token account address to be created
+    ///   2. `[]` Wallet address for the new associated token account
+    ///   3. `[]` The token mint for the new associated token account
+    ///   4. `[]` SPL Token program
+    ///   5. `[]` Optional rent sysvar
+    #[cfg_attr(
+        feature = "codama",
+        codama(optional_account_strategy = omitted),
+        codama(account(
+            name = "funder",
+            signer,
+            writable,
+            docs = "Funding account (must be a system account)"
+        )),
+        codama(account(
+            name = "associated_token_account",
+            writable,
+            docs = "Associated token account address to be created"
+        )),
+        codama(account(name = "wallet", docs = "Wallet address for the new associated token account")),
+        codama(account(name = "mint", docs = "The token mint for the new associated token account")),
+        codama(account(name = "token_program", docs = "SPL Token program")),
+        codama(account(
+            name = "rent_sysvar",
+            optional,
+            default_value = sysvar("rent"),
+            docs = "Optional rent sysvar"
+        ))
+    )]
+    #[wincode(tag = 4)]
+    CreateCompact {
+        /// Selects whether behaves like `Create` or `CreateIdempotent`.
+        mode: CreateMode,
+        /// The ATA PDA bump seed.
+        #[cfg_attr(feature = "codama", codama(type = number(u8)))]
+        bump: MaybeNull<BumpSeedHint>,
+        /// The account data length for the new ATA.
+        #[cfg_attr(feature = "codama", codama(type = number(u32)))]
+        account_len: MaybeNull<AccountLenHint>,
+    },
+    /// Creates an associated token account for the given wallet address and
+    /// token mint, like `CreateWithArgs` without a bump, but searches for the
+    /// bump seed no lower than `min_bump`.
+    ///
+    /// The cost of the bump search depends on the wallet and mint, one hash per
+    /// bump tried. Capping it bounds the worst case: if the canonical bump is
+    /// below `min_bump`, fails with `BumpSearchLimitExceeded`, and the caller
+    /// must supply the bump through `CreateWithArgs` instead.
+    ///
+    ///   0. `[writeable,signer]` Funding account (must be a system account)
+    ///   1. `[writeable]` Associated token account address to be created
+    ///   2. `[]` Wallet address for the new associated token account
+    ///   3. `[]` The token mint for the new associated token account
+    ///   4. `[]` System program
+    ///   5. `[]` SPL Token program
+    ///   6. `[]` Optional rent sysvar
+    #[cfg_attr(
+        feature = "codama",
+        codama(optional_account_strategy = omitted),
+        codama(account(
+            name = "funder",
+            signer,
+            writable,
+            docs = "Funding account (must be a system account)"
+        )),
+        codama(account(
+            name = "associated_token_account",
+            writable,
+            docs = "Associated token account address to be created"
+        )),
+        codama(account(name = "wallet", docs = "Wallet address for the new associated token account")),
+        codama(account(name = "mint", docs = "The token mint for the new associated token account")),
+        codama(account(
+            name = "system_program",
+            docs = "System program",
+            default_value = program("system")
+        )),
+        codama(account(name = "token_program", docs = "SPL Token program")),
+        codama(account(
+            name = "rent_sysvar",
+            optional,
+            default_value = sysvar("rent"),
+            docs = "Optional rent sysvar"
+        ))
+    )]
+    #[wincode(tag = 6)]
+    CreateWithBumpLimit {
+        /// Selects whether behaves like `Create` or `CreateIdempotent`.
+        mode: CreateMode,
+        /// The lowest bump seed to try.
+        min_bump: u8,
+        /// The account data length for the new ATA.
+        #[cfg_attr(feature = "codama", codama(type = number(u32)))]
+        account_len: MaybeNull<AccountLenHint>,
+    },
+    /// Succeeds only if the given account is the initialized associated token
+    /// account of the wallet and mint, without modifying any account.
+    ///
+    /// Lets a transaction guard later instructions that assume the account
+    /// exists, even when it is sent without simulation.
+    ///
+    ///   0. `[]` Associated token account address
+    ///   1. `[]` Wallet address of the associated token account
+    ///   2. `[]` The token mint of the associated token account
+    ///   3. `[]` SPL Token program
+    #[cfg_attr(
+        feature = "codama",
+        codama(account(
+            name = "associated_token_account",
+            docs = "Associated token account address"
+        )),
+        codama(account(
+            name = "wallet",
+            docs = "Wallet address of the associated token account"
+        )),
+        codama(account(name = "mint", docs = "The token mint of the associated token account")),
+        codama(account(name = "token_program", docs = "SPL Token program"))
+    )]
+    #[wincode(tag = 7)]
+    AssertAtaExists,
+    /// Creates the associated token account of each of several wallet and
+    /// mint pairs, like `Create` or `CreateIdempotent` depending on `mode`.
+    ///
+    /// Each account is created as if by its own instruction, searching for
+    /// the bump seed and sizing the account from the mint, and any failure
+    /// fails them all. The pairs may use different token programs.
+    ///
+    ///   0. `[writeable,signer]` Funding account (must be a system account)
+    ///   1. `[]` System program
+    ///
+    /// Followed by one group of four accounts per associated token account,
+    /// at least one and at most `MAX_CREATE_MANY_GROUPS`:
+    ///
+    ///   0. `[writeable]` Associated token account address to be created
+    ///   1. `[]` Wallet address for the new associated token account
+    ///   2. `[]` The token mint for the new associated token account
+    ///   3. `[]` SPL Token program
+    #[cfg_attr(
+        feature = "codama",
+        codama(account(
+            name = "funder",
+            signer,
+            writable,
+            docs = "Funding account (must be a system account)"
+        )),
+        codama(account(
+            name = "system_program",
+            docs = "System program",
+            default_value = program("system")
+        ))
+    )]
+    #[wincode(tag = 8)]
+    CreateMany {
+        /// Selects whether behaves like `Create` or `CreateIdempotent`.
+        mode: CreateMode,
+    },
+    /// Recovers nested tokens like `RecoverNested`, with the same accounts,
+    /// but takes the bump seeds of the three associated token accounts
+    /// instead of searching for them.
+    ///
+    /// The owner bump signs the transfer and close, so it must be canonical:
+    /// every higher bump is derived and must be on-curve, or the instruction
+    /// fails with `InvalidSeeds`. The nested and destination hints are checked
+    /// with a single derivation each. A null hint falls back to the search.
+    #[cfg_attr(
+        feature = "codama",
+        codama(optional_account_strategy = omitted),
+        codama(account(
+            name = "nested_associated_token_account",
+            writable,
+            docs = "Nested associated token account, must be owned by \
+                    `owner_associated_token_account`"
+        )),
+        codama(account(
+            name = "nested_mint",
+            docs = "Token mint for the nested associated token account"
+        )),
+        codama(account(
+            name = "destination_associated_token_account",
+            writable,
+            docs = "Wallet's associated token account"
+        )),
+        codama(account(
+            name = "owner_associated_token_account",
+            docs = "Owner associated token account address, must be owned by `wallet`"
+        )),
+        codama(account(
+            name = "owner_mint",
+            docs = "Token mint for the owner associated token account"
+        )),
+        codama(account(
+            name = "wallet",
+            signer = "either",
+            writable,
+            docs = "Wallet address for the owner associated token account. If multisig, not a signer."
+        )),
+        codama(account(
+            name = "owner_token_program",
+            docs = "Token program for the owner mint"
+        )),
+        codama(account(
+            name = "nested_token_program",
+            optional,
+            docs = "Optional token program for the nested mint, if different from the owner \
+                    mint's token program. Required when the wallet is a multisig or transfer \
+                    hook accounts follow."
+        ))
+    )]
+    #[wincode(tag = 9)]
+    RecoverNestedWithArgs {
+        /// The bump seed of the owner associated token account.
+        #[cfg_attr(feature = "codama", codama(type = number(u8)))]
+        owner_bump: MaybeNull<BumpSeedHint>,
+        /// The bump seed of the nested associated token account.
+        #[cfg_attr(feature = "codama", codama(type = number(u8)))]
+        nested_bump: MaybeNull<BumpSeedHint>,
+        /// The bump seed of the destination associated token account.
+        #[cfg_attr(feature = "codama", codama(type = number(u8)))]
+        destination_bump: MaybeNull<BumpSeedHint>,
+    },
+    /// Recovers nested tokens like `RecoverNested`, with the same accounts,
+    /// but succeeds without modifying any account if the nested associated
+    /// token account does not exist or holds no tokens.
+    ///
+    /// Lets wallets include recovery in cleanup transactions without checking
+    /// for a nested account first. The addresses and the wallet's signature
+    /// are still checked.
+    #[cfg_attr(
+        feature = "codama",
+        codama(optional_account_strategy = omitted),
+        codama(account(
+            name = "nested_associated_token_account",
+            writable,
+            docs = "Nested associated token account, must be owned by \
+                    `owner_associated_token_account`"
+        )),
+        codama(account(
+            name = "nested_mint",
+            docs = "Token mint for the nested associated token account"
+        )),
+        codama(account(
+            name = "destination_associated_token_account",
+            writable,
+            docs = "Wallet's associated token account"
+        )),
+        codama(account(
+            name = "owner_associated_token_account",
+            docs = "Owner associated token account address, must be owned by `wallet`"
+        )),
+        codama(account(
+            name = "owner_mint",
+            docs = "Token mint for the owner associated token account"
+        )),
+        codama(account(
+            name = "wallet",
+            signer = "either",
+            writable,
+            docs = "Wallet address for the owner associated token account. If multisig, not a signer."
+        )),
+        codama(account(
+            name = "owner_token_program",
+            docs = "Token program for the owner mint"
+        )),
+        codama(account(
+            name = "nested_token_program",
+            optional,
+            docs = "Optional token program for the nested mint, if different from the owner \
+                    mint's token program. Required when the wallet is a multisig or transfer \
+                    hook accounts follow."
+        ))
+    )]
+    #[wincode(tag = 10)]
+    RecoverNestedIdempotent,
+    /// Closes the wallet's associated token account for the mint with the
+    /// token program's `CloseAccount`, returning its lamports to the wallet,
+    /// or to the destination if one is given.
+    ///
+    /// The wallet signs as the owner of the account, which also authorizes
+    /// the destination. The token program rejects an account that still holds
+    /// tokens, except a native one, whose wrapped lamports are returned along
+    /// with the rent.
+    ///
+    ///   0. `[writeable]` Associated token account address to be closed
+    ///   1. `[writeable,signer]` Wallet address of the associated token account
+    ///   2. `[]` The token mint of the associated token account
+    ///   3. `[]` SPL Token program
+    ///   4. `[writeable]` Optional destination of the lamports, if not the
+    ///      wallet. Cannot be the account being closed.
+    #[cfg_attr(
+        feature = "codama",
+        codama(account(
+            name = "associated_token_account",
+            writable,
+            docs = "Associated token account address to be closed"
+        )),
+        codama(account(
+            name = "wallet",
+            signer,
+            writable,
+            docs = "Wallet address of the associated token account"
+        )),
+        codama(account(name = "mint", docs = "The token mint of the associated token account")),
+        codama(account(name = "token_program", docs = "SPL Token program")),
+        codama(account(
+            name = "destination",
+            optional,
+            writable,
+            docs = "Optional destination of the lamports, if not the wallet. Cannot be the \
+                    account being closed."
+        ))
+    )]
+    #[wincode(tag = 11)]
+    Close,
+    /// Closes every empty associated token account of the wallet among the
+    /// given ones, like `Close` for each, returning their lamports to the
+    /// wallet.
+    ///
+    /// Every given account must be the wallet's associated token account for
+    /// its mint. Those that do not exist or still hold tokens are skipped.
+    ///
+    ///   0. `[writeable,signer]` Wallet address of the associated token
+    ///      accounts
+    ///
+    /// Followed by one group of three accounts per associated token account,
+    /// at least one and at most `MAX_CLOSE_EMPTY_GROUPS`:
+    ///
+    ///   0. `[writeable]` Associated token account address to be closed
+    ///   1. `[]` The token mint of the associated token account
+    ///   2. `[]` SPL Token program
+    #[cfg_attr(
+        feature = "codama",
+        codama(account(
+            name = "wallet",
+            signer,
+            writable,
+            docs = "Wallet address of the associated token accounts"
+        ))
+    )]
+    #[wincode(tag = 12)]
+    CloseEmpty,
+    /// Creates the associated token account of a native mint like `Create`
+    /// or `CreateIdempotent`, depending on `mode`, then wraps `lamports` into
+    /// it: moves them from the funding account and syncs the token amount
+    /// with the token program's `SyncNative`.
+    ///
+    /// Fails for any mint other than the token program's native mint. With
+    /// `CreateMode::Idempotent`, an existing account is topped up.
+    ///
+    ///   0. `[writeable,signer]` Funding account (must be a system account)
+    ///   1. `[writeable]` Associated token account address to be created
+    ///   2. `[]` Wallet address for the new associated token account
+    ///   3. `[]` The native mint of the token program
     ///   4. `[]` System program
     ///   5. `[]` SPL Token program
     #[cfg_attr(
@@ -38,7 +611,7 @@ pub enum AssociatedTokenAccountInstruction {
             docs = "Associated token account address to be created"
         )),
         codama(account(name = "wallet", docs = "Wallet address for the new associated token account")),
-        codama(account(name = "mint", docs = "The token mint for the new associated token account")),
+        codama(account(name = "mint", docs = "The native mint of the token program")),
         codama(account(
             name = "system_program",
             docs = "System program",
@@ -46,24 +619,39 @@ pub enum AssociatedTokenAccountInstruction {
         )),
         codama(account(name = "token_program", docs = "SPL Token program"))
     )]
-    Create,
-    /// Creates an associated token account for the given wallet address and
-    /// token mint, if it doesn't already exist.  Returns an error if the
-    /// account exists, but with a different owner.
+    #[wincode(tag = 13)]
+    CreateWrapped {
+        /// Selects whether behaves like `Create` or `CreateIdempotent`.
+        mode: CreateMode,
+        /// Lamports to wrap into the account, on top of its rent.
+        lamports: u64,
+    },
+    /// Creates the associated token account like `Create` or
+    /// `CreateIdempotent`, depending on `mode`, then deposits `amount` tokens
+    /// into it with a `TransferChecked` from the funder's associated token
+    /// account for the mint, signed by the funder.
     ///
-    ///   0. `[writeable,signer]` Funding account (must be a system account)
+    /// With `CreateMode::Idempotent`, an existing account receives the
+    /// deposit. For mints with a transfer hook, the extra accounts the hook
+    /// requires are passed after the source account and forwarded to the
+    /// transfer.
+    ///
+    ///   0. `[writeable,signer]` Funding account (must be a system account),
+    ///      owner of the source token account
     ///   1. `[writeable]` Associated token account address to be created
     ///   2. `[]` Wallet address for the new associated token account
     ///   3. `[]` The token mint for the new associated token account
     ///   4. `[]` System program
     ///   5. `[]` SPL Token program
+    ///   6. `[writeable]` Funder's associated token account for the mint
+    ///   7. `..` Extra accounts of the mint's transfer hook, if it has one
     #[cfg_attr(
         feature = "codama",
         codama(account(
             name = "funder",
             signer,
             writable,
-            docs = "Funding account (must be a system account)"
+            docs = "Funding account (must be a system account), owner of the source token account"
         )),
         codama(account(
             name = "associated_token_account",
@@ -77,49 +665,110 @@ pub enum AssociatedTokenAccountInstruction {
             docs = "System program",
             default_value = program("system")
         )),
-        codama(account(name = "token_program", docs = "SPL Token program"))
+        codama(account(name = "token_program", docs = "SPL Token program")),
+        codama(account(
+            name = "source",
+            writable,
+            docs = "Funder's associated token account for the mint"
+        ))
     )]
-    CreateIdempotent,
-    /// Transfers from and closes a nested associated token account: an
-    /// associated token account owned by an associated token account.
+    #[wincode(tag = 14)]
+    CreateWithDeposit {
+        /// Selects whether behaves like `Create` or `CreateIdempotent`.
+        mode: CreateMode,
+        /// Tokens to deposit into the account.
+        amount: u64,
+        /// Decimals of the mint, checked by `TransferChecked`.
+        decimals: u8,
+    },
+    /// Creates the associated token account of a Token-2022 mint with the
+    /// `ConfidentialTransferMint` extension like `Create` or
+    /// `CreateIdempotent`, depending on `mode`, then configures it for
+    /// confidential transfers with the token program's `ConfigureAccount`.
     ///
-    /// The tokens are moved from the nested associated token account to the
-    /// wallet's associated token account, and the nested account lamports are
-    /// moved to the wallet.
+    /// The account is sized for the `ConfidentialTransferAccount` extension.
+    /// The wallet signs as the account owner, and the ElGamal public key is
+    /// taken from the `PubkeyValidity` proof, verified in the same
+    /// transaction or beforehand into a context state account. With
+    /// `CreateMode::Idempotent`, an existing account is left as it is.
     ///
-    /// Note: Nested token accounts are an anti-pattern, and almost always
-    /// created unintentionally, so this instruction should only be used to
-    /// recover from errors.
+    ///   0. `[writeable,signer]` Funding account (must be a system account)
+    ///   1. `[writeable]` Associated token account address to be created
+    ///   2. `[signer]` Wallet address for the new associated token account
+    ///   3. `[]` The token mint for the new associated token account
+    ///   4. `[]` System program
+    ///   5. `[]` Token-2022 program
+    ///   6. `[]` Instructions sysvar if `proof_instruction_offset` is not `0`,
+    ///      otherwise the `PubkeyValidity` proof context state account
+    #[cfg_attr(
+        feature = "codama",
+        codama(account(
+            name = "funder",
+            signer,
+            writable,
+            docs = "Funding account (must be a system account)"
+        )),
+        codama(account(
+            name = "associated_token_account",
+            writable,
+            docs = "Associated token account address to be created"
+        )),
+        codama(account(
+            name = "wallet",
+            signer,
+            docs = "Wallet address for the new associated token account"
+        )),
+        codama(account(name = "mint", docs = "The token mint for the new associated token account")),
+        codama(account(
+            name = "system_program",
+            docs = "System program",
+            default_value = program("system")
+        )),
+        codama(account(name = "token_program", docs = "Token-2022 program")),
+        codama(account(
+            name = "proof",
+            docs = "Instructions sysvar, or the proof context state account if the offset is 0"
+        ))
+    )]
+    #[wincode(tag = 15)]
+    CreateConfidential {
+        /// Selects whether behaves like `Create` or `CreateIdempotent`.
+        mode: CreateMode,
+        /// The account's available balance of zero, encrypted with its
+        /// authenticated encryption key.
+        decryptable_zero_balance: [u8; DECRYPTABLE_BALANCE_LEN],
+        /// Most credits to the pending balance before it must be applied.
+        maximum_pending_balance_credit_counter: u64,
+        /// Offset of the `VerifyPubkeyValidity` instruction from this one in
+        /// the transaction, or `0` if the proof is in a context state account.
+        proof_instruction_offset: i8,
+    },
+    /// Recovers nested tokens like `RecoverNested`, first passing the memo
+    /// that follows `memo_len` in the instruction data to the memo program.
     ///
-    /// Accounts expected by this instruction:
+    /// Lets recovery deliver to a destination associated token account with
+    /// the `MemoTransfer` extension requiring incoming transfer memos. The
+    /// memo is exactly `memo_len` bytes, at most `MAX_MEMO_LEN`, and must be
+    /// valid UTF-8 for the memo program. An empty memo skips the memo program.
     ///
-    ///   * Single owner
     ///   0. `[writeable]` Nested associated token account, must be owned by `3`
     ///   1. `[]` Token mint for the nested associated token account
     ///   2. `[writeable]` Wallet's associated token account
     ///   3. `[]` Owner associated token account address, must be owned by `5`
     ///   4. `[]` Token mint for the owner associated token account
     ///   5. `[writeable, signer]` Wallet address for the owner associated token
-    ///      account
-    ///   6. `[]` Token program for the owner mint
-    ///   7. `[]` Optional token program for the nested mint, if different from
-    ///      the owner mint's token program
-    ///
-    ///   * Multisignature owner
-    ///   0. `[writeable]` Nested associated token account, must be owned by `3`
-    ///   1. `[]` Token mint for the nested associated token account
-    ///   2. `[writeable]` Wallet's associated token account
-    ///   3. `[]` Owner associated token account address, must be owned by `5`
-    ///   4. `[]` Token mint for the owner associated token account
-    ///   5. `[writeable]` Multisignature wallet address for the owner associated
-    ///      token account
+    ///      account, not a signer if it is a multisig
     ///   6. `[]` Token program for the owner mint
-    ///   7. `[]` Token program for the nested mint
-    ///   8. `..+M` `[signer]` M multisig signer accounts that authorize the
-    ///      wallet
+    ///   7. `[]` Token program for the nested mint, even if the same as `6`.
+    ///      Unlike `RecoverNested`, it is mandatory, as the memo program
+    ///      follows it
+    ///   8. `[]` SPL Memo program
+    ///   9. `..+M` `[signer]` M multisig signer accounts that authorize the
+    ///      wallet, if it is a multisig
+    ///   9+M. `..` Extra accounts of the nested mint's transfer hook, if it has
+    ///      one
     #[cfg_attr(
         feature = "codama",
-        codama(optional_account_strategy = omitted),
         codama(account(
             name = "nested_associated_token_account",
             writable,
@@ -155,63 +804,115 @@ pub enum AssociatedTokenAccountInstruction {
         )),
         codama(account(
             name = "nested_token_program",
-            optional,
-            docs = "Optional token program for the nested mint, if different from the owner \
-                    mint's token program. Required when the wallet is a multisig."
-        ))
+            docs = "Token program for the nested mint, mandatory even if the same as the owner \
+                    mint's"
+        )),
+        codama(account(name = "memo_program", docs = "SPL Memo program"))
     )]
-    RecoverNested,
-    /// Creates an associated token account for the given wallet address and
-    /// token mint. Accepts optional optimization arguments to lower CU usage.
+    #[wincode(tag = 16)]
+    RecoverNestedWithMemo {
+        /// Length of the memo following it, at most `MAX_MEMO_LEN`.
+        memo_len: u16,
+    },
+    /// Transfers the lamports held at the wallet's associated token account
+    /// address to the wallet, if no account has been created there yet.
     ///
-    ///   0. `[writeable,signer]` Funding account (must be a system account)
-    ///   1. `[writeable]` Associated token account address to be created
-    ///   2. `[]` Wallet address for the new associated token account
-    ///   3. `[]` The token mint for the new associated token account
+    /// Anyone can send lamports to an address, which the ATA program then
+    /// keeps as part of the rent once the account is created. Until then only
+    /// the ATA program can sign for the address, so this returns them to the
+    /// wallet, which must sign. Only available if the program is built with
+    /// the `reclaim-uninitialized` feature.
+    ///
+    ///   0. `[writeable]` Associated token account address holding the lamports
+    ///   1. `[writeable,signer]` Wallet address of the associated token account
+    ///   2. `[]` The token mint of the associated token account
+    ///   3. `[]` SPL Token program
     ///   4. `[]` System program
-    ///   5. `[]` SPL Token program
-    ///   6. `[]` Optional rent sysvar
     #[cfg_attr(
         feature = "codama",
-        codama(optional_account_strategy = omitted),
         codama(account(
-            name = "funder",
-            signer,
+            name = "associated_token_account",
             writable,
-            docs = "Funding account (must be a system account)"
+            docs = "Associated token account address holding the lamports"
         )),
         codama(account(
-            name = "associated_token_account",
+            name = "wallet",
+            signer,
             writable,
-            docs = "Associated token account address to be created"
+            docs = "Wallet address of the associated token account"
         )),
-        codama(account(name = "wallet", docs = "Wallet address for the new associated token account")),
-        codama(account(name = "mint", docs = "The token mint for the new associated token account")),
+        codama(account(name = "mint", docs = "The token mint of the associated token account")),
+        codama(account(name = "token_program", docs = "SPL Token program")),
         codama(account(
             name = "system_program",
             docs = "System program",
             default_value = program("system")
-        )),
-        codama(account(name = "token_program", docs = "SPL Token program")),
-        codama(account(
-            name = "rent_sysvar",
-            optional,
-            default_value = sysvar("rent"),
-            docs = "Optional rent sysvar"
         ))
     )]
-    CreateWithArgs {
-        /// Selects whether behaves like `Create` or `CreateIdempotent`.
-        mode: CreateMode,
-        /// The ATA PDA bump seed.
-        #[cfg_attr(feature = "codama", codama(type = number(u8)))]
-        bump: MaybeNull<BumpSeedHint>,
-        /// The account data length for the new ATA.
-        #[cfg_attr(feature = "codama", codama(type = number(u32)))]
-        account_len: MaybeNull<AccountLenHint>,
-    },
+    #[wincode(tag = 17)]
+    ReclaimUninitialized,
+    /// Returns the program version and the `FEATURE_*` flags it supports as
+    /// [`ProgramVersion`](crate::version::ProgramVersion) return data.
+    ///
+    /// Its discriminator, 255, is reserved for it so that it stays the same
+    /// whatever instructions are added below it.
+    ///
+    /// No accounts are expected by this instruction.
+    #[wincode(tag = 255)]
+    GetVersion,
 }
 
+/// Length of an authenticated encryption ciphertext, the encoding of
+/// `CreateConfidential`'s `decryptable_zero_balance`.
+pub const DECRYPTABLE_BALANCE_LEN: usize = 36;
+
+/// Discriminator and `u16` memo length preceding the memo of
+/// `RecoverNestedWithMemo`.
+const MEMO_HEADER_LEN: usize = size_of::<u8>() + size_of::<u16>();
+
+/// Maximum size of a serialized transaction.
+const PACKET_DATA_SIZE: usize = 1232;
+
+/// Length of a transaction signature.
+const SIGNATURE_LEN: usize = 64;
+
+/// Length of an account address or blockhash.
+const ADDRESS_LEN: usize = 32;
+
+/// Length of a transaction message header.
+const MESSAGE_HEADER_LEN: usize = 3;
+
+/// Length of a compact-u16 below 128.
+const SHORT_COMPACT_U16_LEN: usize = 1;
+
+/// Length of a compact-u16 from 128 up to 16383.
+const LONG_COMPACT_U16_LEN: usize = 2;
+
+/// Distinct addresses of a single-signer `RecoverNestedWithMemo`
+/// transaction: the accounts before the nested token program, which shares
+/// the owner token program's address, the memo program and the ATA program.
+const RECOVER_NESTED_WITH_MEMO_ADDRESSES: usize = RECOVER_NESTED_WITH_MEMO_NESTED_TOKEN_PROGRAM + 2;
+
+/// Bytes of a single-signer `RecoverNestedWithMemo` transaction other than
+/// its instruction data.
+const RECOVER_NESTED_WITH_MEMO_TRANSACTION_OVERHEAD: usize =
+    // The wallet's signature
+    SHORT_COMPACT_U16_LEN
+    + SIGNATURE_LEN
+    + MESSAGE_HEADER_LEN
+    // Account addresses
+    + SHORT_COMPACT_U16_LEN
+    + RECOVER_NESTED_WITH_MEMO_ADDRESSES * ADDRESS_LEN
+    // Recent blockhash
+    + ADDRESS_LEN
+    // Instruction count, program id index and account indices
+    + SHORT_COMPACT_U16_LEN
+    + size_of::<u8>()
+    + SHORT_COMPACT_U16_LEN
+    + RECOVER_NESTED_WITH_MEMO_MULTISIG_SIGNERS
+    // Instruction data length
+    + LONG_COMPACT_U16_LEN;
+
 impl AssociatedTokenAccountInstruction {
     #[inline(always)]
     pub fn try_from_bytes(instruction_data: &[u8]) -> Result<Self, ProgramError> {
@@ -219,11 +920,206 @@ impl AssociatedTokenAccountInstruction {
             [] | [0] => Ok(Self::Create),
             [1] => Ok(Self::CreateIdempotent),
             [2] => Ok(Self::RecoverNested),
-            [3, ..] => wincode::deserialize_exact(instruction_data)
+            [7] => Ok(Self::AssertAtaExists),
+            [10] => Ok(Self::RecoverNestedIdempotent),
+            [11] => Ok(Self::Close),
+            [12] => Ok(Self::CloseEmpty),
+            // The memo is the remaining data, read by the processor through `Self::memo`
+            [16, memo_len_low, memo_len_high, memo @ ..] => {
+                let memo_len = u16::from_le_bytes([*memo_len_low, *memo_len_high]);
+                if memo.len() != usize::from(memo_len) || memo.len() > Self::MAX_MEMO_LEN {
+                    return Err(ProgramError::InvalidInstructionData);
+                }
+                Ok(Self::RecoverNestedWithMemo { memo_len })
+            }
+            [17] => Ok(Self::ReclaimUninitialized),
+            [255] => Ok(Self::GetVersion),
+            [3 | 4 | 6 | 8 | 9 | 13 | 14 | 15, ..] => wincode::deserialize_exact(instruction_data)
                 .map_err(|_| ProgramError::InvalidInstructionData),
             _ => Err(ProgramError::InvalidInstructionData),
         }
     }
+
+    /// Maximum encoded instruction data length across all instructions,
+    /// including the longest `RecoverNestedWithMemo` memo, for sizing stack
+    /// buffers passed to [`Self::write_data`].
+    pub const MAX_DATA_LEN: usize = MEMO_HEADER_LEN + Self::MAX_MEMO_LEN;
+
+    /// Longest memo `RecoverNestedWithMemo` accepts, in bytes: the most that
+    /// fits in a transaction signed by the wallet alone, with both token
+    /// programs the same and no multisig signers or transfer hook accounts.
+    /// Longer memos fail to decode with `InvalidInstructionData`.
+    pub const MAX_MEMO_LEN: usize =
+        PACKET_DATA_SIZE - RECOVER_NESTED_WITH_MEMO_TRANSACTION_OVERHEAD - MEMO_HEADER_LEN;
+
+    /// The memo of `RecoverNestedWithMemo` instruction data accepted by
+    /// [`Self::try_from_bytes`], after the discriminator and its length.
+    pub fn memo(instruction_data: &[u8]) -> &[u8] {
+        instruction_data.get(MEMO_HEADER_LEN..).unwrap_or_default()
+    }
+
+    /// The instruction discriminator, the first byte of the encoded data.
+    pub const fn discriminator(&self) -> u8 {
+        match self {
+            Self::Create => 0,
+            Self::CreateIdempotent => 1,
+            Self::RecoverNested => 2,
+            Self::CreateWithArgs { .. } => 3,
+            Self::CreateCompact { .. } => 4,
+            Self::CreateWithBumpLimit { .. } => 6,
+            Self::AssertAtaExists => 7,
+            Self::CreateMany { .. } => 8,
+            Self::RecoverNestedWithArgs { .. } => 9,
+            Self::RecoverNestedIdempotent => 10,
+            Self::Close => 11,
+            Self::CloseEmpty => 12,
+            Self::CreateWrapped { .. } => 13,
+            Self::CreateWithDeposit { .. } => 14,
+            Self::CreateConfidential { .. } => 15,
+            Self::RecoverNestedWithMemo { .. } => 16,
+            Self::ReclaimUninitialized => 17,
+            Self::GetVersion => 255,
+        }
+    }
+
+    /// The encoded instruction data length, excluding the memo of
+    /// `RecoverNestedWithMemo`, which callers append after its length.
+    pub const fn data_len(&self) -> usize {
+        match self {
+            Self::Create
+            | Self::CreateIdempotent
+            | Self::RecoverNested
+            | Self::GetVersion
+            | Self::AssertAtaExists
+            | Self::RecoverNestedIdempotent
+            | Self::Close
+            | Self::CloseEmpty
+            | Self::ReclaimUninitialized => 1,
+            Self::CreateMany { .. } => 2,
+            Self::RecoverNestedWithMemo { .. } => MEMO_HEADER_LEN,
+            Self::RecoverNestedWithArgs { .. } => 4,
+            Self::CreateWithArgs { .. }
+            | Self::CreateCompact { .. }
+            | Self::CreateWithBumpLimit { .. } => 7,
+            Self::CreateWrapped { .. } => 10,
+            Self::CreateWithDeposit { .. } => 11,
+            Self::CreateConfidential { .. } => 47,
+        }
+    }
+
+    /// Encodes the instruction data into `buffer` without allocating, returning
+    /// the number of bytes written.
+    pub fn write_data(&self, buffer: &mut [u8]) -> Result<usize, ProgramError> {
+        let data = buffer
+            .get_mut(..self.data_len())
+            .ok_or(ProgramError::InvalidArgument)?;
+        data[0] = self.discriminator();
+        match *self {
+            Self::Create
+            | Self::CreateIdempotent
+            | Self::RecoverNested
+            | Self::AssertAtaExists
+            | Self::RecoverNestedIdempotent
+            | Self::Close
+            | Self::CloseEmpty
+            | Self::ReclaimUninitialized
+            | Self::GetVersion => {}
+            Self::CreateWithArgs {
+                mode,
+                bump,
+                account_len,
+            }
+            | Self::CreateCompact {
+                mode,
+                bump,
+                account_len,
+            } => write_create_fields(
+                data,
+                mode,
+                bump.get().map_or(0, u8::from),
+                account_len.get().map_or(0, u32::from),
+            ),
+            Self::CreateWithBumpLimit {
+                mode,
+                min_bump,
+                account_len,
+            } => write_create_fields(data, mode, min_bump, account_len.get().map_or(0, u32::from)),
+            Self::CreateMany { mode } => data[1] = mode as u8,
+            Self::RecoverNestedWithArgs {
+                owner_bump,
+                nested_bump,
+                destination_bump,
+            } => {
+                data[1] = owner_bump.get().map_or(0, u8::from);
+                data[2] = nested_bump.get().map_or(0, u8::from);
+                data[3] = destination_bump.get().map_or(0, u8::from);
+            }
+            Self::CreateWrapped { mode, lamports } => {
+                data[1] = mode as u8;
+                data[2..10].copy_from_slice(&lamports.to_le_bytes());
+            }
+            Self::CreateWithDeposit {
+                mode,
+                amount,
+                decimals,
+            } => {
+                data[1] = mode as u8;
+                data[2..10].copy_from_slice(&amount.to_le_bytes());
+                data[10] = decimals;
+            }
+            Self::CreateConfidential {
+                mode,
+                decryptable_zero_balance,
+                maximum_pending_balance_credit_counter,
+                proof_instruction_offset,
+            } => {
+                data[1] = mode as u8;
+                data[2..38].copy_from_slice(&decryptable_zero_balance);
+                data[38..46].copy_from_slice(&maximum_pending_balance_credit_counter.to_le_bytes());
+                data[46] = proof_instruction_offset as u8;
+            }
+            Self::RecoverNestedWithMemo { memo_len } => {
+                data[1..3].copy_from_slice(&memo_len.to_le_bytes());
+            }
+        }
+        Ok(data.len())
+    }
+}
+
+/// Encodes `CreateWithArgs` data into `buffer` without allocating, returning
+/// the number of bytes written, for programs creating an ATA by CPI. A `None`
+/// bump or account length is encoded as the null hint. `Some(0)` fails with
+/// `InvalidArgument`, since `0` is the null hint's encoding.
+pub fn write_create_instruction_data(
+    buffer: &mut [u8],
+    mode: CreateMode,
+    bump: Option<u8>,
+    account_len: Option<u32>,
+) -> Result<usize, ProgramError> {
+    let bump = match bump {
+        Some(bump) => BumpSeedHint::new(bump).ok_or(ProgramError::InvalidArgument)?,
+        None => BumpSeedHint::NONE,
+    };
+    let account_len = match account_len {
+        Some(account_len) => {
+            AccountLenHint::new(account_len).ok_or(ProgramError::InvalidArgument)?
+        }
+        None => AccountLenHint::NONE,
+    };
+    AssociatedTokenAccountInstruction::CreateWithArgs {
+        mode,
+        bump: MaybeNull::from(bump),
+        account_len: MaybeNull::from(account_len),
+    }
+    .write_data(buffer)
+}
+
+/// Mode, bump and account length, the fields shared by the encodings of
+/// `CreateWithArgs`, `CreateCompact` and `CreateWithBumpLimit`.
+fn write_create_fields(data: &mut [u8], mode: CreateMode, bump: u8, account_len: u32) {
+    data[1] = mode as u8;
+    data[2] = bump;
+    data[3..7].copy_from_slice(&account_len.to_le_bytes());
 }
 
 /// Specify when to create the associated token account.
@@ -292,7 +1188,10 @@ impl From<AccountLenHint> for u32 {
 #[cfg(test)]
 mod tests {
     use {
-        super::{AccountLenHint, AssociatedTokenAccountInstruction, BumpSeedHint, CreateMode},
+        super::{
+            AccountLenHint, AssociatedTokenAccountInstruction, BumpSeedHint, CreateMode,
+            DECRYPTABLE_BALANCE_LEN, write_create_instruction_data,
+        },
         pinocchio::error::ProgramError,
         solana_nullable::{MaybeNull, Nullable},
         wincode::Serialize,
@@ -313,6 +1212,12 @@ mod tests {
         let decoded: AssociatedTokenAccountInstruction =
             wincode::deserialize_exact(&expected).unwrap();
         assert_eq!(decoded, instruction);
+
+        let mut buffer = [0xff; AssociatedTokenAccountInstruction::MAX_DATA_LEN];
+        assert_eq!(instruction.data_len(), N);
+        assert_eq!(instruction.write_data(&mut buffer), Ok(N));
+        assert_eq!(buffer[..N], expected);
+        assert_eq!(buffer[0], instruction.discriminator());
     }
 
     #[test]
@@ -320,6 +1225,18 @@ mod tests {
         assert_wire(AssociatedTokenAccountInstruction::Create, [0]);
         assert_wire(AssociatedTokenAccountInstruction::CreateIdempotent, [1]);
         assert_wire(AssociatedTokenAccountInstruction::RecoverNested, [2]);
+        assert_wire(AssociatedTokenAccountInstruction::AssertAtaExists, [7]);
+        assert_wire(
+            AssociatedTokenAccountInstruction::RecoverNestedIdempotent,
+            [10],
+        );
+        assert_wire(AssociatedTokenAccountInstruction::Close, [11]);
+        assert_wire(AssociatedTokenAccountInstruction::CloseEmpty, [12]);
+        assert_wire(
+            AssociatedTokenAccountInstruction::ReclaimUninitialized,
+            [17],
+        );
+        assert_wire(AssociatedTokenAccountInstruction::GetVersion, [255]);
         assert_wire(
             AssociatedTokenAccountInstruction::CreateWithArgs {
                 mode: CreateMode::Always,
@@ -356,6 +1273,183 @@ mod tests {
             },
             [3, 1, 253, 1, 2, 3, 4],
         );
+        assert_wire(
+            AssociatedTokenAccountInstruction::CreateCompact {
+                mode: CreateMode::Always,
+                bump: MaybeNull::from(BumpSeedHint::NONE),
+                account_len: MaybeNull::from(AccountLenHint::NONE),
+            },
+            [4, 0, 0, 0, 0, 0, 0],
+        );
+        assert_wire(
+            AssociatedTokenAccountInstruction::CreateCompact {
+                mode: CreateMode::Idempotent,
+                bump: BumpSeedHint::new(253).unwrap().into(),
+                account_len: AccountLenHint::new(u32::from_le_bytes([1, 2, 3, 4]))
+                    .unwrap()
+                    .into(),
+            },
+            [4, 1, 253, 1, 2, 3, 4],
+        );
+        assert_wire(
+            AssociatedTokenAccountInstruction::CreateWithBumpLimit {
+                mode: CreateMode::Idempotent,
+                min_bump: 250,
+                account_len: AccountLenHint::new(u32::from_le_bytes([1, 2, 3, 4]))
+                    .unwrap()
+                    .into(),
+            },
+            [6, 1, 250, 1, 2, 3, 4],
+        );
+        assert_wire(
+            AssociatedTokenAccountInstruction::CreateMany {
+                mode: CreateMode::Always,
+            },
+            [8, 0],
+        );
+        assert_wire(
+            AssociatedTokenAccountInstruction::CreateMany {
+                mode: CreateMode::Idempotent,
+            },
+            [8, 1],
+        );
+        assert_wire(
+            AssociatedTokenAccountInstruction::RecoverNestedWithArgs {
+                owner_bump: MaybeNull::from(BumpSeedHint::NONE),
+                nested_bump: MaybeNull::from(BumpSeedHint::NONE),
+                destination_bump: MaybeNull::from(BumpSeedHint::NONE),
+            },
+            [9, 0, 0, 0],
+        );
+        assert_wire(
+            AssociatedTokenAccountInstruction::RecoverNestedWithArgs {
+                owner_bump: BumpSeedHint::new(255).unwrap().into(),
+                nested_bump: BumpSeedHint::new(254).unwrap().into(),
+                destination_bump: BumpSeedHint::new(253).unwrap().into(),
+            },
+            [9, 255, 254, 253],
+        );
+        assert_wire(
+            AssociatedTokenAccountInstruction::CreateWrapped {
+                mode: CreateMode::Idempotent,
+                lamports: u64::from_le_bytes([1, 2, 3, 4, 5, 6, 7, 8]),
+            },
+            [13, 1, 1, 2, 3, 4, 5, 6, 7, 8],
+        );
+        assert_wire(
+            AssociatedTokenAccountInstruction::CreateWithDeposit {
+                mode: CreateMode::Idempotent,
+                amount: u64::from_le_bytes([1, 2, 3, 4, 5, 6, 7, 8]),
+                decimals: 6,
+            },
+            [14, 1, 1, 2, 3, 4, 5, 6, 7, 8, 6],
+        );
+        let mut expected = [0; 47];
+        expected[0] = 15;
+        expected[2..38].fill(7);
+        expected[38] = 65;
+        expected[46] = 0xff;
+        assert_wire(
+            AssociatedTokenAccountInstruction::CreateConfidential {
+                mode: CreateMode::Always,
+                decryptable_zero_balance: [7; DECRYPTABLE_BALANCE_LEN],
+                maximum_pending_balance_credit_counter: 65,
+                proof_instruction_offset: -1,
+            },
+            expected,
+        );
+    }
+
+    #[test]
+    fn recover_nested_with_memo_takes_length_prefixed_memo() {
+        assert_wire(
+            AssociatedTokenAccountInstruction::RecoverNestedWithMemo { memo_len: 0 },
+            [16, 0, 0],
+        );
+        let data = b"\x10\x04\x00memo";
+        assert_eq!(
+            AssociatedTokenAccountInstruction::try_from_bytes(data).unwrap(),
+            AssociatedTokenAccountInstruction::RecoverNestedWithMemo { memo_len: 4 }
+        );
+        assert_eq!(AssociatedTokenAccountInstruction::memo(data), b"memo");
+    }
+
+    #[test]
+    fn recover_nested_with_memo_caps_memo_len() {
+        let max_memo_len = AssociatedTokenAccountInstruction::MAX_MEMO_LEN;
+        for (memo_len, accepted) in [
+            (max_memo_len, true),
+            (max_memo_len.checked_add(1).unwrap(), false),
+        ] {
+            let instruction = AssociatedTokenAccountInstruction::RecoverNestedWithMemo {
+                memo_len: u16::try_from(memo_len).unwrap(),
+            };
+            let mut data = [b'a'; AssociatedTokenAccountInstruction::MAX_DATA_LEN + 1];
+            let len = instruction
+                .write_data(&mut data)
+                .unwrap()
+                .checked_add(memo_len)
+                .unwrap();
+            assert_eq!(
+                AssociatedTokenAccountInstruction::try_from_bytes(&data[..len]).is_ok(),
+                accepted
+            );
+        }
+    }
+
+    #[test]
+    fn write_data_rejects_short_buffer() {
+        let instruction = AssociatedTokenAccountInstruction::CreateWithArgs {
+            mode: CreateMode::Always,
+            bump: MaybeNull::from(BumpSeedHint::NONE),
+            account_len: MaybeNull::from(AccountLenHint::NONE),
+        };
+        let mut buffer = [0; 6];
+        assert_eq!(
+            instruction.write_data(&mut buffer),
+            Err(ProgramError::InvalidArgument)
+        );
+        assert_eq!(
+            AssociatedTokenAccountInstruction::Create.write_data(&mut []),
+            Err(ProgramError::InvalidArgument)
+        );
+    }
+
+    #[test]
+    fn write_create_instruction_data_encodes_create_with_args() {
+        let mut buffer = [0xff; AssociatedTokenAccountInstruction::MAX_DATA_LEN];
+        assert_eq!(
+            write_create_instruction_data(
+                &mut buffer,
+                CreateMode::Idempotent,
+                Some(253),
+                Some(u32::from_le_bytes([1, 2, 3, 4])),
+            ),
+            Ok(7)
+        );
+        assert_eq!(buffer[..7], [3, 1, 253, 1, 2, 3, 4]);
+        assert_eq!(
+            write_create_instruction_data(&mut buffer, CreateMode::Always, None, None),
+            Ok(7)
+        );
+        assert_eq!(buffer[..7], [3, 0, 0, 0, 0, 0, 0]);
+        assert_eq!(
+            write_create_instruction_data(&mut [0; 6], CreateMode::Always, None, None),
+            Err(ProgramError::InvalidArgument)
+        );
+    }
+
+    #[test]
+    fn write_create_instruction_data_rejects_zero_hints() {
+        let mut buffer = [0; AssociatedTokenAccountInstruction::MAX_DATA_LEN];
+        assert_eq!(
+            write_create_instruction_data(&mut buffer, CreateMode::Always, Some(0), None),
+            Err(ProgramError::InvalidArgument)
+        );
+        assert_eq!(
+            write_create_instruction_data(&mut buffer, CreateMode::Always, None, Some(0)),
+            Err(ProgramError::InvalidArgument)
+        );
     }
 
     #[test]
@@ -369,16 +1463,43 @@ mod tests {
     #[test]
     fn instruction_parser_rejects_non_canonical_payloads() {
         let cases: &[&[u8]] = &[
-            &[4],                      // unknown discriminator
-            &[0, 0],                   // trailing byte after Create
-            &[1, 9, 9],                // trailing bytes after CreateIdempotent
-            &[2, 0],                   // trailing byte after RecoverNested
-            &[3],                      // missing CreateWithArgs mode
-            &[3, 2, 0, 0, 0, 0, 0],    // invalid CreateWithArgs mode
-            &[3, 0],                   // missing bump hint
-            &[3, 0, 0],                // missing account_len hint
-            &[3, 0, 0, 0, 0, 0],       // truncated account_len hint
-            &[3, 0, 0, 0, 0, 0, 0, 0], // trailing byte after CreateWithArgs
+            &[18],                                  // unknown discriminator
+            &[0, 0],                                // trailing byte after Create
+            &[1, 9, 9],                             // trailing bytes after CreateIdempotent
+            &[2, 0],                                // trailing byte after RecoverNested
+            &[3],                                   // missing CreateWithArgs mode
+            &[3, 2, 0, 0, 0, 0, 0],                 // invalid CreateWithArgs mode
+            &[3, 0],                                // missing bump hint
+            &[3, 0, 0],                             // missing account_len hint
+            &[3, 0, 0, 0, 0, 0],                    // truncated account_len hint
+            &[3, 0, 0, 0, 0, 0, 0, 0],              // trailing byte after CreateWithArgs
+            &[4],                                   // missing CreateCompact mode
+            &[4, 0, 0, 0, 0, 0],                    // truncated CreateCompact account_len hint
+            &[4, 0, 0, 0, 0, 0, 0, 0],              // trailing byte after CreateCompact
+            &[5],                                   // reserved discriminator
+            &[6, 0, 250],                           // missing CreateWithBumpLimit account_len hint
+            &[6, 0, 0, 0, 0, 0, 0, 0],              // trailing byte after CreateWithBumpLimit
+            &[7, 0],                                // trailing byte after AssertAtaExists
+            &[8],                                   // missing CreateMany mode
+            &[8, 2],                                // invalid CreateMany mode
+            &[8, 0, 0],                             // trailing byte after CreateMany
+            &[9],                                   // missing RecoverNestedWithArgs bumps
+            &[9, 255, 254],                         // missing destination bump
+            &[9, 255, 254, 253, 0],                 // trailing byte after RecoverNestedWithArgs
+            &[10, 0],                               // trailing byte after RecoverNestedIdempotent
+            &[11, 0],                               // trailing byte after Close
+            &[12, 0],                               // trailing byte after CloseEmpty
+            &[13, 0],                               // missing CreateWrapped lamports
+            &[13, 2, 0, 0, 0, 0, 0, 0, 0, 0],       // invalid CreateWrapped mode
+            &[13, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0],    // trailing byte after CreateWrapped
+            &[14, 0, 0, 0, 0, 0, 0, 0, 0, 0],       // missing CreateWithDeposit decimals
+            &[14, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0], // trailing byte after CreateWithDeposit
+            &[15, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0], // truncated CreateConfidential zero balance
+            &[16],                                  // missing RecoverNestedWithMemo memo_len
+            &[16, 1, 0],                            // memo shorter than memo_len
+            &[16, 0, 0, 0],                         // memo longer than memo_len
+            &[17, 0],                               // trailing byte after ReclaimUninitialized
+            &[255, 0],                              // trailing byte after GetVersion
         ];
 
         for data in cases {
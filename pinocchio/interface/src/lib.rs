@@ -2,8 +2,13 @@
 
 #![no_std]
 
+pub mod account_indices;
 pub mod error;
+pub mod funding;
 pub mod instruction;
+pub mod mint_risk;
 pub mod pda;
+pub mod validation;
+pub mod version;
 
 solana_address::declare_id!("ATokenGPvbdGVxr1b2hvZbsiqW5xWH25efTNsLJA8knL");
@@ -0,0 +1,225 @@
+//! Token-2022 mint extensions that wallets should surface before creating an
+//! associated token account for the mint.
+
+use {core::ops::BitOr, pinocchio::error::ProgramError};
+
+/// Length of an SPL Token or Token-2022 mint without extensions.
+const MINT_BASE_LEN: usize = 82;
+/// Offset of the account type byte in extended Token-2022 accounts, which pad
+/// the mint up to the base token account length.
+const ACCOUNT_TYPE_OFFSET: usize = 165;
+/// `AccountType::Mint`
+const ACCOUNT_TYPE_MINT: u8 = 1;
+const TLV_START: usize = ACCOUNT_TYPE_OFFSET + 1;
+const TLV_HEADER_LEN: usize = 4;
+
+/// `ExtensionType` discriminators, see `spl_token_2022_interface::extension::ExtensionType`.
+const EXTENSION_UNINITIALIZED: u16 = 0;
+const EXTENSION_MINT_CLOSE_AUTHORITY: u16 = 3;
+const EXTENSION_NON_TRANSFERABLE: u16 = 9;
+const EXTENSION_PERMANENT_DELEGATE: u16 = 12;
+const EXTENSION_TRANSFER_HOOK: u16 = 14;
+const EXTENSION_PAUSABLE: u16 = 26;
+
+/// Mint extensions that let someone other than the account owner move or
+/// freeze tokens, or that restrict what the owner can do with them.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub struct MintRiskFlags(u8);
+
+impl MintRiskFlags {
+    /// `PermanentDelegate`: a delegate can transfer or burn from every account.
+    pub const PERMANENT_DELEGATE: Self = Self(1 << 0);
+    /// `TransferHook`: transfers invoke a program chosen by the mint authority.
+    pub const TRANSFER_HOOK: Self = Self(1 << 1);
+    /// `Pausable`: an authority can halt all transfers, mints and burns.
+    pub const PAUSABLE: Self = Self(1 << 2);
+    /// `NonTransferable`: tokens can never leave the account.
+    pub const NON_TRANSFERABLE: Self = Self(1 << 3);
+    /// `MintCloseAuthority` with a non-zero authority: the mint can be closed
+    /// once its supply is zero, and recreated at the same address.
+    pub const MINT_CLOSE_AUTHORITY: Self = Self(1 << 4);
+
+    /// No flags set.
+    pub const fn empty() -> Self {
+        Self(0)
+    }
+
+    /// The raw flag bits.
+    pub const fn bits(&self) -> u8 {
+        self.0
+    }
+
+    /// Whether no flags are set.
+    pub const fn is_empty(&self) -> bool {
+        self.0 == 0
+    }
+
+    /// Whether all flags in `other` are set.
+    pub const fn contains(&self, other: Self) -> bool {
+        self.0 & other.0 == other.0
+    }
+
+    /// Whether any flag in `other` is set.
+    pub const fn intersects(&self, other: Self) -> bool {
+        self.0 & other.0 != 0
+    }
+
+    /// Parses the flags from SPL Token or Token-2022 mint account data.
+    ///
+    /// Walks the same TLV layout as the program's account size calculation.
+    /// Fails with `InvalidAccountData` if the data is not a mint or an
+    /// extension entry runs past the end of the data.
+    pub fn from_mint_data(data: &[u8]) -> Result<Self, ProgramError> {
+        if data.len() == MINT_BASE_LEN {
+            return Ok(Self::empty());
+        }
+        if data.get(ACCOUNT_TYPE_OFFSET) != Some(&ACCOUNT_TYPE_MINT) {
+            return Err(ProgramError::InvalidAccountData);
+        }
+
+        let mut flags = Self::empty();
+        let mut offset = TLV_START;
+        while let Some(header) = data.get(offset..offset.saturating_add(TLV_HEADER_LEN)) {
+            let extension_type = u16::from_le_bytes([header[0], header[1]]);
+            if extension_type == EXTENSION_UNINITIALIZED {
+                break;
+            }
+            let value_len = u16::from_le_bytes([header[2], header[3]]) as usize;
+            let value_end = offset
+                .saturating_add(TLV_HEADER_LEN)
+                .saturating_add(value_len);
+            if value_end > data.len() {
+                return Err(ProgramError::InvalidAccountData);
+            }
+
+            flags = flags
+                | match extension_type {
+                    EXTENSION_PERMANENT_DELEGATE => Self::PERMANENT_DELEGATE,
+                    EXTENSION_TRANSFER_HOOK => Self::TRANSFER_HOOK,
+                    EXTENSION_PAUSABLE => Self::PAUSABLE,
+                    EXTENSION_NON_TRANSFERABLE => Self::NON_TRANSFERABLE,
+                    // An all-zero `OptionalNonZeroPubkey` means no authority
+                    EXTENSION_MINT_CLOSE_AUTHORITY
+                        if data[offset.saturating_add(TLV_HEADER_LEN)..value_end]
+                            .iter()
+                            .any(|byte| *byte != 0) =>
+                    {
+                        Self::MINT_CLOSE_AUTHORITY
+                    }
+                    _ => Self::empty(),
+                };
+            offset = value_end;
+        }
+        Ok(flags)
+    }
+}
+
+impl BitOr for MintRiskFlags {
+    type Output = Self;
+
+    fn bitor(self, rhs: Self) -> Self {
+        Self(self.0 | rhs.0)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use {
+        super::{
+            ACCOUNT_TYPE_MINT, ACCOUNT_TYPE_OFFSET, MINT_BASE_LEN, MintRiskFlags, TLV_HEADER_LEN,
+            TLV_START,
+        },
+        pinocchio::error::ProgramError,
+    };
+
+    const DATA_LEN: usize = 512;
+
+    /// Extended mint data with `(extension_type, value_len)` TLV entries.
+    fn mint_data(extensions: &[(u16, u16)]) -> ([u8; DATA_LEN], usize) {
+        let mut data = [0; DATA_LEN];
+        data[ACCOUNT_TYPE_OFFSET] = ACCOUNT_TYPE_MINT;
+        let mut offset = TLV_START;
+        for (extension_type, value_len) in extensions {
+            let len_offset = offset.saturating_add(2);
+            let value_start = offset.saturating_add(TLV_HEADER_LEN);
+            data[offset..len_offset].copy_from_slice(&extension_type.to_le_bytes());
+            data[len_offset..value_start].copy_from_slice(&value_len.to_le_bytes());
+            offset = value_start.saturating_add(*value_len as usize);
+        }
+        (data, offset)
+    }
+
+    #[test]
+    fn base_mint_has_no_flags() {
+        assert_eq!(
+            MintRiskFlags::from_mint_data(&[0; MINT_BASE_LEN]),
+            Ok(MintRiskFlags::empty())
+        );
+    }
+
+    #[test]
+    fn detects_risky_extensions() {
+        // MintCloseAuthority (3) without an authority, PermanentDelegate (12),
+        // TransferHook (14), Pausable (26), NonTransferable (9)
+        let (data, len) = mint_data(&[(3, 32), (12, 32), (14, 64), (26, 33), (9, 0)]);
+
+        let flags = MintRiskFlags::from_mint_data(&data[..len]).unwrap();
+
+        assert!(flags.contains(
+            MintRiskFlags::PERMANENT_DELEGATE
+                | MintRiskFlags::TRANSFER_HOOK
+                | MintRiskFlags::PAUSABLE
+                | MintRiskFlags::NON_TRANSFERABLE
+        ));
+        assert_eq!(flags.bits(), 0b1111);
+    }
+
+    #[test]
+    fn detects_mint_close_authority_only_when_set() {
+        let (mut data, len) = mint_data(&[(3, 32)]);
+        let flags = MintRiskFlags::from_mint_data(&data[..len]).unwrap();
+        assert!(flags.is_empty());
+        assert!(!flags.intersects(MintRiskFlags::MINT_CLOSE_AUTHORITY));
+
+        data[TLV_START.saturating_add(TLV_HEADER_LEN)] = 1;
+        let flags = MintRiskFlags::from_mint_data(&data[..len]).unwrap();
+        assert_eq!(flags, MintRiskFlags::MINT_CLOSE_AUTHORITY);
+        assert!(flags.intersects(MintRiskFlags::MINT_CLOSE_AUTHORITY | MintRiskFlags::PAUSABLE));
+    }
+
+    #[test]
+    fn benign_extensions_have_no_flags() {
+        // TransferFeeConfig (1), MetadataPointer (18)
+        let (data, len) = mint_data(&[(1, 108), (18, 64)]);
+
+        assert!(
+            MintRiskFlags::from_mint_data(&data[..len])
+                .unwrap()
+                .is_empty()
+        );
+        // Trailing zeroed space reads as an uninitialized entry
+        assert!(MintRiskFlags::from_mint_data(&data).unwrap().is_empty());
+    }
+
+    #[test]
+    fn rejects_malformed_data() {
+        let (mut data, len) = mint_data(&[(12, 32)]);
+        let cases: &[&[u8]] = &[
+            &data[..MINT_BASE_LEN.saturating_add(1)],
+            &data[..len.saturating_sub(1)], // truncated extension value
+        ];
+        for data in cases {
+            assert_eq!(
+                MintRiskFlags::from_mint_data(data),
+                Err(ProgramError::InvalidAccountData)
+            );
+        }
+
+        // Token account type
+        data[ACCOUNT_TYPE_OFFSET] = 2;
+        assert_eq!(
+            MintRiskFlags::from_mint_data(&data[..len]),
+            Err(ProgramError::InvalidAccountData)
+        );
+    }
+}
@@ -1,6 +1,9 @@
 //! Address derivation helpers for Associated Token Account program-derived addresses.
 
-use pinocchio::{Address, error::ProgramError};
+use {
+    crate::error::AssociatedTokenAccountError,
+    pinocchio::{Address, error::ProgramError},
+};
 
 #[cfg_attr(feature = "codama", derive(codama::CodamaPda))]
 #[cfg_attr(
@@ -26,15 +29,92 @@ impl AssociatedTokenPda {
         token_program_id: &Address,
         token_mint_address: &Address,
     ) -> (Address, u8) {
-        Address::derive_program_address(
-            &[
-                wallet_address.as_ref(),
-                token_program_id.as_ref(),
-                token_mint_address.as_ref(),
-            ],
-            program_id,
+        let (address, bump) = Self::derive_address_and_bump_seed_from_bytes(
+            program_id.as_array(),
+            wallet_address.as_array(),
+            token_program_id.as_array(),
+            token_mint_address.as_array(),
+        );
+        (Address::new_from_array(address), bump)
+    }
+
+    /// Derives the associated token account address and bump seed from raw
+    /// 32-byte keys, for callers whose key type is not `Address` but can be
+    /// viewed as `[u8; 32]`.
+    pub fn derive_address_and_bump_seed_from_bytes(
+        program_id: &[u8; 32],
+        wallet_address: &[u8; 32],
+        token_program_id: &[u8; 32],
+        token_mint_address: &[u8; 32],
+    ) -> ([u8; 32], u8) {
+        let (address, bump) = Address::derive_program_address(
+            &[wallet_address, token_program_id, token_mint_address],
+            &Address::new_from_array(*program_id),
         )
-        .expect("Unable to find a viable program address bump seed")
+        .expect("Unable to find a viable program address bump seed");
+        (address.to_bytes(), bump)
+    }
+
+    /// Derives the associated token account address and bump seed like
+    /// [`Self::derive_address_and_bump_seed`], but only tries bumps from
+    /// `u8::MAX` down to `min_bump`.
+    ///
+    /// Returns `None` if every bump in that range gives an on-curve address,
+    /// meaning the canonical bump, if any, is below `min_bump`.
+    pub fn derive_address_and_bump_seed_with_min_bump(
+        program_id: &Address,
+        wallet_address: &Address,
+        token_program_id: &Address,
+        token_mint_address: &Address,
+        min_bump: u8,
+    ) -> Option<(Address, u8)> {
+        let seeds = [
+            wallet_address.as_ref(),
+            token_program_id.as_ref(),
+            token_mint_address.as_ref(),
+        ];
+
+        (min_bump..=u8::MAX).rev().find_map(|bump| {
+            let address = Address::derive_address(&seeds, Some(bump), program_id);
+            (!address.is_on_curve()).then_some((address, bump))
+        })
+    }
+
+    /// Finds the canonical bump seed of `address`, trying bumps from `u8::MAX`
+    /// down to `min_bump`.
+    ///
+    /// Each bump costs one hash, and one on-curve check unless its address is
+    /// `address`: a match with every higher bump on-curve is the canonical bump
+    /// whenever it is off-curve itself. That check is left to the caller, as in
+    /// [`Self::derive_address_with_bump_hint`], since a signed invocation
+    /// rejects an on-curve address and an existing account cannot live at one.
+    ///
+    /// Fails with `InvalidSeeds` if the canonical address is not `address`, or
+    /// with `BumpSearchLimitExceeded` if every bump tried is on-curve.
+    pub fn find_bump_seed_for_address(
+        program_id: &Address,
+        wallet_address: &Address,
+        token_program_id: &Address,
+        token_mint_address: &Address,
+        address: &Address,
+        min_bump: u8,
+    ) -> Result<u8, ProgramError> {
+        let seeds = [
+            wallet_address.as_ref(),
+            token_program_id.as_ref(),
+            token_mint_address.as_ref(),
+        ];
+
+        for bump in (min_bump..=u8::MAX).rev() {
+            let derived = Address::derive_address(&seeds, Some(bump), program_id);
+            if derived == *address {
+                return Ok(bump);
+            }
+            if !derived.is_on_curve() {
+                return Err(ProgramError::InvalidSeeds);
+            }
+        }
+        Err(AssociatedTokenAccountError::BumpSearchLimitExceeded.into())
     }
 
     /// Derives the associated token account address for the given wallet
@@ -0,0 +1,130 @@
+//! Validation helpers for programs accepting associated token accounts.
+
+use {
+    crate::{error::AssociatedTokenAccountError, pda::AssociatedTokenPda},
+    pinocchio::{AccountView, Address, error::ProgramError},
+};
+
+/// Length of the base token account layout shared by SPL Token and Token-2022.
+const TOKEN_ACCOUNT_BASE_LEN: usize = 165;
+const MINT_OFFSET: usize = 0;
+const OWNER_OFFSET: usize = 32;
+const OWNER_END: usize = 64;
+const STATE_OFFSET: usize = 108;
+
+/// Asserts that `account` is the initialized associated token account of
+/// `wallet` for `mint` under `token_program`.
+///
+/// Checks, in order, that the address matches the ATA derivation, that the
+/// account is owned by `token_program`, and that its token account `mint` and
+/// `owner` fields match. Works for SPL Token and Token-2022 accounts alike.
+pub fn assert_is_ata_of(
+    account: &AccountView,
+    wallet: &Address,
+    mint: &Address,
+    token_program: &Address,
+) -> Result<(), ProgramError> {
+    let expected = AssociatedTokenPda::derive_address(&crate::ID, wallet, token_program, mint);
+    if *account.address() != expected {
+        return Err(ProgramError::InvalidSeeds);
+    }
+    if !account.owned_by(token_program) {
+        return Err(ProgramError::IllegalOwner);
+    }
+
+    let data = account.try_borrow()?;
+    assert_token_account_fields(&data, wallet, mint)
+}
+
+/// Checks the base token account fields of an ATA's `data`.
+#[inline(always)]
+fn assert_token_account_fields(
+    data: &[u8],
+    wallet: &Address,
+    mint: &Address,
+) -> Result<(), ProgramError> {
+    if data.len() < TOKEN_ACCOUNT_BASE_LEN {
+        return Err(ProgramError::InvalidAccountData);
+    }
+    // `AccountState::Uninitialized` is zero
+    if data[STATE_OFFSET] == 0 {
+        return Err(ProgramError::UninitializedAccount);
+    }
+    if data[MINT_OFFSET..OWNER_OFFSET] != *mint.as_ref() {
+        return Err(ProgramError::InvalidAccountData);
+    }
+    if data[OWNER_OFFSET..OWNER_END] != *wallet.as_ref() {
+        return Err(AssociatedTokenAccountError::InvalidOwner.into());
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use {
+        super::{
+            OWNER_END, OWNER_OFFSET, STATE_OFFSET, TOKEN_ACCOUNT_BASE_LEN,
+            assert_token_account_fields,
+        },
+        crate::error::AssociatedTokenAccountError,
+        pinocchio::{Address, error::ProgramError},
+    };
+
+    const WALLET: Address = Address::new_from_array([1; 32]);
+    const MINT: Address = Address::new_from_array([2; 32]);
+
+    fn token_account_data(
+        mint: &Address,
+        owner: &Address,
+        state: u8,
+    ) -> [u8; TOKEN_ACCOUNT_BASE_LEN] {
+        let mut data = [0; TOKEN_ACCOUNT_BASE_LEN];
+        data[..OWNER_OFFSET].copy_from_slice(mint.as_ref());
+        data[OWNER_OFFSET..OWNER_END].copy_from_slice(owner.as_ref());
+        data[STATE_OFFSET] = state;
+        data
+    }
+
+    #[test]
+    fn accepts_matching_fields() {
+        let data = token_account_data(&MINT, &WALLET, 1);
+        assert_eq!(assert_token_account_fields(&data, &WALLET, &MINT), Ok(()));
+    }
+
+    #[test]
+    fn accepts_trailing_extension_data() {
+        let mut data = [0; 170];
+        data[..TOKEN_ACCOUNT_BASE_LEN].copy_from_slice(&token_account_data(&MINT, &WALLET, 1));
+        assert_eq!(assert_token_account_fields(&data, &WALLET, &MINT), Ok(()));
+    }
+
+    #[test]
+    fn rejects_mismatches() {
+        let other = Address::new_from_array([3; 32]);
+        let cases: &[(&[u8], ProgramError)] = &[
+            (
+                &token_account_data(&MINT, &WALLET, 1)[..164],
+                ProgramError::InvalidAccountData,
+            ),
+            (
+                &token_account_data(&MINT, &WALLET, 0),
+                ProgramError::UninitializedAccount,
+            ),
+            (
+                &token_account_data(&other, &WALLET, 1),
+                ProgramError::InvalidAccountData,
+            ),
+            (
+                &token_account_data(&MINT, &other, 1),
+                AssociatedTokenAccountError::InvalidOwner.into(),
+            ),
+        ];
+
+        for (data, expected) in cases {
+            assert_eq!(
+                assert_token_account_fields(data, &WALLET, &MINT).unwrap_err(),
+                *expected
+            );
+        }
+    }
+}
@@ -0,0 +1,137 @@
+//! Return data of the `GetVersion` instruction.
+
+use pinocchio::error::ProgramError;
+
+/// `Create` and `CreateIdempotent` accept an ATA address that already holds
+/// lamports but is not yet allocated.
+pub const FEATURE_PREFUNDED: u32 = 1 << 0;
+/// `CreateWithArgs` and `CreateCompact` accept a bump seed hint.
+pub const FEATURE_BUMP_HINT: u32 = 1 << 1;
+/// `CreateWithArgs` and `CreateCompact` accept an account length hint.
+pub const FEATURE_ACCOUNT_LEN_HINT: u32 = 1 << 2;
+/// `CreateCompact` is supported.
+pub const FEATURE_CREATE_COMPACT: u32 = 1 << 3;
+/// `CreateWithBumpLimit` is supported.
+pub const FEATURE_BUMP_LIMIT: u32 = 1 << 4;
+/// `AssertAtaExists` is supported.
+pub const FEATURE_ASSERT_ATA_EXISTS: u32 = 1 << 5;
+/// `CreateMany` is supported.
+pub const FEATURE_CREATE_MANY: u32 = 1 << 6;
+/// `RecoverNestedWithArgs` is supported.
+pub const FEATURE_RECOVER_NESTED_BUMP_HINT: u32 = 1 << 7;
+/// `RecoverNestedIdempotent` is supported.
+pub const FEATURE_RECOVER_NESTED_IDEMPOTENT: u32 = 1 << 8;
+/// `Close` is supported.
+pub const FEATURE_CLOSE: u32 = 1 << 9;
+/// `CloseEmpty` is supported.
+pub const FEATURE_CLOSE_EMPTY: u32 = 1 << 10;
+/// `CreateWrapped` is supported.
+pub const FEATURE_CREATE_WRAPPED: u32 = 1 << 11;
+/// `CreateWithDeposit` is supported.
+pub const FEATURE_CREATE_WITH_DEPOSIT: u32 = 1 << 12;
+/// `CreateConfidential` is supported.
+pub const FEATURE_CREATE_CONFIDENTIAL: u32 = 1 << 13;
+/// `RecoverNestedWithMemo` is supported.
+pub const FEATURE_RECOVER_NESTED_WITH_MEMO: u32 = 1 << 14;
+/// `ReclaimUninitialized` is supported.
+pub const FEATURE_RECLAIM_UNINITIALIZED: u32 = 1 << 15;
+/// Token-2022 mints whose extensions cannot be parsed locally are sized with
+/// the token program's `GetAccountDataSize` instead of being rejected.
+pub const FEATURE_ACCOUNT_DATA_SIZE_CPI: u32 = 1 << 16;
+
+/// Semantic version and supported features of a deployed program.
+///
+/// Encoded little-endian as `[major: u16, minor: u16, patch: u16, features: u32]`.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct ProgramVersion {
+    /// Major version.
+    pub major: u16,
+    /// Minor version.
+    pub minor: u16,
+    /// Patch version.
+    pub patch: u16,
+    /// Bitmask of `FEATURE_*` flags.
+    pub features: u32,
+}
+
+impl ProgramVersion {
+    /// Encoded length in bytes.
+    pub const LEN: usize = 10;
+
+    /// Encodes the version as return data.
+    pub const fn to_bytes(&self) -> [u8; Self::LEN] {
+        let major = self.major.to_le_bytes();
+        let minor = self.minor.to_le_bytes();
+        let patch = self.patch.to_le_bytes();
+        let features = self.features.to_le_bytes();
+        [
+            major[0],
+            major[1],
+            minor[0],
+            minor[1],
+            patch[0],
+            patch[1],
+            features[0],
+            features[1],
+            features[2],
+            features[3],
+        ]
+    }
+
+    /// Decodes the version from return data.
+    ///
+    /// Trailing bytes are ignored so that later versions can append fields.
+    pub fn try_from_bytes(bytes: &[u8]) -> Result<Self, ProgramError> {
+        let bytes: &[u8; Self::LEN] = bytes
+            .get(..Self::LEN)
+            .and_then(|bytes| bytes.try_into().ok())
+            .ok_or(ProgramError::InvalidAccountData)?;
+        Ok(Self {
+            major: u16::from_le_bytes([bytes[0], bytes[1]]),
+            minor: u16::from_le_bytes([bytes[2], bytes[3]]),
+            patch: u16::from_le_bytes([bytes[4], bytes[5]]),
+            features: u32::from_le_bytes([bytes[6], bytes[7], bytes[8], bytes[9]]),
+        })
+    }
+
+    /// Whether all of the given `FEATURE_*` flags are supported.
+    pub const fn supports(&self, features: u32) -> bool {
+        self.features & features == features
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use {
+        super::{FEATURE_ACCOUNT_LEN_HINT, FEATURE_BUMP_HINT, FEATURE_PREFUNDED, ProgramVersion},
+        pinocchio::error::ProgramError,
+    };
+
+    #[test]
+    fn version_round_trips() {
+        let version = ProgramVersion {
+            major: 1,
+            minor: 258,
+            patch: 3,
+            features: FEATURE_PREFUNDED | FEATURE_ACCOUNT_LEN_HINT,
+        };
+        let bytes = version.to_bytes();
+        assert_eq!(bytes, [1, 0, 2, 1, 3, 0, 5, 0, 0, 0]);
+        assert_eq!(ProgramVersion::try_from_bytes(&bytes), Ok(version));
+
+        assert!(version.supports(FEATURE_PREFUNDED));
+        assert!(version.supports(FEATURE_PREFUNDED | FEATURE_ACCOUNT_LEN_HINT));
+        assert!(!version.supports(FEATURE_PREFUNDED | FEATURE_BUMP_HINT));
+    }
+
+    #[test]
+    fn version_ignores_trailing_bytes_and_rejects_short_data() {
+        let mut bytes = [0; ProgramVersion::LEN + 2];
+        bytes[0] = 7;
+        assert_eq!(ProgramVersion::try_from_bytes(&bytes).unwrap().major, 7);
+        assert_eq!(
+            ProgramVersion::try_from_bytes(&bytes[..ProgramVersion::LEN - 1]),
+            Err(ProgramError::InvalidAccountData)
+        );
+    }
+}
@@ -2,12 +2,21 @@ use {
     mollusk_svm::Mollusk,
     mollusk_svm_bencher::MolluskComputeUnitBencher,
     mollusk_svm_programs_token::{token, token2022},
-    pinocchio_associated_token_account_interface::instruction::CreateMode,
+    pinocchio_associated_token_account_interface::{
+        account_indices::{
+            CREATE_ASSOCIATED_TOKEN_ACCOUNT, CREATE_FUNDER, CREATE_MINT, CREATE_TOKEN_PROGRAM,
+            CREATE_WALLET, RECOVER_NESTED_WALLET,
+        },
+        instruction::{AssociatedTokenAccountInstruction, CreateMode},
+        version::ProgramVersion,
+    },
+    sha2::{Digest, Sha256},
     solana_account::Account,
     solana_address::Address,
     solana_instruction::{AccountMeta, Instruction},
     solana_program_option::COption,
     solana_program_pack::Pack,
+    solana_svm_log_collector::LogCollector,
     solana_system_interface::program as system_program,
     spl_associated_token_account_interface::{
         address::{
@@ -29,7 +38,7 @@ use {
         },
         state::Mint as Token2022Mint,
     },
-    spl_token_interface::state::{Account as TokenAccount, AccountState, Mint},
+    spl_token_interface::state::{Account as TokenAccount, AccountState, Mint, Multisig},
     std::path::PathBuf,
 };
 
@@ -184,14 +193,98 @@ fn recover_nested_case(
     (ix, accs)
 }
 
-fn main() {
-    solana_logger::setup_with("");
+/// `recover_nested` (spl-token) authorized by an `m`-of-`m` multisig wallet
+fn recover_nested_multisig_case(
+    seed: u8,
+    m: u8,
+    spl_token_account: &(Address, Account),
+    t22_account: &(Address, Account),
+) -> (Instruction, Vec<(Address, Account)>) {
+    let wallet = Address::new_from_array([seed; 32]);
+    let (mut ix, mut accs) = recover_nested_case(
+        wallet,
+        Address::new_from_array([seed.wrapping_add(1); 32]),
+        Address::new_from_array([seed.wrapping_add(2); 32]),
+        spl_token_interface::id(),
+        spl_token_interface::id(),
+        spl_token_account,
+        t22_account,
+    );
 
-    let mut mollusk = Mollusk::new(
-        &ata_program_id(),
-        "pinocchio_associated_token_account_program",
+    let signers: Vec<Address> = (0..m)
+        .map(|i| Address::new_from_array([seed.wrapping_add(3).wrapping_add(i); 32]))
+        .collect();
+    let mut multisig = Multisig {
+        m,
+        n: m,
+        is_initialized: true,
+        ..Multisig::default()
+    };
+    multisig.signers[..signers.len()].copy_from_slice(&signers);
+    let mut multisig_data = vec![0; Multisig::LEN];
+    Multisig::pack(multisig, &mut multisig_data).unwrap();
+
+    // The multisig wallet does not sign, and the nested token program is required
+    ix.accounts[RECOVER_NESTED_WALLET] = AccountMeta::new(wallet, false);
+    ix.accounts
+        .push(AccountMeta::new_readonly(spl_token_interface::id(), false));
+    ix.accounts.extend(
+        signers
+            .iter()
+            .map(|signer| AccountMeta::new_readonly(*signer, true)),
     );
-    token::add_program(&mut mollusk);
+    accs[RECOVER_NESTED_WALLET] = (
+        wallet,
+        Account {
+            lamports: solana_rent::Rent::default().minimum_balance(Multisig::LEN),
+            data: multisig_data,
+            owner: spl_token_interface::id(),
+            executable: false,
+            rent_epoch: 0,
+        },
+    );
+    accs.extend(
+        signers
+            .iter()
+            .map(|signer| (*signer, Account::new(1_000_000, 0, &system_program::id()))),
+    );
+
+    (ix, accs)
+}
+
+/// `UpgradeableLoaderState::ProgramData` header before the ELF bytes
+const PROGRAMDATA_METADATA_LEN: usize = 45;
+/// `UpgradeableLoaderState::Program` account size
+const PROGRAM_ACCOUNT_LEN: usize = 36;
+
+fn sbf_out_dir() -> PathBuf {
+    std::env::var_os("SBF_OUT_DIR")
+        .map(PathBuf::from)
+        .unwrap_or_else(|| PathBuf::from(env!("CARGO_MANIFEST_DIR")).join("../../target/deploy"))
+}
+
+/// Version and `GetVersion` feature flags of the ATA program loaded in
+/// `mollusk`, so rows measured against different builds cannot be mistaken for
+/// one another. Builds without `GetVersion`, like the legacy program, are
+/// labelled as such.
+fn program_build(mollusk: &Mollusk) -> String {
+    let get_version = Instruction {
+        program_id: ata_program_id(),
+        accounts: vec![],
+        data: vec![AssociatedTokenAccountInstruction::GetVersion.discriminator()],
+    };
+    let result = mollusk.process_instruction(&get_version, &[]);
+    match ProgramVersion::try_from_bytes(&result.return_data) {
+        Ok(version) if result.program_result.is_ok() => format!(
+            "{}.{}.{} (features {:#x})",
+            version.major, version.minor, version.patch, version.features
+        ),
+        _ => String::from("no GetVersion"),
+    }
+}
+
+fn add_token_programs(mollusk: &mut Mollusk) {
+    token::add_program(mollusk);
 
     // Load Token-2022 with batch instruction support
     let t22_elf_path = PathBuf::from(env!("CARGO_MANIFEST_DIR"))
@@ -202,6 +295,828 @@ fn main() {
         &mollusk_svm::program::loader_keys::LOADER_V3,
         &t22_elf,
     );
+}
+
+/// Runs every case again against the build without default features, which
+/// drops the optional logging, if `make build-sbf-no-logs-pinocchio-program`
+/// has placed one in `SBF_OUT_DIR/no-logs`, and writes both compute unit
+/// counts side by side.
+fn write_logging_overhead(
+    mollusk: &Mollusk,
+    cases: &[(&str, &Instruction, &[(Address, Account)])],
+) {
+    let no_logs_elf_path =
+        sbf_out_dir().join("no-logs/pinocchio_associated_token_account_program.so");
+    if !no_logs_elf_path.exists() {
+        return;
+    }
+    let mut no_logs_mollusk = Mollusk::default();
+    no_logs_mollusk.add_program_with_loader_and_elf(
+        &ata_program_id(),
+        &mollusk_svm::program::loader_keys::LOADER_V3,
+        &mollusk_svm::file::read_file(no_logs_elf_path),
+    );
+    add_token_programs(&mut no_logs_mollusk);
+
+    let build = program_build(mollusk);
+    let no_logs_build = program_build(&no_logs_mollusk);
+
+    let mut report = String::from(concat!(
+        "| Name | Build | Build without logs | CUs | CUs without logs | Logging overhead |\n",
+        "|------|-------|--------------------|-----|------------------|------------------|\n",
+    ));
+    for (name, ix, accs) in cases {
+        let with_logs = mollusk.process_instruction(ix, accs).compute_units_consumed;
+        let without_logs = no_logs_mollusk
+            .process_instruction(ix, accs)
+            .compute_units_consumed;
+        report.push_str(&format!(
+            "| {name} | {build} | {no_logs_build} | {with_logs} | {without_logs} | {} |\n",
+            (with_logs as i64).saturating_sub(without_logs as i64)
+        ));
+    }
+
+    std::fs::write(
+        PathBuf::from(env!("CARGO_MANIFEST_DIR")).join("benches/logging_overhead.md"),
+        report,
+    )
+    .unwrap();
+}
+
+/// Runs `ix` and returns whether it succeeded and its log lines, without the
+/// compute unit consumption lines, which always differ between
+/// implementations.
+fn program_logs(
+    mollusk: &mut Mollusk,
+    ix: &Instruction,
+    accs: &[(Address, Account)],
+) -> (bool, Vec<String>) {
+    let logger = LogCollector::new_ref();
+    let previous_logger = mollusk.logger.replace(logger.clone());
+    let succeeded = mollusk.process_instruction(ix, accs).program_result.is_ok();
+    mollusk.logger = previous_logger;
+
+    let logs = logger
+        .borrow()
+        .get_recorded_content()
+        .iter()
+        .filter(|line| !line.ends_with(" compute units"))
+        .cloned()
+        .collect();
+    (succeeded, logs)
+}
+
+/// Line diff of `legacy` against `pinocchio`, prefixed `-` and `+` like a
+/// unified diff, or `None` if the lines are the same
+fn log_diff(legacy: &[String], pinocchio: &[String]) -> Option<String> {
+    if legacy == pinocchio {
+        return None;
+    }
+    // Longest common subsequence lengths of every pair of suffixes
+    let mut common =
+        vec![vec![0usize; pinocchio.len().saturating_add(1)]; legacy.len().saturating_add(1)];
+    for i in (0..legacy.len()).rev() {
+        for j in (0..pinocchio.len()).rev() {
+            common[i][j] = if legacy[i] == pinocchio[j] {
+                common[i.saturating_add(1)][j.saturating_add(1)].saturating_add(1)
+            } else {
+                common[i.saturating_add(1)][j].max(common[i][j.saturating_add(1)])
+            };
+        }
+    }
+
+    let mut diff = String::new();
+    let (mut i, mut j) = (0, 0);
+    while i < legacy.len() || j < pinocchio.len() {
+        if i < legacy.len() && j < pinocchio.len() && legacy[i] == pinocchio[j] {
+            diff.push_str(&format!("  {}\n", legacy[i]));
+            i = i.saturating_add(1);
+            j = j.saturating_add(1);
+        } else if j < pinocchio.len()
+            && (i == legacy.len()
+                || common[i][j.saturating_add(1)] >= common[i.saturating_add(1)][j])
+        {
+            diff.push_str(&format!("+ {}\n", pinocchio[j]));
+            j = j.saturating_add(1);
+        } else {
+            diff.push_str(&format!("- {}\n", legacy[i]));
+            i = i.saturating_add(1);
+        }
+    }
+    Some(diff)
+}
+
+/// Mollusk with the legacy program in place of p-ATA, if its build is in
+/// `SBF_OUT_DIR`
+fn legacy_mollusk() -> Option<Mollusk> {
+    let legacy_elf_path = sbf_out_dir().join("spl_associated_token_account.so");
+    if !legacy_elf_path.exists() {
+        return None;
+    }
+    let mut legacy_mollusk = Mollusk::default();
+    legacy_mollusk.add_program_with_loader_and_elf(
+        &ata_program_id(),
+        &mollusk_svm::program::loader_keys::LOADER_V3,
+        &mollusk_svm::file::read_file(legacy_elf_path),
+    );
+    add_token_programs(&mut legacy_mollusk);
+    Some(legacy_mollusk)
+}
+
+/// Runs every case against the legacy program too, if its build is in
+/// `SBF_OUT_DIR`, and rewrites `benches/log_divergences.md` with the log lines
+/// that differ, for indexers that parse program logs. Cases the legacy program
+/// rejects while p-ATA accepts them use p-ATA-only instructions and are only
+/// listed.
+fn write_log_divergences(
+    mollusk: &mut Mollusk,
+    cases: &[(&str, &Instruction, &[(Address, Account)])],
+) {
+    let Some(mut legacy_mollusk) = legacy_mollusk() else {
+        return;
+    };
+
+    let mut report = String::from(concat!(
+        "# Log divergences\n",
+        "\n",
+        "<!-- Generated by `make bench-pinocchio-program` -->\n",
+        "\n",
+        "Log lines of each case under the legacy program (`-`) and p-ATA (`+`), ignoring\n",
+        "compute unit consumption lines. Cases with identical logs are omitted.\n",
+    ));
+    report.push_str(&format!(
+        "\nLegacy build: {}. p-ATA build: {}.\n",
+        program_build(&legacy_mollusk),
+        program_build(mollusk)
+    ));
+    let mut pinocchio_only = vec![];
+    for &(name, ix, accs) in cases {
+        let (legacy_succeeded, legacy_logs) = program_logs(&mut legacy_mollusk, ix, accs);
+        let (succeeded, logs) = program_logs(mollusk, ix, accs);
+        if succeeded && !legacy_succeeded {
+            pinocchio_only.push(name);
+            continue;
+        }
+        if let Some(diff) = log_diff(&legacy_logs, &logs) {
+            report.push_str(&format!("\n## {name}\n\n```diff\n{diff}```\n"));
+        }
+    }
+    if !pinocchio_only.is_empty() {
+        report.push_str("\n## Not supported by the legacy program\n\n");
+        for name in pinocchio_only {
+            report.push_str(&format!("- {name}\n"));
+        }
+    }
+
+    std::fs::write(
+        PathBuf::from(env!("CARGO_MANIFEST_DIR")).join("benches/log_divergences.md"),
+        report,
+    )
+    .unwrap();
+}
+
+/// Checks every case against `benches/bench_thresholds.toml`, panicking with
+/// each case over its compute unit budget or without its required legacy
+/// compatibility status, and each reject case accepted or over its budget to
+/// reject. Statuses are only checked if the legacy build is in `SBF_OUT_DIR`.
+fn check_thresholds(
+    mollusk: &Mollusk,
+    cases: &[(&str, &Instruction, &[(Address, Account)])],
+    reject_cases: &[(&str, &Instruction, &[(Address, Account)])],
+) {
+    let thresholds_path =
+        PathBuf::from(env!("CARGO_MANIFEST_DIR")).join("benches/bench_thresholds.toml");
+    let thresholds: toml::Table = std::fs::read_to_string(&thresholds_path)
+        .unwrap()
+        .parse()
+        .unwrap_or_else(|err| panic!("{}: {err}", thresholds_path.display()));
+    for name in thresholds.keys() {
+        assert!(
+            cases
+                .iter()
+                .chain(reject_cases)
+                .any(|(case, _, _)| case == name),
+            "{}: no bench case named `{name}`",
+            thresholds_path.display()
+        );
+    }
+    let legacy_mollusk = legacy_mollusk();
+
+    let mut failures = vec![];
+    for &(name, ix, accs) in cases {
+        let Some(case) = thresholds.get(name) else {
+            continue;
+        };
+        let case = case
+            .as_table()
+            .unwrap_or_else(|| panic!("{name}: thresholds are not a table"));
+        for key in case.keys() {
+            assert!(
+                matches!(key.as_str(), "max_compute_units" | "legacy"),
+                "{name}: unknown threshold `{key}`"
+            );
+        }
+
+        if let Some(max_compute_units) = case.get("max_compute_units") {
+            let max_compute_units = max_compute_units
+                .as_integer()
+                .and_then(|max| u64::try_from(max).ok())
+                .unwrap_or_else(|| {
+                    panic!("{name}: `max_compute_units` is not a compute unit count")
+                });
+            let compute_units = mollusk.process_instruction(ix, accs).compute_units_consumed;
+            if compute_units > max_compute_units {
+                failures.push(format!(
+                    "{name}: {compute_units} CUs, over the budget of {max_compute_units}"
+                ));
+            }
+        }
+
+        if let (Some(legacy), Some(legacy_mollusk)) = (case.get("legacy"), &legacy_mollusk) {
+            let required_success = match legacy.as_str() {
+                Some("compatible") => true,
+                Some("pinocchio-only") => false,
+                _ => panic!("{name}: `legacy` is neither \"compatible\" nor \"pinocchio-only\""),
+            };
+            let legacy_succeeded = legacy_mollusk
+                .process_instruction(ix, accs)
+                .program_result
+                .is_ok();
+            if legacy_succeeded != required_success {
+                failures.push(format!(
+                    "{name}: expected to be {} under the legacy program",
+                    if required_success {
+                        "accepted"
+                    } else {
+                        "rejected"
+                    }
+                ));
+            }
+        }
+    }
+    for &(name, ix, accs) in reject_cases {
+        let Some(case) = thresholds.get(name) else {
+            continue;
+        };
+        let case = case
+            .as_table()
+            .unwrap_or_else(|| panic!("{name}: thresholds are not a table"));
+        for key in case.keys() {
+            assert!(
+                key == "max_compute_units",
+                "{name}: unknown threshold `{key}` for a reject case"
+            );
+        }
+
+        let result = mollusk.process_instruction(ix, accs);
+        if result.program_result.is_ok() {
+            failures.push(format!("{name}: expected to be rejected"));
+            continue;
+        }
+        if let Some(max_compute_units) = case.get("max_compute_units") {
+            let max_compute_units = max_compute_units
+                .as_integer()
+                .and_then(|max| u64::try_from(max).ok())
+                .unwrap_or_else(|| {
+                    panic!("{name}: `max_compute_units` is not a compute unit count")
+                });
+            if result.compute_units_consumed > max_compute_units {
+                failures.push(format!(
+                    "{name}: {} CUs to reject, over the budget of {max_compute_units}",
+                    result.compute_units_consumed
+                ));
+            }
+        }
+    }
+    assert!(
+        failures.is_empty(),
+        "bench thresholds not met:\n{}",
+        failures.join("\n")
+    );
+}
+
+/// Rewrites `benches/reject_costs.md` with the error and compute units of
+/// each reject case, the failures clients hit most often.
+fn write_reject_costs(
+    mollusk: &Mollusk,
+    reject_cases: &[(&str, &Instruction, &[(Address, Account)])],
+) {
+    let build = program_build(mollusk);
+    let mut report = String::from(concat!(
+        "| Name | Build | Error | CUs to reject |\n",
+        "|------|-------|-------|---------------|\n",
+    ));
+    for (name, ix, accs) in reject_cases {
+        let result = mollusk.process_instruction(ix, accs);
+        report.push_str(&format!(
+            "| {name} | {build} | {:?} | {} |\n",
+            result.program_result, result.compute_units_consumed
+        ));
+    }
+
+    std::fs::write(
+        PathBuf::from(env!("CARGO_MANIFEST_DIR")).join("benches/reject_costs.md"),
+        report,
+    )
+    .unwrap();
+}
+
+/// Outcome of `ix` as recorded in the certificate: `"ok"` or the error, and the
+/// compute units consumed
+fn certified_outcome(
+    mollusk: &Mollusk,
+    ix: &Instruction,
+    accs: &[(Address, Account)],
+) -> serde_json::Value {
+    let result = mollusk.process_instruction(ix, accs);
+    serde_json::json!({
+        "result": if result.program_result.is_ok() {
+            String::from("ok")
+        } else {
+            format!("{:?}", result.program_result)
+        },
+        "compute_units": result.compute_units_consumed,
+    })
+}
+
+/// SHA-256 of `link` appended to the previous `chain` hash, both hex encoded
+fn chain_hash(chain: &str, link: &[u8]) -> String {
+    let mut hasher = Sha256::new();
+    hasher.update(chain.as_bytes());
+    hasher.update(link);
+    format!("{:x}", hasher.finalize())
+}
+
+/// Output of `git` with `args` in the repository, trimmed
+fn git(args: &[&str]) -> String {
+    let output = std::process::Command::new("git")
+        .args(args)
+        .current_dir(env!("CARGO_MANIFEST_DIR"))
+        .output()
+        .unwrap_or_else(|err| panic!("certify: cannot run git: {err}"));
+    assert!(output.status.success(), "certify: git {args:?} failed");
+    String::from_utf8(output.stdout).unwrap().trim().to_string()
+}
+
+/// Runs every success and reject case against both p-ATA and the legacy
+/// program, and writes `benches/certificate.json` with the outcome of each
+/// under both and whether they agree.
+///
+/// The certificate is hash-chained: the chain starts from the hash of the git
+/// commit, then takes in the SHA-256 of both program binaries and every case
+/// record in order, each record carrying the chain hash after it. Recomputing
+/// the chain from the binaries an integrator deploys and the records shows
+/// whether the certificate describes those binaries, and the first record
+/// whose hash differs locates any edit.
+fn write_certificate(
+    mollusk: &Mollusk,
+    cases: &[(&str, &Instruction, &[(Address, Account)])],
+    reject_cases: &[(&str, &Instruction, &[(Address, Account)])],
+) {
+    let legacy_mollusk = legacy_mollusk()
+        .expect("certify: the legacy build must be in SBF_OUT_DIR, run `make build-sbf-program`");
+
+    let commit = git(&["rev-parse", "HEAD"]);
+    let dirty = !git(&["status", "--porcelain"]).is_empty();
+    let mut chain = chain_hash("", commit.as_bytes());
+
+    let mut binaries = vec![];
+    for (program, build) in [
+        (
+            "pinocchio_associated_token_account_program",
+            program_build(mollusk),
+        ),
+        (
+            "spl_associated_token_account",
+            program_build(&legacy_mollusk),
+        ),
+    ] {
+        let elf = std::fs::read(sbf_out_dir().join(format!("{program}.so"))).unwrap();
+        let sha256 = format!("{:x}", Sha256::digest(&elf));
+        chain = chain_hash(&chain, sha256.as_bytes());
+        binaries.push(serde_json::json!({
+            "program": program,
+            "build": build,
+            "sha256": sha256,
+            "chain": chain,
+        }));
+    }
+
+    let mut records = vec![];
+    let mut divergent = 0usize;
+    let kinds = cases
+        .iter()
+        .map(|case| ("success", case))
+        .chain(reject_cases.iter().map(|case| ("reject", case)));
+    for (kind, &(name, ix, accs)) in kinds {
+        let pinocchio = certified_outcome(mollusk, ix, accs);
+        let legacy = certified_outcome(&legacy_mollusk, ix, accs);
+        let parity = match (pinocchio["result"].as_str(), legacy["result"].as_str()) {
+            (p, l) if p == l => "identical",
+            (Some("ok"), _) => "pinocchio-only",
+            (_, Some("ok")) => "legacy-only",
+            _ => "different-error",
+        };
+        if parity != "identical" {
+            divergent = divergent.saturating_add(1);
+        }
+        let mut record = serde_json::json!({
+            "name": name,
+            "kind": kind,
+            "pinocchio": pinocchio,
+            "legacy": legacy,
+            "parity": parity,
+        });
+        chain = chain_hash(&chain, record.to_string().as_bytes());
+        record["chain"] = chain.clone().into();
+        records.push(record);
+    }
+
+    let certificate = serde_json::json!({
+        "version": 1,
+        "commit": commit,
+        "dirty": dirty,
+        "binaries": binaries,
+        "summary": {
+            "cases": records.len(),
+            "identical": records.len().saturating_sub(divergent),
+            "divergent": divergent,
+        },
+        "cases": records,
+        "certificate_hash": chain,
+    });
+    std::fs::write(
+        PathBuf::from(env!("CARGO_MANIFEST_DIR")).join("benches/certificate.json"),
+        serde_json::to_string_pretty(&certificate).unwrap(),
+    )
+    .unwrap();
+}
+
+/// Cumulative `CreateWithArgs` inputs compared against the plain instruction:
+/// name, bump hint, account length hint and rent sysvar account.
+const ENCODINGS: [(&str, bool, bool, bool); 4] = [
+    ("plain", false, false, false),
+    ("bump", true, false, false),
+    ("bump + account_len", true, true, false),
+    ("bump + account_len + rent sysvar", true, true, true),
+];
+
+/// Transaction bytes added by an account not otherwise in the transaction:
+/// its key and its index in the instruction
+const EXTRA_ACCOUNT_TX_LEN: usize = 33;
+
+/// Measures every encoding of each plain `Create` / `CreateIdempotent` case,
+/// and rewrites `benches/adoption_guidance.md` with the savings and extra
+/// transaction bytes of each, recommending the cheapest encoding per case.
+fn write_adoption_guidance(
+    mollusk: &Mollusk,
+    use_cases: &[(&str, &Instruction, &[(Address, Account)], u32)],
+    rent_sysvar: &(Address, Account),
+) {
+    let mut report = String::from(concat!(
+        "# Adoption guidance\n",
+        "\n",
+        "<!-- Generated by `make bench-pinocchio-program` -->\n",
+    ));
+    for &(name, ix, accs, account_len) in use_cases {
+        let mode = if ix.data == [1] {
+            CreateMode::Idempotent
+        } else {
+            CreateMode::Always
+        };
+        let (_, bump) = get_associated_token_address_and_bump_seed(
+            &ix.accounts[CREATE_WALLET].pubkey,
+            &ix.accounts[CREATE_MINT].pubkey,
+            &ata_program_id(),
+            &ix.accounts[CREATE_TOKEN_PROGRAM].pubkey,
+        );
+
+        // Name, CUs and extra transaction bytes of each encoding
+        let measured: Vec<(&str, u64, usize)> = ENCODINGS
+            .iter()
+            .map(|&(encoding, bump_hint, account_len_hint, rent)| {
+                if !bump_hint {
+                    let result = mollusk.process_instruction(ix, accs);
+                    assert!(result.program_result.is_ok(), "{name}: {encoding}");
+                    return (encoding, result.compute_units_consumed, 0);
+                }
+                let mut instruction = ix.clone();
+                instruction.data =
+                    encode_create_ata_instruction_data(&CreateAtaInstructionType::CreateWithArgs {
+                        mode,
+                        bump: Some(bump),
+                        account_len: account_len_hint.then_some(account_len),
+                        rent_sysvar: rent,
+                    });
+                let mut accounts = accs.to_vec();
+                let mut extra_len = instruction.data.len().saturating_sub(ix.data.len());
+                if rent {
+                    instruction
+                        .accounts
+                        .push(AccountMeta::new_readonly(rent_sysvar.0, false));
+                    accounts.push(rent_sysvar.clone());
+                    extra_len = extra_len.saturating_add(EXTRA_ACCOUNT_TX_LEN);
+                }
+                let result = mollusk.process_instruction(&instruction, &accounts);
+                assert!(result.program_result.is_ok(), "{name}: {encoding}");
+                (encoding, result.compute_units_consumed, extra_len)
+            })
+            .collect();
+
+        let plain_compute_units = measured[0].1;
+        let saved = |compute_units: u64| plain_compute_units.saturating_sub(compute_units);
+        report.push_str(&format!(
+            concat!(
+                "\n## {}\n",
+                "\n",
+                "| Encoding | CUs | Saved CUs | Extra transaction bytes |\n",
+                "|----------|-----|-----------|-------------------------|\n",
+            ),
+            name
+        ));
+        for &(encoding, compute_units, extra_len) in &measured {
+            report.push_str(&format!(
+                "| {encoding} | {compute_units} | {} | {extra_len} |\n",
+                saved(compute_units)
+            ));
+        }
+
+        let cheapest = |candidates: &[(&'static str, u64, usize)]| {
+            candidates
+                .iter()
+                .copied()
+                .min_by_key(|&(_, compute_units, extra_len)| (compute_units, extra_len))
+                .unwrap()
+        };
+        let (encoding, compute_units, extra_len) = cheapest(&measured);
+        report.push_str(&format!(
+            "\nRecommended: `{encoding}`, saving {} CUs ({:.1}%) for {extra_len} extra bytes.",
+            saved(compute_units),
+            saved(compute_units) as f64 * 100f64 / plain_compute_units as f64
+        ));
+        // The rent sysvar costs the most bytes, so also give the best choice for
+        // transactions close to the size limit
+        let (encoding, compute_units, extra_len) =
+            cheapest(&measured[..measured.len().saturating_sub(1)]);
+        report.push_str(&format!(
+            " Without the rent sysvar account: `{encoding}`, saving {} CUs for {extra_len} extra \
+             bytes.\n",
+            saved(compute_units)
+        ));
+    }
+
+    std::fs::write(
+        PathBuf::from(env!("CARGO_MANIFEST_DIR")).join("benches/adoption_guidance.md"),
+        report,
+    )
+    .unwrap();
+}
+
+/// Writes the number of accounts and the total bytes of account data passed to
+/// each case next to its compute units, to separate what scales with mint
+/// extension data from fixed overhead.
+fn write_input_sizes(mollusk: &Mollusk, cases: &[(&str, &Instruction, &[(Address, Account)])]) {
+    let build = program_build(mollusk);
+    let mut report = String::from(concat!(
+        "| Name | Build | Accounts | Account data (bytes) | CUs |\n",
+        "|------|-------|----------|----------------------|-----|\n",
+    ));
+    for (name, ix, accs) in cases {
+        let data_len: usize = accs.iter().map(|(_, account)| account.data.len()).sum();
+        let compute_units = mollusk.process_instruction(ix, accs).compute_units_consumed;
+        report.push_str(&format!(
+            "| {name} | {build} | {} | {data_len} | {compute_units} |\n",
+            accs.len()
+        ));
+    }
+
+    std::fs::write(
+        PathBuf::from(env!("CARGO_MANIFEST_DIR")).join("benches/input_sizes.md"),
+        report,
+    )
+    .unwrap();
+}
+
+/// Writes the binary size and estimated deployment rent of each ATA program
+/// build found in `SBF_OUT_DIR` next to the compute unit report.
+fn write_deployment_costs() {
+    let out_dir = sbf_out_dir();
+    let rent = solana_rent::Rent::default();
+
+    let mut report = String::from(concat!(
+        "| Program | Build | Binary size (bytes) | Deployment rent (SOL) |\n",
+        "|---------|-------|---------------------|-----------------------|\n",
+    ));
+    for name in [
+        "pinocchio_associated_token_account_program",
+        "spl_associated_token_account",
+    ] {
+        let elf_path = out_dir.join(format!("{name}.so"));
+        let Ok(metadata) = std::fs::metadata(&elf_path) else {
+            continue;
+        };
+        let mut mollusk = Mollusk::default();
+        mollusk.add_program_with_loader_and_elf(
+            &ata_program_id(),
+            &mollusk_svm::program::loader_keys::LOADER_V3,
+            &mollusk_svm::file::read_file(elf_path),
+        );
+        let binary_len = metadata.len() as usize;
+        // The program account plus a program data account sized to the binary
+        let lamports = rent
+            .minimum_balance(PROGRAMDATA_METADATA_LEN.saturating_add(binary_len))
+            .saturating_add(rent.minimum_balance(PROGRAM_ACCOUNT_LEN));
+        report.push_str(&format!(
+            "| {name} | {} | {binary_len} | {:.4} |\n",
+            program_build(&mollusk),
+            lamports as f64 / 1_000_000_000f64
+        ));
+    }
+
+    std::fs::write(
+        PathBuf::from(env!("CARGO_MANIFEST_DIR")).join("benches/deployment_costs.md"),
+        report,
+    )
+    .unwrap();
+}
+
+/// Largest serialized transaction the network accepts
+const PACKET_DATA_SIZE: usize = 1232;
+
+/// Largest `CreateMany` batch measured by [`write_batch_costs`]
+const MAX_BATCH_LEN: usize = 16;
+
+/// Length of a compact-u16 encoding of `len`
+fn short_vec_len(len: usize) -> usize {
+    match len {
+        0..=0x7f => 1,
+        0x80..=0x3fff => 2,
+        _ => 3,
+    }
+}
+
+/// Serialized length of a legacy transaction holding `instructions`, with one
+/// signature per signer account and no address lookup tables
+fn transaction_len(instructions: &[Instruction]) -> usize {
+    let mut keys: Vec<&Address> = vec![];
+    let mut signers = 0usize;
+    for ix in instructions {
+        for meta in &ix.accounts {
+            if !keys.contains(&&meta.pubkey) {
+                keys.push(&meta.pubkey);
+                if meta.is_signer {
+                    signers = signers.saturating_add(1);
+                }
+            }
+        }
+        if !keys.contains(&&ix.program_id) {
+            keys.push(&ix.program_id);
+        }
+    }
+    let instructions_len = instructions.iter().fold(0usize, |len, ix| {
+        len.saturating_add(1)
+            .saturating_add(short_vec_len(ix.accounts.len()))
+            .saturating_add(ix.accounts.len())
+            .saturating_add(short_vec_len(ix.data.len()))
+            .saturating_add(ix.data.len())
+    });
+    // Signatures, message header, account keys, recent blockhash, instructions
+    short_vec_len(signers)
+        .saturating_add(signers.saturating_mul(64))
+        .saturating_add(3)
+        .saturating_add(short_vec_len(keys.len()))
+        .saturating_add(keys.len().saturating_mul(32))
+        .saturating_add(32)
+        .saturating_add(short_vec_len(instructions.len()))
+        .saturating_add(instructions_len)
+}
+
+/// Creates 1 to [`MAX_BATCH_LEN`] SPL Token ATAs with a single `CreateMany`
+/// and with as many `Create` instructions, and rewrites
+/// `benches/batch_costs.md` with the compute units per created account, the
+/// marginal compute units of each additional account and the transaction size
+/// of both, so integrators can pick a batch size.
+fn write_batch_costs(
+    mollusk: &Mollusk,
+    payer: &(Address, Account),
+    mint: &(Address, Account),
+    system_account: &(Address, Account),
+    token_program_account: &(Address, Account),
+) {
+    let token_program_id = spl_token_interface::id();
+    let wallets: Vec<Address> = (0..MAX_BATCH_LEN).map(|_| Address::new_unique()).collect();
+    let mut data = [0; 2];
+    AssociatedTokenAccountInstruction::CreateMany {
+        mode: CreateMode::Always,
+    }
+    .write_data(&mut data)
+    .unwrap();
+
+    let mut report = String::from(concat!(
+        "# Batch costs\n",
+        "\n",
+        "<!-- Generated by `make bench-pinocchio-program` -->\n",
+        "\n",
+        "`CreateMany` creating N SPL Token ATAs for distinct wallets, against N `Create`\n",
+        "instructions in one transaction. Transaction sizes assume a legacy message signed by\n",
+        "the payer alone; sizes over the 1232 byte limit are marked with `*`.\n",
+        "\n",
+        "| N | CUs | CUs per account | Marginal CUs | Transaction bytes | N x `Create` CUs | N x `Create` transaction bytes |\n",
+        "|---|-----|-----------------|--------------|-------------------|------------------|--------------------------------|\n",
+    ));
+    let mut previous_compute_units = 0u64;
+    let mut separate_compute_units = 0u64;
+    let mut separate_instructions = vec![];
+    for (index, wallet) in wallets.iter().enumerate() {
+        let batch_len = index.saturating_add(1);
+
+        let separate =
+            create_associated_token_account(&payer.0, wallet, &mint.0, &token_program_id);
+        let result = mollusk.process_instruction(
+            &separate,
+            &[
+                payer.clone(),
+                (
+                    separate.accounts[CREATE_ASSOCIATED_TOKEN_ACCOUNT].pubkey,
+                    Account::default(),
+                ),
+                (*wallet, Account::new(1_000_000, 0, &system_program::id())),
+                mint.clone(),
+                system_account.clone(),
+                token_program_account.clone(),
+            ],
+        );
+        assert!(
+            result.program_result.is_ok(),
+            "create for batch of {batch_len}"
+        );
+        separate_compute_units =
+            separate_compute_units.saturating_add(result.compute_units_consumed);
+        separate_instructions.push(separate);
+
+        let mut ix = Instruction {
+            program_id: ata_program_id(),
+            accounts: vec![
+                AccountMeta::new(payer.0, true),
+                AccountMeta::new_readonly(system_program::id(), false),
+            ],
+            data: data.to_vec(),
+        };
+        let mut accs = vec![
+            payer.clone(),
+            system_account.clone(),
+            mint.clone(),
+            token_program_account.clone(),
+        ];
+        for wallet in &wallets[..batch_len] {
+            let ata =
+                get_associated_token_address_with_program_id(wallet, &mint.0, &token_program_id);
+            ix.accounts.extend([
+                AccountMeta::new(ata, false),
+                AccountMeta::new_readonly(*wallet, false),
+                AccountMeta::new_readonly(mint.0, false),
+                AccountMeta::new_readonly(token_program_id, false),
+            ]);
+            accs.push((ata, Account::default()));
+            accs.push((*wallet, Account::new(1_000_000, 0, &system_program::id())));
+        }
+        let result = mollusk.process_instruction(&ix, &accs);
+        assert!(result.program_result.is_ok(), "create_many of {batch_len}");
+        let compute_units = result.compute_units_consumed;
+
+        let size = |len: usize| {
+            if len > PACKET_DATA_SIZE {
+                format!("{len}*")
+            } else {
+                len.to_string()
+            }
+        };
+        report.push_str(&format!(
+            "| {batch_len} | {compute_units} | {} | {} | {} | {separate_compute_units} | {} |\n",
+            compute_units.saturating_div(batch_len as u64),
+            compute_units.saturating_sub(previous_compute_units),
+            size(transaction_len(std::slice::from_ref(&ix))),
+            size(transaction_len(&separate_instructions)),
+        ));
+        previous_compute_units = compute_units;
+    }
+
+    std::fs::write(
+        PathBuf::from(env!("CARGO_MANIFEST_DIR")).join("benches/batch_costs.md"),
+        report,
+    )
+    .unwrap();
+}
+
+fn main() {
+    solana_logger::setup_with("");
+    write_deployment_costs();
+
+    let mut mollusk = Mollusk::new(
+        &ata_program_id(),
+        "pinocchio_associated_token_account_program",
+    );
+    add_token_programs(&mut mollusk);
 
     let payer = Address::new_unique();
     let mint_authority = Address::new_unique();
@@ -257,6 +1172,9 @@ fn main() {
     );
     let mut accs1_create_with_args = accs1.clone();
     accs1_create_with_args.push(rent_sysvar.clone());
+    // Old clients send no data at all, which decodes as `Create`
+    let mut ix1_empty_data = ix1.clone();
+    ix1_empty_data.data.clear();
 
     // Bench 2: create (token-2022)
     let wallet2 = Address::new_unique();
@@ -290,6 +1208,8 @@ fn main() {
     );
     let mut accs2_create_with_args = accs2.clone();
     accs2_create_with_args.push(rent_sysvar.clone());
+    let mut ix2_empty_data = ix2.clone();
+    ix2_empty_data.data.clear();
 
     // Bench 3: create_idempotent (new, spl-token)
     let wallet3 = Address::new_unique();
@@ -582,6 +1502,44 @@ fn main() {
     let mut accs2_extended_create_with_args = accs2_extended.clone();
     accs2_extended_create_with_args.push(rent_sysvar.clone());
 
+    // Bench: a long no-bump search, the canonical bump of this wallet and mint
+    // is 237 so the search tries 19 bumps
+    let low_bump_mint = Address::from_str_const("8N6gdBxJaZUG9cBnSSaHDsx7vMeQ4VR1LmCmk9SCu38s");
+    let low_bump_wallet = Address::from_str_const("Fe2TUfAnvzXjFc79MdYXmkWPDmN5D1RtLnEmv1EzorL8");
+    let low_bump_ata = get_associated_token_address_with_program_id(
+        &low_bump_wallet,
+        &low_bump_mint,
+        &spl_token_interface::id(),
+    );
+    let ix_low_bump = create_associated_token_account(
+        &payer,
+        &low_bump_wallet,
+        &low_bump_mint,
+        &spl_token_interface::id(),
+    );
+    let accs_low_bump = vec![
+        (payer, payer_account.clone()),
+        (low_bump_ata, Account::default()),
+        (
+            low_bump_wallet,
+            Account::new(1_000_000, 0, &system_program::id()),
+        ),
+        (low_bump_mint, token_mint_account.clone()),
+        system_account.clone(),
+        spl_token_account.clone(),
+    ];
+    let ix_low_bump_create_with_args = create_associated_token_account_with_args(
+        &payer,
+        &low_bump_wallet,
+        &low_bump_mint,
+        &spl_token_interface::id(),
+        &rent_sysvar.0,
+        CreateMode::Always,
+        token_account_len,
+    );
+    let mut accs_low_bump_create_with_args = accs_low_bump.clone();
+    accs_low_bump_create_with_args.push(rent_sysvar.clone());
+
     // recover_nested benches
     let (ix6, accs6) = recover_nested_case(
         Address::new_from_array([1; 32]),
@@ -620,85 +1578,245 @@ fn main() {
         &t22_account,
     );
 
-    MolluskComputeUnitBencher::new(mollusk)
-        .bench(("create (spl-token)", &ix1, &accs1))
-        .bench((
+    // recover_nested CU as a function of multisig signer count, 1-of-1 through 11-of-11
+    let multisig_cases: Vec<(String, Instruction, Vec<(Address, Account)>)> = (1..=11u8)
+        .map(|m| {
+            let (ix, accs) = recover_nested_multisig_case(
+                m.wrapping_mul(16),
+                m,
+                &spl_token_account,
+                &t22_account,
+            );
+            (
+                format!("recover_nested (multisig {m}-of-{m}, spl-token)"),
+                ix,
+                accs,
+            )
+        })
+        .collect();
+
+    let mut cases: Vec<(&str, &Instruction, &[(Address, Account)])> = vec![
+        ("create (spl-token)", &ix1, &accs1[..]),
+        (
             "create_with_args (spl-token)",
             &ix1_create_with_args,
-            &accs1_create_with_args,
-        ))
-        .bench(("create (token-2022)", &ix2, &accs2))
-        .bench((
+            &accs1_create_with_args[..],
+        ),
+        (
+            "create (empty data, spl-token)",
+            &ix1_empty_data,
+            &accs1[..],
+        ),
+        ("create (token-2022)", &ix2, &accs2[..]),
+        (
+            "create (empty data, token-2022)",
+            &ix2_empty_data,
+            &accs2[..],
+        ),
+        (
             "create_with_args (token-2022)",
             &ix2_create_with_args,
-            &accs2_create_with_args,
-        ))
-        .bench(("create_idempotent (new, spl-token)", &ix3, &accs3))
-        .bench((
+            &accs2_create_with_args[..],
+        ),
+        ("create_idempotent (new, spl-token)", &ix3, &accs3[..]),
+        (
             "create_with_args_idempotent (new, spl-token)",
             &ix3_create_with_args,
-            &accs3_create_with_args,
-        ))
-        .bench(("create_idempotent (new, token-2022)", &ix3b, &accs3b))
-        .bench((
+            &accs3_create_with_args[..],
+        ),
+        ("create_idempotent (new, token-2022)", &ix3b, &accs3b[..]),
+        (
             "create_with_args_idempotent (new, token-2022)",
             &ix3b_create_with_args,
-            &accs3b_create_with_args,
-        ))
-        .bench(("create_idempotent (existing, spl-token)", &ix4, &accs4))
-        .bench((
+            &accs3b_create_with_args[..],
+        ),
+        ("create_idempotent (existing, spl-token)", &ix4, &accs4[..]),
+        (
             "create_with_args_idempotent (existing, spl-token)",
             &ix4_create_with_args,
-            &accs4_create_with_args,
-        ))
-        .bench(("create_idempotent (existing, token-2022)", &ix4b, &accs4b))
-        .bench((
+            &accs4_create_with_args[..],
+        ),
+        (
+            "create_idempotent (existing, token-2022)",
+            &ix4b,
+            &accs4b[..],
+        ),
+        (
             "create_with_args_idempotent (existing, token-2022)",
             &ix4b_create_with_args,
-            &accs4b_create_with_args,
-        ))
-        .bench(("create (prefunded, spl-token)", &ix5, &accs5))
-        .bench((
+            &accs4b_create_with_args[..],
+        ),
+        ("create (prefunded, spl-token)", &ix5, &accs5[..]),
+        (
             "create_with_args (prefunded, spl-token)",
             &ix5_create_with_args,
-            &accs5_create_with_args,
-        ))
-        .bench(("create (prefunded, token-2022)", &ix5b, &accs5b))
-        .bench((
+            &accs5_create_with_args[..],
+        ),
+        ("create (prefunded, token-2022)", &ix5b, &accs5b[..]),
+        (
             "create_with_args (prefunded, token-2022)",
             &ix5b_create_with_args,
-            &accs5b_create_with_args,
-        ))
-        .bench((
+            &accs5b_create_with_args[..],
+        ),
+        (
             "create (token-2022 known mint)",
             &ix2_extended,
-            &accs2_extended,
-        ))
-        .bench((
+            &accs2_extended[..],
+        ),
+        (
             "create_with_args (token-2022 extended mint)",
             &ix2_extended_create_with_args,
-            &accs2_extended_create_with_args,
-        ))
-        .bench((
+            &accs2_extended_create_with_args[..],
+        ),
+        (
+            "create (bump 237, spl-token)",
+            &ix_low_bump,
+            &accs_low_bump[..],
+        ),
+        (
+            "create_with_args (bump 237, spl-token)",
+            &ix_low_bump_create_with_args,
+            &accs_low_bump_create_with_args[..],
+        ),
+        (
             "recover_nested (owner=spl-token, nested=spl-token)",
             &ix6,
-            &accs6,
-        ))
-        .bench((
+            &accs6[..],
+        ),
+        (
             "recover_nested (owner=token-2022, nested=token-2022)",
             &ix6b,
-            &accs6b,
-        ))
-        .bench((
+            &accs6b[..],
+        ),
+        (
             "recover_nested (owner=spl-token, nested=token-2022)",
             &ix6c,
-            &accs6c,
-        ))
-        .bench((
+            &accs6c[..],
+        ),
+        (
             "recover_nested (owner=token-2022, nested=spl-token)",
             &ix6d,
-            &accs6d,
-        ))
+            &accs6d[..],
+        ),
+    ];
+    cases.extend(
+        multisig_cases
+            .iter()
+            .map(|(name, ix, accs)| (name.as_str(), ix, accs.as_slice())),
+    );
+
+    let t22_extended_account_len =
+        ExtensionType::try_calculate_account_len::<spl_token_2022_interface::state::Account>(&[
+            ExtensionType::ImmutableOwner,
+            ExtensionType::TransferFeeAmount,
+        ])
+        .unwrap() as u32;
+    write_adoption_guidance(
+        &mollusk,
+        &[
+            ("create (spl-token)", &ix1, &accs1[..], token_account_len),
+            ("create (token-2022)", &ix2, &accs2[..], t22_account_len),
+            (
+                "create (token-2022 extended mint)",
+                &ix2_extended,
+                &accs2_extended[..],
+                t22_extended_account_len,
+            ),
+            (
+                "create_idempotent (new, spl-token)",
+                &ix3,
+                &accs3[..],
+                token_account_len,
+            ),
+            (
+                "create_idempotent (new, token-2022)",
+                &ix3b,
+                &accs3b[..],
+                t22_account_len,
+            ),
+            (
+                "create_idempotent (existing, spl-token)",
+                &ix4,
+                &accs4[..],
+                token_account_len,
+            ),
+            (
+                "create_idempotent (existing, token-2022)",
+                &ix4b,
+                &accs4b[..],
+                t22_account_len,
+            ),
+        ],
+        &rent_sysvar,
+    );
+    write_input_sizes(&mollusk, &cases);
+    write_batch_costs(
+        &mollusk,
+        &(payer, payer_account.clone()),
+        &(token_mint, token_mint_account.clone()),
+        &system_account,
+        &spl_token_account,
+    );
+    write_logging_overhead(&mollusk, &cases);
+    write_log_divergences(&mut mollusk, &cases);
+
+    // Failures common in practice, each caught before the checks that cost
+    // more than it does. `Create` derives the address first, as SPL ATA does,
+    // so the cases use `CreateWithArgs` without hints, which checks cheapest
+    // first and takes the same accounts.
+    let mut ix_create_with_args = ix1.clone();
+    ix_create_with_args.data =
+        encode_create_ata_instruction_data(&CreateAtaInstructionType::CreateWithArgs {
+            mode: CreateMode::Always,
+            bump: None,
+            account_len: None,
+            rent_sysvar: false,
+        });
+    let mut ix_read_only_ata = ix_create_with_args.clone();
+    ix_read_only_ata.accounts[CREATE_ASSOCIATED_TOKEN_ACCOUNT].is_writable = false;
+    let mut ix_unsigned_payer = ix_create_with_args.clone();
+    ix_unsigned_payer.accounts[CREATE_FUNDER].is_signer = false;
+    let mut ix_existing_ata = ix4.clone();
+    ix_existing_ata.data = ix_create_with_args.data.clone();
+    let wrong_ata = Address::new_unique();
+    let mut ix_wrong_ata = ix_create_with_args.clone();
+    ix_wrong_ata.accounts[CREATE_ASSOCIATED_TOKEN_ACCOUNT].pubkey = wrong_ata;
+    let mut accs_wrong_ata = accs1.clone();
+    accs_wrong_ata[CREATE_ASSOCIATED_TOKEN_ACCOUNT].0 = wrong_ata;
+    let reject_cases: [(&str, &Instruction, &[(Address, Account)]); 4] = [
+        (
+            "reject: create_with_args (read-only ata, spl-token)",
+            &ix_read_only_ata,
+            &accs1[..],
+        ),
+        (
+            "reject: create_with_args (existing ata, spl-token)",
+            &ix_existing_ata,
+            &accs4[..],
+        ),
+        (
+            "reject: create_with_args (missing payer signature, spl-token)",
+            &ix_unsigned_payer,
+            &accs1[..],
+        ),
+        (
+            "reject: create_with_args (wrong ata address, spl-token)",
+            &ix_wrong_ata,
+            &accs_wrong_ata[..],
+        ),
+    ];
+    write_reject_costs(&mollusk, &reject_cases);
+    check_thresholds(&mollusk, &cases, &reject_cases);
+    if std::env::args().skip(1).any(|arg| arg == "certify") {
+        write_certificate(&mollusk, &cases, &reject_cases);
+    }
+
+    cases
+        .iter()
+        .fold(
+            MolluskComputeUnitBencher::new(mollusk),
+            |bencher, &(name, ix, accs)| bencher.bench((name, ix, accs)),
+        )
         .must_pass(true)
         .execute();
 }
@@ -0,0 +1,63 @@
+use {
+    pinocchio::{AccountView, Address, ProgramResult, error::ProgramError},
+    pinocchio_associated_token_account_interface::{
+        error::AssociatedTokenAccountError, pda::AssociatedTokenPda,
+    },
+    pinocchio_token_2022::state::{Account, AccountState, StateWithExtensions},
+};
+
+/// Checks that the associated token account of the wallet and mint exists and
+/// is initialized, without writing to or invoking anything.
+///
+/// The checks match the `CreateIdempotent` no-op path, except that a missing
+/// account fails instead of being created.
+#[inline(always)]
+pub(crate) fn process_assert_ata_exists(
+    program_id: &Address,
+    accounts: &mut [AccountView],
+) -> ProgramResult {
+    let [associated_token_account, wallet, mint, token_program, ..] = accounts else {
+        return Err(ProgramError::NotEnoughAccountKeys);
+    };
+
+    if *token_program.address() != pinocchio_token::ID
+        && *token_program.address() != pinocchio_token_2022::ID
+    {
+        return Err(ProgramError::IncorrectProgramId);
+    }
+
+    if !associated_token_account.owned_by(token_program.address()) {
+        // Nothing has been created at the address yet
+        if associated_token_account.owned_by(&pinocchio_system::ID) {
+            return Err(ProgramError::UninitializedAccount);
+        }
+        return Err(ProgramError::IllegalOwner);
+    }
+
+    {
+        let ata_data = associated_token_account.try_borrow()?;
+        let token_account = StateWithExtensions::<Account>::from_bytes(&ata_data)
+            .map_err(|_| ProgramError::InvalidAccountData)?;
+        if token_account.base.state()? == AccountState::Uninitialized {
+            return Err(ProgramError::UninitializedAccount);
+        }
+        if token_account.base.owner() != wallet.address() {
+            return Err(AssociatedTokenAccountError::InvalidOwner.into());
+        }
+        if token_account.base.mint() != mint.address() {
+            return Err(ProgramError::InvalidAccountData);
+        }
+    }
+
+    // An existing account cannot be at an on-curve address, so the search
+    // skips that check for the matching bump
+    AssociatedTokenPda::find_bump_seed_for_address(
+        program_id,
+        wallet.address(),
+        token_program.address(),
+        mint.address(),
+        associated_token_account.address(),
+        0,
+    )?;
+    Ok(())
+}
@@ -0,0 +1,136 @@
+use {
+    pinocchio::{AccountView, Address, ProgramResult, error::ProgramError},
+    pinocchio_associated_token_account_interface::pda::AssociatedTokenPda,
+    pinocchio_log::log,
+    pinocchio_token_2022::instructions::CloseAccount,
+};
+
+/// Closes the wallet's associated token account for the mint, returning its lamports to the wallet,
+/// or to the optional destination account following the token program.
+///
+/// The wallet's signature is passed through to the token program's `CloseAccount` as the account
+/// owner's authority, so the token program decides whether the account can be closed: it rejects a
+/// non-native account that still holds tokens, and returns a native account's wrapped lamports along
+/// with the rent. The same signature authorizes a destination other than the wallet, such as the
+/// fee payer that funded the account.
+#[inline(always)]
+pub(crate) fn process_close(program_id: &Address, accounts: &mut [AccountView]) -> ProgramResult {
+    let [
+        associated_token_account,
+        wallet,
+        mint,
+        token_program,
+        remaining @ ..,
+    ] = accounts
+    else {
+        return Err(ProgramError::NotEnoughAccountKeys);
+    };
+    let destination = remaining.first().unwrap_or(wallet);
+
+    check_token_program(token_program)?;
+    check_wallet(wallet)?;
+    check_writable(associated_token_account)?;
+    check_destination(associated_token_account, destination)?;
+
+    if !associated_token_account.owned_by(token_program.address()) {
+        // Nothing has been created at the address yet
+        if associated_token_account.owned_by(&pinocchio_system::ID) {
+            return Err(ProgramError::UninitializedAccount);
+        }
+        return Err(ProgramError::IllegalOwner);
+    }
+    check_address(
+        program_id,
+        associated_token_account,
+        wallet,
+        mint,
+        token_program,
+    )?;
+
+    close_associated_token_account(associated_token_account, wallet, destination, token_program)
+}
+
+pub(crate) fn check_token_program(token_program: &AccountView) -> ProgramResult {
+    if *token_program.address() != pinocchio_token::ID
+        && *token_program.address() != pinocchio_token_2022::ID
+    {
+        return Err(ProgramError::IncorrectProgramId);
+    }
+    Ok(())
+}
+
+/// The wallet authorizes the close and receives the lamports. Checked up front rather than left to
+/// the runtime, which would only fail after the token CPI has run.
+pub(crate) fn check_wallet(wallet: &AccountView) -> ProgramResult {
+    if !wallet.is_writable() {
+        log!("Error: Wallet must be writable");
+        return Err(ProgramError::Immutable);
+    }
+    if !wallet.is_signer() {
+        log!("Wallet of the associated token account must sign");
+        return Err(ProgramError::MissingRequiredSignature);
+    }
+    Ok(())
+}
+
+pub(crate) fn check_writable(associated_token_account: &AccountView) -> ProgramResult {
+    if !associated_token_account.is_writable() {
+        log!("Error: Associated token account must be writable");
+        return Err(ProgramError::Immutable);
+    }
+    Ok(())
+}
+
+/// The destination is credited with the closed account's lamports, so it must be writable and
+/// cannot be the closed account itself, whose lamports would be lost with it.
+fn check_destination(
+    associated_token_account: &AccountView,
+    destination: &AccountView,
+) -> ProgramResult {
+    if destination.address() == associated_token_account.address() {
+        log!("Error: Destination cannot be the account being closed");
+        return Err(ProgramError::InvalidArgument);
+    }
+    if !destination.is_writable() {
+        log!("Error: Destination must be writable");
+        return Err(ProgramError::Immutable);
+    }
+    Ok(())
+}
+
+/// Fails with `InvalidSeeds` unless `associated_token_account` is the ATA of the wallet and mint.
+///
+/// An existing account cannot be at an on-curve address, so the search skips that check for the
+/// matching bump.
+pub(crate) fn check_address(
+    program_id: &Address,
+    associated_token_account: &AccountView,
+    wallet: &AccountView,
+    mint: &AccountView,
+    token_program: &AccountView,
+) -> ProgramResult {
+    AssociatedTokenPda::find_bump_seed_for_address(
+        program_id,
+        wallet.address(),
+        token_program.address(),
+        mint.address(),
+        associated_token_account.address(),
+        0,
+    )?;
+    Ok(())
+}
+
+pub(crate) fn close_associated_token_account(
+    associated_token_account: &AccountView,
+    wallet: &AccountView,
+    destination: &AccountView,
+    token_program: &AccountView,
+) -> ProgramResult {
+    CloseAccount {
+        account: associated_token_account,
+        destination,
+        authority: wallet,
+        token_program: token_program.address(),
+    }
+    .invoke()
+}
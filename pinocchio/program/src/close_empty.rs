@@ -0,0 +1,76 @@
+use {
+    crate::close::{
+        check_address, check_token_program, check_wallet, check_writable,
+        close_associated_token_account,
+    },
+    pinocchio::{AccountView, Address, ProgramResult, error::ProgramError},
+    pinocchio_associated_token_account_interface::{
+        account_indices::{CLOSE_EMPTY_GROUP_LEN, MAX_CLOSE_EMPTY_GROUPS},
+        error::AssociatedTokenAccountError,
+    },
+    pinocchio_token_2022::state::{Account, StateWithExtensions},
+};
+
+/// Closes every empty ATA of the wallet among the `[ata, mint, token_program]` groups that follow
+/// it, returning their lamports to the wallet.
+///
+/// Every group must name one of the wallet's ATAs, or the instruction fails. ATAs that were never
+/// created or still hold tokens are left as they are, so a wallet can pass all of its ATAs without
+/// checking their balances first. Each close is a `Close`, so the token program may still refuse
+/// one, such as a Token-2022 account with withheld transfer fees, which fails the instruction. More
+/// than `MAX_CLOSE_EMPTY_GROUPS` groups fail before any is processed.
+#[inline(always)]
+pub(crate) fn process_close_empty(
+    program_id: &Address,
+    accounts: &mut [AccountView],
+) -> ProgramResult {
+    let [wallet, groups @ ..] = accounts else {
+        return Err(ProgramError::NotEnoughAccountKeys);
+    };
+
+    // A missing group or a trailing partial one means the caller lost track
+    // of the account list, so reject it rather than skip it
+    let groups = groups.chunks_exact(CLOSE_EMPTY_GROUP_LEN);
+    if groups.len() == 0 || !groups.remainder().is_empty() {
+        return Err(ProgramError::NotEnoughAccountKeys);
+    }
+    if groups.len() > MAX_CLOSE_EMPTY_GROUPS {
+        return Err(AssociatedTokenAccountError::GroupLimitExceeded.into());
+    }
+    check_wallet(wallet)?;
+
+    for group in groups {
+        let [associated_token_account, mint, token_program] = group else {
+            return Err(ProgramError::NotEnoughAccountKeys);
+        };
+        check_token_program(token_program)?;
+        check_address(
+            program_id,
+            associated_token_account,
+            wallet,
+            mint,
+            token_program,
+        )?;
+
+        // Nothing to close at an address that was never created
+        if associated_token_account.owned_by(&pinocchio_system::ID) {
+            continue;
+        }
+        if !associated_token_account.owned_by(token_program.address()) {
+            return Err(ProgramError::IllegalOwner);
+        }
+        let amount = {
+            let data = associated_token_account.try_borrow()?;
+            StateWithExtensions::<Account>::from_bytes(&data)?
+                .base
+                .amount()
+        };
+        if amount != 0 {
+            continue;
+        }
+
+        check_writable(associated_token_account)?;
+        close_associated_token_account(associated_token_account, wallet, wallet, token_program)?;
+    }
+    Ok(())
+}
@@ -1,5 +1,8 @@
 use {
-    crate::{batch::batch_init_and_lock_owner, size::get_token_2022_account_data_size},
+    crate::{
+        batch::batch_init_and_lock_owner, close::check_token_program,
+        size::get_token_2022_account_data_size,
+    },
     pinocchio::{
         AccountView, Address, ProgramResult, cpi::Signer, error::ProgramError, instruction::seeds,
     },
@@ -10,28 +13,188 @@ use {
     pinocchio_token::instructions::{InitializeAccount, InitializeAccount3},
     pinocchio_token_2022::state::{Account, AccountState, StateWithExtensions},
 };
+#[cfg(feature = "log-nested-wallet")]
+use {pinocchio_log::log, pinocchio_token_2022::state::Multisig};
+
+/// `AccountType::Account`, stored after the base layout of extended Token-2022 accounts.
+#[cfg(feature = "log-nested-wallet")]
+const ACCOUNT_TYPE_ACCOUNT: u8 = 2;
+
+/// Largest account data length the runtime allows, `MAX_PERMITTED_DATA_LENGTH`.
+const MAX_PERMITTED_DATA_LENGTH: u64 = 10 * 1024 * 1024;
+/// Most an inner instruction may grow an account's data by,
+/// `MAX_PERMITTED_DATA_INCREASE`. The ATA is allocated by a CPI, so this bounds
+/// its length well below `MAX_PERMITTED_DATA_LENGTH`.
+const MAX_PERMITTED_DATA_INCREASE: u64 = 10 * 1024;
+
+/// Account list layouts accepted by the create instructions.
+#[derive(Clone, Copy, PartialEq)]
+pub(crate) enum CreateAccounts {
+    /// `[payer, ata, wallet, mint, system_program, token_program, ..]`
+    WithSystemProgram,
+    /// `[payer, ata, wallet, mint, token_program, ..]`
+    WithoutSystemProgram,
+}
+
+/// How the create instructions find the ATA bump seed.
+#[derive(Clone, Copy, PartialEq)]
+pub(crate) enum Bump {
+    /// Search every bump for the canonical one
+    Search,
+    /// Caller-supplied bump, checked to be the canonical one
+    Hint(u8),
+    /// Search for the canonical bump, giving up below the given bump
+    SearchDownTo(u8),
+}
+
+/// Order in which the create instructions check their accounts.
+#[derive(Clone, Copy, PartialEq)]
+pub(crate) enum CheckOrder {
+    /// SPL ATA's order, for `Create` and `CreateIdempotent`: the ATA address is
+    /// derived before the accounts are checked, so every input fails with the
+    /// error SPL ATA returns for it. Trailing accounts are ignored.
+    Spl,
+    /// Cheapest checks first, for the instructions only p-ATA has. A trailing
+    /// account is taken as the rent sysvar.
+    CheapFirst,
+}
+
+/// Accounts of a single ATA creation, whichever layout they were passed in.
+pub(crate) struct CreateAccountViews<'a> {
+    pub(crate) payer: &'a AccountView,
+    pub(crate) associated_token_account: &'a AccountView,
+    pub(crate) wallet: &'a AccountView,
+    pub(crate) mint: &'a AccountView,
+    pub(crate) token_program: &'a AccountView,
+    pub(crate) rent_sysvar: Option<&'a AccountView>,
+}
 
 #[inline(always)]
 pub(crate) fn process_create_associated_token_account(
     program_id: &Address,
     accounts: &mut [AccountView],
+    layout: CreateAccounts,
     create_mode: CreateMode,
-    accept_rent_sysvar: bool,
-    bump_hint: Option<u8>,
+    order: CheckOrder,
+    bump: Bump,
     account_len_hint: Option<u32>,
 ) -> ProgramResult {
-    let [
+    // The system program is never read, the CPI resolves it by address
+    let (payer, associated_token_account, wallet, mint, token_program, remaining) = match layout {
+        CreateAccounts::WithSystemProgram => {
+            let [
+                payer,
+                associated_token_account,
+                wallet,
+                mint,
+                _system_program,
+                token_program,
+                remaining @ ..,
+            ] = accounts
+            else {
+                return Err(ProgramError::NotEnoughAccountKeys);
+            };
+            (
+                payer,
+                associated_token_account,
+                wallet,
+                mint,
+                token_program,
+                remaining,
+            )
+        }
+        CreateAccounts::WithoutSystemProgram => {
+            let [
+                payer,
+                associated_token_account,
+                wallet,
+                mint,
+                token_program,
+                remaining @ ..,
+            ] = accounts
+            else {
+                return Err(ProgramError::NotEnoughAccountKeys);
+            };
+            (
+                payer,
+                associated_token_account,
+                wallet,
+                mint,
+                token_program,
+                remaining,
+            )
+        }
+    };
+
+    let rent_sysvar = match order {
+        // `Create` / `CreateIdempotent` ignore trailing accounts
+        CheckOrder::Spl => None,
+        // `CreateWithArgs` / `CreateCompact` accept rent as an optional account
+        CheckOrder::CheapFirst => remaining.first(),
+    };
+
+    create_associated_token_account(
+        program_id,
+        CreateAccountViews {
+            payer,
+            associated_token_account,
+            wallet,
+            mint,
+            token_program,
+            rent_sysvar,
+        },
+        create_mode,
+        order,
+        bump,
+        account_len_hint,
+    )
+}
+
+/// Creates one ATA from accounts already picked out of the account list.
+///
+/// With [`CheckOrder::CheapFirst`], checks run cheapest first, so the failures
+/// clients hit most often are rejected before any compute is spent on deriving
+/// the address:
+///
+/// 1. The program passed as its own token program.
+/// 2. The `CreateIdempotent` no-op, which must succeed whatever follows.
+/// 3. Account owners and flags: a read-only payer or ATA, an ATA that already
+///    exists, a payer that does not sign, a token program that is neither SPL
+///    Token nor Token-2022.
+/// 4. The ATA address, one hash and on-curve check per bump tried.
+/// 5. Mint policy, account size and the CPIs, which the token program may
+///    still reject.
+///
+/// With [`CheckOrder::Spl`], the account checks of step 3 run after the
+/// address is derived, in the order SPL ATA fails them, and the program passed
+/// as its own token program is rejected there as any other unknown program.
+///
+/// `tests/reject_cost.rs` and the `reject:` cases of
+/// `benches/bench_thresholds.toml` bound the cost of each step's rejections.
+#[inline(always)]
+pub(crate) fn create_associated_token_account(
+    program_id: &Address,
+    accounts: CreateAccountViews,
+    create_mode: CreateMode,
+    order: CheckOrder,
+    bump: Bump,
+    account_len_hint: Option<u32>,
+) -> ProgramResult {
+    let CreateAccountViews {
         payer,
         associated_token_account,
         wallet,
         mint,
-        _system_program,
         token_program,
-        remaining @ ..,
-    ] = accounts
-    else {
-        return Err(ProgramError::NotEnoughAccountKeys);
-    };
+        rent_sysvar,
+    } = accounts;
+
+    // The only CPIs go to the system and token programs, so the program can never
+    // be made to invoke itself. Passed as the token program it would still get
+    // as far as the size check, so reject it before deriving anything.
+    if order == CheckOrder::CheapFirst && token_program.address() == program_id {
+        return Err(ProgramError::IncorrectProgramId);
+    }
 
     // For `CreateIdempotent`, if the ATA already exists and is valid, it's a no-op
     if create_mode == CreateMode::Idempotent
@@ -52,29 +215,44 @@ pub(crate) fn process_create_associated_token_account(
                         return Err(ProgramError::InvalidAccountData);
                     }
                     // Validate expected address, using bump hint if provided
-                    let derived_ata_addr = if let Some(bump) = bump_hint {
+                    match bump {
                         // When a `bump` is provided, the address is derived directly without performing
                         // an on-curve check, since the account already exists. An ATA cannot be created
                         // with either a non-canonical bump or an on-curve address.
-                        Address::derive_address(
-                            &[
-                                wallet.address().as_array(),
-                                token_program.address().as_array(),
-                                mint.address().as_array(),
-                            ],
-                            Some(bump),
-                            program_id,
-                        )
-                    } else {
-                        AssociatedTokenPda::derive_address(
-                            program_id,
-                            wallet.address(),
-                            token_program.address(),
-                            mint.address(),
-                        )
-                    };
-                    if derived_ata_addr != *associated_token_account.address() {
-                        return Err(ProgramError::InvalidSeeds);
+                        Bump::Hint(bump) => {
+                            let derived_ata_addr = Address::derive_address(
+                                &[
+                                    wallet.address().as_array(),
+                                    token_program.address().as_array(),
+                                    mint.address().as_array(),
+                                ],
+                                Some(bump),
+                                program_id,
+                            );
+                            if derived_ata_addr != *associated_token_account.address() {
+                                return Err(ProgramError::InvalidSeeds);
+                            }
+                        }
+                        Bump::Search => {
+                            find_bump(
+                                program_id,
+                                associated_token_account,
+                                wallet,
+                                token_program,
+                                mint,
+                                0,
+                            )?;
+                        }
+                        Bump::SearchDownTo(min_bump) => {
+                            find_bump(
+                                program_id,
+                                associated_token_account,
+                                wallet,
+                                token_program,
+                                mint,
+                                min_bump,
+                            )?;
+                        }
                     }
                     // Confirmed `CreateIdempotent` no-op
                     return Ok(());
@@ -83,55 +261,90 @@ pub(crate) fn process_create_associated_token_account(
         }
     }
 
-    let rent_sysvar = if accept_rent_sysvar {
-        // `CreateWithArgs` accepts rent as an optional account
-        remaining.first()
-    } else {
-        // `Create` / `CreateIdempotent` ignore trailing accounts
-        None
-    };
+    if order == CheckOrder::CheapFirst {
+        check_writable_accounts(payer, associated_token_account)?;
+        // An ATA that already exists is the usual reason a create fails, so it
+        // is caught here rather than after the address derivation
+        check_uninitialized(associated_token_account)?;
+        check_payer_signs(payer, associated_token_account)?;
+        check_token_program(token_program)?;
+    }
 
-    let (derived_ata_addr, bump_seed) = match bump_hint {
-        Some(bump) => (
-            AssociatedTokenPda::derive_address_with_bump_hint(
+    // Without a hint the search stops at the ATA address, leaving its on-curve
+    // check to the signed `CreateAccount` below
+    let bump_seed = match bump {
+        Bump::Hint(bump) => {
+            let derived_ata_addr = AssociatedTokenPda::derive_address_with_bump_hint(
                 program_id,
                 wallet.address(),
                 token_program.address(),
                 mint.address(),
                 bump,
-            )?,
-            bump,
-        ),
-        None => AssociatedTokenPda::derive_address_and_bump_seed(
+            )?;
+            if derived_ata_addr != *associated_token_account.address() {
+                return Err(ProgramError::InvalidSeeds);
+            }
+            bump
+        }
+        Bump::Search => find_bump(
             program_id,
-            wallet.address(),
-            token_program.address(),
-            mint.address(),
-        ),
+            associated_token_account,
+            wallet,
+            token_program,
+            mint,
+            0,
+        )?,
+        Bump::SearchDownTo(min_bump) => find_bump(
+            program_id,
+            associated_token_account,
+            wallet,
+            token_program,
+            mint,
+            min_bump,
+        )?,
     };
-    if derived_ata_addr != *associated_token_account.address() {
-        return Err(ProgramError::InvalidSeeds);
+
+    if order == CheckOrder::Spl {
+        // SPL ATA fails an existing ATA first and an unknown token program in
+        // its `GetAccountDataSize` CPI, leaving the rest to `CreateAccount`
+        check_uninitialized(associated_token_account)?;
+        check_token_program(token_program)?;
+        check_writable_accounts(payer, associated_token_account)?;
+        check_payer_signs(payer, associated_token_account)?;
     }
 
-    if !associated_token_account.owned_by(&pinocchio_system::ID) {
-        return Err(ProgramError::IllegalOwner);
+    #[cfg(any(
+        feature = "reject-permanent-delegate-mints",
+        feature = "reject-mint-close-authority-mints"
+    ))]
+    crate::policy::check_mint_policy(mint)?;
+
+    // Nested ATAs are almost always a mistake that later needs `RecoverNested`
+    #[cfg(feature = "log-nested-wallet")]
+    if is_token_account(wallet) {
+        log!("Warning: wallet is a token account, creating a nested associated token account");
     }
 
     let is_spl_token = *token_program.address() == pinocchio_token::ID;
     let account_len = if is_spl_token {
         Account::BASE_LEN as u64
-    } else if *token_program.address() == pinocchio_token_2022::ID {
+    } else if let Some(account_len_hint) = account_len_hint {
         // Undersized accounts fail during initialization and excessive sizes fail
-        // through rent/system account-size limits.
-        if let Some(account_len_hint) = account_len_hint {
-            account_len_hint as u64
-        } else {
-            get_token_2022_account_data_size(mint, token_program)?
-        }
+        // the length check below.
+        account_len_hint as u64
     } else {
-        return Err(ProgramError::IncorrectProgramId);
+        get_token_2022_account_data_size(mint, token_program)?
     };
 
+    // Past either limit the `CreateAccount` CPI would fail, but only after
+    // the system program has run and with a generic error
+    if account_len > MAX_PERMITTED_DATA_LENGTH
+        || account_len.saturating_sub(associated_token_account.data_len() as u64)
+            > MAX_PERMITTED_DATA_INCREASE
+    {
+        return Err(AssociatedTokenAccountError::AccountDataLengthExceeded.into());
+    }
+
     // Create the PDA (handles pre-funded accounts)
     let bump_ref = &[bump_seed];
     let seeds = seeds!(
@@ -167,3 +380,78 @@ pub(crate) fn process_create_associated_token_account(
         InitializeAccount3::new(associated_token_account, mint, wallet.address()).invoke()
     }
 }
+
+/// Past the `CreateIdempotent` no-op, which SPL ATA accepts with read-only
+/// accounts, both the payer and the ATA are written by the `CreateAccount` CPI.
+#[inline(always)]
+fn check_writable_accounts(
+    payer: &AccountView,
+    associated_token_account: &AccountView,
+) -> ProgramResult {
+    if !payer.is_writable() || !associated_token_account.is_writable() {
+        return Err(ProgramError::Immutable);
+    }
+    Ok(())
+}
+
+/// Only an address the system program still owns can be created.
+#[inline(always)]
+fn check_uninitialized(associated_token_account: &AccountView) -> ProgramResult {
+    if !associated_token_account.owned_by(&pinocchio_system::ID) {
+        return Err(ProgramError::IllegalOwner);
+    }
+    Ok(())
+}
+
+/// Without lamports at the ATA address the system program has to move the
+/// rent from the payer, which the runtime only allows with its signature.
+/// A prefunded address may still be created without one.
+#[inline(always)]
+fn check_payer_signs(payer: &AccountView, associated_token_account: &AccountView) -> ProgramResult {
+    if !payer.is_signer() && associated_token_account.lamports() == 0 {
+        return Err(ProgramError::MissingRequiredSignature);
+    }
+    Ok(())
+}
+
+/// Canonical bump of the ATA, if it is no lower than `min_bump`.
+#[inline(always)]
+fn find_bump(
+    program_id: &Address,
+    associated_token_account: &AccountView,
+    wallet: &AccountView,
+    token_program: &AccountView,
+    mint: &AccountView,
+    min_bump: u8,
+) -> Result<u8, ProgramError> {
+    AssociatedTokenPda::find_bump_seed_for_address(
+        program_id,
+        wallet.address(),
+        token_program.address(),
+        mint.address(),
+        associated_token_account.address(),
+        min_bump,
+    )
+}
+
+/// Whether `wallet` is an SPL Token or Token-2022 token account.
+///
+/// `wallet` may be the mint account itself, which is never mistaken for a token
+/// account: a base mint is shorter than `Account::BASE_LEN`, and an extended
+/// mint's account type byte marks it as a mint.
+#[cfg(feature = "log-nested-wallet")]
+#[inline(always)]
+fn is_token_account(wallet: &AccountView) -> bool {
+    if !wallet.owned_by(&pinocchio_token::ID) && !wallet.owned_by(&pinocchio_token_2022::ID) {
+        return false;
+    }
+    match wallet.data_len() {
+        Account::BASE_LEN => true,
+        // Multisig wallets are expected to own ATAs
+        Multisig::LEN => false,
+        len if len > Account::BASE_LEN => wallet
+            .try_borrow()
+            .is_ok_and(|data| data[Account::BASE_LEN] == ACCOUNT_TYPE_ACCOUNT),
+        _ => false,
+    }
+}
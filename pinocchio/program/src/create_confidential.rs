@@ -0,0 +1,132 @@
+use {
+    crate::{
+        create::{Bump, CheckOrder, CreateAccountViews, create_associated_token_account},
+        size::get_confidential_account_data_size,
+    },
+    pinocchio::{
+        AccountView, Address, ProgramResult,
+        cpi::invoke,
+        error::ProgramError,
+        instruction::{InstructionAccount, InstructionView},
+    },
+    pinocchio_associated_token_account_interface::instruction::{
+        CreateMode, DECRYPTABLE_BALANCE_LEN,
+    },
+    pinocchio_log::log,
+};
+
+/// `TokenInstruction::ConfidentialTransferExtension`.
+const CONFIDENTIAL_TRANSFER_EXTENSION: u8 = 27;
+/// `ConfidentialTransferInstruction::ConfigureAccount`.
+const CONFIGURE_ACCOUNT: u8 = 2;
+/// Discriminators, decryptable balance, credit counter and proof offset.
+const CONFIGURE_ACCOUNT_DATA_LEN: usize = 2 + DECRYPTABLE_BALANCE_LEN + 8 + 1;
+
+/// Creates the wallet's ATA for a mint with the `ConfidentialTransferMint`
+/// extension, then configures it for confidential transfers.
+///
+/// The account is allocated with room for the `ConfidentialTransferAccount`
+/// extension, which the token program's `ConfigureAccount` fills in. That
+/// instruction is signed by the wallet as the account owner and checks the
+/// `PubkeyValidity` proof, passed as the instructions sysvar or a context
+/// state account, optionally followed by the record account the proof is read
+/// from. With `CreateMode::Idempotent` an existing account is left as it is,
+/// whether it was configured or not.
+#[inline(always)]
+pub(crate) fn process_create_confidential(
+    program_id: &Address,
+    accounts: &mut [AccountView],
+    create_mode: CreateMode,
+    decryptable_zero_balance: &[u8; DECRYPTABLE_BALANCE_LEN],
+    maximum_pending_balance_credit_counter: u64,
+    proof_instruction_offset: i8,
+) -> ProgramResult {
+    // The system program is never read, the CPI resolves it by address
+    let [
+        payer,
+        associated_token_account,
+        wallet,
+        mint,
+        _system_program,
+        token_program,
+        proof,
+        remaining @ ..,
+    ] = accounts
+    else {
+        return Err(ProgramError::NotEnoughAccountKeys);
+    };
+
+    // Only Token-2022 has the extension, so fail before creating an SPL Token
+    // account that could never be configured
+    if *token_program.address() != pinocchio_token_2022::ID {
+        log!("Error: Confidential transfers require the Token-2022 program");
+        return Err(ProgramError::IncorrectProgramId);
+    }
+    if !wallet.is_signer() {
+        return Err(ProgramError::MissingRequiredSignature);
+    }
+
+    // Past `create_associated_token_account`, an account owned by the token
+    // program can only be the `CreateIdempotent` no-op
+    let exists = associated_token_account.owned_by(token_program.address());
+    let account_len = get_confidential_account_data_size(mint)?;
+
+    create_associated_token_account(
+        program_id,
+        CreateAccountViews {
+            payer,
+            associated_token_account,
+            wallet,
+            mint,
+            token_program,
+            rent_sysvar: None,
+        },
+        create_mode,
+        CheckOrder::CheapFirst,
+        Bump::Search,
+        Some(account_len),
+    )?;
+
+    if exists {
+        return Ok(());
+    }
+
+    let mut data = [0u8; CONFIGURE_ACCOUNT_DATA_LEN];
+    data[0] = CONFIDENTIAL_TRANSFER_EXTENSION;
+    data[1] = CONFIGURE_ACCOUNT;
+    data[2..38].copy_from_slice(decryptable_zero_balance);
+    data[38..46].copy_from_slice(&maximum_pending_balance_credit_counter.to_le_bytes());
+    data[46] = proof_instruction_offset as u8;
+
+    // The token program only reads a record account when the proof
+    // instruction points to one, so it is passed only if the client did
+    match remaining.first() {
+        Some(record) => invoke(
+            &InstructionView {
+                program_id: token_program.address(),
+                accounts: &[
+                    InstructionAccount::writable(associated_token_account.address()),
+                    InstructionAccount::readonly(mint.address()),
+                    InstructionAccount::readonly(proof.address()),
+                    InstructionAccount::readonly(record.address()),
+                    InstructionAccount::readonly_signer(wallet.address()),
+                ],
+                data: &data,
+            },
+            &[associated_token_account, mint, proof, record, wallet],
+        ),
+        None => invoke(
+            &InstructionView {
+                program_id: token_program.address(),
+                accounts: &[
+                    InstructionAccount::writable(associated_token_account.address()),
+                    InstructionAccount::readonly(mint.address()),
+                    InstructionAccount::readonly(proof.address()),
+                    InstructionAccount::readonly_signer(wallet.address()),
+                ],
+                data: &data,
+            },
+            &[associated_token_account, mint, proof, wallet],
+        ),
+    }
+}
@@ -0,0 +1,60 @@
+use {
+    crate::create::{Bump, CheckOrder, CreateAccountViews, create_associated_token_account},
+    pinocchio::{AccountView, Address, ProgramResult, error::ProgramError},
+    pinocchio_associated_token_account_interface::{
+        account_indices::{CREATE_MANY_GROUP_LEN, MAX_CREATE_MANY_GROUPS},
+        error::AssociatedTokenAccountError,
+        instruction::CreateMode,
+    },
+};
+
+/// Creates the ATA of every `[ata, wallet, mint, token_program]` group that
+/// follows the funder and the system program.
+///
+/// Each group goes through the same checks as a single `Create` or
+/// `CreateIdempotent`, in account order, so the first failing group fails the
+/// instruction and every account created before it is rolled back. More than
+/// `MAX_CREATE_MANY_GROUPS` groups fail before any is processed.
+#[inline(always)]
+pub(crate) fn process_create_many(
+    program_id: &Address,
+    accounts: &mut [AccountView],
+    create_mode: CreateMode,
+) -> ProgramResult {
+    // The system program is never read, the CPI resolves it by address
+    let [payer, _system_program, groups @ ..] = accounts else {
+        return Err(ProgramError::NotEnoughAccountKeys);
+    };
+
+    // A missing group or a trailing partial one means the caller lost track
+    // of the account list, so reject it rather than skip it
+    let groups = groups.chunks_exact(CREATE_MANY_GROUP_LEN);
+    if groups.len() == 0 || !groups.remainder().is_empty() {
+        return Err(ProgramError::NotEnoughAccountKeys);
+    }
+    if groups.len() > MAX_CREATE_MANY_GROUPS {
+        return Err(AssociatedTokenAccountError::GroupLimitExceeded.into());
+    }
+
+    for group in groups {
+        let [associated_token_account, wallet, mint, token_program] = group else {
+            return Err(ProgramError::NotEnoughAccountKeys);
+        };
+        create_associated_token_account(
+            program_id,
+            CreateAccountViews {
+                payer,
+                associated_token_account,
+                wallet,
+                mint,
+                token_program,
+                rent_sysvar: None,
+            },
+            create_mode,
+            CheckOrder::CheapFirst,
+            Bump::Search,
+            None,
+        )?;
+    }
+    Ok(())
+}
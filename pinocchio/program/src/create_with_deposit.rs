@@ -0,0 +1,87 @@
+use {
+    crate::{
+        close::check_address,
+        create::{Bump, CheckOrder, CreateAccountViews, create_associated_token_account},
+        recover::invoke_transfer_checked,
+    },
+    pinocchio::{AccountView, Address, ProgramResult, error::ProgramError},
+    pinocchio_associated_token_account_interface::instruction::CreateMode,
+    pinocchio_token_2022::instructions::TransferChecked,
+};
+
+/// Creates the wallet's ATA for the mint, then deposits `amount` tokens into
+/// it from the funder's associated token account.
+///
+/// The source must be the funder's ATA for the mint and token program, so a
+/// funder delegated on another account cannot move its tokens here. The
+/// funder's signature is passed through to the token program's
+/// `TransferChecked` as the source owner's authority, so the token program
+/// checks the balance and the mint's decimals. Any failure there rolls back
+/// the creation as well.
+///
+/// Accounts after the source are forwarded to the transfer, stripped of signer
+/// privileges, for the token program to pick the extra accounts of a mint's
+/// `TransferHook` from.
+#[inline(always)]
+pub(crate) fn process_create_with_deposit(
+    program_id: &Address,
+    accounts: &mut [AccountView],
+    create_mode: CreateMode,
+    amount: u64,
+    decimals: u8,
+) -> ProgramResult {
+    // The system program is never read, the CPI resolves it by address
+    let [
+        payer,
+        associated_token_account,
+        wallet,
+        mint,
+        _system_program,
+        token_program,
+        source,
+        hook_accounts @ ..,
+    ] = accounts
+    else {
+        return Err(ProgramError::NotEnoughAccountKeys);
+    };
+
+    check_address(program_id, source, payer, mint, token_program)?;
+
+    create_associated_token_account(
+        program_id,
+        CreateAccountViews {
+            payer,
+            associated_token_account,
+            wallet,
+            mint,
+            token_program,
+            rent_sysvar: None,
+        },
+        create_mode,
+        CheckOrder::CheapFirst,
+        Bump::Search,
+        None,
+    )?;
+
+    if hook_accounts.is_empty() {
+        return TransferChecked {
+            from: source,
+            mint,
+            to: associated_token_account,
+            authority: payer,
+            amount,
+            decimals,
+            token_program: token_program.address(),
+        }
+        .invoke();
+    }
+    invoke_transfer_checked(
+        token_program.address(),
+        [source, mint, associated_token_account, payer],
+        hook_accounts,
+        amount,
+        decimals,
+        None,
+        &[],
+    )
+}
@@ -0,0 +1,84 @@
+use {
+    crate::create::{Bump, CheckOrder, CreateAccountViews, create_associated_token_account},
+    pinocchio::{AccountView, Address, ProgramResult, error::ProgramError},
+    pinocchio_associated_token_account_interface::instruction::CreateMode,
+    pinocchio_log::log,
+    pinocchio_system::instructions::Transfer,
+    pinocchio_token_2022::instructions::SyncNative,
+};
+
+/// Native mint of SPL Token.
+const NATIVE_MINT: Address = Address::from_str_const("So11111111111111111111111111111111111111112");
+/// Native mint of Token-2022.
+const NATIVE_MINT_2022: Address =
+    Address::from_str_const("9pan9bMn5HatX4EJdBwg9VgCa7Uz5HL8N1m5D3NdXejP");
+
+/// Creates the wallet's ATA for the token program's native mint, then wraps
+/// `lamports` into it.
+///
+/// The lamports move from the funder with a system transfer, and the token
+/// program's `SyncNative` brings the token amount in line with the account's
+/// balance. With `CreateMode::Idempotent` an existing account is topped up the
+/// same way, so one instruction covers both a first deposit and later ones.
+#[inline(always)]
+pub(crate) fn process_create_wrapped(
+    program_id: &Address,
+    accounts: &mut [AccountView],
+    create_mode: CreateMode,
+    lamports: u64,
+) -> ProgramResult {
+    // The system program is never read, the CPI resolves it by address
+    let [
+        payer,
+        associated_token_account,
+        wallet,
+        mint,
+        _system_program,
+        token_program,
+        ..,
+    ] = accounts
+    else {
+        return Err(ProgramError::NotEnoughAccountKeys);
+    };
+
+    let native_mint = if *token_program.address() == pinocchio_token_2022::ID {
+        &NATIVE_MINT_2022
+    } else {
+        &NATIVE_MINT
+    };
+    if mint.address() != native_mint {
+        log!("Error: Mint is not the native mint of the token program");
+        return Err(ProgramError::InvalidArgument);
+    }
+
+    create_associated_token_account(
+        program_id,
+        CreateAccountViews {
+            payer,
+            associated_token_account,
+            wallet,
+            mint,
+            token_program,
+            rent_sysvar: None,
+        },
+        create_mode,
+        CheckOrder::CheapFirst,
+        Bump::Search,
+        None,
+    )?;
+
+    if lamports > 0 {
+        Transfer {
+            from: payer,
+            to: associated_token_account,
+            lamports,
+        }
+        .invoke()?;
+    }
+
+    SyncNative {
+        native_token: associated_token_account,
+        token_program: token_program.address(),
+    }
+    .invoke()
+}
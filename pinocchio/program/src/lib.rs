@@ -4,9 +4,24 @@
 
 #![no_std]
 
+mod assert_exists;
 mod batch;
+mod close;
+mod close_empty;
 mod create;
+mod create_confidential;
+mod create_many;
+mod create_with_deposit;
+mod create_wrapped;
 mod entrypoint;
+#[cfg(any(
+    feature = "reject-permanent-delegate-mints",
+    feature = "reject-mint-close-authority-mints"
+))]
+mod policy;
 mod processor;
+#[cfg(feature = "reclaim-uninitialized")]
+mod reclaim;
 mod recover;
 mod size;
+mod version;
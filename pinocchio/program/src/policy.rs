@@ -0,0 +1,37 @@
+//! Deployment policy restricting which mints associated token accounts are
+//! created for, selected with the `reject-*-mints` features.
+
+use {
+    pinocchio::{AccountView, ProgramResult},
+    pinocchio_associated_token_account_interface::{
+        error::AssociatedTokenAccountError, mint_risk::MintRiskFlags,
+    },
+    pinocchio_log::log,
+};
+
+/// Mint risk flags enabled for rejection in this build.
+#[inline(always)]
+fn rejected_mint_risks() -> MintRiskFlags {
+    let mut rejected = MintRiskFlags::empty();
+    #[cfg(feature = "reject-permanent-delegate-mints")]
+    {
+        rejected = rejected | MintRiskFlags::PERMANENT_DELEGATE;
+    }
+    #[cfg(feature = "reject-mint-close-authority-mints")]
+    {
+        rejected = rejected | MintRiskFlags::MINT_CLOSE_AUTHORITY;
+    }
+    rejected
+}
+
+/// Fails with `MintRejectedByPolicy` if `mint` has an extension rejected by
+/// this build.
+#[inline(always)]
+pub(crate) fn check_mint_policy(mint: &AccountView) -> ProgramResult {
+    let mint_risks = MintRiskFlags::from_mint_data(&mint.try_borrow()?)?;
+    if mint_risks.intersects(rejected_mint_risks()) {
+        log!("Error: mint is rejected by this deployment's mint policy");
+        return Err(AssociatedTokenAccountError::MintRejectedByPolicy.into());
+    }
+    Ok(())
+}
@@ -1,5 +1,20 @@
+#[cfg(feature = "reclaim-uninitialized")]
+use crate::reclaim::process_reclaim_uninitialized;
+#[cfg(not(feature = "empty-data-create"))]
+use pinocchio_associated_token_account_interface::error::AssociatedTokenAccountError;
 use {
-    crate::{create::process_create_associated_token_account, recover::process_recover_nested},
+    crate::{
+        assert_exists::process_assert_ata_exists,
+        close::process_close,
+        close_empty::process_close_empty,
+        create::{Bump, CheckOrder, CreateAccounts, process_create_associated_token_account},
+        create_confidential::process_create_confidential,
+        create_many::process_create_many,
+        create_with_deposit::process_create_with_deposit,
+        create_wrapped::process_create_wrapped,
+        recover::{RecoverBumps, process_recover_nested},
+        version::process_get_version,
+    },
     pinocchio::{AccountView, Address, ProgramResult},
     pinocchio_associated_token_account_interface::instruction::{
         AssociatedTokenAccountInstruction, CreateMode,
@@ -12,22 +27,30 @@ pub fn process_instruction(
     accounts: &mut [AccountView],
     instruction_data: &[u8],
 ) -> ProgramResult {
-    match AssociatedTokenAccountInstruction::try_from_bytes(instruction_data)? {
+    // SPL ATA predates instruction data and treats an empty buffer as `Create`
+    #[cfg(not(feature = "empty-data-create"))]
+    if instruction_data.is_empty() {
+        return Err(AssociatedTokenAccountError::EmptyInstructionData.into());
+    }
+
+    let result = match AssociatedTokenAccountInstruction::try_from_bytes(instruction_data)? {
         AssociatedTokenAccountInstruction::Create => process_create_associated_token_account(
             program_id,
             accounts,
+            CreateAccounts::WithSystemProgram,
             CreateMode::Always,
-            false,
-            None,
+            CheckOrder::Spl,
+            Bump::Search,
             None,
         ),
         AssociatedTokenAccountInstruction::CreateIdempotent => {
             process_create_associated_token_account(
                 program_id,
                 accounts,
+                CreateAccounts::WithSystemProgram,
                 CreateMode::Idempotent,
-                false,
-                None,
+                CheckOrder::Spl,
+                Bump::Search,
                 None,
             )
         }
@@ -38,13 +61,124 @@ pub fn process_instruction(
         } => process_create_associated_token_account(
             program_id,
             accounts,
+            CreateAccounts::WithSystemProgram,
             mode,
-            true,
-            bump.get().map(Into::into),
+            CheckOrder::CheapFirst,
+            bump.get()
+                .map_or(Bump::Search, |bump| Bump::Hint(bump.into())),
+            account_len.get().map(Into::into),
+        ),
+        AssociatedTokenAccountInstruction::CreateCompact {
+            mode,
+            bump,
+            account_len,
+        } => process_create_associated_token_account(
+            program_id,
+            accounts,
+            CreateAccounts::WithoutSystemProgram,
+            mode,
+            CheckOrder::CheapFirst,
+            bump.get()
+                .map_or(Bump::Search, |bump| Bump::Hint(bump.into())),
+            account_len.get().map(Into::into),
+        ),
+        AssociatedTokenAccountInstruction::CreateWithBumpLimit {
+            mode,
+            min_bump,
+            account_len,
+        } => process_create_associated_token_account(
+            program_id,
+            accounts,
+            CreateAccounts::WithSystemProgram,
+            mode,
+            CheckOrder::CheapFirst,
+            Bump::SearchDownTo(min_bump),
             account_len.get().map(Into::into),
         ),
         AssociatedTokenAccountInstruction::RecoverNested => {
-            process_recover_nested(program_id, accounts)
+            process_recover_nested(program_id, accounts, RecoverBumps::default(), false, None)
         }
+        AssociatedTokenAccountInstruction::RecoverNestedWithArgs {
+            owner_bump,
+            nested_bump,
+            destination_bump,
+        } => process_recover_nested(
+            program_id,
+            accounts,
+            RecoverBumps {
+                owner: owner_bump.get().map(Into::into),
+                nested: nested_bump.get().map(Into::into),
+                destination: destination_bump.get().map(Into::into),
+            },
+            false,
+            None,
+        ),
+        AssociatedTokenAccountInstruction::RecoverNestedIdempotent => {
+            process_recover_nested(program_id, accounts, RecoverBumps::default(), true, None)
+        }
+        AssociatedTokenAccountInstruction::Close => process_close(program_id, accounts),
+        AssociatedTokenAccountInstruction::CloseEmpty => process_close_empty(program_id, accounts),
+        AssociatedTokenAccountInstruction::GetVersion => process_get_version(),
+        AssociatedTokenAccountInstruction::AssertAtaExists => {
+            process_assert_ata_exists(program_id, accounts)
+        }
+        AssociatedTokenAccountInstruction::CreateMany { mode } => {
+            process_create_many(program_id, accounts, mode)
+        }
+        AssociatedTokenAccountInstruction::CreateWrapped { mode, lamports } => {
+            process_create_wrapped(program_id, accounts, mode, lamports)
+        }
+        AssociatedTokenAccountInstruction::CreateWithDeposit {
+            mode,
+            amount,
+            decimals,
+        } => process_create_with_deposit(program_id, accounts, mode, amount, decimals),
+        AssociatedTokenAccountInstruction::CreateConfidential {
+            mode,
+            decryptable_zero_balance,
+            maximum_pending_balance_credit_counter,
+            proof_instruction_offset,
+        } => process_create_confidential(
+            program_id,
+            accounts,
+            mode,
+            &decryptable_zero_balance,
+            maximum_pending_balance_credit_counter,
+            proof_instruction_offset,
+        ),
+        AssociatedTokenAccountInstruction::RecoverNestedWithMemo { .. } => process_recover_nested(
+            program_id,
+            accounts,
+            RecoverBumps::default(),
+            false,
+            Some(AssociatedTokenAccountInstruction::memo(instruction_data)),
+        ),
+        #[cfg(feature = "reclaim-uninitialized")]
+        AssociatedTokenAccountInstruction::ReclaimUninitialized => {
+            process_reclaim_uninitialized(program_id, accounts)
+        }
+        // Decoded like any other instruction, but left out of the build
+        #[cfg(not(feature = "reclaim-uninitialized"))]
+        AssociatedTokenAccountInstruction::ReclaimUninitialized => {
+            Err(pinocchio::error::ProgramError::InvalidInstructionData)
+        }
+    };
+
+    #[cfg(any(debug_assertions, feature = "paranoid"))]
+    if result.is_ok() {
+        assert_not_owned_by_program(program_id, accounts)?;
+    }
+
+    result
+}
+
+/// The ATA program never owns accounts, so any account it ends up owning
+/// indicates an ownership assignment bug in the instruction just processed.
+#[cfg(any(debug_assertions, feature = "paranoid"))]
+fn assert_not_owned_by_program(program_id: &Address, accounts: &[AccountView]) -> ProgramResult {
+    if accounts.iter().any(|account| account.owned_by(program_id)) {
+        pinocchio_log::log!("Error: account left owned by the associated token account program");
+        return Err(pinocchio::error::ProgramError::IllegalOwner);
     }
+    Ok(())
 }
@@ -0,0 +1,77 @@
+use {
+    crate::close::{check_token_program, check_wallet, check_writable},
+    pinocchio::{
+        AccountView, Address, ProgramResult, cpi::Signer, error::ProgramError, instruction::seeds,
+    },
+    pinocchio_associated_token_account_interface::pda::AssociatedTokenPda,
+    pinocchio_log::log,
+    pinocchio_system::instructions::Transfer,
+};
+
+/// Transfers the lamports held at the wallet's associated token account address to the wallet,
+/// as long as no account has been created there.
+///
+/// Only the ATA program can sign for the address, so lamports sent to it before the account exists
+/// cannot be moved by anyone else. `create_associated_token_account` keeps them as part of the
+/// rent, and this returns them to the wallet instead. The address must be the canonical ATA of the
+/// wallet, mint and token program, and the wallet must sign, so nobody else can redirect them.
+#[inline(always)]
+pub(crate) fn process_reclaim_uninitialized(
+    program_id: &Address,
+    accounts: &mut [AccountView],
+) -> ProgramResult {
+    // The system program is never read, the CPI resolves it by address
+    let [
+        associated_token_account,
+        wallet,
+        mint,
+        token_program,
+        _system_program,
+        ..,
+    ] = accounts
+    else {
+        return Err(ProgramError::NotEnoughAccountKeys);
+    };
+
+    check_token_program(token_program)?;
+    check_wallet(wallet)?;
+    check_writable(associated_token_account)?;
+
+    // Only a bare system account can be debited by the system program, and anything else at the
+    // address has been created already
+    if !associated_token_account.owned_by(&pinocchio_system::ID)
+        || associated_token_account.data_len() != 0
+    {
+        log!("Error: Associated token account already initialized");
+        return Err(ProgramError::AccountAlreadyInitialized);
+    }
+
+    // The signed `Transfer` rejects an on-curve address
+    let bump_seed = AssociatedTokenPda::find_bump_seed_for_address(
+        program_id,
+        wallet.address(),
+        token_program.address(),
+        mint.address(),
+        associated_token_account.address(),
+        0,
+    )?;
+
+    let lamports = associated_token_account.lamports();
+    if lamports == 0 {
+        return Ok(());
+    }
+
+    let bump_ref = &[bump_seed];
+    let seeds = seeds!(
+        wallet.address().as_ref(),
+        token_program.address().as_ref(),
+        mint.address().as_ref(),
+        bump_ref
+    );
+    Transfer {
+        from: associated_token_account,
+        to: wallet,
+        lamports,
+    }
+    .invoke_signed(&[Signer::from(&seeds)])
+}
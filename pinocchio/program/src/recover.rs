@@ -1,6 +1,11 @@
 use {
+    core::{mem::MaybeUninit, slice::from_raw_parts},
     pinocchio::{
-        AccountView, Address, ProgramResult, cpi::Signer, error::ProgramError, instruction::seeds,
+        AccountView, Address, ProgramResult,
+        cpi::{CpiAccount, Signer, invoke, invoke_signed_unchecked, set_return_data},
+        error::ProgramError,
+        instruction::{InstructionAccount, InstructionView, seeds},
+        sysvars::{Sysvar, clock::Clock},
     },
     pinocchio_associated_token_account_interface::{
         error::AssociatedTokenAccountError, pda::AssociatedTokenPda,
@@ -10,8 +15,43 @@ use {
         instructions::{CloseAccount, MAX_MULTISIG_SIGNERS, TransferChecked},
         state::{Account, Mint, Multisig, StateWithExtensions},
     },
+    spl_token_2022_interface::{
+        extension::{
+            BaseStateWithExtensions, StateWithExtensions as SplStateWithExtensions,
+            transfer_fee::TransferFeeConfig,
+        },
+        state::Mint as SplMint,
+    },
 };
 
+/// Most transfer hook extra accounts forwarded to `TransferChecked`, bounding
+/// the CPI buffers of [`invoke_transfer_checked`].
+const MAX_TRANSFER_HOOK_ACCOUNTS: usize = 24;
+/// `TokenInstruction::TransferChecked`.
+const TRANSFER_CHECKED: u8 = 12;
+/// Source, mint, destination and authority.
+const TRANSFER_CHECKED_ACCOUNTS: usize = 4;
+/// Discriminator, amount and decimals.
+const TRANSFER_CHECKED_DATA_LEN: usize = 10;
+/// `TokenInstruction::TransferFeeExtension`.
+const TRANSFER_FEE_EXTENSION: u8 = 26;
+/// `TransferFeeInstruction::TransferCheckedWithFee`.
+const TRANSFER_CHECKED_WITH_FEE: u8 = 1;
+/// Discriminators, amount, decimals and fee.
+const TRANSFER_CHECKED_WITH_FEE_DATA_LEN: usize = 19;
+
+/// SPL Memo program, invoked by `RecoverNestedWithMemo`.
+const MEMO_PROGRAM_ID: Address =
+    Address::from_str_const("MemoSq4gqABAXKb96qnH8TysNcWxMyWCqXgDLGmfcHr");
+
+/// Caller-supplied bump seeds of the ATAs involved in `RecoverNested`.
+#[derive(Clone, Copy, Default)]
+pub(crate) struct RecoverBumps {
+    pub(crate) owner: Option<u8>,
+    pub(crate) nested: Option<u8>,
+    pub(crate) destination: Option<u8>,
+}
+
 /// Recovers tokens stuck in a "nested" ATA (one that was created by mistakenly using an ATA address
 /// as the wallet/owner when deriving a new ATA). Since that ATA is a PDA, the tokens would be
 /// permanently inaccessible without this instruction.
@@ -40,10 +80,45 @@ use {
 ///                         │
 ///                   rent ──▶ wallet
 /// ```
-#[inline(always)]
+///
+/// Native mint (WSOL) accounts take the same path: for them the token program moves the `amount`
+/// lamports along with the tokens in `TransferChecked`, leaving the destination's rent-exempt
+/// reserve intact, and `CloseAccount` returns the nested account's reserve and any lamports never
+/// synced into its `amount` to the wallet.
+///
+/// Each of the three addresses is found by a bump search unless `bumps` gives its bump. The owner
+/// ATA's bump signs the transfer and close, so a given one must be canonical: every higher bump
+/// is derived and must be on-curve. The nested and destination bumps are only compared against the
+/// given addresses, so each is checked with a single derivation: the nested ATA must already exist
+/// and the destination must be an initialized token account for the transfer to succeed.
+///
+/// If `idempotent`, a nested ATA that does not exist or holds no tokens is left as is and the
+/// instruction succeeds once the addresses and the wallet's authority are checked.
+///
+/// Mints with the `TransferHook` extension make the nested token program invoke the hook program
+/// during `TransferChecked`, with the extra accounts it requires. Every account after the nested
+/// token program, or after the memo program with a memo, is forwarded to the transfer for the token
+/// program to pick them from, stripped of signer privileges so that the signers of a multisig
+/// wallet, passed there as well, cannot be used by the hook.
+///
+/// Mints with the `TransferFeeConfig` extension withhold a fee from the recovered tokens. The fee for
+/// the current epoch is computed here and the transfer made with `TransferCheckedWithFee`, so that
+/// the token program fails rather than withholding a different amount. The fee is logged and set
+/// as return data, as a little-endian `u64`, once the nested ATA is closed.
+///
+/// With a `memo`, the nested token program account is required and followed by the memo program,
+/// which is invoked with the memo right before the transfer, as the `MemoTransfer` extension of a
+/// destination requiring memos checks. An empty memo skips the memo program.
+///
+/// Kept out of line, as the four `RecoverNested` variants dispatch here and
+/// would otherwise each get a copy of its body.
+#[inline(never)]
 pub(crate) fn process_recover_nested(
     program_id: &Address,
     accounts: &mut [AccountView],
+    bumps: RecoverBumps,
+    idempotent: bool,
+    memo: Option<&[u8]>,
 ) -> ProgramResult {
     let [
         nested_ata,
@@ -59,16 +134,60 @@ pub(crate) fn process_recover_nested(
         return Err(ProgramError::NotEnoughAccountKeys);
     };
 
-    // Optional to specify nested token program if different from owner one
-    let nested_token_program = remaining.first().unwrap_or(owner_token_program);
+    // The nested ATA is drained and closed into the wallet, and the destination
+    // is credited. Checked up front rather than left to the runtime, which would
+    // only fail after both token CPIs have run.
+    if !nested_ata.is_writable() || !destination_ata.is_writable() || !wallet.is_writable() {
+        log!("Error: Nested, destination and wallet accounts must be writable");
+        return Err(ProgramError::Immutable);
+    }
 
-    // `owner_ata` must be the canonical ATA for wallet & `owner_token_mint`
-    let (derived_owner_ata, bump_seed) = AssociatedTokenPda::derive_address_and_bump_seed(
-        program_id,
-        wallet.address(),
-        owner_token_program.address(),
-        owner_token_mint.address(),
-    );
+    // Optional to specify nested token program if different from owner one,
+    // unless the memo program follows it
+    let (nested_token_program, memo, extra_accounts) = match memo {
+        Some(memo) => {
+            let [nested_token_program, memo_program, extra_accounts @ ..] = &*remaining else {
+                return Err(ProgramError::NotEnoughAccountKeys);
+            };
+            if *memo_program.address() != MEMO_PROGRAM_ID {
+                log!("Error: Memo program account is not the SPL Memo program");
+                return Err(ProgramError::IncorrectProgramId);
+            }
+            (
+                nested_token_program,
+                Some((memo_program, memo)),
+                extra_accounts,
+            )
+        }
+        None => (
+            remaining.first().unwrap_or(owner_token_program),
+            None,
+            remaining.get(1..).unwrap_or_default(),
+        ),
+    };
+
+    // `owner_ata` must be the canonical ATA for wallet & `owner_token_mint`.
+    // The program signs for it with this bump, and the runtime accepts any
+    // off-curve bump, so a hint is only taken if every higher bump is on-curve.
+    let (derived_owner_ata, bump_seed) = match bumps.owner {
+        Some(bump) => (
+            AssociatedTokenPda::derive_address_with_bump_hint(
+                program_id,
+                wallet.address(),
+                owner_token_program.address(),
+                owner_token_mint.address(),
+                bump,
+            )?,
+            bump,
+        ),
+        None => derive_ata(
+            program_id,
+            wallet,
+            owner_token_program,
+            owner_token_mint,
+            None,
+        ),
+    };
     if derived_owner_ata != *owner_ata.address() {
         log!("Error: Owner associated address does not match seed derivation");
         return Err(ProgramError::InvalidSeeds);
@@ -76,11 +195,12 @@ pub(crate) fn process_recover_nested(
 
     // `nested_ata` must be derived from owner_ata as the "wallet".
     // The `owner_ata` address was mistakenly used where a wallet address should have been.
-    let derived_nested_ata = AssociatedTokenPda::derive_address(
+    let (derived_nested_ata, _) = derive_ata(
         program_id,
-        owner_ata.address(),
-        nested_token_program.address(),
-        nested_token_mint.address(),
+        owner_ata,
+        nested_token_program,
+        nested_token_mint,
+        bumps.nested,
     );
     if derived_nested_ata != *nested_ata.address() {
         log!("Error: Nested associated address does not match seed derivation");
@@ -88,29 +208,43 @@ pub(crate) fn process_recover_nested(
     }
 
     // `destination_ata` must be the wallet's correct ATA for the nested mint
-    let derived_destination_ata = AssociatedTokenPda::derive_address(
+    let (derived_destination_ata, _) = derive_ata(
         program_id,
-        wallet.address(),
-        nested_token_program.address(),
-        nested_token_mint.address(),
+        wallet,
+        nested_token_program,
+        nested_token_mint,
+        bumps.destination,
     );
     if derived_destination_ata != *destination_ata.address() {
         log!("Error: Destination associated address does not match seed derivation");
         return Err(ProgramError::InvalidSeeds);
     }
 
+    // Both token programs are invoked, never this program. Checked after the
+    // derivations, which fail with `InvalidSeeds` as in SPL ATA unless the
+    // addresses were derived with this program as the token program, and
+    // before the mint ownership checks would reject it less clearly.
+    if owner_token_program.address() == program_id || nested_token_program.address() == program_id {
+        log!("Error: Token program cannot be the associated token account program");
+        return Err(ProgramError::IncorrectProgramId);
+    }
+
     // Multisig wallets are authorized by their configured signer accounts.
     // Other wallet accounts must sign directly.
     if wallet.data_len() == Multisig::LEN
         && (wallet.owned_by(&pinocchio_token::ID) || wallet.owned_by(&pinocchio_token_2022::ID))
     {
-        let wallet_signers = remaining.get(1..).unwrap_or_default();
-        validate_multisig_wallet(wallet, wallet_signers)?;
+        validate_multisig_wallet(wallet, extra_accounts)?;
     } else if !wallet.is_signer() {
         log!("Wallet of the owner associated token account must sign");
         return Err(ProgramError::MissingRequiredSignature);
     }
 
+    // Nothing to recover from a nested ATA that was never created
+    if idempotent && nested_ata.owned_by(&pinocchio_system::ID) {
+        return Ok(());
+    }
+
     // The owner mint must belong to the token program we will CPI into
     if !owner_token_mint.owned_by(owner_token_program.address()) {
         log!("Owner mint not owned by provided token program");
@@ -162,7 +296,14 @@ pub(crate) fn process_recover_nested(
     let nested_mint = StateWithExtensions::<Mint>::from_bytes(&nested_mint_data)?;
     let amount = nested_account.base.amount();
     let decimals = nested_mint.base.decimals();
+    let fee = transfer_fee(nested_token_program, &nested_mint_data, amount)?;
     drop(nested_account_data);
+    drop(nested_mint_data);
+
+    // An empty nested ATA is left open rather than closed
+    if idempotent && amount == 0 {
+        return Ok(());
+    }
 
     let bump_ref = &[bump_seed];
     let seeds = seeds!(
@@ -172,17 +313,45 @@ pub(crate) fn process_recover_nested(
         bump_ref
     );
 
+    // Token-2022 looks for the memo in the instruction processed right before
+    // the transfer
+    if let Some((memo_program, memo)) = memo {
+        if !memo.is_empty() {
+            invoke(
+                &InstructionView {
+                    program_id: memo_program.address(),
+                    accounts: &[],
+                    data: memo,
+                },
+                &[],
+            )?;
+        }
+    }
+
     // Move all tokens from the nested ATA to the wallet's correct ATA
-    TransferChecked {
-        from: nested_ata,
-        mint: nested_token_mint,
-        to: destination_ata,
-        authority: owner_ata,
-        amount,
-        decimals,
-        token_program: nested_token_program.address(),
+    let hook_accounts = extra_accounts;
+    if hook_accounts.is_empty() && fee.is_none() {
+        TransferChecked {
+            from: nested_ata,
+            mint: nested_token_mint,
+            to: destination_ata,
+            authority: owner_ata,
+            amount,
+            decimals,
+            token_program: nested_token_program.address(),
+        }
+        .invoke_signed(&[Signer::from(&seeds)])?;
+    } else {
+        invoke_transfer_checked(
+            nested_token_program.address(),
+            [nested_ata, nested_token_mint, destination_ata, owner_ata],
+            hook_accounts,
+            amount,
+            decimals,
+            fee,
+            &[Signer::from(&seeds)],
+        )?;
     }
-    .invoke_signed(&[Signer::from(&seeds)])?;
 
     // Close the now-empty nested ATA and return its rent lamports to the wallet
     CloseAccount {
@@ -191,7 +360,160 @@ pub(crate) fn process_recover_nested(
         authority: owner_ata,
         token_program: nested_token_program.address(),
     }
-    .invoke_signed(&[Signer::from(&seeds)])
+    .invoke_signed(&[Signer::from(&seeds)])?;
+
+    if let Some(fee) = fee {
+        log!("Transfer fee withheld: {}", fee);
+        set_return_data(&fee.to_le_bytes());
+    }
+
+    Ok(())
+}
+
+/// Fee the nested mint's `TransferFeeConfig` withholds from `amount` in the
+/// current epoch, or `None` if the mint has no transfer fee.
+#[inline(always)]
+fn transfer_fee(
+    token_program: &AccountView,
+    mint_data: &[u8],
+    amount: u64,
+) -> Result<Option<u64>, ProgramError> {
+    // Only extended Token-2022 mints can have the extension
+    if *token_program.address() != pinocchio_token_2022::ID || mint_data.len() <= Mint::BASE_LEN {
+        return Ok(None);
+    }
+    let mint = SplStateWithExtensions::<SplMint>::unpack(mint_data)?;
+    let Ok(transfer_fee_config) = mint.get_extension::<TransferFeeConfig>() else {
+        return Ok(None);
+    };
+    let epoch = Clock::get()?.epoch;
+    transfer_fee_config
+        .calculate_epoch_fee(epoch, amount)
+        .map(Some)
+        .ok_or(ProgramError::ArithmeticOverflow)
+}
+
+/// `TransferChecked` of `amount` from `source` to `destination`, with
+/// `hook_accounts` appended for the mint's transfer hook, or
+/// `TransferCheckedWithFee` if `fee` is given. The authority signs through
+/// the transaction or `signers`.
+///
+/// `pinocchio-token-2022`'s `TransferChecked` takes no extra accounts, so the
+/// CPI is built here. Kept out of line so that its buffers only take stack
+/// space when hook accounts or a fee are passed.
+#[inline(never)]
+pub(crate) fn invoke_transfer_checked(
+    token_program: &Address,
+    [source, mint, destination, authority]: [&AccountView; 4],
+    hook_accounts: &[AccountView],
+    amount: u64,
+    decimals: u8,
+    fee: Option<u64>,
+    signers: &[Signer],
+) -> ProgramResult {
+    if hook_accounts.len() > MAX_TRANSFER_HOOK_ACCOUNTS {
+        log!("Error: Too many transfer hook accounts");
+        return Err(ProgramError::InvalidArgument);
+    }
+    let accounts_len = TRANSFER_CHECKED_ACCOUNTS.saturating_add(hook_accounts.len());
+
+    let mut data = [0u8; TRANSFER_CHECKED_WITH_FEE_DATA_LEN];
+    let data_len = match fee {
+        Some(fee) => {
+            data[0] = TRANSFER_FEE_EXTENSION;
+            data[1] = TRANSFER_CHECKED_WITH_FEE;
+            data[2..10].copy_from_slice(&amount.to_le_bytes());
+            data[10] = decimals;
+            data[11..19].copy_from_slice(&fee.to_le_bytes());
+            TRANSFER_CHECKED_WITH_FEE_DATA_LEN
+        }
+        None => {
+            data[0] = TRANSFER_CHECKED;
+            data[1..9].copy_from_slice(&amount.to_le_bytes());
+            data[9] = decimals;
+            TRANSFER_CHECKED_DATA_LEN
+        }
+    };
+
+    let mut instruction_accounts = [const { MaybeUninit::<InstructionAccount>::uninit() };
+        TRANSFER_CHECKED_ACCOUNTS + MAX_TRANSFER_HOOK_ACCOUNTS];
+    let mut cpi_accounts = [const { MaybeUninit::<CpiAccount>::uninit() };
+        TRANSFER_CHECKED_ACCOUNTS + MAX_TRANSFER_HOOK_ACCOUNTS];
+    let transfer_accounts = [
+        (source, InstructionAccount::writable(source.address())),
+        (mint, InstructionAccount::readonly(mint.address())),
+        (
+            destination,
+            InstructionAccount::writable(destination.address()),
+        ),
+        (
+            authority,
+            InstructionAccount::readonly_signer(authority.address()),
+        ),
+    ];
+    let hook_accounts = hook_accounts.iter().map(|account| {
+        (
+            account,
+            InstructionAccount::new(account.address(), account.is_writable(), false),
+        )
+    });
+    for (index, (account, instruction_account)) in transfer_accounts
+        .into_iter()
+        .chain(hook_accounts)
+        .enumerate()
+    {
+        instruction_accounts[index].write(instruction_account);
+        cpi_accounts[index].write(CpiAccount::from(account));
+    }
+
+    // SAFETY: The first `accounts_len` entries of both buffers were written
+    // above. No account data is borrowed: callers drop their borrows of the
+    // source account and mint first.
+    unsafe {
+        invoke_signed_unchecked(
+            &InstructionView {
+                program_id: token_program,
+                accounts: from_raw_parts(instruction_accounts.as_ptr() as _, accounts_len),
+                data: &data[..data_len],
+            },
+            from_raw_parts(cpi_accounts.as_ptr().cast(), accounts_len),
+            signers,
+        );
+    }
+
+    Ok(())
+}
+
+/// ATA address and bump of `wallet` and `mint`, derived directly from `bump`
+/// if given, otherwise by searching for the canonical bump.
+#[inline(always)]
+fn derive_ata(
+    program_id: &Address,
+    wallet: &AccountView,
+    token_program: &AccountView,
+    mint: &AccountView,
+    bump: Option<u8>,
+) -> (Address, u8) {
+    match bump {
+        Some(bump) => (
+            Address::derive_address(
+                &[
+                    wallet.address().as_array(),
+                    token_program.address().as_array(),
+                    mint.address().as_array(),
+                ],
+                Some(bump),
+                program_id,
+            ),
+            bump,
+        ),
+        None => AssociatedTokenPda::derive_address_and_bump_seed(
+            program_id,
+            wallet.address(),
+            token_program.address(),
+            mint.address(),
+        ),
+    }
 }
 
 #[inline(always)]
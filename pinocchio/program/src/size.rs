@@ -1,10 +1,12 @@
+#[cfg(feature = "account-data-size-cpi")]
 use {
-    pinocchio::{AccountView, cpi::get_return_data, error::ProgramError},
+    pinocchio::cpi::get_return_data,
     pinocchio_log::log,
-    pinocchio_token_2022::{
-        instructions::GetAccountDataSize,
-        state::{Account, ExtensionType, Mint},
-    },
+    pinocchio_token_2022::{instructions::GetAccountDataSize, state::ExtensionType},
+};
+use {
+    pinocchio::{AccountView, error::ProgramError},
+    pinocchio_token_2022::state::{Account, Mint},
     spl_token_2022_interface::extension::{
         ExtensionType as SplExtensionType, account_len::try_calculate_account_len_from_mint_data,
     },
@@ -22,11 +24,13 @@ const TOKEN_2022_BASE_ACCOUNT_DATA_SIZE: u64 =
     Account::BASE_LEN as u64 + ACCOUNT_TYPE_SIZE as u64 + TLV_HEADER_LEN as u64;
 
 /// Get the required Token-2022 account data size when no account length hint was supplied.
-/// Short-circuits when size is known and falls back to `GetAccountDataSize` CPI for
-/// everything else.
+/// Short-circuits when size is known and otherwise computes it from the mint's TLV data.
+/// Unparsable mint data is rejected, unless the `account-data-size-cpi` feature is
+/// enabled, in which case it falls back to a `GetAccountDataSize` CPI.
 #[inline(always)]
 pub(crate) fn get_token_2022_account_data_size(
     mint: &AccountView,
+    #[cfg_attr(not(feature = "account-data-size-cpi"), allow(unused_variables))]
     token_program: &AccountView,
 ) -> Result<u64, ProgramError> {
     // Associated token accounts for Token-2022 always enable `ImmutableOwner`.
@@ -37,17 +41,21 @@ pub(crate) fn get_token_2022_account_data_size(
         return Ok(TOKEN_2022_BASE_ACCOUNT_DATA_SIZE);
     }
 
-    // Avoid the CPI when the mint data can be used to derive the account size locally.
-    // If there is failure, fallback to a normal CPI to the token program.
-    if let Ok(len) = mint.try_borrow().and_then(|mint_data| {
+    // Derive the account size locally from the mint's extensions
+    let len = mint.try_borrow().and_then(|mint_data| {
         try_calculate_account_len_from_mint_data(&mint_data, &[SplExtensionType::ImmutableOwner])
-    }) {
-        return Ok(len as u64);
+    });
+
+    // Escape hatch: defer to the token program if the mint data cannot be parsed locally
+    #[cfg(feature = "account-data-size-cpi")]
+    if len.is_err() {
+        return get_account_data_size_cpi(mint, token_program);
     }
 
-    get_account_data_size_cpi(mint, token_program)
+    len.map(|len| len as u64)
 }
 
+#[cfg(feature = "account-data-size-cpi")]
 fn get_account_data_size_cpi(
     mint: &AccountView,
     token_program: &AccountView,
@@ -82,3 +90,20 @@ fn get_account_data_size_cpi(
             })
         })
 }
+
+/// Token-2022 account data size with room for the `ConfidentialTransferAccount`
+/// extension as well as `ImmutableOwner`, for `CreateConfidential`.
+/// Computed from the mint's TLV data, so it is returned as the account length
+/// hint that skips the usual size lookup.
+#[inline(always)]
+pub(crate) fn get_confidential_account_data_size(mint: &AccountView) -> Result<u32, ProgramError> {
+    let mint_data = mint.try_borrow()?;
+    try_calculate_account_len_from_mint_data(
+        &mint_data,
+        &[
+            SplExtensionType::ImmutableOwner,
+            SplExtensionType::ConfidentialTransferAccount,
+        ],
+    )
+    .and_then(|len| u32::try_from(len).map_err(|_| ProgramError::InvalidAccountData))
+}
@@ -0,0 +1,69 @@
+use {
+    pinocchio::{ProgramResult, cpi::set_return_data},
+    pinocchio_associated_token_account_interface::version::{
+        FEATURE_ACCOUNT_DATA_SIZE_CPI, FEATURE_ACCOUNT_LEN_HINT, FEATURE_ASSERT_ATA_EXISTS,
+        FEATURE_BUMP_HINT, FEATURE_BUMP_LIMIT, FEATURE_CLOSE, FEATURE_CLOSE_EMPTY,
+        FEATURE_CREATE_COMPACT, FEATURE_CREATE_CONFIDENTIAL, FEATURE_CREATE_MANY,
+        FEATURE_CREATE_WITH_DEPOSIT, FEATURE_CREATE_WRAPPED, FEATURE_PREFUNDED,
+        FEATURE_RECLAIM_UNINITIALIZED, FEATURE_RECOVER_NESTED_BUMP_HINT,
+        FEATURE_RECOVER_NESTED_IDEMPOTENT, FEATURE_RECOVER_NESTED_WITH_MEMO, ProgramVersion,
+    },
+};
+
+const VERSION: ProgramVersion = ProgramVersion {
+    major: parse_version_component(env!("CARGO_PKG_VERSION_MAJOR")),
+    minor: parse_version_component(env!("CARGO_PKG_VERSION_MINOR")),
+    patch: parse_version_component(env!("CARGO_PKG_VERSION_PATCH")),
+    features: FEATURE_PREFUNDED
+        | FEATURE_BUMP_HINT
+        | FEATURE_ACCOUNT_LEN_HINT
+        | FEATURE_CREATE_COMPACT
+        | FEATURE_BUMP_LIMIT
+        | FEATURE_ASSERT_ATA_EXISTS
+        | FEATURE_CREATE_MANY
+        | FEATURE_RECOVER_NESTED_BUMP_HINT
+        | FEATURE_RECOVER_NESTED_IDEMPOTENT
+        | FEATURE_CLOSE
+        | FEATURE_CLOSE_EMPTY
+        | FEATURE_CREATE_WRAPPED
+        | FEATURE_CREATE_WITH_DEPOSIT
+        | FEATURE_CREATE_CONFIDENTIAL
+        | FEATURE_RECOVER_NESTED_WITH_MEMO
+        | if cfg!(feature = "account-data-size-cpi") {
+            FEATURE_ACCOUNT_DATA_SIZE_CPI
+        } else {
+            0
+        }
+        | if cfg!(feature = "reclaim-uninitialized") {
+            FEATURE_RECLAIM_UNINITIALIZED
+        } else {
+            0
+        },
+};
+
+/// Parses a decimal Cargo version component at compile time.
+const fn parse_version_component(component: &str) -> u16 {
+    let bytes = component.as_bytes();
+    let mut value: u16 = 0;
+    let mut i = 0;
+    while i < bytes.len() {
+        let digit = bytes[i].wrapping_sub(b'0');
+        assert!(digit < 10, "non-decimal version component");
+        value = match value.checked_mul(10) {
+            Some(value) => match value.checked_add(digit as u16) {
+                Some(value) => value,
+                None => panic!("version component overflows u16"),
+            },
+            None => panic!("version component overflows u16"),
+        };
+        i = i.saturating_add(1);
+    }
+    value
+}
+
+/// Sets the program version and supported features as return data.
+#[inline(always)]
+pub fn process_get_version() -> ProgramResult {
+    set_return_data(&VERSION.to_bytes());
+    Ok(())
+}
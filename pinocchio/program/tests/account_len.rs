@@ -1,13 +1,16 @@
 use {
     mollusk_svm_result::Check,
-    pinocchio_associated_token_account_interface::instruction::CreateMode,
+    pinocchio_associated_token_account_interface::{
+        error::AssociatedTokenAccountError, instruction::CreateMode,
+    },
     solana_address::Address,
     solana_program_error::ProgramError,
     solana_program_pack::Pack,
     solana_rent::Rent,
     spl_associated_token_account_mollusk_harness::{
-        AtaProgram, AtaTestHarness, CreateAtaInstructionType,
-        token_2022_immutable_owner_account_len, token_account_rent_exempt_balance,
+        AtaProgram, AtaTestHarness, CreateAtaInstructionType, expected_ata_len_for_mint,
+        expected_rent_for_mint, token_2022_immutable_owner_account_len,
+        token_account_rent_exempt_balance,
     },
     spl_token_2022_interface::{extension::ExtensionType, state::Account as Token2022Account},
     test_case::{test_case, test_matrix},
@@ -75,10 +78,8 @@ fn always_mode_with_existing_ata_fails_before_hint_check() {
             rent_sysvar: false,
         });
 
-    // u32::MAX would fail with InvalidArgument if the hint were checked first.
-    harness
-        .ctx
-        .process_and_validate_instruction(&instruction, &[Check::err(ProgramError::IllegalOwner)]);
+    // u32::MAX would fail with AccountDataLengthExceeded if the hint were checked first.
+    harness.process_and_validate_failed_create(&instruction, ProgramError::IllegalOwner);
 }
 
 #[test_matrix(
@@ -123,10 +124,44 @@ fn token_2022_fails_when_hint_exceeds_system_max_account_size() {
             rent_sysvar: false,
         });
 
-    harness.ctx.process_and_validate_instruction(
-        &instruction,
-        &[Check::err(ProgramError::InvalidArgument)],
-    );
+    harness.process_and_validate_failed_create(&instruction, account_data_length_exceeded());
+}
+
+fn account_data_length_exceeded() -> ProgramError {
+    ProgramError::Custom(AssociatedTokenAccountError::AccountDataLengthExceeded as u32)
+}
+
+// `MAX_PERMITTED_DATA_INCREASE` is the most a CPI can allocate for the new ATA
+#[test_case(10_240, None; "at limit")]
+#[test_case(10_241, Some(account_data_length_exceeded()); "over limit")]
+fn token_2022_hint_is_bounded_by_cpi_growth_limit(
+    account_len: u32,
+    expected_err: Option<ProgramError>,
+) {
+    let mut harness = token_2022_harness(false);
+    let instruction =
+        harness.build_create_ata_instruction(CreateAtaInstructionType::CreateWithArgs {
+            mode: CreateMode::Always,
+            bump: None,
+            account_len: Some(account_len),
+            rent_sysvar: false,
+        });
+
+    match expected_err {
+        Some(err) => harness.process_and_validate_failed_create(&instruction, err),
+        None => {
+            let ata_address = harness.ata_address.unwrap();
+            harness.ctx.process_and_validate_instruction(
+                &instruction,
+                &[
+                    Check::success(),
+                    Check::account(&ata_address)
+                        .space(account_len as usize)
+                        .build(),
+                ],
+            );
+        }
+    }
 }
 
 #[test_case(false; "base mint")]
@@ -141,10 +176,7 @@ fn token_2022_fails_when_hint_is_tiny(transfer_fee_mint: bool) {
             rent_sysvar: false,
         });
 
-    harness.ctx.process_and_validate_instruction(
-        &instruction,
-        &[Check::err(ProgramError::InvalidAccountData)],
-    );
+    harness.process_and_validate_failed_create(&instruction, ProgramError::InvalidAccountData);
 }
 
 #[test_case(false; "base mint")]
@@ -160,10 +192,7 @@ fn token_2022_fails_when_hint_is_one_byte_short(transfer_fee_mint: bool) {
             rent_sysvar: false,
         });
 
-    harness.ctx.process_and_validate_instruction(
-        &instruction,
-        &[Check::err(ProgramError::InvalidAccountData)],
-    );
+    harness.process_and_validate_failed_create(&instruction, ProgramError::InvalidAccountData);
 }
 
 #[test]
@@ -178,10 +207,7 @@ fn token_2022_extended_fails_when_hint_omits_required_extension_space() {
             rent_sysvar: false,
         });
 
-    harness.ctx.process_and_validate_instruction(
-        &instruction,
-        &[Check::err(ProgramError::InvalidAccountData)],
-    );
+    harness.process_and_validate_failed_create(&instruction, ProgramError::InvalidAccountData);
 }
 
 #[test_case(false; "base mint")]
@@ -246,3 +272,20 @@ fn token_2022_allocates_full_hint_when_valid(
         ],
     );
 }
+
+#[test_case(false; "base mint")]
+#[test_case(true; "transfer fee mint")]
+fn expected_ata_len_follows_mint_extensions(transfer_fee_mint: bool) {
+    let mut harness = token_2022_harness(transfer_fee_mint);
+    let mint_account = harness.get_account(harness.mint.unwrap());
+    let account_len = token_2022_required_account_len(transfer_fee_mint);
+
+    assert_eq!(expected_ata_len_for_mint(&mint_account), account_len);
+    assert_eq!(
+        expected_rent_for_mint(&mint_account),
+        Rent::default().minimum_balance(account_len)
+    );
+
+    // `create_ata` checks the created account against the mint-derived space
+    harness.create_ata(CreateAtaInstructionType::Create);
+}
@@ -0,0 +1,38 @@
+use {
+    solana_address::Address,
+    spl_associated_token_account_mollusk_harness::{
+        AtaProgram, AtaTestHarness, CreateAtaInstructionType, HarnessStore, OrderedAccountStore,
+        TokenChecks,
+    },
+    test_case::test_case,
+};
+
+#[test_case(spl_token_interface::id())]
+#[test_case(spl_token_2022_interface::id())]
+fn ordered_store_backs_create(token_program_id: Address) {
+    let mut harness = AtaTestHarness::new_with_store(
+        &token_program_id,
+        AtaProgram::Pinocchio,
+        OrderedAccountStore::default(),
+    )
+    .with_wallet_and_mint(1_000_000, 6);
+
+    let ata_address = harness.create_ata(CreateAtaInstructionType::Create);
+
+    TokenChecks::account(ata_address)
+        .owner_field(harness.wallet.unwrap())
+        .mint_field(harness.mint.unwrap())
+        .assert(&harness);
+
+    // Iteration follows address order regardless of insertion order
+    let addresses: Vec<Address> = harness
+        .ctx
+        .account_store
+        .borrow()
+        .accounts()
+        .into_iter()
+        .map(|(address, _)| address)
+        .collect();
+    assert!(addresses.contains(&ata_address));
+    assert!(addresses.windows(2).all(|pair| pair[0] < pair[1]));
+}
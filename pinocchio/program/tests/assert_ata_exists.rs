@@ -0,0 +1,122 @@
+use {
+    mollusk_svm_result::Check,
+    pinocchio_associated_token_account_interface::error::AssociatedTokenAccountError,
+    solana_address::Address,
+    solana_instruction::{AccountMeta, Instruction},
+    solana_program_error::ProgramError,
+    spl_associated_token_account_interface::address::get_associated_token_address_with_program_id,
+    spl_associated_token_account_mollusk_harness::{AccountBuilder, AtaProgram, AtaTestHarness},
+    test_case::test_case,
+};
+
+fn assert_ata_exists_instruction(
+    ata: Address,
+    wallet: Address,
+    mint: Address,
+    token_program_id: Address,
+) -> Instruction {
+    Instruction {
+        program_id: spl_associated_token_account_interface::program::id(),
+        accounts: vec![
+            AccountMeta::new_readonly(ata, false),
+            AccountMeta::new_readonly(wallet, false),
+            AccountMeta::new_readonly(mint, false),
+            AccountMeta::new_readonly(token_program_id, false),
+        ],
+        data: vec![7],
+    }
+}
+
+fn harness_with_ata(token_program_id: Address) -> AtaTestHarness {
+    AtaTestHarness::new_with_ata_program(&token_program_id, AtaProgram::Pinocchio)
+        .with_wallet_and_mint(1_000_000, 6)
+        .with_ata()
+}
+
+#[test_case(spl_token_interface::id())]
+#[test_case(spl_token_2022_interface::id())]
+fn succeeds_for_existing_ata_without_writing(token_program_id: Address) {
+    let harness = harness_with_ata(token_program_id);
+    let (ata, wallet, mint) = (
+        harness.ata_address.unwrap(),
+        harness.wallet.unwrap(),
+        harness.mint.unwrap(),
+    );
+
+    harness.process_and_assert_untouched(
+        &assert_ata_exists_instruction(ata, wallet, mint, token_program_id),
+        &[Check::success()],
+        &[ata, wallet, mint, harness.payer],
+    );
+}
+
+#[test_case(spl_token_interface::id())]
+#[test_case(spl_token_2022_interface::id())]
+fn fails_for_missing_ata(token_program_id: Address) {
+    let harness = AtaTestHarness::new_with_ata_program(&token_program_id, AtaProgram::Pinocchio)
+        .with_wallet_and_mint(1_000_000, 6);
+    let (wallet, mint) = (harness.wallet.unwrap(), harness.mint.unwrap());
+    let ata = get_associated_token_address_with_program_id(&wallet, &mint, &token_program_id);
+
+    harness.ctx.process_and_validate_instruction(
+        &assert_ata_exists_instruction(ata, wallet, mint, token_program_id),
+        &[Check::err(ProgramError::UninitializedAccount)],
+    );
+}
+
+#[test_case(spl_token_interface::id())]
+#[test_case(spl_token_2022_interface::id())]
+fn fails_for_mismatched_accounts(token_program_id: Address) {
+    let harness = harness_with_ata(token_program_id);
+    let (ata, wallet, mint) = (
+        harness.ata_address.unwrap(),
+        harness.wallet.unwrap(),
+        harness.mint.unwrap(),
+    );
+    let other = Address::new_unique();
+    // A token account of the right wallet and mint, but not at the ATA address
+    let non_canonical = Address::new_unique();
+    harness.ctx.account_store.borrow_mut().insert(
+        non_canonical,
+        AccountBuilder::token_account(&mint, &wallet, 0, &token_program_id),
+    );
+
+    let cases = [
+        (
+            assert_ata_exists_instruction(ata, other, mint, token_program_id),
+            ProgramError::Custom(AssociatedTokenAccountError::InvalidOwner as u32),
+        ),
+        (
+            assert_ata_exists_instruction(ata, wallet, other, token_program_id),
+            ProgramError::InvalidAccountData,
+        ),
+        (
+            assert_ata_exists_instruction(non_canonical, wallet, mint, token_program_id),
+            ProgramError::InvalidSeeds,
+        ),
+        (
+            assert_ata_exists_instruction(ata, wallet, mint, other),
+            ProgramError::IncorrectProgramId,
+        ),
+    ];
+    for (instruction, expected) in cases {
+        harness
+            .ctx
+            .process_and_validate_instruction(&instruction, &[Check::err(expected)]);
+    }
+}
+
+#[test]
+fn fails_for_account_of_other_token_program() {
+    let harness = harness_with_ata(spl_token_interface::id());
+
+    harness.ctx.process_and_validate_instruction(
+        &assert_ata_exists_instruction(
+            harness.ata_address.unwrap(),
+            harness.wallet.unwrap(),
+            harness.mint.unwrap(),
+            spl_token_2022_interface::id(),
+        ),
+        &[Check::err(ProgramError::IllegalOwner)],
+    );
+}
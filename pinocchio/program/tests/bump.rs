@@ -4,7 +4,9 @@ use {
     common::expected_bump,
     mollusk_svm_programs_token::token,
     mollusk_svm_result::Check,
-    pinocchio_associated_token_account_interface::instruction::CreateMode,
+    pinocchio_associated_token_account_interface::{
+        error::AssociatedTokenAccountError, instruction::CreateMode,
+    },
     solana_address::Address,
     solana_instruction::{AccountMeta, error::InstructionError},
     solana_program_error::ProgramError,
@@ -99,9 +101,7 @@ fn create_with_args_rejects_lower_off_curve_bump_hint(
             rent_sysvar: false,
         });
     instruction.accounts[1] = AccountMeta::new(lower_bump_addr, false);
-    harness
-        .ctx
-        .process_and_validate_instruction(&instruction, &[Check::err(ProgramError::InvalidSeeds)]);
+    harness.process_and_validate_failed_create(&instruction, ProgramError::InvalidSeeds);
 }
 
 #[test]
@@ -133,9 +133,7 @@ fn create_with_args_rejects_max_bump_hint_when_derived_address_mismatches_accoun
             rent_sysvar: false,
         });
 
-    harness
-        .ctx
-        .process_and_validate_instruction(&instruction, &[Check::err(ProgramError::InvalidSeeds)]);
+    harness.process_and_validate_failed_create(&instruction, ProgramError::InvalidSeeds);
 }
 
 #[test]
@@ -166,9 +164,7 @@ fn create_with_args_rejects_canonical_bump_hint_for_wrong_ata_account() {
         });
     instruction.accounts[1] = AccountMeta::new(wrong_ata_account_addr, false);
 
-    harness
-        .ctx
-        .process_and_validate_instruction(&instruction, &[Check::err(ProgramError::InvalidSeeds)]);
+    harness.process_and_validate_failed_create(&instruction, ProgramError::InvalidSeeds);
 }
 
 #[test]
@@ -301,3 +297,125 @@ fn create_with_args_idempotent_accepts_existing_ata_with_canonical_bump_hint(
         .ctx
         .process_and_validate_instruction(&instruction, &[Check::success()]);
 }
+
+fn create_with_bump_limit_instruction(
+    harness: &mut AtaTestHarness,
+    mode: CreateMode,
+    min_bump: u8,
+) -> solana_instruction::Instruction {
+    // Same accounts as `CreateWithArgs`
+    let mut instruction =
+        harness.build_create_ata_instruction(CreateAtaInstructionType::CreateWithArgs {
+            mode,
+            bump: None,
+            account_len: None,
+            rent_sysvar: false,
+        });
+    instruction.data = vec![6, mode as u8, min_bump, 0, 0, 0, 0];
+    instruction
+}
+
+#[test_case(0)]
+#[test_case(250)]
+#[test_case(253)]
+fn create_with_bump_limit_at_or_below_canonical_bump(min_bump: u8) {
+    let token_program_id = spl_token_interface::id();
+    let mut harness = create_mint_account(&token_program_id);
+    let wallet = Address::from_str_const("3zPynWFGj3nyJBtHhCy8UEJoGvbn1TmgHca2afHTSByQ");
+    harness.ensure_account_exists_with_lamports(wallet, 1_000_000);
+    harness.wallet = Some(wallet);
+    assert_eq!(expected_bump(&harness), 253);
+
+    let instruction =
+        create_with_bump_limit_instruction(&mut harness, CreateMode::Always, min_bump);
+
+    harness.ctx.process_and_validate_instruction(
+        &instruction,
+        &[
+            Check::success(),
+            Check::account(&harness.ata_address.unwrap())
+                .owner(&token_program_id)
+                .space(spl_token_interface::state::Account::LEN)
+                .build(),
+        ],
+    );
+}
+
+#[test_matrix(
+    [CreateMode::Always, CreateMode::Idempotent],
+    [false, true]
+)]
+fn create_with_bump_limit_above_canonical_bump(mode: CreateMode, existing: bool) {
+    let token_program_id = spl_token_interface::id();
+    let mut harness = create_mint_account(&token_program_id);
+    let wallet = Address::from_str_const("3zPynWFGj3nyJBtHhCy8UEJoGvbn1TmgHca2afHTSByQ");
+    harness.ensure_account_exists_with_lamports(wallet, 1_000_000);
+    harness.wallet = Some(wallet);
+    if existing {
+        harness = harness.with_ata();
+    }
+
+    // The search stops at 254 without reaching the canonical bump
+    let instruction = create_with_bump_limit_instruction(&mut harness, mode, 254);
+
+    harness.ctx.process_and_validate_instruction(
+        &instruction,
+        &[Check::err(ProgramError::Custom(
+            AssociatedTokenAccountError::BumpSearchLimitExceeded as u32,
+        ))],
+    );
+}
+
+#[test_case(CreateAtaInstructionType::Create, false)]
+#[test_case(CreateAtaInstructionType::CreateIdempotent, false)]
+#[test_case(CreateAtaInstructionType::CreateIdempotent, true)]
+fn create_without_bump_hint_finds_low_canonical_bump(
+    instruction_type: CreateAtaInstructionType,
+    existing: bool,
+) {
+    let token_program_id = spl_token_interface::id();
+    let mut harness = create_mint_account(&token_program_id);
+    let wallet = Address::from_str_const("Fe2TUfAnvzXjFc79MdYXmkWPDmN5D1RtLnEmv1EzorL8");
+    harness.ensure_account_exists_with_lamports(wallet, 1_000_000);
+    harness.wallet = Some(wallet);
+    assert_eq!(expected_bump(&harness), 237);
+    if existing {
+        harness = harness.with_ata();
+    }
+
+    let instruction = harness.build_create_ata_instruction(instruction_type);
+
+    harness.ctx.process_and_validate_instruction(
+        &instruction,
+        &[
+            Check::success(),
+            Check::account(&harness.ata_address.unwrap())
+                .owner(&token_program_id)
+                .space(spl_token_interface::state::Account::LEN)
+                .build(),
+        ],
+    );
+}
+
+#[test]
+fn create_without_bump_hint_rejects_on_curve_higher_bump_address() {
+    let token_program_id = spl_token_interface::id();
+    let mut harness = create_mint_account(&token_program_id);
+    let wallet = Address::from_str_const("3zPynWFGj3nyJBtHhCy8UEJoGvbn1TmgHca2afHTSByQ");
+    harness.ensure_account_exists_with_lamports(wallet, 1_000_000);
+    harness.wallet = Some(wallet);
+    let on_curve_address = ata_address_with_bump(&wallet, &MINT, &token_program_id, 255);
+    assert!(on_curve_address.is_on_curve());
+
+    let mut instruction = harness.build_create_ata_instruction(CreateAtaInstructionType::Create);
+    instruction.accounts[1] = AccountMeta::new(on_curve_address, false);
+
+    // The search matches the address before checking it, and signing the
+    // `CreateAccount` for an on-curve address fails
+    harness.ctx.process_and_validate_instruction(
+        &instruction,
+        &[Check::instruction_err(
+            InstructionError::ProgramFailedToComplete,
+        )],
+    );
+}
@@ -0,0 +1,200 @@
+use {
+    mollusk_svm_result::Check,
+    pinocchio_associated_token_account_interface::account_indices::{
+        CLOSE_DESTINATION, CLOSE_WALLET,
+    },
+    solana_address::Address,
+    solana_instruction::{AccountMeta, Instruction},
+    solana_program_error::ProgramError,
+    spl_associated_token_account_mollusk_harness::{AccountBuilder, AtaProgram, AtaTestHarness},
+    spl_token_interface::error::TokenError,
+    test_case::test_case,
+};
+
+fn close_instruction(
+    ata: Address,
+    wallet: Address,
+    mint: Address,
+    token_program_id: Address,
+) -> Instruction {
+    Instruction {
+        program_id: spl_associated_token_account_interface::program::id(),
+        accounts: vec![
+            AccountMeta::new(ata, false),
+            AccountMeta::new(wallet, true),
+            AccountMeta::new_readonly(mint, false),
+            AccountMeta::new_readonly(token_program_id, false),
+        ],
+        data: vec![11],
+    }
+}
+
+fn harness_with_ata(token_program_id: Address) -> AtaTestHarness {
+    AtaTestHarness::new_with_ata_program(&token_program_id, AtaProgram::Pinocchio)
+        .with_wallet_and_mint(1_000_000, 6)
+        .with_ata()
+}
+
+fn harness_close_instruction(harness: &AtaTestHarness) -> Instruction {
+    close_instruction(
+        harness.ata_address.unwrap(),
+        harness.wallet.unwrap(),
+        harness.mint.unwrap(),
+        harness.token_program_id,
+    )
+}
+
+#[test_case(spl_token_interface::id())]
+#[test_case(spl_token_2022_interface::id())]
+fn closes_empty_ata_into_wallet(token_program_id: Address) {
+    let harness = harness_with_ata(token_program_id);
+    let (ata, wallet) = (harness.ata_address.unwrap(), harness.wallet.unwrap());
+    let wallet_lamports = harness
+        .get_account(wallet)
+        .lamports
+        .checked_add(harness.get_account(ata).lamports)
+        .unwrap();
+
+    harness.ctx.process_and_validate_instruction(
+        &harness_close_instruction(&harness),
+        &[
+            Check::success(),
+            Check::account(&wallet).lamports(wallet_lamports).build(),
+            Check::account(&ata).closed().build(),
+        ],
+    );
+}
+
+#[test_case(spl_token_interface::id())]
+#[test_case(spl_token_2022_interface::id())]
+fn fails_for_ata_holding_tokens(token_program_id: Address) {
+    let mut harness = harness_with_ata(token_program_id);
+    harness.mint_tokens(1);
+
+    harness.ctx.process_and_validate_instruction(
+        &harness_close_instruction(&harness),
+        &[Check::err(ProgramError::Custom(
+            TokenError::NonNativeHasBalance as u32,
+        ))],
+    );
+}
+
+fn close_to_destination_instruction(harness: &AtaTestHarness, destination: Address) -> Instruction {
+    let mut instruction = harness_close_instruction(harness);
+    instruction
+        .accounts
+        .push(AccountMeta::new(destination, false));
+    instruction
+}
+
+#[test_case(spl_token_interface::id())]
+#[test_case(spl_token_2022_interface::id())]
+fn closes_empty_ata_into_destination(token_program_id: Address) {
+    let harness = harness_with_ata(token_program_id);
+    let (ata, wallet) = (harness.ata_address.unwrap(), harness.wallet.unwrap());
+    let wallet_lamports = harness.get_account(wallet).lamports;
+    let ata_lamports = harness.get_account(ata).lamports;
+    let destination = Address::new_unique();
+
+    harness.ctx.process_and_validate_instruction(
+        &close_to_destination_instruction(&harness, destination),
+        &[
+            Check::success(),
+            Check::account(&destination).lamports(ata_lamports).build(),
+            Check::account(&wallet).lamports(wallet_lamports).build(),
+            Check::account(&ata).closed().build(),
+        ],
+    );
+}
+
+#[test]
+fn fails_for_destination_that_is_the_closed_account() {
+    let harness = harness_with_ata(spl_token_interface::id());
+    let instruction = close_to_destination_instruction(&harness, harness.ata_address.unwrap());
+
+    harness.ctx.process_and_validate_instruction(
+        &instruction,
+        &[Check::err(ProgramError::InvalidArgument)],
+    );
+}
+
+#[test]
+fn fails_for_read_only_destination() {
+    let harness = harness_with_ata(spl_token_interface::id());
+    let mut instruction = close_to_destination_instruction(&harness, Address::new_unique());
+    instruction.accounts[CLOSE_DESTINATION].is_writable = false;
+
+    harness
+        .ctx
+        .process_and_validate_instruction(&instruction, &[Check::err(ProgramError::Immutable)]);
+}
+
+#[test]
+fn fails_for_destination_without_wallet_signature() {
+    let harness = harness_with_ata(spl_token_interface::id());
+    let destination = Address::new_unique();
+    let mut instruction = close_to_destination_instruction(&harness, destination);
+    instruction.accounts[CLOSE_WALLET].is_signer = false;
+
+    harness.ctx.process_and_validate_instruction(
+        &instruction,
+        &[
+            Check::err(ProgramError::MissingRequiredSignature),
+            Check::account(&destination).lamports(0).build(),
+        ],
+    );
+}
+
+#[test]
+fn fails_without_wallet_signature() {
+    let harness = harness_with_ata(spl_token_interface::id());
+    let mut instruction = harness_close_instruction(&harness);
+    instruction.accounts[CLOSE_WALLET].is_signer = false;
+
+    harness.ctx.process_and_validate_instruction(
+        &instruction,
+        &[Check::err(ProgramError::MissingRequiredSignature)],
+    );
+}
+
+#[test_case(spl_token_interface::id())]
+#[test_case(spl_token_2022_interface::id())]
+fn fails_for_missing_or_mismatched_accounts(token_program_id: Address) {
+    let harness = harness_with_ata(token_program_id);
+    let (ata, wallet, mint) = (
+        harness.ata_address.unwrap(),
+        harness.wallet.unwrap(),
+        harness.mint.unwrap(),
+    );
+    let other = Address::new_unique();
+    // A token account the wallet could close itself, but not at the ATA address
+    let non_canonical = Address::new_unique();
+    harness.ctx.account_store.borrow_mut().insert(
+        non_canonical,
+        AccountBuilder::token_account(&mint, &wallet, 0, &token_program_id),
+    );
+
+    let cases = [
+        (
+            close_instruction(other, wallet, mint, token_program_id),
+            ProgramError::UninitializedAccount,
+        ),
+        (
+            close_instruction(non_canonical, wallet, mint, token_program_id),
+            ProgramError::InvalidSeeds,
+        ),
+        (
+            close_instruction(ata, wallet, other, token_program_id),
+            ProgramError::InvalidSeeds,
+        ),
+        (
+            close_instruction(ata, wallet, mint, other),
+            ProgramError::IncorrectProgramId,
+        ),
+    ];
+    for (instruction, expected) in cases {
+        harness
+            .ctx
+            .process_and_validate_instruction(&instruction, &[Check::err(expected)]);
+    }
+}
@@ -0,0 +1,200 @@
+use {
+    mollusk_svm_result::Check,
+    pinocchio_associated_token_account_interface::{
+        account_indices::MAX_CLOSE_EMPTY_GROUPS, error::AssociatedTokenAccountError,
+    },
+    solana_address::Address,
+    solana_instruction::{AccountMeta, Instruction},
+    solana_program_error::ProgramError,
+    spl_associated_token_account_mollusk_harness::{AtaProgram, AtaTestHarness},
+};
+
+/// `(ata, mint, token_program)` of an ATA to close
+type Group = (Address, Address, Address);
+
+fn close_empty_instruction(wallet: Address, groups: &[Group]) -> Instruction {
+    let mut accounts = vec![AccountMeta::new(wallet, true)];
+    for (ata, mint, token_program_id) in groups {
+        accounts.extend([
+            AccountMeta::new(*ata, false),
+            AccountMeta::new_readonly(*mint, false),
+            AccountMeta::new_readonly(*token_program_id, false),
+        ]);
+    }
+    Instruction {
+        program_id: spl_associated_token_account_interface::program::id(),
+        accounts,
+        data: vec![12],
+    }
+}
+
+struct CloseEmptySetup {
+    harness: AtaTestHarness,
+    wallet: Address,
+    /// Empty SPL Token and Token-2022 ATAs
+    empty: [Group; 2],
+    /// SPL Token ATA holding tokens
+    funded: Group,
+    /// Token-2022 ATA address that was never created
+    missing: Group,
+}
+
+impl CloseEmptySetup {
+    fn groups(&self) -> Vec<Group> {
+        vec![self.empty[0], self.funded, self.missing, self.empty[1]]
+    }
+}
+
+fn close_empty_setup() -> CloseEmptySetup {
+    let mut harness =
+        AtaTestHarness::new_with_ata_program(&spl_token_interface::id(), AtaProgram::Pinocchio)
+            .with_wallet(1_000_000);
+    let wallet = harness.wallet.unwrap();
+    let ata_of = |harness: &mut AtaTestHarness, token_program_id: Address| {
+        let (mint, mint_authority) = harness.create_mint_with_token_program(token_program_id, 6);
+        let ata = harness.create_ata_for_owner_with_token_program(
+            wallet,
+            1_000_000,
+            mint,
+            token_program_id,
+        );
+        (ata, mint, mint_authority)
+    };
+
+    let (token_ata, token_mint, _) = ata_of(&mut harness, spl_token_interface::id());
+    let (token_2022_ata, token_2022_mint, _) = ata_of(&mut harness, spl_token_2022_interface::id());
+    let (funded_ata, funded_mint, funded_mint_authority) =
+        ata_of(&mut harness, spl_token_interface::id());
+    harness.mint_tokens_to_with_token_program(
+        funded_mint,
+        funded_mint_authority,
+        funded_ata,
+        spl_token_interface::id(),
+        1,
+    );
+    let (missing_mint, _) =
+        harness.create_mint_with_token_program(spl_token_2022_interface::id(), 6);
+    let missing_ata =
+        harness.derive_ata_address(&wallet, &missing_mint, &spl_token_2022_interface::id());
+
+    CloseEmptySetup {
+        harness,
+        wallet,
+        empty: [
+            (token_ata, token_mint, spl_token_interface::id()),
+            (
+                token_2022_ata,
+                token_2022_mint,
+                spl_token_2022_interface::id(),
+            ),
+        ],
+        funded: (funded_ata, funded_mint, spl_token_interface::id()),
+        missing: (missing_ata, missing_mint, spl_token_2022_interface::id()),
+    }
+}
+
+#[test]
+fn closes_only_empty_atas() {
+    let setup = close_empty_setup();
+    let harness = &setup.harness;
+    let wallet_lamports = setup.empty.iter().fold(
+        harness.get_account(setup.wallet).lamports,
+        |lamports, (ata, _, _)| {
+            lamports
+                .checked_add(harness.get_account(*ata).lamports)
+                .unwrap()
+        },
+    );
+    let funded_account = harness.get_account(setup.funded.0);
+
+    harness.ctx.process_and_validate_instruction(
+        &close_empty_instruction(setup.wallet, &setup.groups()),
+        &[
+            Check::success(),
+            Check::account(&setup.wallet)
+                .lamports(wallet_lamports)
+                .build(),
+            Check::account(&setup.empty[0].0).closed().build(),
+            Check::account(&setup.empty[1].0).closed().build(),
+        ],
+    );
+
+    assert_eq!(harness.get_account(setup.funded.0), funded_account);
+}
+
+#[test]
+fn fails_for_account_of_other_wallet() {
+    let setup = close_empty_setup();
+    let mut groups = setup.groups();
+    let (_, mint, token_program_id) = groups[3];
+    groups[3].0 =
+        setup
+            .harness
+            .derive_ata_address(&Address::new_unique(), &mint, &token_program_id);
+
+    setup.harness.ctx.process_and_validate_instruction(
+        &close_empty_instruction(setup.wallet, &groups),
+        &[Check::err(ProgramError::InvalidSeeds)],
+    );
+}
+
+#[test]
+fn fails_without_wallet_signature() {
+    let setup = close_empty_setup();
+    let mut instruction = close_empty_instruction(setup.wallet, &setup.groups());
+    instruction.accounts[0].is_signer = false;
+
+    setup.harness.ctx.process_and_validate_instruction(
+        &instruction,
+        &[Check::err(ProgramError::MissingRequiredSignature)],
+    );
+}
+
+#[test]
+fn rejects_missing_or_partial_group() {
+    let setup = close_empty_setup();
+    let no_groups = close_empty_instruction(setup.wallet, &[]);
+    let mut partial_group = close_empty_instruction(setup.wallet, &setup.empty);
+    partial_group.accounts.pop();
+
+    for instruction in [&no_groups, &partial_group] {
+        setup.harness.ctx.process_and_validate_instruction(
+            instruction,
+            &[Check::err(ProgramError::NotEnoughAccountKeys)],
+        );
+    }
+}
+
+#[test]
+fn caps_groups_at_limit() {
+    let setup = close_empty_setup();
+    let wallet_lamports = setup
+        .harness
+        .get_account(setup.wallet)
+        .lamports
+        .checked_add(setup.harness.get_account(setup.empty[0].0).lamports)
+        .unwrap();
+    // One empty ATA to close, then addresses that were never created
+    let mut groups = vec![setup.missing; MAX_CLOSE_EMPTY_GROUPS];
+    groups[0] = setup.empty[0];
+
+    let mut over_limit = groups.clone();
+    over_limit.push(setup.missing);
+    setup.harness.ctx.process_and_validate_instruction(
+        &close_empty_instruction(setup.wallet, &over_limit),
+        &[Check::err(ProgramError::Custom(
+            AssociatedTokenAccountError::GroupLimitExceeded as u32,
+        ))],
+    );
+
+    setup.harness.ctx.process_and_validate_instruction(
+        &close_empty_instruction(setup.wallet, &groups),
+        &[
+            Check::success(),
+            Check::account(&setup.wallet)
+                .lamports(wallet_lamports)
+                .build(),
+            Check::account(&setup.empty[0].0).closed().build(),
+        ],
+    );
+}
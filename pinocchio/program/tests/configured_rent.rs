@@ -0,0 +1,45 @@
+use {
+    solana_account::Account,
+    solana_address::Address,
+    solana_rent::Rent,
+    solana_system_interface::program as system_program,
+    spl_associated_token_account_mollusk_harness::{
+        AtaProgram, AtaTestHarness, CreateAtaInstructionType,
+    },
+    test_case::test_matrix,
+};
+
+#[test_matrix(
+    [spl_token_interface::id(), spl_token_2022_interface::id()],
+    [AtaProgram::Legacy, AtaProgram::Pinocchio]
+)]
+fn create_ata_checks_follow_configured_rent(token_program_id: Address, ata_program: AtaProgram) {
+    let mut harness = AtaTestHarness::new_with_ata_program(&token_program_id, ata_program)
+        .with_wallet_and_mint(1_000_000, 6);
+    harness.ctx.mollusk.sysvars.rent = Rent::free();
+
+    // Checks the ATA holds exactly the configured minimum, here nothing
+    let ata = harness.create_ata(CreateAtaInstructionType::Create);
+
+    assert_eq!(harness.get_account(ata).lamports, 0);
+}
+
+#[test]
+#[should_panic(expected = "below the rent-exempt minimum")]
+fn assert_rent_exempt_rejects_underfunded_account() {
+    let harness =
+        AtaTestHarness::new_with_ata_program(&spl_token_interface::id(), AtaProgram::Pinocchio);
+    let address = Address::new_unique();
+    harness.ctx.account_store.borrow_mut().insert(
+        address,
+        Account {
+            lamports: 1,
+            data: vec![0; 165],
+            owner: system_program::id(),
+            executable: false,
+            rent_epoch: 0,
+        },
+    );
+
+    harness.assert_rent_exempt(address);
+}
@@ -0,0 +1,86 @@
+use {
+    mollusk_svm_result::Check,
+    spl_associated_token_account_mollusk_harness::{
+        AtaProgram, AtaTestHarness, TokenChecks, build_recover_nested_instruction,
+    },
+    spl_token_2022_interface::extension::{
+        ExtensionType,
+        cpi_guard::{CpiGuard, instruction::enable_cpi_guard},
+    },
+};
+
+// CpiGuard only restricts what an account's owner can do through CPI: moving
+// tokens out, approving, closing to a third party. Recovery transfers out of and
+// closes the nested account, which cannot have the guard enabled because its
+// owner is a PDA, so a guarded destination still receives the tokens.
+#[test]
+fn recover_nested_into_cpi_guarded_destination() {
+    let token_program_id = spl_token_2022_interface::id();
+    let mut harness =
+        AtaTestHarness::new_with_ata_program(&token_program_id, AtaProgram::Pinocchio)
+            .with_wallet(1_000_000);
+    let wallet = harness.wallet.unwrap();
+
+    let (owner_mint, _) = harness.create_mint_with_token_program(token_program_id, 0);
+    let owner_ata = harness.create_ata_for_owner_with_token_program(
+        wallet,
+        1_000_000,
+        owner_mint,
+        token_program_id,
+    );
+    let (nested_mint, nested_mint_authority) =
+        harness.create_mint_with_token_program(token_program_id, 0);
+    let nested_ata = harness.create_ata_for_owner_with_token_program(
+        owner_ata,
+        1_000_000,
+        nested_mint,
+        token_program_id,
+    );
+    harness.mint_tokens_to_with_token_program(
+        nested_mint,
+        nested_mint_authority,
+        nested_ata,
+        token_program_id,
+        100,
+    );
+    let destination_ata = harness.create_ata_for_owner_with_token_program(
+        wallet,
+        1_000_000,
+        nested_mint,
+        token_program_id,
+    );
+
+    let reallocate = spl_token_2022_interface::instruction::reallocate(
+        &token_program_id,
+        &destination_ata,
+        &harness.payer,
+        &wallet,
+        &[],
+        &[ExtensionType::CpiGuard],
+    )
+    .unwrap();
+    let enable = enable_cpi_guard(&token_program_id, &destination_ata, &wallet, &[]).unwrap();
+    harness.ctx.process_and_validate_instruction_chain(&[
+        (&reallocate, &[Check::success()]),
+        (&enable, &[Check::success()]),
+    ]);
+
+    let instruction = build_recover_nested_instruction(
+        &wallet,
+        &owner_mint,
+        &nested_mint,
+        &token_program_id,
+        &token_program_id,
+        &[],
+    );
+    harness.process_and_validate_with_token_checks(
+        &instruction,
+        &[
+            Check::success(),
+            Check::account(&nested_ata).closed().build(),
+        ],
+        &[TokenChecks::account(destination_ata)
+            .balance(100)
+            .extension_initialized::<CpiGuard>()],
+    );
+}
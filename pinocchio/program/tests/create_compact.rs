@@ -0,0 +1,136 @@
+mod common;
+
+use {
+    common::expected_bump,
+    mollusk_svm_result::Check,
+    pinocchio_associated_token_account_interface::{
+        account_indices::CREATE_COMPACT_TOKEN_PROGRAM, instruction::CreateMode,
+    },
+    solana_address::Address,
+    solana_program_error::ProgramError,
+    solana_program_pack::Pack,
+    solana_system_interface::program as system_program,
+    spl_associated_token_account_mollusk_harness::{
+        AtaProgram, AtaTestHarness, CreateAtaInstructionType, TokenChecks,
+        token_2022_immutable_owner_account_len, token_2022_immutable_owner_rent_exempt_balance,
+        token_account_rent_exempt_balance,
+    },
+    spl_token_2022_interface::extension::immutable_owner::ImmutableOwner,
+    spl_token_interface::state::AccountState,
+    test_case::test_matrix,
+};
+
+fn expected_account_len(token_program_id: &Address) -> usize {
+    if *token_program_id == spl_token_2022_interface::id() {
+        token_2022_immutable_owner_account_len()
+    } else {
+        spl_token_interface::state::Account::LEN
+    }
+}
+
+fn expected_rent_exempt_balance(token_program_id: &Address) -> u64 {
+    if *token_program_id == spl_token_2022_interface::id() {
+        token_2022_immutable_owner_rent_exempt_balance()
+    } else {
+        token_account_rent_exempt_balance()
+    }
+}
+
+#[test_matrix(
+    [spl_token_interface::id(), spl_token_2022_interface::id()],
+    [CreateMode::Always, CreateMode::Idempotent],
+    [false, true],
+    [false, true]
+)]
+fn create_compact_omits_system_program(
+    token_program_id: Address,
+    mode: CreateMode,
+    hints: bool,
+    rent_sysvar: bool,
+) {
+    let mut harness =
+        AtaTestHarness::new_with_ata_program(&token_program_id, AtaProgram::Pinocchio)
+            .with_wallet_and_mint(1_000_000, 6);
+    let (bump, account_len) = if hints {
+        (
+            Some(expected_bump(&harness)),
+            Some(expected_account_len(&token_program_id) as u32),
+        )
+    } else {
+        (None, None)
+    };
+    let instruction =
+        harness.build_create_ata_instruction(CreateAtaInstructionType::CreateCompact {
+            mode,
+            bump,
+            account_len,
+            rent_sysvar,
+        });
+    assert!(
+        instruction
+            .accounts
+            .iter()
+            .all(|account| account.pubkey != system_program::id())
+    );
+    let ata_address = harness.ata_address.unwrap();
+    let mut token_checks = TokenChecks::account(ata_address)
+        .mint_field(harness.mint.unwrap())
+        .owner_field(harness.wallet.unwrap())
+        .balance(0)
+        .state(AccountState::Initialized);
+    if token_program_id == spl_token_2022_interface::id() {
+        token_checks = token_checks.extension_initialized::<ImmutableOwner>();
+    }
+
+    harness.process_and_validate_with_token_checks(
+        &instruction,
+        &[
+            Check::success(),
+            Check::account(&ata_address)
+                .space(expected_account_len(&token_program_id))
+                .owner(&token_program_id)
+                .lamports(expected_rent_exempt_balance(&token_program_id))
+                .build(),
+        ],
+        &[token_checks],
+    );
+}
+
+#[test_matrix([spl_token_interface::id(), spl_token_2022_interface::id()])]
+fn create_compact_idempotent_accepts_existing_ata(token_program_id: Address) {
+    let mut harness =
+        AtaTestHarness::new_with_ata_program(&token_program_id, AtaProgram::Pinocchio)
+            .with_wallet_and_mint(1_000_000, 6);
+    let wallet = harness.wallet.unwrap();
+    harness.insert_token_account_at_ata_address(wallet);
+
+    let instruction =
+        harness.build_create_ata_instruction(CreateAtaInstructionType::CreateCompact {
+            mode: CreateMode::Idempotent,
+            bump: None,
+            account_len: None,
+            rent_sysvar: false,
+        });
+
+    harness
+        .ctx
+        .process_and_validate_instruction(&instruction, &[Check::success()]);
+}
+
+#[test_matrix([spl_token_interface::id(), spl_token_2022_interface::id()])]
+fn create_compact_rejects_too_few_accounts(token_program_id: Address) {
+    let mut harness =
+        AtaTestHarness::new_with_ata_program(&token_program_id, AtaProgram::Pinocchio)
+            .with_wallet_and_mint(1_000_000, 6);
+
+    let mut instruction =
+        harness.build_create_ata_instruction(CreateAtaInstructionType::CreateCompact {
+            mode: CreateMode::Always,
+            bump: None,
+            account_len: None,
+            rent_sysvar: false,
+        });
+    instruction.accounts.truncate(CREATE_COMPACT_TOKEN_PROGRAM);
+
+    harness.process_and_validate_failed_create(&instruction, ProgramError::NotEnoughAccountKeys);
+}
@@ -0,0 +1,206 @@
+use {
+    mollusk_svm_result::Check,
+    solana_account::Account,
+    solana_address::Address,
+    solana_instruction::{AccountMeta, Instruction},
+    solana_program_error::ProgramError,
+    solana_system_interface::program as system_program,
+    spl_associated_token_account_interface::address::get_associated_token_address_with_program_id,
+    spl_associated_token_account_mollusk_harness::{AtaProgram, AtaTestHarness, TokenChecks},
+    spl_token_2022_interface::{
+        extension::{
+            BaseStateWithExtensions, ExtensionType, StateWithExtensionsOwned,
+            confidential_transfer::ConfidentialTransferAccount,
+        },
+        state::Account as TokenAccount,
+    },
+    test_case::test_case,
+};
+
+const ZK_ELGAMAL_PROOF_PROGRAM: Address =
+    Address::from_str_const("ZkE1Gama1Proof11111111111111111111111111111");
+/// `ProofType::PubkeyValidity`
+const PUBKEY_VALIDITY_PROOF_TYPE: u8 = 4;
+const MAXIMUM_PENDING_BALANCE_CREDIT_COUNTER: u64 = 65_536;
+
+fn create_confidential_instruction(
+    harness: &AtaTestHarness,
+    mode: u8,
+    proof: Address,
+) -> Instruction {
+    let wallet = harness.wallet.unwrap();
+    let mint = harness.mint.unwrap();
+    let mut data = vec![15, mode];
+    // Never decrypted by the token program
+    data.extend_from_slice(&[0; 36]);
+    data.extend_from_slice(&MAXIMUM_PENDING_BALANCE_CREDIT_COUNTER.to_le_bytes());
+    // The proof is read from a context state account
+    data.push(0);
+    Instruction {
+        program_id: spl_associated_token_account_interface::program::id(),
+        accounts: vec![
+            AccountMeta::new(harness.payer, true),
+            AccountMeta::new(
+                get_associated_token_address_with_program_id(
+                    &wallet,
+                    &mint,
+                    &harness.token_program_id,
+                ),
+                false,
+            ),
+            AccountMeta::new_readonly(wallet, true),
+            AccountMeta::new_readonly(mint, false),
+            AccountMeta::new_readonly(system_program::id(), false),
+            AccountMeta::new_readonly(harness.token_program_id, false),
+            AccountMeta::new_readonly(proof, false),
+        ],
+        data,
+    }
+}
+
+fn confidential_harness() -> AtaTestHarness {
+    AtaTestHarness::new_with_ata_program(&spl_token_2022_interface::id(), AtaProgram::Pinocchio)
+        .with_wallet(1_000_000)
+        .with_mint_with_extensions(&[ExtensionType::ConfidentialTransferMint])
+        .initialize_confidential_transfer_mint()
+        .initialize_mint(6)
+}
+
+// A `PubkeyValidity` proof context state account, as left by the ZK ElGamal
+// proof program after verifying a proof for an arbitrary ElGamal public key
+fn insert_pubkey_validity_context(harness: &AtaTestHarness) -> Address {
+    let context_state = Address::new_unique();
+    let mut data = Vec::with_capacity(65);
+    data.extend_from_slice(harness.wallet.unwrap().as_ref());
+    data.push(PUBKEY_VALIDITY_PROOF_TYPE);
+    data.extend_from_slice(&[7; 32]);
+    harness.ctx.account_store.borrow_mut().insert(
+        context_state,
+        Account {
+            lamports: 1_000_000,
+            data,
+            owner: ZK_ELGAMAL_PROOF_PROGRAM,
+            executable: false,
+            rent_epoch: 0,
+        },
+    );
+    context_state
+}
+
+fn confidential_transfer_account(harness: &AtaTestHarness, address: Address) -> (u64, bool) {
+    let account =
+        StateWithExtensionsOwned::<TokenAccount>::unpack(harness.get_account(address).data)
+            .unwrap();
+    let extension = account
+        .get_extension::<ConfidentialTransferAccount>()
+        .unwrap();
+    (
+        extension.maximum_pending_balance_credit_counter.into(),
+        extension.approved.into(),
+    )
+}
+
+#[test_case(0)]
+#[test_case(1)]
+fn creates_ata_configured_for_confidential_transfers(mode: u8) {
+    let harness = confidential_harness();
+    let proof = insert_pubkey_validity_context(&harness);
+    let instruction = create_confidential_instruction(&harness, mode, proof);
+    let ata = instruction.accounts[1].pubkey;
+
+    harness.process_and_validate_with_token_checks(
+        &instruction,
+        &[Check::success()],
+        &[TokenChecks::account(ata)
+            .mint_field(harness.mint.unwrap())
+            .owner_field(harness.wallet.unwrap())
+            .extension_initialized::<ConfidentialTransferAccount>()],
+    );
+    assert_eq!(
+        confidential_transfer_account(&harness, ata),
+        (MAXIMUM_PENDING_BALANCE_CREDIT_COUNTER, true)
+    );
+}
+
+#[test]
+fn idempotent_mode_leaves_existing_ata_untouched() {
+    let harness = confidential_harness();
+    let proof = insert_pubkey_validity_context(&harness);
+    let instruction = create_confidential_instruction(&harness, 1, proof);
+
+    harness
+        .ctx
+        .process_and_validate_instruction(&instruction, &[Check::success()]);
+    harness
+        .ctx
+        .process_and_validate_instruction(&instruction, &[Check::success()]);
+}
+
+#[test]
+fn create_mode_fails_for_existing_ata() {
+    let harness = confidential_harness();
+    let proof = insert_pubkey_validity_context(&harness);
+    let instruction = create_confidential_instruction(&harness, 0, proof);
+
+    harness
+        .ctx
+        .process_and_validate_instruction(&instruction, &[Check::success()]);
+    harness
+        .ctx
+        .process_and_validate_instruction(&instruction, &[Check::err(ProgramError::IllegalOwner)]);
+}
+
+#[test]
+fn fails_for_spl_token() {
+    let harness =
+        AtaTestHarness::new_with_ata_program(&spl_token_interface::id(), AtaProgram::Pinocchio)
+            .with_wallet_and_mint(1_000_000, 6);
+    let instruction = create_confidential_instruction(&harness, 0, Address::new_unique());
+
+    harness.ctx.process_and_validate_instruction(
+        &instruction,
+        &[Check::err(ProgramError::IncorrectProgramId)],
+    );
+}
+
+#[test]
+fn fails_without_wallet_signature() {
+    let harness = confidential_harness();
+    let proof = insert_pubkey_validity_context(&harness);
+    let mut instruction = create_confidential_instruction(&harness, 0, proof);
+    instruction.accounts[2].is_signer = false;
+
+    harness.ctx.process_and_validate_instruction(
+        &instruction,
+        &[Check::err(ProgramError::MissingRequiredSignature)],
+    );
+}
+
+#[test]
+fn fails_for_mint_without_confidential_transfers() {
+    let harness = AtaTestHarness::new_with_ata_program(
+        &spl_token_2022_interface::id(),
+        AtaProgram::Pinocchio,
+    )
+    .with_wallet_and_mint(1_000_000, 6);
+    let proof = insert_pubkey_validity_context(&harness);
+    let instruction = create_confidential_instruction(&harness, 0, proof);
+
+    // `ConfigureAccount` finds no `ConfidentialTransferMint` extension
+    harness.ctx.process_and_validate_instruction(
+        &instruction,
+        &[Check::err(ProgramError::InvalidAccountData)],
+    );
+}
+
+#[test]
+fn fails_without_proof_account() {
+    let harness = confidential_harness();
+    let mut instruction = create_confidential_instruction(&harness, 0, Address::new_unique());
+    instruction.accounts.pop();
+
+    harness.ctx.process_and_validate_instruction(
+        &instruction,
+        &[Check::err(ProgramError::NotEnoughAccountKeys)],
+    );
+}
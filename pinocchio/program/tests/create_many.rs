@@ -0,0 +1,170 @@
+use {
+    mollusk_svm_result::Check,
+    pinocchio_associated_token_account_interface::{
+        account_indices::MAX_CREATE_MANY_GROUPS, error::AssociatedTokenAccountError,
+        instruction::CreateMode,
+    },
+    solana_address::Address,
+    solana_instruction::{AccountMeta, Instruction},
+    solana_program_error::ProgramError,
+    solana_system_interface::program as system_program,
+    spl_associated_token_account_mollusk_harness::{AtaProgram, AtaTestHarness, TokenChecks},
+    test_case::test_case,
+};
+
+/// `(wallet, mint, token_program)` of an ATA to create
+type Pair = (Address, Address, Address);
+
+fn create_many_instruction(
+    harness: &AtaTestHarness,
+    mode: CreateMode,
+    pairs: &[Pair],
+) -> Instruction {
+    let mut accounts = vec![
+        AccountMeta::new(harness.payer, true),
+        AccountMeta::new_readonly(system_program::id(), false),
+    ];
+    for (wallet, mint, token_program_id) in pairs {
+        accounts.extend([
+            AccountMeta::new(
+                harness.derive_ata_address(wallet, mint, token_program_id),
+                false,
+            ),
+            AccountMeta::new_readonly(*wallet, false),
+            AccountMeta::new_readonly(*mint, false),
+            AccountMeta::new_readonly(*token_program_id, false),
+        ]);
+    }
+    Instruction {
+        program_id: harness.ata_program_id,
+        accounts,
+        data: vec![8, mode as u8],
+    }
+}
+
+/// A harness with an SPL Token mint, a Token-2022 mint and two wallets, and
+/// every wallet and mint pair among them
+fn harness_with_pairs() -> (AtaTestHarness, Vec<Pair>) {
+    let mut harness =
+        AtaTestHarness::new_with_ata_program(&spl_token_interface::id(), AtaProgram::Pinocchio)
+            .with_wallet_and_mint(1_000_000, 6);
+    let token_mint = harness.mint.unwrap();
+    let (token_2022_mint, _) =
+        harness.create_mint_with_token_program(spl_token_2022_interface::id(), 6);
+    let wallets = [harness.wallet.unwrap(), Address::new_unique()];
+    harness.ensure_accounts_with_lamports(&[(wallets[1], 1_000_000)]);
+
+    let pairs = wallets
+        .into_iter()
+        .flat_map(|wallet| {
+            [
+                (wallet, token_mint, spl_token_interface::id()),
+                (wallet, token_2022_mint, spl_token_2022_interface::id()),
+            ]
+        })
+        .collect();
+    (harness, pairs)
+}
+
+fn assert_created(harness: &AtaTestHarness, pairs: &[Pair]) {
+    for (wallet, mint, token_program_id) in pairs {
+        let ata = harness.derive_ata_address(wallet, mint, token_program_id);
+        assert_eq!(harness.get_account(ata).owner, *token_program_id);
+        TokenChecks::account(ata)
+            .owner_field(*wallet)
+            .mint_field(*mint)
+            .balance(0)
+            .assert(harness);
+    }
+}
+
+#[test_case(CreateMode::Always)]
+#[test_case(CreateMode::Idempotent)]
+fn creates_every_ata_across_token_programs(mode: CreateMode) {
+    let (harness, pairs) = harness_with_pairs();
+
+    harness.ctx.process_and_validate_instruction(
+        &create_many_instruction(&harness, mode, &pairs),
+        &[Check::success()],
+    );
+
+    assert_created(&harness, &pairs);
+}
+
+#[test_case(CreateMode::Always, Some(ProgramError::IllegalOwner))]
+#[test_case(CreateMode::Idempotent, None)]
+fn existing_ata_follows_mode(mode: CreateMode, expected_err: Option<ProgramError>) {
+    let (mut harness, pairs) = harness_with_pairs();
+    let (wallet, mint, token_program_id) = pairs[1];
+    harness.create_ata_for_owner_with_token_program(wallet, 1_000_000, mint, token_program_id);
+
+    let check = expected_err.map_or(Check::success(), Check::err);
+    harness.ctx.process_and_validate_instruction(
+        &create_many_instruction(&harness, mode, &pairs),
+        &[check],
+    );
+
+    if expected_err.is_none() {
+        assert_created(&harness, &pairs);
+    }
+}
+
+#[test]
+fn rejects_missing_or_partial_group() {
+    let (harness, pairs) = harness_with_pairs();
+    let mut no_groups = create_many_instruction(&harness, CreateMode::Always, &[]);
+    let mut partial_group = create_many_instruction(&harness, CreateMode::Always, &pairs[..2]);
+    partial_group.accounts.pop();
+
+    for instruction in [&no_groups, &partial_group] {
+        harness.ctx.process_and_validate_instruction(
+            instruction,
+            &[Check::err(ProgramError::NotEnoughAccountKeys)],
+        );
+    }
+
+    // Without the system program there is no group to read either
+    no_groups.accounts.pop();
+    harness.ctx.process_and_validate_instruction(
+        &no_groups,
+        &[Check::err(ProgramError::NotEnoughAccountKeys)],
+    );
+}
+
+#[test]
+fn failing_group_fails_instruction() {
+    let (harness, pairs) = harness_with_pairs();
+    let mut instruction = create_many_instruction(&harness, CreateMode::Always, &pairs);
+    // The last group's ATA address does not match its wallet and mint
+    let last_ata = instruction.accounts.len().checked_sub(4).unwrap();
+    instruction.accounts[last_ata].pubkey = Address::new_unique();
+
+    harness
+        .ctx
+        .process_and_validate_instruction(&instruction, &[Check::err(ProgramError::InvalidSeeds)]);
+}
+
+#[test]
+fn caps_groups_at_limit() {
+    let harness =
+        AtaTestHarness::new_with_ata_program(&spl_token_interface::id(), AtaProgram::Pinocchio)
+            .with_wallet_and_mint(1_000_000, 6);
+    let mint = harness.mint.unwrap();
+    let pairs: Vec<Pair> = (0..=MAX_CREATE_MANY_GROUPS)
+        .map(|_| (Address::new_unique(), mint, spl_token_interface::id()))
+        .collect();
+
+    harness.ctx.process_and_validate_instruction(
+        &create_many_instruction(&harness, CreateMode::Always, &pairs),
+        &[Check::err(ProgramError::Custom(
+            AssociatedTokenAccountError::GroupLimitExceeded as u32,
+        ))],
+    );
+
+    let pairs = &pairs[..MAX_CREATE_MANY_GROUPS];
+    harness.ctx.process_and_validate_instruction(
+        &create_many_instruction(&harness, CreateMode::Always, pairs),
+        &[Check::success()],
+    );
+    assert_created(&harness, pairs);
+}
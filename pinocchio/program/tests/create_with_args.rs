@@ -3,7 +3,9 @@ mod common;
 use {
     common::expected_bump,
     mollusk_svm_result::Check,
-    pinocchio_associated_token_account_interface::instruction::CreateMode,
+    pinocchio_associated_token_account_interface::{
+        account_indices::CREATE_WITH_ARGS_RENT_SYSVAR, instruction::CreateMode,
+    },
     pinocchio_token::instructions::{Batch, InitializeAccount, InitializeImmutableOwner},
     solana_address::Address,
     solana_instruction::AccountMeta,
@@ -80,9 +82,7 @@ fn create_with_args_always_rejects_existing_ata(token_program_id: Address) {
             rent_sysvar: false,
         });
 
-    harness
-        .ctx
-        .process_and_validate_instruction(&instruction, &[Check::err(ProgramError::IllegalOwner)]);
+    harness.process_and_validate_failed_create(&instruction, ProgramError::IllegalOwner);
 }
 
 #[test_matrix(
@@ -103,16 +103,14 @@ fn create_with_args_rejects_wrong_rent_account(token_program_id: Address, mode:
             account_len: Some(expected_account_len(&token_program_id) as u32),
             rent_sysvar: true,
         });
-    let rent_sysvar = instruction.accounts[6].pubkey;
-    instruction.accounts[6] = AccountMeta::new_readonly(incorrect_rent_sysvar, false);
+    let rent_sysvar = instruction.accounts[CREATE_WITH_ARGS_RENT_SYSVAR].pubkey;
+    instruction.accounts[CREATE_WITH_ARGS_RENT_SYSVAR] =
+        AccountMeta::new_readonly(incorrect_rent_sysvar, false);
     instruction
         .accounts
         .push(AccountMeta::new_readonly(rent_sysvar, false));
 
-    harness.ctx.process_and_validate_instruction(
-        &instruction,
-        &[Check::err(ProgramError::InvalidArgument)],
-    );
+    harness.process_and_validate_failed_create(&instruction, ProgramError::InvalidArgument);
 }
 
 #[test_matrix(
@@ -0,0 +1,237 @@
+use {
+    mollusk_svm_result::Check,
+    solana_address::Address,
+    solana_instruction::{AccountMeta, Instruction},
+    solana_program_error::ProgramError,
+    solana_system_interface::program as system_program,
+    spl_associated_token_account_interface::address::get_associated_token_address_with_program_id,
+    spl_associated_token_account_mollusk_harness::{
+        AccountBuilder, AtaProgram, AtaTestHarness, TokenChecks,
+    },
+    spl_token_2022_interface::extension::{ExtensionType, transfer_hook},
+    spl_token_interface::error::TokenError,
+    test_case::test_case,
+};
+
+const SOURCE_BALANCE: u64 = 1_000_000;
+const DEPOSIT: u64 = 10_000;
+const DECIMALS: u8 = 6;
+
+struct DepositSetup {
+    harness: AtaTestHarness,
+    mint: Address,
+    source: Address,
+    ata: Address,
+}
+
+fn create_with_deposit_instruction(
+    setup: &DepositSetup,
+    mode: u8,
+    amount: u64,
+    decimals: u8,
+) -> Instruction {
+    let mut data = vec![14, mode];
+    data.extend_from_slice(&amount.to_le_bytes());
+    data.push(decimals);
+    Instruction {
+        program_id: spl_associated_token_account_interface::program::id(),
+        accounts: vec![
+            AccountMeta::new(setup.harness.payer, true),
+            AccountMeta::new(setup.ata, false),
+            AccountMeta::new_readonly(setup.harness.wallet.unwrap(), false),
+            AccountMeta::new_readonly(setup.mint, false),
+            AccountMeta::new_readonly(system_program::id(), false),
+            AccountMeta::new_readonly(setup.harness.token_program_id, false),
+            AccountMeta::new(setup.source, false),
+        ],
+        data,
+    }
+}
+
+// The payer's ATA holding `SOURCE_BALANCE` tokens of the harness mint
+fn deposit_setup(mut harness: AtaTestHarness) -> DepositSetup {
+    let token_program_id = harness.token_program_id;
+    let payer = harness.payer;
+    let wallet = harness.wallet.unwrap();
+    let mint = harness.mint.unwrap();
+    let source = harness.create_ata_for_owner_with_token_program(payer, 0, mint, token_program_id);
+    harness.mint_tokens_to_with_token_program(
+        mint,
+        harness.mint_authority.unwrap(),
+        source,
+        token_program_id,
+        SOURCE_BALANCE,
+    );
+    let ata = get_associated_token_address_with_program_id(&wallet, &mint, &token_program_id);
+    DepositSetup {
+        harness,
+        mint,
+        source,
+        ata,
+    }
+}
+
+fn harness_with_mint(token_program_id: Address) -> AtaTestHarness {
+    AtaTestHarness::new_with_ata_program(&token_program_id, AtaProgram::Pinocchio)
+        .with_wallet_and_mint(1_000_000, DECIMALS)
+}
+
+#[test_case(spl_token_interface::id())]
+#[test_case(spl_token_2022_interface::id())]
+fn creates_ata_holding_deposit(token_program_id: Address) {
+    let setup = deposit_setup(harness_with_mint(token_program_id));
+
+    setup.harness.process_and_validate_with_token_checks(
+        &create_with_deposit_instruction(&setup, 0, DEPOSIT, DECIMALS),
+        &[Check::success()],
+        &[
+            TokenChecks::account(setup.ata)
+                .mint_field(setup.mint)
+                .owner_field(setup.harness.wallet.unwrap())
+                .balance(DEPOSIT),
+            TokenChecks::account(setup.source)
+                .balance(SOURCE_BALANCE.checked_sub(DEPOSIT).unwrap()),
+        ],
+    );
+}
+
+#[test_case(spl_token_interface::id())]
+#[test_case(spl_token_2022_interface::id())]
+fn idempotent_mode_deposits_into_existing_ata(token_program_id: Address) {
+    let setup = deposit_setup(harness_with_mint(token_program_id));
+    let instruction = create_with_deposit_instruction(&setup, 1, DEPOSIT, DECIMALS);
+
+    setup
+        .harness
+        .ctx
+        .process_and_validate_instruction(&instruction, &[Check::success()]);
+    setup.harness.process_and_validate_with_token_checks(
+        &instruction,
+        &[Check::success()],
+        &[TokenChecks::account(setup.ata).balance(DEPOSIT.checked_mul(2).unwrap())],
+    );
+}
+
+#[test]
+fn transfer_fee_is_withheld_from_deposit() {
+    let harness = AtaTestHarness::new_with_ata_program(
+        &spl_token_2022_interface::id(),
+        AtaProgram::Pinocchio,
+    )
+    .with_wallet(1_000_000)
+    .with_mint_with_extensions(&[ExtensionType::TransferFeeConfig])
+    .initialize_transfer_fee(100, 1_000)
+    .initialize_mint(DECIMALS);
+    let setup = deposit_setup(harness);
+
+    // 1% of the deposit, under the maximum fee
+    let fee = DEPOSIT.checked_div(100).unwrap();
+    setup.harness.process_and_validate_with_token_checks(
+        &create_with_deposit_instruction(&setup, 0, DEPOSIT, DECIMALS),
+        &[Check::success()],
+        &[TokenChecks::account(setup.ata).balance(DEPOSIT.checked_sub(fee).unwrap())],
+    );
+}
+
+// The hook program is left unset, so that the token program accepts any extra
+// accounts
+#[test]
+fn forwards_transfer_hook_accounts() {
+    let token_program_id = spl_token_2022_interface::id();
+    let harness = AtaTestHarness::new_with_ata_program(&token_program_id, AtaProgram::Pinocchio)
+        .with_wallet(1_000_000)
+        .with_mint_with_extensions(&[ExtensionType::TransferHook]);
+    let initialize_hook = transfer_hook::instruction::initialize(
+        &token_program_id,
+        &harness.mint.unwrap(),
+        harness.mint_authority,
+        None,
+    )
+    .unwrap();
+    harness
+        .ctx
+        .process_and_validate_instruction(&initialize_hook, &[Check::success()]);
+    let setup = deposit_setup(harness.initialize_mint(DECIMALS));
+    let [readonly, writable] = [Address::new_unique(), Address::new_unique()];
+    setup
+        .harness
+        .ensure_accounts_with_lamports(&[(readonly, 1_000_000), (writable, 1_000_000)]);
+
+    let mut instruction = create_with_deposit_instruction(&setup, 0, DEPOSIT, DECIMALS);
+    instruction.accounts.extend([
+        AccountMeta::new_readonly(readonly, false),
+        AccountMeta::new(writable, false),
+    ]);
+    setup.harness.process_and_validate_with_token_checks(
+        &instruction,
+        &[Check::success()],
+        &[TokenChecks::account(setup.ata).balance(DEPOSIT)],
+    );
+}
+
+#[test_case(spl_token_interface::id())]
+#[test_case(spl_token_2022_interface::id())]
+fn fails_for_wrong_decimals(token_program_id: Address) {
+    let setup = deposit_setup(harness_with_mint(token_program_id));
+
+    setup.harness.ctx.process_and_validate_instruction(
+        &create_with_deposit_instruction(&setup, 0, DEPOSIT, DECIMALS.checked_add(1).unwrap()),
+        &[Check::err(ProgramError::Custom(
+            TokenError::MintDecimalsMismatch as u32,
+        ))],
+    );
+}
+
+#[test_case(spl_token_interface::id())]
+#[test_case(spl_token_2022_interface::id())]
+fn fails_for_deposit_above_source_balance(token_program_id: Address) {
+    let setup = deposit_setup(harness_with_mint(token_program_id));
+
+    setup.harness.ctx.process_and_validate_instruction(
+        &create_with_deposit_instruction(
+            &setup,
+            0,
+            SOURCE_BALANCE.checked_add(1).unwrap(),
+            DECIMALS,
+        ),
+        &[Check::err(ProgramError::Custom(
+            TokenError::InsufficientFunds as u32,
+        ))],
+    );
+}
+
+#[test]
+fn fails_without_source_account() {
+    let setup = deposit_setup(harness_with_mint(spl_token_interface::id()));
+    let mut instruction = create_with_deposit_instruction(&setup, 0, DEPOSIT, DECIMALS);
+    instruction.accounts.pop();
+
+    setup.harness.ctx.process_and_validate_instruction(
+        &instruction,
+        &[Check::err(ProgramError::NotEnoughAccountKeys)],
+    );
+}
+
+// A token account the payer owns at an address other than its ATA, as one it
+// is only a delegate of would be
+#[test]
+fn fails_for_source_other_than_payer_ata() {
+    let token_program_id = spl_token_interface::id();
+    let mut setup = deposit_setup(harness_with_mint(token_program_id));
+    let source = Address::new_unique();
+    setup.harness.ctx.account_store.borrow_mut().insert(
+        source,
+        AccountBuilder::token_account(
+            &setup.mint,
+            &setup.harness.payer,
+            SOURCE_BALANCE,
+            &token_program_id,
+        ),
+    );
+    setup.source = source;
+
+    setup.harness.ctx.process_and_validate_instruction(
+        &create_with_deposit_instruction(&setup, 0, DEPOSIT, DECIMALS),
+        &[Check::err(ProgramError::InvalidSeeds)],
+    );
+}
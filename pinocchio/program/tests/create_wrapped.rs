@@ -0,0 +1,183 @@
+use {
+    mollusk_svm_programs_token::{token, token2022},
+    mollusk_svm_result::Check,
+    solana_address::Address,
+    solana_instruction::{AccountMeta, Instruction},
+    solana_program_error::ProgramError,
+    solana_program_option::COption,
+    solana_system_interface::program as system_program,
+    spl_associated_token_account_interface::address::get_associated_token_address_with_program_id,
+    spl_associated_token_account_mollusk_harness::{AtaProgram, AtaTestHarness},
+    spl_token_2022_interface::{extension::StateWithExtensionsOwned, state::Account},
+    spl_token_interface::state::Mint,
+    test_case::test_case,
+};
+
+const DEPOSIT: u64 = 5_000_000;
+
+fn create_wrapped_instruction(
+    harness: &AtaTestHarness,
+    mint: Address,
+    mode: u8,
+    lamports: u64,
+) -> Instruction {
+    let wallet = harness.wallet.unwrap();
+    let mut data = vec![13, mode];
+    data.extend_from_slice(&lamports.to_le_bytes());
+    Instruction {
+        program_id: spl_associated_token_account_interface::program::id(),
+        accounts: vec![
+            AccountMeta::new(harness.payer, true),
+            AccountMeta::new(
+                get_associated_token_address_with_program_id(
+                    &wallet,
+                    &mint,
+                    &harness.token_program_id,
+                ),
+                false,
+            ),
+            AccountMeta::new_readonly(wallet, false),
+            AccountMeta::new_readonly(mint, false),
+            AccountMeta::new_readonly(system_program::id(), false),
+            AccountMeta::new_readonly(harness.token_program_id, false),
+        ],
+        data,
+    }
+}
+
+// Harness with a wallet and the token program's native mint
+fn native_harness(token_program_id: Address) -> (AtaTestHarness, Address) {
+    let harness = AtaTestHarness::new_with_ata_program(&token_program_id, AtaProgram::Pinocchio)
+        .with_wallet(1_000_000);
+    let native_mint_state = Mint {
+        mint_authority: COption::None,
+        supply: 0,
+        decimals: 9,
+        is_initialized: true,
+        freeze_authority: COption::None,
+    };
+    let (native_mint, native_mint_account) = if token_program_id == spl_token_2022_interface::id() {
+        (
+            spl_token_2022_interface::native_mint::id(),
+            token2022::create_account_for_mint(native_mint_state),
+        )
+    } else {
+        (
+            spl_token_interface::native_mint::id(),
+            token::create_account_for_mint(native_mint_state),
+        )
+    };
+    harness
+        .ctx
+        .account_store
+        .borrow_mut()
+        .insert(native_mint, native_mint_account);
+    (harness, native_mint)
+}
+
+fn token_account_state(harness: &AtaTestHarness, address: Address) -> Account {
+    StateWithExtensionsOwned::<Account>::unpack(harness.get_account(address).data)
+        .unwrap()
+        .base
+}
+
+#[test_case(spl_token_interface::id())]
+#[test_case(spl_token_2022_interface::id())]
+fn creates_ata_holding_wrapped_lamports(token_program_id: Address) {
+    let (harness, native_mint) = native_harness(token_program_id);
+    let instruction = create_wrapped_instruction(&harness, native_mint, 0, DEPOSIT);
+    let ata = instruction.accounts[1].pubkey;
+
+    harness
+        .ctx
+        .process_and_validate_instruction(&instruction, &[Check::success()]);
+
+    let state = token_account_state(&harness, ata);
+    assert_eq!(state.mint, native_mint);
+    assert_eq!(state.owner, harness.wallet.unwrap());
+    assert_eq!(state.amount, DEPOSIT);
+    let rent_exempt_reserve = harness
+        .get_account(ata)
+        .lamports
+        .checked_sub(DEPOSIT)
+        .unwrap();
+    assert_eq!(state.is_native, COption::Some(rent_exempt_reserve));
+}
+
+#[test_case(spl_token_interface::id())]
+#[test_case(spl_token_2022_interface::id())]
+fn idempotent_mode_tops_up_existing_ata(token_program_id: Address) {
+    let (harness, native_mint) = native_harness(token_program_id);
+    let instruction = create_wrapped_instruction(&harness, native_mint, 1, DEPOSIT);
+    let ata = instruction.accounts[1].pubkey;
+
+    harness
+        .ctx
+        .process_and_validate_instruction(&instruction, &[Check::success()]);
+    harness
+        .ctx
+        .process_and_validate_instruction(&instruction, &[Check::success()]);
+
+    assert_eq!(
+        token_account_state(&harness, ata).amount,
+        DEPOSIT.checked_mul(2).unwrap()
+    );
+}
+
+#[test_case(spl_token_interface::id())]
+#[test_case(spl_token_2022_interface::id())]
+fn zero_lamports_creates_empty_native_ata(token_program_id: Address) {
+    let (harness, native_mint) = native_harness(token_program_id);
+    let instruction = create_wrapped_instruction(&harness, native_mint, 0, 0);
+    let ata = instruction.accounts[1].pubkey;
+
+    harness
+        .ctx
+        .process_and_validate_instruction(&instruction, &[Check::success()]);
+
+    let state = token_account_state(&harness, ata);
+    assert_eq!(state.amount, 0);
+    assert!(state.is_native.is_some());
+}
+
+#[test_case(spl_token_interface::id())]
+#[test_case(spl_token_2022_interface::id())]
+fn fails_for_non_native_mint(token_program_id: Address) {
+    let harness = AtaTestHarness::new_with_ata_program(&token_program_id, AtaProgram::Pinocchio)
+        .with_wallet_and_mint(1_000_000, 9);
+    let instruction = create_wrapped_instruction(&harness, harness.mint.unwrap(), 0, DEPOSIT);
+
+    harness.ctx.process_and_validate_instruction(
+        &instruction,
+        &[Check::err(ProgramError::InvalidArgument)],
+    );
+}
+
+#[test]
+fn fails_for_native_mint_of_other_token_program() {
+    let (harness, _) = native_harness(spl_token_interface::id());
+    let instruction = create_wrapped_instruction(
+        &harness,
+        spl_token_2022_interface::native_mint::id(),
+        0,
+        DEPOSIT,
+    );
+
+    harness.ctx.process_and_validate_instruction(
+        &instruction,
+        &[Check::err(ProgramError::InvalidArgument)],
+    );
+}
+
+#[test]
+fn create_mode_fails_for_existing_ata() {
+    let (harness, native_mint) = native_harness(spl_token_interface::id());
+    let instruction = create_wrapped_instruction(&harness, native_mint, 0, DEPOSIT);
+
+    harness
+        .ctx
+        .process_and_validate_instruction(&instruction, &[Check::success()]);
+    harness
+        .ctx
+        .process_and_validate_instruction(&instruction, &[Check::err(ProgramError::IllegalOwner)]);
+}
@@ -0,0 +1,81 @@
+use {
+    mollusk_svm_result::Check,
+    pinocchio_associated_token_account_interface::instruction::CreateMode,
+    solana_address::Address,
+    solana_program_error::ProgramError,
+    spl_associated_token_account_mollusk_harness::{
+        AtaProgram, AtaTestHarness, CreateAtaInstructionType,
+    },
+    test_case::test_matrix,
+};
+
+fn harness(token_program_id: &Address) -> AtaTestHarness {
+    AtaTestHarness::new_with_ata_program(token_program_id, AtaProgram::Pinocchio)
+        .with_wallet_and_mint(1_000_000, 6)
+}
+
+fn create_with_args(mode: CreateMode) -> CreateAtaInstructionType {
+    CreateAtaInstructionType::CreateWithArgs {
+        mode,
+        bump: None,
+        account_len: None,
+        rent_sysvar: false,
+    }
+}
+
+// Batching tooling commonly emits the same create twice in one transaction:
+// `Create` must reject the duplicate, idempotent variants must no-op
+#[test_matrix([spl_token_interface::id(), spl_token_2022_interface::id()])]
+fn duplicate_create_fails_second_time(token_program_id: Address) {
+    let mut harness = harness(&token_program_id);
+    let first = harness.build_create_ata_instruction(CreateAtaInstructionType::Create);
+    let second = harness.build_create_ata_instruction(CreateAtaInstructionType::Create);
+
+    harness.ctx.process_and_validate_instruction_chain(&[
+        (&first, &[Check::success()]),
+        (&second, &[Check::err(ProgramError::IllegalOwner)]),
+    ]);
+}
+
+#[test_matrix([spl_token_interface::id(), spl_token_2022_interface::id()])]
+fn duplicate_create_idempotent_is_a_no_op(token_program_id: Address) {
+    let mut harness = harness(&token_program_id);
+    let first = harness.build_create_ata_instruction(CreateAtaInstructionType::CreateIdempotent);
+    let second = harness.build_create_ata_instruction(CreateAtaInstructionType::CreateIdempotent);
+
+    harness.ctx.process_and_validate_instruction_chain(&[
+        (&first, &[Check::success()]),
+        (&second, &[Check::success()]),
+    ]);
+}
+
+#[test_matrix([spl_token_interface::id(), spl_token_2022_interface::id()])]
+fn create_then_create_idempotent_succeeds(token_program_id: Address) {
+    let mut harness = harness(&token_program_id);
+    let first = harness.build_create_ata_instruction(CreateAtaInstructionType::Create);
+    let second = harness.build_create_ata_instruction(CreateAtaInstructionType::CreateIdempotent);
+
+    harness.ctx.process_and_validate_instruction_chain(&[
+        (&first, &[Check::success()]),
+        (&second, &[Check::success()]),
+    ]);
+}
+
+#[test_matrix(
+    [spl_token_interface::id(), spl_token_2022_interface::id()],
+    [CreateMode::Always, CreateMode::Idempotent]
+)]
+fn duplicate_create_with_args(token_program_id: Address, mode: CreateMode) {
+    let mut harness = harness(&token_program_id);
+    let first = harness.build_create_ata_instruction(create_with_args(mode));
+    let second = harness.build_create_ata_instruction(create_with_args(mode));
+    let second_check = match mode {
+        CreateMode::Always => Check::err(ProgramError::IllegalOwner),
+        CreateMode::Idempotent => Check::success(),
+    };
+
+    harness.ctx.process_and_validate_instruction_chain(&[
+        (&first, &[Check::success()]),
+        (&second, &[second_check]),
+    ]);
+}
@@ -0,0 +1,63 @@
+use {
+    pinocchio_associated_token_account_interface::error::AssociatedTokenAccountError,
+    solana_address::Address,
+    solana_program_error::ProgramError,
+    spl_associated_token_account_mollusk_harness::{
+        AtaProgram, AtaTestHarness, CreateAtaInstructionType,
+    },
+    test_case::{test_case, test_matrix},
+};
+
+// The program under test must be built with the same `empty-data-create`
+// setting as this test crate.
+#[test_matrix(
+    [spl_token_interface::id(), spl_token_2022_interface::id()],
+    [AtaProgram::Legacy, AtaProgram::Pinocchio]
+)]
+fn empty_instruction_data(token_program_id: Address, ata_program: AtaProgram) {
+    let mut harness = AtaTestHarness::new_with_ata_program(&token_program_id, ata_program)
+        .with_wallet_and_mint(1_000_000, 6);
+
+    if ata_program == AtaProgram::Legacy || cfg!(feature = "empty-data-create") {
+        // Same result as `Create`
+        harness.create_and_check_ata_with_custom_instruction(
+            CreateAtaInstructionType::Create,
+            |instruction| instruction.data.clear(),
+        );
+    } else {
+        let mut instruction =
+            harness.build_create_ata_instruction(CreateAtaInstructionType::Create);
+        instruction.data.clear();
+        harness.process_and_validate_failed_create(
+            &instruction,
+            ProgramError::Custom(AssociatedTokenAccountError::EmptyInstructionData as u32),
+        );
+    }
+}
+
+// The legacy program predates every discriminator after `RecoverNested`, so
+// old clients only ever send these encodings.
+#[test_case(spl_token_interface::id(), &[], false; "empty spl-token")]
+#[test_case(spl_token_2022_interface::id(), &[], false; "empty token-2022")]
+#[test_case(spl_token_interface::id(), &[0], false; "create spl-token")]
+#[test_case(spl_token_2022_interface::id(), &[0], false; "create token-2022")]
+#[test_case(spl_token_interface::id(), &[0], true; "create existing spl-token")]
+#[test_case(spl_token_interface::id(), &[1], false; "idempotent spl-token")]
+#[test_case(spl_token_2022_interface::id(), &[1], false; "idempotent token-2022")]
+#[test_case(spl_token_interface::id(), &[1], true; "idempotent existing spl-token")]
+#[test_case(spl_token_2022_interface::id(), &[1], true; "idempotent existing token-2022")]
+fn legacy_encodings_match_legacy_program(token_program_id: Address, data: &[u8], existing: bool) {
+    if data.is_empty() && !cfg!(feature = "empty-data-create") {
+        return;
+    }
+    let mut harness =
+        AtaTestHarness::new_with_ata_program(&token_program_id, AtaProgram::Pinocchio)
+            .with_wallet_and_mint(1_000_000, 6);
+    if existing {
+        harness = harness.with_ata();
+    }
+    let mut instruction = harness.build_create_ata_instruction(CreateAtaInstructionType::Create);
+    instruction.data = data.to_vec();
+
+    harness.assert_no_regression_against(&instruction, AtaProgram::Legacy);
+}
@@ -0,0 +1,40 @@
+use {
+    mollusk_svm_result::Check,
+    solana_address::Address,
+    spl_associated_token_account_mollusk_harness::{
+        AtaProgram, AtaTestHarness, CreateAtaInstructionType,
+    },
+    test_case::test_matrix,
+};
+
+// Creating and funding an ATA should leave most of a transaction's budget for
+// whatever the caller does next
+const CREATE_AND_FUND_BUDGET: u64 = 50_000;
+
+#[test_matrix([spl_token_interface::id(), spl_token_2022_interface::id()])]
+fn create_and_fund_fits_in_one_transaction(token_program_id: Address) {
+    let mut harness =
+        AtaTestHarness::new_with_ata_program(&token_program_id, AtaProgram::Pinocchio)
+            .with_wallet_and_mint(1_000_000, 6);
+    let create = harness.build_create_ata_instruction(CreateAtaInstructionType::CreateIdempotent);
+    let ata_address = harness.ata_address.unwrap();
+    let fund = spl_token_2022_interface::instruction::mint_to(
+        &token_program_id,
+        &harness.mint.unwrap(),
+        &ata_address,
+        &harness.mint_authority.unwrap(),
+        &[],
+        1_000,
+    )
+    .unwrap();
+
+    harness.process_and_validate_flow(
+        &[
+            (&create, &[Check::success()]),
+            // A repeated idempotent create, as batching tooling often emits
+            (&create, &[Check::success()]),
+            (&fund, &[Check::success()]),
+        ],
+        CREATE_AND_FUND_BUDGET,
+    );
+}
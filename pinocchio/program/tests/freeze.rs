@@ -0,0 +1,114 @@
+use {
+    mollusk_svm_result::Check,
+    solana_address::Address,
+    solana_program_error::ProgramError,
+    spl_associated_token_account_mollusk_harness::{
+        AtaProgram, AtaTestHarness, CreateAtaInstructionType, TokenChecks,
+        build_recover_nested_instruction,
+    },
+    spl_token_interface::{error::TokenError, state::AccountState},
+    test_case::{test_case, test_matrix},
+};
+
+#[test_matrix([spl_token_interface::id(), spl_token_2022_interface::id()])]
+fn create_idempotent_accepts_frozen_ata(token_program_id: Address) {
+    let mut harness =
+        AtaTestHarness::new_with_ata_program(&token_program_id, AtaProgram::Pinocchio)
+            .with_wallet_and_mint(1_000_000, 6)
+            .with_ata();
+    harness.freeze_ata();
+
+    let instruction =
+        harness.build_create_ata_instruction(CreateAtaInstructionType::CreateIdempotent);
+    let ata_address = harness.ata_address.unwrap();
+
+    harness.process_and_validate_with_token_checks(
+        &instruction,
+        &[Check::success()],
+        &[TokenChecks::account(ata_address).state(AccountState::Frozen)],
+    );
+}
+
+#[test_matrix([spl_token_interface::id(), spl_token_2022_interface::id()])]
+fn create_with_dedicated_freeze_authority(token_program_id: Address) {
+    let freeze_authority = Address::new_unique();
+    let mut harness =
+        AtaTestHarness::new_with_ata_program(&token_program_id, AtaProgram::Pinocchio)
+            .with_wallet(1_000_000)
+            .with_freeze_authority(freeze_authority)
+            .with_mint(6);
+    let ata_address = harness.create_ata(CreateAtaInstructionType::Create);
+
+    harness.freeze_ata();
+
+    TokenChecks::account(ata_address)
+        .state(AccountState::Frozen)
+        .assert(&harness);
+}
+
+#[test_case(true; "frozen nested account")]
+#[test_case(false; "frozen destination account")]
+fn recover_nested_rejects_frozen_accounts(freeze_nested: bool) {
+    let token_program_id = spl_token_2022_interface::id();
+    let mut harness =
+        AtaTestHarness::new_with_ata_program(&token_program_id, AtaProgram::Pinocchio)
+            .with_wallet(1_000_000);
+    let wallet = harness.wallet.unwrap();
+
+    let (owner_mint, _) = harness.create_mint_with_token_program(token_program_id, 0);
+    let owner_ata = harness.create_ata_for_owner_with_token_program(
+        wallet,
+        1_000_000,
+        owner_mint,
+        token_program_id,
+    );
+    // The mint authority doubles as the freeze authority for harness-created mints
+    let (nested_mint, nested_mint_authority) =
+        harness.create_mint_with_token_program(token_program_id, 0);
+    let nested_ata = harness.create_ata_for_owner_with_token_program(
+        owner_ata,
+        1_000_000,
+        nested_mint,
+        token_program_id,
+    );
+    harness.mint_tokens_to_with_token_program(
+        nested_mint,
+        nested_mint_authority,
+        nested_ata,
+        token_program_id,
+        100,
+    );
+    let destination_ata = harness.create_ata_for_owner_with_token_program(
+        wallet,
+        1_000_000,
+        nested_mint,
+        token_program_id,
+    );
+
+    let frozen = if freeze_nested {
+        nested_ata
+    } else {
+        destination_ata
+    };
+    harness.freeze_account_with_token_program(
+        frozen,
+        nested_mint,
+        nested_mint_authority,
+        token_program_id,
+    );
+
+    let instruction = build_recover_nested_instruction(
+        &wallet,
+        &owner_mint,
+        &nested_mint,
+        &token_program_id,
+        &token_program_id,
+        &[],
+    );
+    harness.ctx.process_and_validate_instruction(
+        &instruction,
+        &[Check::err(ProgramError::Custom(
+            TokenError::AccountFrozen as u32,
+        ))],
+    );
+}
@@ -0,0 +1,268 @@
+//! Integration recipes run against the p-ATA program.
+//!
+//! Each recipe sets up its accounts with the harness, runs the client code
+//! between its `// recipe:` and `// end recipe` markers, and checks the
+//! outcome. With `GENERATE_EXAMPLES` set, every recipe also writes that code
+//! and a dump of the accounts it touched to `generated_examples/<name>.md`,
+//! so the published snippets are exactly the code these tests run.
+//!
+//! Run with `make generate-examples-pinocchio-program`.
+
+use {
+    mollusk_svm_result::Check,
+    pinocchio_associated_token_account_interface::account_indices::RECOVER_NESTED_WALLET,
+    solana_address::Address,
+    solana_instruction::AccountMeta,
+    solana_program_pack::Pack,
+    solana_rent::Rent,
+    spl_associated_token_account_interface::{
+        address::get_associated_token_address_with_program_id,
+        instruction::{create_associated_token_account, recover_nested},
+        sponsored::{sponsored_create_associated_token_accounts, verify_sponsored_create},
+    },
+    spl_associated_token_account_mollusk_harness::{AtaProgram, AtaTestHarness, TokenChecks},
+    spl_token_2022_interface::extension::{ExtensionType, transfer_fee::TransferFeeAmount},
+    spl_token_interface::{instruction::initialize_multisig2, state::Multisig},
+    std::path::PathBuf,
+};
+
+const SOURCE: &str = include_str!("generated_examples.rs");
+
+/// The code between the markers of `name` in this file, without its indentation
+fn recipe_source(name: &str) -> String {
+    let start_marker = format!("// recipe: {name}");
+    let mut lines = SOURCE
+        .lines()
+        .skip_while(|line| line.trim() != start_marker)
+        .skip(1)
+        .take_while(|line| line.trim() != "// end recipe")
+        .collect::<Vec<_>>();
+    assert!(!lines.is_empty(), "recipe {name} not found");
+    let indent = lines
+        .iter()
+        .filter(|line| !line.trim().is_empty())
+        .map(|line| line.len().saturating_sub(line.trim_start().len()))
+        .min()
+        .unwrap_or(0);
+    for line in &mut lines {
+        *line = line.get(indent..).unwrap_or("");
+    }
+    lines.join("\n")
+}
+
+/// Write the recipe's code and the dumped accounts to
+/// `generated_examples/<name>.md` when `GENERATE_EXAMPLES` is set
+fn write_example(harness: &AtaTestHarness, name: &str, title: &str, accounts: &[(&str, Address)]) {
+    if std::env::var_os("GENERATE_EXAMPLES").is_none() {
+        return;
+    }
+    let example = format!(
+        "# {title}\n\n\
+         Generated by `tests/generated_examples.rs`, do not edit.\n\n\
+         ```rust\n{}\n```\n\n\
+         ## Resulting accounts\n\n\
+         ```text\n{}```\n",
+        recipe_source(name),
+        harness.dump_accounts(accounts),
+    );
+    let dir = PathBuf::from(env!("CARGO_MANIFEST_DIR")).join("generated_examples");
+    std::fs::create_dir_all(&dir).expect("failed to create generated_examples");
+    std::fs::write(dir.join(format!("{name}.md")), example)
+        .unwrap_or_else(|error| panic!("failed to write example {name}: {error}"));
+}
+
+#[test]
+fn token_2022_transfer_fee() {
+    let harness = AtaTestHarness::new_with_ata_program(
+        &spl_token_2022_interface::id(),
+        AtaProgram::Pinocchio,
+    )
+    .with_wallet(1_000_000)
+    .with_mint_with_extensions(&[ExtensionType::TransferFeeConfig])
+    .initialize_transfer_fee(100, 1_000)
+    .initialize_mint(6);
+    let payer = harness.payer;
+    let wallet = harness.wallet.unwrap();
+    let mint = harness.mint.unwrap();
+
+    // recipe: token_2022_transfer_fee
+    // The program sizes the account for the mint's `TransferFeeAmount`
+    // extension, the client passes the same accounts as for any mint
+    let instruction =
+        create_associated_token_account(&payer, &wallet, &mint, &spl_token_2022_interface::id());
+    let ata = get_associated_token_address_with_program_id(
+        &wallet,
+        &mint,
+        &spl_token_2022_interface::id(),
+    );
+    // end recipe
+
+    harness.process_and_validate_with_token_checks(
+        &instruction,
+        &[Check::success()],
+        &[TokenChecks::account(ata)
+            .mint_field(mint)
+            .owner_field(wallet)
+            .balance(0)
+            .extension_initialized::<TransferFeeAmount>()],
+    );
+
+    write_example(
+        &harness,
+        "token_2022_transfer_fee",
+        "Create an ATA for a Token-2022 mint with a transfer fee",
+        &[("wallet", wallet), ("mint", mint), ("ata", ata)],
+    );
+}
+
+#[test]
+fn sponsor_paid_create() {
+    let (harness, sponsor) =
+        AtaTestHarness::new_with_ata_program(&spl_token_interface::id(), AtaProgram::Pinocchio)
+            .with_mint(6)
+            .with_additional_wallet(1_000_000_000);
+    let mint = harness.mint.unwrap();
+    // The wallet holds no SOL and does not sign
+    let wallet = Address::new_unique();
+
+    // recipe: sponsor_paid_create
+    let transaction = sponsored_create_associated_token_accounts(
+        &sponsor,
+        &wallet,
+        &[mint],
+        &spl_token_interface::id(),
+    );
+    // A relayer signing a transaction it did not build checks it first
+    verify_sponsored_create(&sponsor, &transaction.instructions).unwrap();
+    let ata =
+        get_associated_token_address_with_program_id(&wallet, &mint, &spl_token_interface::id());
+    // end recipe
+
+    assert_eq!(transaction.required_signers, vec![sponsor]);
+    let rent = Rent::default().minimum_balance(spl_token_interface::state::Account::LEN);
+    let sponsor_lamports = harness.get_account(sponsor).lamports;
+    for instruction in &transaction.instructions {
+        harness
+            .ctx
+            .process_and_validate_instruction(instruction, &[Check::success()]);
+    }
+    assert_eq!(
+        harness.get_account(sponsor).lamports,
+        sponsor_lamports.saturating_sub(rent)
+    );
+    TokenChecks::account(ata)
+        .mint_field(mint)
+        .owner_field(wallet)
+        .assert(&harness);
+
+    write_example(
+        &harness,
+        "sponsor_paid_create",
+        "Create an ATA paid for by a sponsor",
+        &[
+            ("sponsor", sponsor),
+            ("wallet", wallet),
+            ("mint", mint),
+            ("ata", ata),
+        ],
+    );
+}
+
+#[test]
+fn recover_nested_multisig() {
+    const AMOUNT: u64 = 100;
+    let token_program_id = spl_token_interface::id();
+    let mut harness =
+        AtaTestHarness::new_with_ata_program(&token_program_id, AtaProgram::Pinocchio);
+
+    // A 2-of-3 multisig wallet
+    let signers = [
+        Address::new_unique(),
+        Address::new_unique(),
+        Address::new_unique(),
+    ];
+    let multisig = Address::new_unique();
+    harness.ctx.process_and_validate_instruction(
+        &solana_system_interface::instruction::create_account(
+            &harness.payer,
+            &multisig,
+            Rent::default().minimum_balance(Multisig::LEN),
+            Multisig::LEN as u64,
+            &token_program_id,
+        ),
+        &[Check::success()],
+    );
+    harness.ctx.process_and_validate_instruction(
+        &initialize_multisig2(
+            &token_program_id,
+            &multisig,
+            &signers.iter().collect::<Vec<_>>(),
+            2,
+        )
+        .unwrap(),
+        &[Check::success()],
+    );
+
+    // Tokens sent to the multisig's ATA as if it were a wallet
+    let (owner_mint, _) = harness.create_mint_with_token_program(token_program_id, 0);
+    let owner_ata =
+        harness.create_ata_for_owner_with_token_program(multisig, 0, owner_mint, token_program_id);
+    let (nested_mint, nested_mint_authority) =
+        harness.create_mint_with_token_program(token_program_id, 0);
+    let nested_ata = harness.create_ata_for_owner_with_token_program(
+        owner_ata,
+        0,
+        nested_mint,
+        token_program_id,
+    );
+    harness.mint_tokens_to_with_token_program(
+        nested_mint,
+        nested_mint_authority,
+        nested_ata,
+        token_program_id,
+        AMOUNT,
+    );
+    let destination_ata =
+        harness.create_ata_for_owner_with_token_program(multisig, 0, nested_mint, token_program_id);
+
+    // recipe: recover_nested_multisig
+    let mut instruction = recover_nested(
+        &multisig,
+        &owner_mint,
+        &nested_mint,
+        &spl_token_interface::id(),
+    );
+    // A multisig wallet signs through its signers, which follow the nested
+    // token program account
+    instruction.accounts[RECOVER_NESTED_WALLET].is_signer = false;
+    instruction
+        .accounts
+        .push(AccountMeta::new_readonly(spl_token_interface::id(), false));
+    instruction.accounts.extend(
+        [signers[0], signers[1]]
+            .iter()
+            .map(|signer| AccountMeta::new_readonly(*signer, true)),
+    );
+    // end recipe
+
+    harness.process_and_validate_with_token_checks(
+        &instruction,
+        &[
+            Check::success(),
+            Check::account(&nested_ata).closed().build(),
+        ],
+        &[TokenChecks::account(destination_ata).balance(AMOUNT)],
+    );
+
+    write_example(
+        &harness,
+        "recover_nested_multisig",
+        "Recover tokens from a nested ATA of a multisig wallet",
+        &[
+            ("multisig", multisig),
+            ("owner ata", owner_ata),
+            ("nested ata", nested_ata),
+            ("destination ata", destination_ata),
+        ],
+    );
+}
@@ -0,0 +1,67 @@
+use {
+    mollusk_svm_result::Check,
+    pinocchio_associated_token_account_interface::{
+        instruction::AssociatedTokenAccountInstruction,
+        version::{
+            FEATURE_ACCOUNT_DATA_SIZE_CPI, FEATURE_ACCOUNT_LEN_HINT, FEATURE_ASSERT_ATA_EXISTS,
+            FEATURE_BUMP_HINT, FEATURE_BUMP_LIMIT, FEATURE_CLOSE, FEATURE_CLOSE_EMPTY,
+            FEATURE_CREATE_COMPACT, FEATURE_CREATE_CONFIDENTIAL, FEATURE_CREATE_MANY,
+            FEATURE_CREATE_WITH_DEPOSIT, FEATURE_CREATE_WRAPPED, FEATURE_PREFUNDED,
+            FEATURE_RECLAIM_UNINITIALIZED, FEATURE_RECOVER_NESTED_BUMP_HINT,
+            FEATURE_RECOVER_NESTED_IDEMPOTENT, FEATURE_RECOVER_NESTED_WITH_MEMO, ProgramVersion,
+        },
+    },
+    solana_instruction::Instruction,
+    spl_associated_token_account_mollusk_harness::{AtaProgram, AtaTestHarness},
+};
+
+/// Features every build supports, whatever its Cargo features.
+const INVARIANT_FEATURES: u32 = FEATURE_PREFUNDED
+    | FEATURE_BUMP_HINT
+    | FEATURE_ACCOUNT_LEN_HINT
+    | FEATURE_CREATE_COMPACT
+    | FEATURE_BUMP_LIMIT
+    | FEATURE_ASSERT_ATA_EXISTS
+    | FEATURE_CREATE_MANY
+    | FEATURE_RECOVER_NESTED_BUMP_HINT
+    | FEATURE_RECOVER_NESTED_IDEMPOTENT
+    | FEATURE_CLOSE
+    | FEATURE_CLOSE_EMPTY
+    | FEATURE_CREATE_WRAPPED
+    | FEATURE_CREATE_WITH_DEPOSIT
+    | FEATURE_CREATE_CONFIDENTIAL
+    | FEATURE_RECOVER_NESTED_WITH_MEMO;
+
+/// Features that depend on the Cargo features the binary was built with,
+/// which need not match this test crate's.
+const OPTIONAL_FEATURES: u32 = FEATURE_ACCOUNT_DATA_SIZE_CPI | FEATURE_RECLAIM_UNINITIALIZED;
+
+#[test]
+fn get_version_returns_crate_version_and_features() {
+    let harness =
+        AtaTestHarness::new_with_ata_program(&spl_token_interface::id(), AtaProgram::Pinocchio);
+    let instruction = Instruction {
+        program_id: spl_associated_token_account_interface::program::id(),
+        accounts: vec![],
+        data: vec![AssociatedTokenAccountInstruction::GetVersion.discriminator()],
+    };
+
+    let result = harness
+        .ctx
+        .process_and_validate_instruction(&instruction, &[Check::success()]);
+
+    let version = ProgramVersion::try_from_bytes(&result.return_data).unwrap();
+    assert_eq!(
+        (version.major, version.minor, version.patch),
+        (
+            env!("CARGO_PKG_VERSION_MAJOR").parse().unwrap(),
+            env!("CARGO_PKG_VERSION_MINOR").parse().unwrap(),
+            env!("CARGO_PKG_VERSION_PATCH").parse().unwrap(),
+        )
+    );
+    assert!(version.supports(INVARIANT_FEATURES));
+    assert_eq!(
+        version.features & !(INVARIANT_FEATURES | OPTIONAL_FEATURES),
+        0
+    );
+}
@@ -0,0 +1,41 @@
+use {
+    spl_associated_token_account_mollusk_harness::{
+        AtaProgram, AtaTestHarness, CreateAtaInstructionType, TokenChecks,
+        expected_ata_len_for_mint, token_2022_immutable_owner_account_len,
+    },
+    spl_token_2022_interface::{
+        extension::{BaseStateWithExtensions, ExtensionType, StateWithExtensions},
+        state::Mint,
+    },
+    test_case::test_case,
+};
+
+fn harness() -> AtaTestHarness {
+    AtaTestHarness::new_with_ata_program(&spl_token_2022_interface::id(), AtaProgram::Pinocchio)
+        .with_wallet(1_000_000)
+}
+
+// Group and member pointers are mint-only extensions, so ATAs for collection
+// mints need no account extensions beyond `ImmutableOwner`
+#[test_case(harness().with_group_mint(0), ExtensionType::GroupPointer; "group mint")]
+#[test_case(harness().with_group_member_mint(0), ExtensionType::GroupMemberPointer; "member mint")]
+fn create_for_group_mints(mut harness: AtaTestHarness, mint_extension: ExtensionType) {
+    let mint_account = harness.get_account(harness.mint.unwrap());
+    let mint_state = StateWithExtensions::<Mint>::unpack(&mint_account.data).unwrap();
+    assert!(
+        mint_state
+            .get_extension_types()
+            .unwrap()
+            .contains(&mint_extension)
+    );
+    assert_eq!(
+        expected_ata_len_for_mint(&mint_account),
+        token_2022_immutable_owner_account_len()
+    );
+
+    let ata_address = harness.create_ata(CreateAtaInstructionType::Create);
+
+    TokenChecks::account(ata_address)
+        .owner_field(harness.wallet.unwrap())
+        .assert(&harness);
+}
@@ -0,0 +1,162 @@
+use {
+    pinocchio_associated_token_account_interface::instruction::{
+        AssociatedTokenAccountInstruction, CreateMode,
+    },
+    spl_associated_token_account_mollusk_harness::{
+        CreateAtaInstructionType, encode_create_ata_instruction_data,
+    },
+    std::{env, fs},
+};
+
+const ENCODING_DOCS_PATH: &str = concat!(
+    env!("CARGO_MANIFEST_DIR"),
+    "/../interface/instruction_encoding.md"
+);
+const TEST_BUMP: u8 = 254;
+const TEST_ACCOUNT_LEN: u32 = 170;
+
+const HEADER: &str = concat!(
+    "# p-ATA instruction data encoding\n",
+    "\n",
+    "<!-- Generated by pinocchio/program/tests/instruction_encoding.rs, ",
+    "run it with UPDATE_ENCODING_DOCS=1 to refresh -->\n",
+    "\n",
+    "Byte layout of every `CreateAtaInstructionType` the test harness builds. ",
+    "A bump or account length of `-` is\n",
+    "encoded as the null value `0`. ",
+    "Legacy clients may also send empty data, which decodes as `Create`.\n",
+    "\n",
+    "| Variant | Mode | Bump | Account len | Data |\n",
+    "|---------|------|------|-------------|------|\n",
+);
+
+fn variants() -> Vec<CreateAtaInstructionType> {
+    let mut variants = vec![
+        CreateAtaInstructionType::Create,
+        CreateAtaInstructionType::CreateIdempotent,
+    ];
+    for compact in [false, true] {
+        for mode in [CreateMode::Always, CreateMode::Idempotent] {
+            for bump in [None, Some(TEST_BUMP)] {
+                for account_len in [None, Some(TEST_ACCOUNT_LEN)] {
+                    variants.push(if compact {
+                        CreateAtaInstructionType::CreateCompact {
+                            mode,
+                            bump,
+                            account_len,
+                            rent_sysvar: false,
+                        }
+                    } else {
+                        CreateAtaInstructionType::CreateWithArgs {
+                            mode,
+                            bump,
+                            account_len,
+                            rent_sysvar: false,
+                        }
+                    });
+                }
+            }
+        }
+    }
+    variants
+}
+
+/// Name, mode, bump and account length as the harness variant specifies them
+fn fields(
+    variant: &CreateAtaInstructionType,
+) -> (&'static str, Option<CreateMode>, Option<u8>, Option<u32>) {
+    match variant {
+        CreateAtaInstructionType::Create => ("Create", None, None, None),
+        CreateAtaInstructionType::CreateIdempotent => ("CreateIdempotent", None, None, None),
+        CreateAtaInstructionType::CreateWithArgs {
+            mode,
+            bump,
+            account_len,
+            ..
+        } => ("CreateWithArgs", Some(*mode), *bump, *account_len),
+        CreateAtaInstructionType::CreateCompact {
+            mode,
+            bump,
+            account_len,
+            ..
+        } => ("CreateCompact", Some(*mode), *bump, *account_len),
+    }
+}
+
+fn or_dash<T: ToString>(value: Option<T>) -> String {
+    value.map_or_else(|| "-".to_string(), |value| value.to_string())
+}
+
+fn render() -> String {
+    let mut docs = HEADER.to_string();
+    for variant in variants() {
+        let (name, mode, bump, account_len) = fields(&variant);
+        let data = encode_create_ata_instruction_data(&variant)
+            .iter()
+            .map(|byte| format!("{byte:02x}"))
+            .collect::<Vec<_>>()
+            .join(" ");
+        docs.push_str(&format!(
+            "| `{name}` | {} | {} | {} | `{data}` |\n",
+            or_dash(mode.map(|mode| format!("{mode:?}"))),
+            or_dash(bump),
+            or_dash(account_len),
+        ));
+    }
+    docs
+}
+
+#[test]
+fn harness_encodings_round_trip_through_interface() {
+    for variant in variants() {
+        let data = encode_create_ata_instruction_data(&variant);
+        let instruction = AssociatedTokenAccountInstruction::try_from_bytes(&data).unwrap();
+
+        let mut buffer = [0; AssociatedTokenAccountInstruction::MAX_DATA_LEN];
+        let len = instruction.write_data(&mut buffer).unwrap();
+        assert_eq!(&buffer[..len], data.as_slice(), "{variant:?}");
+
+        let decoded = match instruction {
+            AssociatedTokenAccountInstruction::Create => ("Create", None, None, None),
+            AssociatedTokenAccountInstruction::CreateIdempotent => {
+                ("CreateIdempotent", None, None, None)
+            }
+            AssociatedTokenAccountInstruction::CreateWithArgs {
+                mode,
+                bump,
+                account_len,
+            } => (
+                "CreateWithArgs",
+                Some(mode),
+                bump.get().map(u8::from),
+                account_len.get().map(u32::from),
+            ),
+            AssociatedTokenAccountInstruction::CreateCompact {
+                mode,
+                bump,
+                account_len,
+            } => (
+                "CreateCompact",
+                Some(mode),
+                bump.get().map(u8::from),
+                account_len.get().map(u32::from),
+            ),
+            other => panic!("{variant:?} decoded as {other:?}"),
+        };
+        assert_eq!(decoded, fields(&variant), "{variant:?}");
+    }
+}
+
+#[test]
+fn encoding_docs_are_up_to_date() {
+    let docs = render();
+    if env::var_os("UPDATE_ENCODING_DOCS").is_some() {
+        fs::write(ENCODING_DOCS_PATH, docs).unwrap();
+        return;
+    }
+    assert_eq!(
+        fs::read_to_string(ENCODING_DOCS_PATH).unwrap(),
+        docs,
+        "run with UPDATE_ENCODING_DOCS=1 to refresh {ENCODING_DOCS_PATH}"
+    );
+}
@@ -0,0 +1,169 @@
+use {
+    mollusk_svm_result::Check,
+    pinocchio_associated_token_account_interface::{
+        account_indices::{
+            CREATE_ASSOCIATED_TOKEN_ACCOUNT, CREATE_FUNDER, CREATE_MINT, CREATE_SYSTEM_PROGRAM,
+            CREATE_TOKEN_PROGRAM, CREATE_WALLET,
+            RECOVER_NESTED_DESTINATION_ASSOCIATED_TOKEN_ACCOUNT,
+            RECOVER_NESTED_NESTED_ASSOCIATED_TOKEN_ACCOUNT, RECOVER_NESTED_NESTED_MINT,
+            RECOVER_NESTED_OWNER_ASSOCIATED_TOKEN_ACCOUNT, RECOVER_NESTED_OWNER_MINT,
+            RECOVER_NESTED_OWNER_TOKEN_PROGRAM, RECOVER_NESTED_WALLET,
+        },
+        instruction::AssociatedTokenAccountInstruction,
+    },
+    solana_address::Address,
+    solana_instruction::Instruction,
+    solana_system_interface::program as system_program,
+    spl_associated_token_account_interface::{
+        address::get_associated_token_address_with_program_id,
+        instruction::{
+            create_associated_token_account, create_associated_token_account_idempotent,
+            recover_nested,
+        },
+    },
+    spl_associated_token_account_mollusk_harness::{
+        AtaProgram, AtaTestHarness, CreateAtaInstructionType,
+    },
+    test_case::{test_case, test_matrix},
+};
+
+fn harness(token_program_id: Address) -> AtaTestHarness {
+    AtaTestHarness::new_with_ata_program(&token_program_id, AtaProgram::Pinocchio)
+        .with_wallet_and_mint(1_000_000, 6)
+}
+
+/// Create instruction built by the SPL interface crate for the harness
+/// wallet and mint
+fn spl_create_instruction(harness: &AtaTestHarness, idempotent: bool) -> Instruction {
+    let build = if idempotent {
+        create_associated_token_account_idempotent
+    } else {
+        create_associated_token_account
+    };
+    build(
+        &harness.payer,
+        &harness.wallet.unwrap(),
+        &harness.mint.unwrap(),
+        &harness.token_program_id,
+    )
+}
+
+#[test_case(false, AssociatedTokenAccountInstruction::Create)]
+#[test_case(true, AssociatedTokenAccountInstruction::CreateIdempotent)]
+fn spl_create_decodes_with_pinocchio_layout(
+    idempotent: bool,
+    expected: AssociatedTokenAccountInstruction,
+) {
+    let harness = harness(spl_token_2022_interface::id());
+    let (wallet, mint) = (harness.wallet.unwrap(), harness.mint.unwrap());
+    let instruction = spl_create_instruction(&harness, idempotent);
+
+    assert_eq!(
+        instruction.program_id,
+        pinocchio_associated_token_account_interface::ID
+    );
+    assert_eq!(
+        AssociatedTokenAccountInstruction::try_from_bytes(&instruction.data),
+        Ok(expected)
+    );
+    let keys: Vec<Address> = instruction
+        .accounts
+        .iter()
+        .map(|meta| meta.pubkey)
+        .collect();
+    assert_eq!(keys[CREATE_FUNDER], harness.payer);
+    assert_eq!(
+        keys[CREATE_ASSOCIATED_TOKEN_ACCOUNT],
+        get_associated_token_address_with_program_id(&wallet, &mint, &harness.token_program_id)
+    );
+    assert_eq!(keys[CREATE_WALLET], wallet);
+    assert_eq!(keys[CREATE_MINT], mint);
+    assert_eq!(keys[CREATE_SYSTEM_PROGRAM], system_program::id());
+    assert_eq!(keys[CREATE_TOKEN_PROGRAM], harness.token_program_id);
+}
+
+#[test]
+fn spl_recover_nested_decodes_with_pinocchio_layout() {
+    let (wallet, owner_mint, nested_mint) = (
+        Address::new_unique(),
+        Address::new_unique(),
+        Address::new_unique(),
+    );
+    let token_program_id = spl_token_interface::id();
+    let instruction = recover_nested(&wallet, &owner_mint, &nested_mint, &token_program_id);
+    let owner_ata =
+        get_associated_token_address_with_program_id(&wallet, &owner_mint, &token_program_id);
+
+    assert_eq!(
+        AssociatedTokenAccountInstruction::try_from_bytes(&instruction.data),
+        Ok(AssociatedTokenAccountInstruction::RecoverNested)
+    );
+    let keys: Vec<Address> = instruction
+        .accounts
+        .iter()
+        .map(|meta| meta.pubkey)
+        .collect();
+    assert_eq!(
+        keys[RECOVER_NESTED_NESTED_ASSOCIATED_TOKEN_ACCOUNT],
+        get_associated_token_address_with_program_id(&owner_ata, &nested_mint, &token_program_id)
+    );
+    assert_eq!(keys[RECOVER_NESTED_NESTED_MINT], nested_mint);
+    assert_eq!(
+        keys[RECOVER_NESTED_DESTINATION_ASSOCIATED_TOKEN_ACCOUNT],
+        get_associated_token_address_with_program_id(&wallet, &nested_mint, &token_program_id)
+    );
+    assert_eq!(
+        keys[RECOVER_NESTED_OWNER_ASSOCIATED_TOKEN_ACCOUNT],
+        owner_ata
+    );
+    assert_eq!(keys[RECOVER_NESTED_OWNER_MINT], owner_mint);
+    assert_eq!(keys[RECOVER_NESTED_WALLET], wallet);
+    assert_eq!(keys[RECOVER_NESTED_OWNER_TOKEN_PROGRAM], token_program_id);
+    assert!(instruction.accounts[RECOVER_NESTED_WALLET].is_signer);
+}
+
+// SPL interface builders against p-ATA, with the reference program's result
+// as the expectation
+#[test_matrix(
+    [spl_token_interface::id(), spl_token_2022_interface::id()],
+    [false, true],
+    [false, true]
+)]
+fn spl_create_processes_like_reference(
+    token_program_id: Address,
+    idempotent: bool,
+    existing: bool,
+) {
+    let mut harness = harness(token_program_id);
+    if existing {
+        harness = harness.with_ata();
+    }
+    let instruction = spl_create_instruction(&harness, idempotent);
+
+    let result = harness.assert_matches_reference(&instruction);
+    assert_eq!(!result.program_result.is_err(), idempotent || !existing);
+}
+
+// Instruction data written by the p-ATA interface, with account lists from the
+// harness, against the reference program
+#[test_matrix(
+    [spl_token_interface::id(), spl_token_2022_interface::id()],
+    [AssociatedTokenAccountInstruction::Create, AssociatedTokenAccountInstruction::CreateIdempotent]
+)]
+fn pinocchio_encoding_processes_under_reference(
+    token_program_id: Address,
+    instruction: AssociatedTokenAccountInstruction,
+) {
+    let mut harness = harness(token_program_id);
+    let mut data = [0; AssociatedTokenAccountInstruction::MAX_DATA_LEN];
+    let len = instruction.write_data(&mut data).unwrap();
+    let mut create = harness.build_create_ata_instruction(CreateAtaInstructionType::Create);
+    create.data = data[..len].to_vec();
+
+    let result = harness.assert_matches_reference(&create);
+    assert!(!result.program_result.is_err());
+    harness.ctx.process_and_validate_instruction(
+        &spl_create_instruction(&harness, true),
+        &[Check::success()],
+    );
+}
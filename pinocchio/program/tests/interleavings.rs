@@ -0,0 +1,137 @@
+use {
+    solana_address::Address,
+    solana_instruction::{AccountMeta, Instruction},
+    spl_associated_token_account_interface::address::get_associated_token_address_with_program_id,
+    spl_associated_token_account_mollusk_harness::{
+        AtaProgram, AtaTestHarness, CreateAtaInstructionType, build_create_ata_instruction,
+        build_recover_nested_instruction, interleavings,
+    },
+    test_case::test_case,
+};
+
+#[test]
+fn interleavings_preserve_each_actor_order() {
+    let orderings = interleavings(&[1, 2], &[3]);
+    assert_eq!(
+        orderings,
+        vec![vec![&1, &2, &3], vec![&1, &3, &2], vec![&3, &1, &2]]
+    );
+}
+
+/// p-ATA `Close` of `wallet`'s ATA for `mint`
+fn close_instruction(
+    ata: Address,
+    wallet: Address,
+    mint: Address,
+    token_program_id: Address,
+) -> Instruction {
+    Instruction {
+        program_id: spl_associated_token_account_interface::program::id(),
+        accounts: vec![
+            AccountMeta::new(ata, false),
+            AccountMeta::new(wallet, true),
+            AccountMeta::new_readonly(mint, false),
+            AccountMeta::new_readonly(token_program_id, false),
+        ],
+        data: vec![11],
+    }
+}
+
+#[test_case(spl_token_interface::id())]
+#[test_case(spl_token_2022_interface::id())]
+fn create_transfer_recover_and_close_interleavings_are_clean(token_program_id: Address) {
+    let mut harness =
+        AtaTestHarness::new_with_ata_program(&token_program_id, AtaProgram::Pinocchio)
+            .with_wallet(1_000_000);
+    let wallet = harness.wallet.unwrap();
+
+    let (owner_mint, _) = harness.create_mint_with_token_program(token_program_id, 0);
+    let owner_ata = harness.create_ata_for_owner_with_token_program(
+        wallet,
+        1_000_000,
+        owner_mint,
+        token_program_id,
+    );
+    let (nested_mint, nested_mint_authority) =
+        harness.create_mint_with_token_program(token_program_id, 0);
+    let nested_ata = harness.create_ata_for_owner_with_token_program(
+        owner_ata,
+        1_000_000,
+        nested_mint,
+        token_program_id,
+    );
+    harness.mint_tokens_to_with_token_program(
+        nested_mint,
+        nested_mint_authority,
+        nested_ata,
+        token_program_id,
+        100,
+    );
+    let payer = harness.payer;
+    let payer_ata =
+        harness.create_ata_for_owner_with_token_program(payer, 0, nested_mint, token_program_id);
+    harness.mint_tokens_to_with_token_program(
+        nested_mint,
+        nested_mint_authority,
+        payer_ata,
+        token_program_id,
+        50,
+    );
+    let destination_ata =
+        get_associated_token_address_with_program_id(&wallet, &nested_mint, &token_program_id);
+
+    let create_destination = |instruction_type| {
+        build_create_ata_instruction(
+            spl_associated_token_account_interface::program::id(),
+            harness.payer,
+            destination_ata,
+            wallet,
+            nested_mint,
+            token_program_id,
+            instruction_type,
+        )
+    };
+    let recover = build_recover_nested_instruction(
+        &wallet,
+        &owner_mint,
+        &nested_mint,
+        &token_program_id,
+        &token_program_id,
+        &[],
+    );
+
+    let transfer = spl_token_2022_interface::instruction::transfer_checked(
+        &token_program_id,
+        &payer_ata,
+        &nested_mint,
+        &destination_ata,
+        &payer,
+        &[],
+        50,
+        0,
+    )
+    .unwrap();
+    let close_destination =
+        close_instruction(destination_ata, wallet, nested_mint, token_program_id);
+
+    // Both actors race to create, fund, drain into and close the destination,
+    // so every ordering hits at least one duplicate create, premature recover
+    // or transfer, or close of a funded or missing account
+    let actor_a = [
+        create_destination(CreateAtaInstructionType::CreateIdempotent),
+        transfer,
+        recover.clone(),
+    ];
+    let actor_b = [
+        recover.clone(),
+        create_destination(CreateAtaInstructionType::Create),
+        close_destination,
+        recover,
+    ];
+
+    harness.assert_interleavings_are_clean(
+        &actor_a,
+        &actor_b,
+        &[owner_ata, nested_ata, destination_ata, payer_ata],
+    );
+}
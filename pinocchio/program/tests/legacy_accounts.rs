@@ -0,0 +1,123 @@
+use {
+    mollusk_svm_result::Check,
+    solana_program_error::ProgramError,
+    solana_program_pack::Pack,
+    spl_associated_token_account_mollusk_harness::{
+        AtaProgram, AtaTestHarness, CreateAtaInstructionType, build_recover_nested_instruction,
+    },
+    spl_token_2022_interface::{
+        extension::{BaseStateWithExtensions, ExtensionType, StateWithExtensionsOwned},
+        state::Account,
+    },
+    test_case::test_case,
+};
+
+fn token_2022_harness(transfer_fee_mint: bool) -> AtaTestHarness {
+    let harness = AtaTestHarness::new_with_ata_program(
+        &spl_token_2022_interface::id(),
+        AtaProgram::Pinocchio,
+    );
+
+    if transfer_fee_mint {
+        harness
+            .with_wallet(1_000_000)
+            .with_mint_with_extensions(&[ExtensionType::TransferFeeConfig])
+            .initialize_transfer_fee(1_000, 100)
+            .initialize_mint(0)
+    } else {
+        harness.with_wallet_and_mint(1_000_000, 6)
+    }
+}
+
+fn extension_types(account: &[u8]) -> Vec<ExtensionType> {
+    StateWithExtensionsOwned::<Account>::unpack(account.to_vec())
+        .unwrap()
+        .get_extension_types()
+        .unwrap()
+}
+
+#[test_case(false; "base mint")]
+#[test_case(true; "transfer fee mint")]
+fn legacy_ata_has_no_immutable_owner(transfer_fee_mint: bool) {
+    let harness = token_2022_harness(transfer_fee_mint).with_legacy_token_2022_ata();
+    let ata = harness.get_account(harness.ata_address.unwrap());
+
+    if transfer_fee_mint {
+        assert_eq!(
+            extension_types(&ata.data),
+            [ExtensionType::TransferFeeAmount]
+        );
+    } else {
+        assert_eq!(ata.data.len(), Account::LEN);
+    }
+}
+
+#[test_case(false; "base mint")]
+#[test_case(true; "transfer fee mint")]
+fn create_idempotent_accepts_legacy_ata(transfer_fee_mint: bool) {
+    let mut harness = token_2022_harness(transfer_fee_mint).with_legacy_token_2022_ata();
+    let ata_address = harness.ata_address.unwrap();
+    let ata = harness.get_account(ata_address);
+    let instruction =
+        harness.build_create_ata_instruction(CreateAtaInstructionType::CreateIdempotent);
+
+    harness.ctx.process_and_validate_instruction(
+        &instruction,
+        &[
+            Check::success(),
+            Check::account(&ata_address)
+                .data(&ata.data)
+                .lamports(ata.lamports)
+                .build(),
+        ],
+    );
+}
+
+#[test]
+fn create_rejects_legacy_ata() {
+    let mut harness = token_2022_harness(false).with_legacy_token_2022_ata();
+    let instruction = harness.build_create_ata_instruction(CreateAtaInstructionType::Create);
+
+    harness.process_and_validate_failed_create(&instruction, ProgramError::IllegalOwner);
+}
+
+#[test]
+fn recover_nested_from_legacy_accounts() {
+    let token_program_id = spl_token_2022_interface::id();
+    let mut harness =
+        AtaTestHarness::new_with_ata_program(&token_program_id, AtaProgram::Pinocchio)
+            .with_wallet(1_000_000);
+    let wallet = harness.wallet.unwrap();
+    let (owner_mint, _) = harness.create_mint_with_token_program(token_program_id, 0);
+    let owner_ata = harness.insert_legacy_token_2022_ata_for_owner(wallet, owner_mint);
+    let (nested_mint, nested_mint_authority) =
+        harness.create_mint_with_token_program(token_program_id, 0);
+    let nested_ata = harness.insert_legacy_token_2022_ata_for_owner(owner_ata, nested_mint);
+    harness.mint_tokens_to_with_token_program(
+        nested_mint,
+        nested_mint_authority,
+        nested_ata,
+        token_program_id,
+        100,
+    );
+    let destination_ata = harness.insert_legacy_token_2022_ata_for_owner(wallet, nested_mint);
+
+    let instruction = build_recover_nested_instruction(
+        &wallet,
+        &owner_mint,
+        &nested_mint,
+        &token_program_id,
+        &token_program_id,
+        &[],
+    );
+    harness.ctx.process_and_validate_instruction(
+        &instruction,
+        &[
+            Check::success(),
+            Check::account(&nested_ata).closed().build(),
+        ],
+    );
+
+    let destination = harness.get_account(destination_ata);
+    assert_eq!(Account::unpack(&destination.data).unwrap().amount, 100);
+}
@@ -0,0 +1,13 @@
+use {
+    solana_address::Address,
+    spl_associated_token_account_mollusk_harness::{AtaProgram, run_lifecycle_test},
+    test_case::test_matrix,
+};
+
+#[test_matrix(
+    [spl_token_interface::id(), spl_token_2022_interface::id()],
+    [AtaProgram::Legacy, AtaProgram::Pinocchio]
+)]
+fn lifecycle(token_program_id: Address, ata_program: AtaProgram) {
+    run_lifecycle_test(&token_program_id, ata_program);
+}
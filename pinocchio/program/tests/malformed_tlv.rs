@@ -0,0 +1,42 @@
+use {
+    pinocchio_associated_token_account_interface::mint_risk::MintRiskFlags,
+    solana_rent::Rent,
+    spl_associated_token_account_mollusk_harness::{
+        AtaProgram, AtaTestHarness, CreateAtaInstructionType, malformed_mint_tlv_corpus,
+    },
+};
+
+// Well under a successful create: the extension walk stops at the first bad
+// entry, whatever length it declares.
+const REJECT_BUDGET: u64 = 10_000;
+
+#[test]
+fn malformed_mints_are_rejected_with_bounded_work() {
+    for (name, data) in malformed_mint_tlv_corpus() {
+        // Must not panic; the result only matters to policy checks
+        let _ = MintRiskFlags::from_mint_data(&data);
+
+        let lamports = Rent::default().minimum_balance(data.len());
+        for instruction_type in [
+            CreateAtaInstructionType::Create,
+            CreateAtaInstructionType::CreateIdempotent,
+        ] {
+            let mut harness = AtaTestHarness::new_with_ata_program(
+                &spl_token_2022_interface::id(),
+                AtaProgram::Pinocchio,
+            )
+            .with_wallet(1_000_000)
+            .with_raw_mint(spl_token_2022_interface::id(), lamports, data.clone());
+            let instruction = harness.build_create_ata_instruction(instruction_type);
+
+            let result = harness.ctx.process_instruction(&instruction);
+
+            assert!(result.raw_result.is_err(), "{name}: mint was accepted");
+            assert!(
+                result.compute_units_consumed <= REJECT_BUDGET,
+                "{name}: rejection consumed {} CUs",
+                result.compute_units_consumed
+            );
+        }
+    }
+}
@@ -0,0 +1,61 @@
+use {
+    mollusk_svm_programs_token::token,
+    solana_account::Account,
+    solana_address::Address,
+    solana_instruction::AccountMeta,
+    spl_associated_token_account_mollusk_harness::{
+        AccountBuilder, AtaProgram, AtaTestHarness, CreateAtaInstructionType, minimize_failure,
+    },
+};
+
+#[test]
+fn minimize_failure_preserves_result() {
+    let token_program_id = spl_token_interface::id();
+    let mut harness =
+        AtaTestHarness::new_with_ata_program(&token_program_id, AtaProgram::Pinocchio)
+            .with_wallet_and_mint(1_000_000, 6);
+    let mut instruction = harness.build_create_ata_instruction(CreateAtaInstructionType::Create);
+
+    // A wrong ATA address, and an account the program never reads
+    let wrong_ata = Address::new_unique();
+    let unused = Address::new_unique();
+    instruction.accounts[1].pubkey = wrong_ata;
+    instruction.accounts.push(AccountMeta::new(unused, false));
+    let accounts = vec![
+        (harness.payer, harness.get_account(harness.payer)),
+        (wrong_ata, Account::default()),
+        (
+            harness.wallet.unwrap(),
+            harness.get_account(harness.wallet.unwrap()),
+        ),
+        (
+            harness.mint.unwrap(),
+            harness.get_account(harness.mint.unwrap()),
+        ),
+        mollusk_svm::program::keyed_account_for_system_program(),
+        token::keyed_account(),
+        (unused, AccountBuilder::system_account(1_000_000)),
+    ];
+
+    let mollusk = &harness.ctx.mollusk;
+    let expected = mollusk
+        .process_instruction(&instruction, &accounts)
+        .program_result;
+    let minimized = minimize_failure(mollusk, &instruction, &accounts);
+
+    assert_eq!(
+        mollusk
+            .process_instruction(&minimized.instruction, &minimized.accounts)
+            .program_result,
+        expected
+    );
+    assert!(minimized.instruction.accounts.len() < instruction.accounts.len());
+    assert!(minimized.instruction.data.len() <= instruction.data.len());
+    assert!(minimized.accounts.iter().all(|(address, _)| {
+        minimized
+            .instruction
+            .accounts
+            .iter()
+            .any(|meta| meta.pubkey == *address)
+    }));
+}
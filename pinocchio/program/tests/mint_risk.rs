@@ -0,0 +1,72 @@
+use {
+    pinocchio_associated_token_account_interface::mint_risk::MintRiskFlags,
+    solana_program_pack::Pack,
+    spl_token_2022_interface::{
+        extension::{
+            BaseStateWithExtensionsMut, ExtensionType, StateWithExtensionsMut,
+            mint_close_authority::MintCloseAuthority, non_transferable::NonTransferable,
+            pausable::PausableConfig, permanent_delegate::PermanentDelegate,
+            transfer_hook::TransferHook,
+        },
+        state::Mint,
+    },
+    test_case::test_case,
+};
+
+fn mint_data(mint_extensions: &[ExtensionType]) -> Vec<u8> {
+    let mint_space = ExtensionType::try_calculate_account_len::<Mint>(mint_extensions).unwrap();
+    let mut mint_data = vec![0; mint_space];
+    let mut state = StateWithExtensionsMut::<Mint>::unpack_uninitialized(&mut mint_data).unwrap();
+
+    for extension_type in mint_extensions {
+        match extension_type {
+            ExtensionType::PermanentDelegate => {
+                state.init_extension::<PermanentDelegate>(true).unwrap();
+            }
+            ExtensionType::TransferHook => {
+                state.init_extension::<TransferHook>(true).unwrap();
+            }
+            ExtensionType::Pausable => {
+                state.init_extension::<PausableConfig>(true).unwrap();
+            }
+            ExtensionType::NonTransferable => {
+                state.init_extension::<NonTransferable>(true).unwrap();
+            }
+            ExtensionType::MintCloseAuthority => {
+                state.init_extension::<MintCloseAuthority>(true).unwrap();
+            }
+            _ => panic!("unsupported mint extension for this test"),
+        }
+    }
+    state.base.is_initialized = true;
+    state.pack_base();
+    state.init_account_type().unwrap();
+
+    mint_data
+}
+
+#[test_case(&[], MintRiskFlags::empty(); "no extensions")]
+#[test_case(&[ExtensionType::MintCloseAuthority], MintRiskFlags::empty(); "unset close authority")]
+#[test_case(&[ExtensionType::PermanentDelegate], MintRiskFlags::PERMANENT_DELEGATE; "permanent delegate")]
+#[test_case(&[ExtensionType::TransferHook], MintRiskFlags::TRANSFER_HOOK; "transfer hook")]
+#[test_case(&[ExtensionType::Pausable], MintRiskFlags::PAUSABLE; "pausable")]
+#[test_case(&[ExtensionType::NonTransferable], MintRiskFlags::NON_TRANSFERABLE; "non-transferable")]
+#[test_case(
+    &[
+        ExtensionType::MintCloseAuthority,
+        ExtensionType::PermanentDelegate,
+        ExtensionType::TransferHook,
+        ExtensionType::Pausable,
+    ],
+    MintRiskFlags::PERMANENT_DELEGATE | MintRiskFlags::TRANSFER_HOOK | MintRiskFlags::PAUSABLE;
+    "mixed extensions"
+)]
+fn flags_match_token_2022_mint_layout(mint_extensions: &[ExtensionType], expected: MintRiskFlags) {
+    let data = if mint_extensions.is_empty() {
+        vec![0; Mint::LEN]
+    } else {
+        mint_data(mint_extensions)
+    };
+
+    assert_eq!(MintRiskFlags::from_mint_data(&data), Ok(expected));
+}
@@ -0,0 +1,158 @@
+use {
+    mollusk_svm_result::Check,
+    pinocchio_associated_token_account_interface::account_indices::{
+        RECLAIM_UNINITIALIZED_ASSOCIATED_TOKEN_ACCOUNT, RECLAIM_UNINITIALIZED_WALLET,
+    },
+    solana_address::Address,
+    solana_instruction::{AccountMeta, Instruction},
+    solana_program_error::ProgramError,
+    solana_system_interface::program as system_program,
+    spl_associated_token_account_interface::address::get_associated_token_address_with_program_id,
+    spl_associated_token_account_mollusk_harness::{AccountBuilder, AtaProgram, AtaTestHarness},
+    test_case::test_case,
+};
+
+const STRANDED_LAMPORTS: u64 = 10_000;
+
+fn reclaim_instruction(
+    ata: Address,
+    wallet: Address,
+    mint: Address,
+    token_program_id: Address,
+) -> Instruction {
+    Instruction {
+        program_id: spl_associated_token_account_interface::program::id(),
+        accounts: vec![
+            AccountMeta::new(ata, false),
+            AccountMeta::new(wallet, true),
+            AccountMeta::new_readonly(mint, false),
+            AccountMeta::new_readonly(token_program_id, false),
+            AccountMeta::new_readonly(system_program::id(), false),
+        ],
+        data: vec![17],
+    }
+}
+
+// Lamports sent to the wallet's ATA address before the account was created
+fn harness_with_stranded_lamports(token_program_id: Address) -> (AtaTestHarness, Instruction) {
+    let harness = AtaTestHarness::new_with_ata_program(&token_program_id, AtaProgram::Pinocchio)
+        .with_wallet_and_mint(1_000_000, 6);
+    let (wallet, mint) = (harness.wallet.unwrap(), harness.mint.unwrap());
+    let ata = get_associated_token_address_with_program_id(&wallet, &mint, &token_program_id);
+    harness
+        .ctx
+        .account_store
+        .borrow_mut()
+        .insert(ata, AccountBuilder::system_account(STRANDED_LAMPORTS));
+    let instruction = reclaim_instruction(ata, wallet, mint, token_program_id);
+    (harness, instruction)
+}
+
+// The program under test must be built with the same `reclaim-uninitialized`
+// setting as this test crate.
+#[test_case(spl_token_interface::id())]
+#[test_case(spl_token_2022_interface::id())]
+fn reclaims_lamports_into_wallet(token_program_id: Address) {
+    let (harness, instruction) = harness_with_stranded_lamports(token_program_id);
+
+    if !cfg!(feature = "reclaim-uninitialized") {
+        harness.ctx.process_and_validate_instruction(
+            &instruction,
+            &[Check::err(ProgramError::InvalidInstructionData)],
+        );
+        return;
+    }
+
+    let wallet = harness.wallet.unwrap();
+    let ata = instruction.accounts[RECLAIM_UNINITIALIZED_ASSOCIATED_TOKEN_ACCOUNT].pubkey;
+    let wallet_lamports = harness
+        .get_account(wallet)
+        .lamports
+        .checked_add(STRANDED_LAMPORTS)
+        .unwrap();
+    harness.ctx.process_and_validate_instruction(
+        &instruction,
+        &[
+            Check::success(),
+            Check::account(&wallet).lamports(wallet_lamports).build(),
+            Check::account(&ata).lamports(0).build(),
+        ],
+    );
+}
+
+#[test]
+fn fails_without_wallet_signature() {
+    if !cfg!(feature = "reclaim-uninitialized") {
+        return;
+    }
+    let (harness, mut instruction) = harness_with_stranded_lamports(spl_token_interface::id());
+    instruction.accounts[RECLAIM_UNINITIALIZED_WALLET].is_signer = false;
+
+    harness.ctx.process_and_validate_instruction(
+        &instruction,
+        &[Check::err(ProgramError::MissingRequiredSignature)],
+    );
+}
+
+#[test_case(spl_token_interface::id())]
+#[test_case(spl_token_2022_interface::id())]
+fn fails_for_created_ata(token_program_id: Address) {
+    if !cfg!(feature = "reclaim-uninitialized") {
+        return;
+    }
+    let harness = AtaTestHarness::new_with_ata_program(&token_program_id, AtaProgram::Pinocchio)
+        .with_wallet_and_mint(1_000_000, 6)
+        .with_ata();
+
+    harness.ctx.process_and_validate_instruction(
+        &reclaim_instruction(
+            harness.ata_address.unwrap(),
+            harness.wallet.unwrap(),
+            harness.mint.unwrap(),
+            token_program_id,
+        ),
+        &[Check::err(ProgramError::AccountAlreadyInitialized)],
+    );
+}
+
+#[test]
+fn fails_for_address_of_other_wallet_or_mint() {
+    if !cfg!(feature = "reclaim-uninitialized") {
+        return;
+    }
+    let token_program_id = spl_token_interface::id();
+    let (harness, instruction) = harness_with_stranded_lamports(token_program_id);
+    let ata = instruction.accounts[RECLAIM_UNINITIALIZED_ASSOCIATED_TOKEN_ACCOUNT].pubkey;
+    let (wallet, mint) = (harness.wallet.unwrap(), harness.mint.unwrap());
+    // A signing wallet that does not own the address
+    let other = Address::new_unique();
+    harness
+        .ctx
+        .account_store
+        .borrow_mut()
+        .insert(other, AccountBuilder::system_account(1_000_000));
+
+    let cases = [
+        (
+            reclaim_instruction(ata, other, mint, token_program_id),
+            ProgramError::InvalidSeeds,
+        ),
+        (
+            reclaim_instruction(ata, wallet, other, token_program_id),
+            ProgramError::InvalidSeeds,
+        ),
+        (
+            reclaim_instruction(ata, wallet, mint, spl_token_2022_interface::id()),
+            ProgramError::InvalidSeeds,
+        ),
+        (
+            reclaim_instruction(ata, wallet, mint, other),
+            ProgramError::IncorrectProgramId,
+        ),
+    ];
+    for (instruction, expected) in cases {
+        harness
+            .ctx
+            .process_and_validate_instruction(&instruction, &[Check::err(expected)]);
+    }
+}
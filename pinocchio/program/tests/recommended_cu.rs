@@ -0,0 +1,158 @@
+use {
+    mollusk_svm_result::Check,
+    solana_address::Address,
+    spl_associated_token_account_interface::compute_budget::{
+        RECOMMENDED_CU_CREATE, RECOMMENDED_CU_CREATE_IDEMPOTENT, RECOMMENDED_CU_RECOVER_NESTED,
+    },
+    spl_associated_token_account_mollusk_harness::{
+        AtaProgram, AtaTestHarness, CreateAtaInstructionType, assert_cu_close_to,
+        build_recover_nested_instruction,
+    },
+    spl_token_2022_interface::extension::ExtensionType,
+    test_case::{test_case, test_matrix},
+};
+
+fn harness_for(token_program_id: Address, transfer_fee_mint: bool) -> AtaTestHarness {
+    let harness = AtaTestHarness::new_with_ata_program(&token_program_id, AtaProgram::Pinocchio);
+    if transfer_fee_mint {
+        harness
+            .with_wallet(1_000_000)
+            .with_mint_with_extensions(&[ExtensionType::TransferFeeConfig])
+            .initialize_transfer_fee(1_000, 100)
+            .initialize_mint(0)
+    } else {
+        harness.with_wallet_and_mint(1_000_000, 6)
+    }
+}
+
+#[test_case(spl_token_interface::id(), false; "spl-token")]
+#[test_case(spl_token_2022_interface::id(), false; "token-2022")]
+#[test_case(spl_token_2022_interface::id(), true; "token-2022 transfer fee mint")]
+fn create_fits_recommended_limit(token_program_id: Address, transfer_fee_mint: bool) {
+    let mut harness = harness_for(token_program_id, transfer_fee_mint);
+    let instruction = harness.build_create_ata_instruction(CreateAtaInstructionType::Create);
+
+    let result = harness
+        .ctx
+        .process_and_validate_instruction(&instruction, &[Check::success()]);
+
+    assert!(
+        result.compute_units_consumed <= u64::from(RECOMMENDED_CU_CREATE),
+        "create consumed {} CUs",
+        result.compute_units_consumed
+    );
+}
+
+#[test_case(spl_token_interface::id(), false, false; "new spl-token")]
+#[test_case(spl_token_2022_interface::id(), false, false; "new token-2022")]
+#[test_case(spl_token_2022_interface::id(), true, false; "new token-2022 transfer fee mint")]
+#[test_case(spl_token_interface::id(), false, true; "existing spl-token")]
+#[test_case(spl_token_2022_interface::id(), false, true; "existing token-2022")]
+#[test_case(spl_token_2022_interface::id(), true, true; "existing token-2022 transfer fee mint")]
+fn create_idempotent_fits_recommended_limit(
+    token_program_id: Address,
+    transfer_fee_mint: bool,
+    existing: bool,
+) {
+    let mut harness = harness_for(token_program_id, transfer_fee_mint);
+    if existing {
+        harness = harness.with_ata();
+    }
+    let instruction =
+        harness.build_create_ata_instruction(CreateAtaInstructionType::CreateIdempotent);
+
+    let result = harness
+        .ctx
+        .process_and_validate_instruction(&instruction, &[Check::success()]);
+
+    assert!(
+        result.compute_units_consumed <= u64::from(RECOMMENDED_CU_CREATE_IDEMPOTENT),
+        "create_idempotent consumed {} CUs",
+        result.compute_units_consumed
+    );
+}
+
+#[test_matrix(
+    [spl_token_interface::id(), spl_token_2022_interface::id()],
+    [spl_token_interface::id(), spl_token_2022_interface::id()]
+)]
+fn recover_nested_fits_recommended_limit(
+    owner_token_program_id: Address,
+    nested_token_program_id: Address,
+) {
+    let mut harness =
+        AtaTestHarness::new_with_ata_program(&owner_token_program_id, AtaProgram::Pinocchio)
+            .with_wallet(1_000_000);
+    let wallet = harness.wallet.unwrap();
+    let (owner_mint, _) = harness.create_mint_with_token_program(owner_token_program_id, 0);
+    let owner_ata = harness.create_ata_for_owner_with_token_program(
+        wallet,
+        1_000_000,
+        owner_mint,
+        owner_token_program_id,
+    );
+    let (nested_mint, nested_mint_authority) =
+        harness.create_mint_with_token_program(nested_token_program_id, 0);
+    let nested_ata = harness.create_ata_for_owner_with_token_program(
+        owner_ata,
+        1_000_000,
+        nested_mint,
+        nested_token_program_id,
+    );
+    harness.mint_tokens_to_with_token_program(
+        nested_mint,
+        nested_mint_authority,
+        nested_ata,
+        nested_token_program_id,
+        100,
+    );
+    harness.create_ata_for_owner_with_token_program(
+        wallet,
+        1_000_000,
+        nested_mint,
+        nested_token_program_id,
+    );
+
+    let instruction = build_recover_nested_instruction(
+        &wallet,
+        &owner_mint,
+        &nested_mint,
+        &owner_token_program_id,
+        &nested_token_program_id,
+        &[],
+    );
+    let result = harness
+        .ctx
+        .process_and_validate_instruction(&instruction, &[Check::success()]);
+
+    assert!(
+        result.compute_units_consumed <= u64::from(RECOMMENDED_CU_RECOVER_NESTED),
+        "recover_nested consumed {} CUs",
+        result.compute_units_consumed
+    );
+}
+
+#[test_case(spl_token_interface::id(); "spl-token")]
+#[test_case(spl_token_2022_interface::id(); "token-2022")]
+fn create_idempotent_existing_costs_less_than_create(token_program_id: Address) {
+    let mut harness = harness_for(token_program_id, false);
+    let instruction =
+        harness.build_create_ata_instruction(CreateAtaInstructionType::CreateIdempotent);
+    let process = |harness: &mut AtaTestHarness| {
+        harness
+            .ctx
+            .process_and_validate_instruction(&instruction, &[Check::success()])
+    };
+
+    let create = harness.measure_cu(process);
+    let existing = harness.measure_cu(process);
+
+    assert!(existing < create, "{existing} CUs, {create} CUs to create");
+    assert_cu_close_to(existing, existing.saturating_add(10), 10);
+}
+
+#[test]
+#[should_panic(expected = "is not within")]
+fn assert_cu_close_to_rejects_values_outside_tolerance() {
+    assert_cu_close_to(1_011, 1_000, 10);
+}
@@ -1,17 +1,36 @@
 use {
+    mollusk_svm_programs_memo::memo,
+    mollusk_svm_programs_token::{token, token2022},
     mollusk_svm_result::Check,
+    pinocchio_associated_token_account_interface::{
+        account_indices::{
+            RECOVER_NESTED_NESTED_ASSOCIATED_TOKEN_ACCOUNT, RECOVER_NESTED_NESTED_TOKEN_PROGRAM,
+            RECOVER_NESTED_OWNER_ASSOCIATED_TOKEN_ACCOUNT, RECOVER_NESTED_WALLET,
+            RECOVER_NESTED_WITH_MEMO_MEMO_PROGRAM,
+        },
+        instruction::AssociatedTokenAccountInstruction,
+    },
     solana_address::Address,
-    solana_instruction::Instruction,
+    solana_instruction::{AccountMeta, Instruction},
     solana_program_error::ProgramError,
+    solana_program_option::COption,
     solana_program_pack::Pack,
     solana_rent::Rent,
+    spl_associated_token_account_interface::address::get_associated_token_address_and_bump_seed,
     spl_associated_token_account_mollusk_harness::{
-        AtaProgram, AtaTestHarness, build_recover_nested_instruction,
+        AccountBuilder, AtaProgram, AtaTestHarness, build_recover_nested_instruction,
     },
     spl_token_2022_interface::{
-        extension::StateWithExtensionsOwned, instruction::initialize_multisig2, state::Account,
+        error::TokenError,
+        extension::{
+            BaseStateWithExtensions, ExtensionType, StateWithExtensionsOwned,
+            memo_transfer::instruction::enable_required_transfer_memos,
+            transfer_fee::TransferFeeAmount, transfer_hook,
+        },
+        instruction::{initialize_multisig2, reallocate, sync_native},
+        state::Account,
     },
-    spl_token_interface::state::Multisig,
+    spl_token_interface::state::{Mint, Multisig},
     test_case::{test_case, test_matrix},
 };
 
@@ -138,7 +157,9 @@ fn fail_missing_extra_account_when_programs_differ() {
     );
 
     // Drop the optional nested token program account
-    recover_instruction.accounts.truncate(7);
+    recover_instruction
+        .accounts
+        .truncate(RECOVER_NESTED_NESTED_TOKEN_PROGRAM);
 
     setup.harness.ctx.process_and_validate_instruction(
         &recover_instruction,
@@ -162,7 +183,8 @@ fn fail_wrong_nested_token_program_account() {
     );
 
     // Point the nested token program account at the owner program to break PDA derivation
-    recover_instruction.accounts[7].pubkey = owner_token_program_id;
+    recover_instruction.accounts[RECOVER_NESTED_NESTED_TOKEN_PROGRAM].pubkey =
+        owner_token_program_id;
 
     setup.harness.ctx.process_and_validate_instruction(
         &recover_instruction,
@@ -236,7 +258,7 @@ fn fail_standard_wallet_did_not_sign() {
     );
 
     let mut recover_instruction = build_recover_instruction(&setup, &[]);
-    recover_instruction.accounts[5].is_signer = false;
+    recover_instruction.accounts[RECOVER_NESTED_WALLET].is_signer = false;
 
     setup.harness.ctx.process_and_validate_instruction(
         &recover_instruction,
@@ -614,3 +636,817 @@ fn success_extra_signer_accounts_ignored() {
 
     assert_recover_nested_success(setup, recover_instruction);
 }
+
+// A Token-2022 nested mint with the `TransferHook` extension, its hook program
+// left unset so that the token program accepts any extra accounts
+fn transfer_hook_recover_nested_setup() -> RecoverNestedSetup {
+    let token_program_id = spl_token_2022_interface::id();
+    let harness = AtaTestHarness::new_with_ata_program(&token_program_id, AtaProgram::Pinocchio)
+        .with_wallet(1_000_000)
+        .with_mint_with_extensions(&[ExtensionType::TransferHook]);
+    let nested_mint = harness.mint.unwrap();
+    let nested_mint_authority = harness.mint_authority.unwrap();
+    let initialize_hook = transfer_hook::instruction::initialize(
+        &token_program_id,
+        &nested_mint,
+        Some(nested_mint_authority),
+        None,
+    )
+    .unwrap();
+    harness
+        .ctx
+        .process_and_validate_instruction(&initialize_hook, &[Check::success()]);
+
+    extension_mint_recover_nested_setup(harness.initialize_mint(0))
+}
+
+// Nested ATA layout for the harness mint, which must be a Token-2022 mint
+// initialized with its extensions
+fn extension_mint_recover_nested_setup(mut harness: AtaTestHarness) -> RecoverNestedSetup {
+    let token_program_id = spl_token_2022_interface::id();
+    let nested_mint = harness.mint.unwrap();
+    let nested_mint_authority = harness.mint_authority.unwrap();
+    let wallet = harness.wallet.unwrap();
+    let (owner_mint, _) = harness.create_mint_with_token_program(token_program_id, 0);
+    let owner_ata = harness.create_ata_for_owner_with_token_program(
+        wallet,
+        1_000_000,
+        owner_mint,
+        token_program_id,
+    );
+    let nested_ata = harness.create_ata_for_owner_with_token_program(
+        owner_ata,
+        1_000_000,
+        nested_mint,
+        token_program_id,
+    );
+    harness.mint_tokens_to_with_token_program(
+        nested_mint,
+        nested_mint_authority,
+        nested_ata,
+        token_program_id,
+        TEST_MINT_AMOUNT,
+    );
+    let destination_ata = harness.create_ata_for_owner_with_token_program(
+        wallet,
+        1_000_000,
+        nested_mint,
+        token_program_id,
+    );
+
+    RecoverNestedSetup {
+        harness,
+        wallet,
+        owner_mint,
+        nested_mint,
+        nested_ata,
+        destination_ata,
+    }
+}
+
+// Recover instruction with the nested token program and `extra_accounts`
+// trailing, as transfer hook accounts are passed
+fn recover_instruction_with_hook_accounts(
+    setup: &RecoverNestedSetup,
+    extra_accounts: &[AccountMeta],
+) -> Instruction {
+    let token_program_id = spl_token_2022_interface::id();
+    let mut instruction = build_recover_nested_instruction(
+        &setup.wallet,
+        &setup.owner_mint,
+        &setup.nested_mint,
+        &token_program_id,
+        &token_program_id,
+        &[],
+    );
+    instruction
+        .accounts
+        .push(AccountMeta::new_readonly(token_program_id, false));
+    instruction.accounts.extend_from_slice(extra_accounts);
+    instruction
+}
+
+#[test]
+fn success_transfer_hook_mint_with_hook_accounts() {
+    let setup = transfer_hook_recover_nested_setup();
+    let [readonly, writable, signer] = [
+        Address::new_unique(),
+        Address::new_unique(),
+        Address::new_unique(),
+    ];
+    setup.harness.ensure_accounts_with_lamports(&[
+        (readonly, 1_000_000),
+        (writable, 1_000_000),
+        (signer, 1_000_000),
+    ]);
+
+    let recover_instruction = recover_instruction_with_hook_accounts(
+        &setup,
+        &[
+            AccountMeta::new_readonly(readonly, false),
+            AccountMeta::new(writable, false),
+            AccountMeta::new_readonly(signer, true),
+        ],
+    );
+
+    assert_recover_nested_success(setup, recover_instruction);
+}
+
+#[test_case(&[]; "without hook accounts")]
+#[test_case(&[Address::new_from_array([7; 32])]; "with hook accounts")]
+fn success_transfer_fee_mint(hook_accounts: &[Address]) {
+    let harness = AtaTestHarness::new_with_ata_program(
+        &spl_token_2022_interface::id(),
+        AtaProgram::Pinocchio,
+    )
+    .with_wallet(1_000_000)
+    .with_mint_with_extensions(&[ExtensionType::TransferFeeConfig])
+    .initialize_transfer_fee(1_000, 5)
+    .initialize_mint(0);
+    let setup = extension_mint_recover_nested_setup(harness);
+    for address in hook_accounts {
+        setup
+            .harness
+            .ensure_account_exists_with_lamports(*address, 1_000_000);
+    }
+    let hook_accounts: Vec<AccountMeta> = hook_accounts
+        .iter()
+        .map(|address| AccountMeta::new_readonly(*address, false))
+        .collect();
+
+    // 10% of the recovered tokens, capped at the maximum fee
+    let fee: u64 = 5;
+    setup.harness.ctx.process_and_validate_instruction(
+        &recover_instruction_with_hook_accounts(&setup, &hook_accounts),
+        &[
+            Check::success(),
+            Check::return_data(&fee.to_le_bytes()),
+            Check::account(&setup.nested_ata).closed().build(),
+        ],
+    );
+
+    let destination = StateWithExtensionsOwned::<Account>::unpack(
+        setup.harness.get_account(setup.destination_ata).data,
+    )
+    .unwrap();
+    assert_eq!(
+        destination.base.amount,
+        TEST_MINT_AMOUNT.checked_sub(fee).unwrap()
+    );
+    assert_eq!(
+        u64::from(
+            destination
+                .get_extension::<TransferFeeAmount>()
+                .unwrap()
+                .withheld_amount
+        ),
+        fee
+    );
+}
+
+#[test]
+fn fail_too_many_transfer_hook_accounts() {
+    let setup = transfer_hook_recover_nested_setup();
+    let hook_accounts: Vec<AccountMeta> = (0..25)
+        .map(|_| AccountMeta::new_readonly(Address::new_unique(), false))
+        .collect();
+    for account in &hook_accounts {
+        setup
+            .harness
+            .ensure_account_exists_with_lamports(account.pubkey, 1_000_000);
+    }
+
+    setup.harness.ctx.process_and_validate_instruction(
+        &recover_instruction_with_hook_accounts(&setup, &hook_accounts),
+        &[Check::err(ProgramError::InvalidArgument)],
+    );
+}
+
+const NATIVE_DEPOSIT: u64 = 5_000_000;
+
+fn add_lamports(harness: &AtaTestHarness, address: Address, lamports: u64) {
+    let mut store = harness.ctx.account_store.borrow_mut();
+    let account = store.get_mut(&address).unwrap();
+    account.lamports = account.lamports.checked_add(lamports).unwrap();
+}
+
+fn token_account_state(harness: &AtaTestHarness, address: Address) -> Account {
+    StateWithExtensionsOwned::<Account>::unpack(harness.get_account(address).data)
+        .unwrap()
+        .base
+}
+
+// Nested and destination ATAs of the native mint, the nested one holding
+// `NATIVE_DEPOSIT` synced lamports plus `unsynced_lamports` sent without a
+// `SyncNative`
+fn native_recover_nested_setup(
+    owner_token_program_id: Address,
+    nested_token_program_id: Address,
+    unsynced_lamports: u64,
+) -> RecoverNestedSetup {
+    let mut harness =
+        AtaTestHarness::new_with_ata_program(&owner_token_program_id, AtaProgram::Pinocchio)
+            .with_wallet(1_000_000);
+    let wallet = harness.wallet.unwrap();
+
+    let native_mint_state = Mint {
+        mint_authority: COption::None,
+        supply: 0,
+        decimals: 9,
+        is_initialized: true,
+        freeze_authority: COption::None,
+    };
+    let (nested_mint, native_mint_account) =
+        if nested_token_program_id == spl_token_2022_interface::id() {
+            (
+                spl_token_2022_interface::native_mint::id(),
+                token2022::create_account_for_mint(native_mint_state),
+            )
+        } else {
+            (
+                spl_token_interface::native_mint::id(),
+                token::create_account_for_mint(native_mint_state),
+            )
+        };
+    harness
+        .ctx
+        .account_store
+        .borrow_mut()
+        .insert(nested_mint, native_mint_account);
+
+    let (owner_mint, _) = harness.create_mint_with_token_program(owner_token_program_id, 0);
+    let owner_ata = harness.create_ata_for_owner_with_token_program(
+        wallet,
+        1_000_000,
+        owner_mint,
+        owner_token_program_id,
+    );
+    let nested_ata = harness.create_ata_for_owner_with_token_program(
+        owner_ata,
+        1_000_000,
+        nested_mint,
+        nested_token_program_id,
+    );
+    add_lamports(&harness, nested_ata, NATIVE_DEPOSIT);
+    harness.ctx.process_and_validate_instruction(
+        &sync_native(&nested_token_program_id, &nested_ata).unwrap(),
+        &[Check::success()],
+    );
+    add_lamports(&harness, nested_ata, unsynced_lamports);
+
+    let destination_ata = harness.create_ata_for_owner_with_token_program(
+        wallet,
+        1_000_000,
+        nested_mint,
+        nested_token_program_id,
+    );
+
+    RecoverNestedSetup {
+        harness,
+        wallet,
+        owner_mint,
+        nested_mint,
+        nested_ata,
+        destination_ata,
+    }
+}
+
+// The synced amount moves to the destination with its lamports, while the
+// nested account's rent-exempt reserve and any unsynced lamports are returned
+// to the wallet when it is closed
+#[test_matrix(
+    [spl_token_interface::id(), spl_token_2022_interface::id()],
+    [spl_token_interface::id(), spl_token_2022_interface::id()],
+    [0, 1_234]
+)]
+fn success_native_mint(
+    owner_token_program_id: Address,
+    nested_token_program_id: Address,
+    unsynced_lamports: u64,
+) {
+    let setup = native_recover_nested_setup(
+        owner_token_program_id,
+        nested_token_program_id,
+        unsynced_lamports,
+    );
+    let nested = token_account_state(&setup.harness, setup.nested_ata);
+    assert_eq!(nested.amount, NATIVE_DEPOSIT);
+    let nested_reserve = Option::<u64>::from(nested.is_native).unwrap();
+    let destination = token_account_state(&setup.harness, setup.destination_ata);
+    assert_eq!(destination.amount, 0);
+    let destination_reserve = Option::<u64>::from(destination.is_native).unwrap();
+
+    let wallet_lamports = setup.harness.get_account(setup.wallet).lamports;
+    let nested_lamports = setup.harness.get_account(setup.nested_ata).lamports;
+    let destination_lamports = setup.harness.get_account(setup.destination_ata).lamports;
+    assert_eq!(
+        nested_lamports,
+        nested_reserve
+            .checked_add(NATIVE_DEPOSIT)
+            .and_then(|lamports| lamports.checked_add(unsynced_lamports))
+            .unwrap()
+    );
+    assert_eq!(destination_lamports, destination_reserve);
+
+    let recover_instruction = build_recover_nested_instruction(
+        &setup.wallet,
+        &setup.owner_mint,
+        &setup.nested_mint,
+        &owner_token_program_id,
+        &nested_token_program_id,
+        &[],
+    );
+    setup.harness.ctx.process_and_validate_instruction(
+        &recover_instruction,
+        &[
+            Check::success(),
+            Check::account(&setup.wallet)
+                .lamports(
+                    wallet_lamports
+                        .checked_add(nested_reserve)
+                        .and_then(|lamports| lamports.checked_add(unsynced_lamports))
+                        .unwrap(),
+                )
+                .build(),
+            Check::account(&setup.destination_ata)
+                .lamports(destination_lamports.checked_add(NATIVE_DEPOSIT).unwrap())
+                .build(),
+            Check::account(&setup.nested_ata).closed().build(),
+        ],
+    );
+
+    let destination = token_account_state(&setup.harness, setup.destination_ata);
+    assert_eq!(destination.amount, NATIVE_DEPOSIT);
+    assert_eq!(
+        Option::<u64>::from(destination.is_native),
+        Some(destination_reserve)
+    );
+}
+
+/// `RecoverNestedWithArgs` data for `setup`, with the canonical bump of each
+/// ATA whose `hints` entry is set, in owner, nested, destination order
+fn recover_nested_with_args_data(
+    setup: &RecoverNestedSetup,
+    owner_token_program_id: Address,
+    nested_token_program_id: Address,
+    hints: [bool; 3],
+) -> Vec<u8> {
+    let ata_program_id = setup.harness.ata_program_id;
+    let (owner_ata, owner_bump) = get_associated_token_address_and_bump_seed(
+        &setup.wallet,
+        &setup.owner_mint,
+        &ata_program_id,
+        &owner_token_program_id,
+    );
+    let (_, nested_bump) = get_associated_token_address_and_bump_seed(
+        &owner_ata,
+        &setup.nested_mint,
+        &ata_program_id,
+        &nested_token_program_id,
+    );
+    let (_, destination_bump) = get_associated_token_address_and_bump_seed(
+        &setup.wallet,
+        &setup.nested_mint,
+        &ata_program_id,
+        &nested_token_program_id,
+    );
+
+    let mut data = vec![9];
+    for (bump, hint) in [owner_bump, nested_bump, destination_bump]
+        .into_iter()
+        .zip(hints)
+    {
+        data.push(if hint { bump } else { 0 });
+    }
+    data
+}
+
+#[test_matrix(
+    [spl_token_interface::id(), spl_token_2022_interface::id()],
+    [spl_token_interface::id(), spl_token_2022_interface::id()],
+    [[false, false, false], [true, false, false], [false, true, true], [true, true, true]]
+)]
+fn success_with_bump_hints(
+    owner_token_program_id: Address,
+    nested_token_program_id: Address,
+    hints: [bool; 3],
+) {
+    let setup = recover_nested_setup(owner_token_program_id, nested_token_program_id);
+    let mut recover_instruction = build_recover_nested_instruction(
+        &setup.wallet,
+        &setup.owner_mint,
+        &setup.nested_mint,
+        &owner_token_program_id,
+        &nested_token_program_id,
+        &[],
+    );
+    recover_instruction.data = recover_nested_with_args_data(
+        &setup,
+        owner_token_program_id,
+        nested_token_program_id,
+        hints,
+    );
+
+    assert_recover_nested_success(setup, recover_instruction);
+}
+
+#[test_case(1; "owner")]
+#[test_case(2; "nested")]
+#[test_case(3; "destination")]
+fn fail_wrong_bump_hint(index: usize) {
+    let token_program_id = spl_token_interface::id();
+    let setup = recover_nested_setup(token_program_id, token_program_id);
+    let mut recover_instruction = build_recover_nested_instruction(
+        &setup.wallet,
+        &setup.owner_mint,
+        &setup.nested_mint,
+        &token_program_id,
+        &token_program_id,
+        &[],
+    );
+    recover_instruction.data =
+        recover_nested_with_args_data(&setup, token_program_id, token_program_id, [true; 3]);
+    recover_instruction.data[index] = recover_instruction.data[index].wrapping_sub(1);
+
+    setup.harness.ctx.process_and_validate_instruction(
+        &recover_instruction,
+        &[Check::err(ProgramError::InvalidSeeds)],
+    );
+}
+
+// The runtime accepts signer seeds with any off-curve bump, so an account at a
+// lower off-curve PDA of the wallet and owner mint could be signed for
+#[test]
+fn fail_non_canonical_owner_bump_hint() {
+    let token_program_id = spl_token_interface::id();
+    let mut harness =
+        AtaTestHarness::new_with_ata_program(&token_program_id, AtaProgram::Pinocchio)
+            .with_wallet(1_000_000);
+    let wallet = harness.wallet.unwrap();
+    let ata_program_id = harness.ata_program_id;
+    let (owner_mint, _) = harness.create_mint_with_token_program(token_program_id, 0);
+    let (_, canonical_bump) = get_associated_token_address_and_bump_seed(
+        &wallet,
+        &owner_mint,
+        &ata_program_id,
+        &token_program_id,
+    );
+    let (owner_ata, owner_bump) = (0..canonical_bump)
+        .rev()
+        .map(|bump| {
+            let address = Address::derive_address(
+                &[
+                    wallet.as_ref(),
+                    token_program_id.as_ref(),
+                    owner_mint.as_ref(),
+                ],
+                Some(bump),
+                &ata_program_id,
+            );
+            (address, bump)
+        })
+        .find(|(address, _)| !address.is_on_curve())
+        .unwrap();
+    harness.ctx.account_store.borrow_mut().insert(
+        owner_ata,
+        AccountBuilder::token_account(&owner_mint, &wallet, 0, &token_program_id),
+    );
+
+    let (nested_mint, nested_mint_authority) =
+        harness.create_mint_with_token_program(token_program_id, 0);
+    let nested_ata = harness.create_ata_for_owner_with_token_program(
+        owner_ata,
+        1_000_000,
+        nested_mint,
+        token_program_id,
+    );
+    harness.mint_tokens_to_with_token_program(
+        nested_mint,
+        nested_mint_authority,
+        nested_ata,
+        token_program_id,
+        TEST_MINT_AMOUNT,
+    );
+    harness.create_ata_for_owner_with_token_program(
+        wallet,
+        1_000_000,
+        nested_mint,
+        token_program_id,
+    );
+
+    let mut recover_instruction = build_recover_nested_instruction(
+        &wallet,
+        &owner_mint,
+        &nested_mint,
+        &token_program_id,
+        &token_program_id,
+        &[],
+    );
+    recover_instruction.accounts[RECOVER_NESTED_OWNER_ASSOCIATED_TOKEN_ACCOUNT].pubkey = owner_ata;
+    recover_instruction.accounts[RECOVER_NESTED_NESTED_ASSOCIATED_TOKEN_ACCOUNT].pubkey =
+        nested_ata;
+    recover_instruction.data = vec![9, owner_bump, 0, 0];
+
+    harness.ctx.process_and_validate_instruction(
+        &recover_instruction,
+        &[Check::err(ProgramError::InvalidSeeds)],
+    );
+}
+
+#[test]
+fn bump_hints_save_compute_units() {
+    let token_program_id = spl_token_interface::id();
+    let compute_units = |hints| {
+        let setup = recover_nested_setup(token_program_id, token_program_id);
+        let mut recover_instruction = build_recover_nested_instruction(
+            &setup.wallet,
+            &setup.owner_mint,
+            &setup.nested_mint,
+            &token_program_id,
+            &token_program_id,
+            &[],
+        );
+        recover_instruction.data =
+            recover_nested_with_args_data(&setup, token_program_id, token_program_id, hints);
+        setup
+            .harness
+            .ctx
+            .process_and_validate_instruction(&recover_instruction, &[Check::success()])
+            .compute_units_consumed
+    };
+
+    assert!(compute_units([true; 3]) < compute_units([false; 3]));
+}
+
+/// `RecoverNestedIdempotent` of `setup`, whose owner and nested ATAs both use
+/// `token_program_id`
+fn recover_nested_idempotent_instruction(
+    setup: &RecoverNestedSetup,
+    token_program_id: Address,
+) -> Instruction {
+    let mut recover_instruction = build_recover_nested_instruction(
+        &setup.wallet,
+        &setup.owner_mint,
+        &setup.nested_mint,
+        &token_program_id,
+        &token_program_id,
+        &[],
+    );
+    recover_instruction.data = vec![10];
+    recover_instruction
+}
+
+/// Replaces the nested ATA of `setup` with an address that was never funded
+fn remove_nested_ata(setup: &RecoverNestedSetup) {
+    let mut store = setup.harness.ctx.account_store.borrow_mut();
+    *store.get_mut(&setup.nested_ata).unwrap() = solana_account::Account::default();
+}
+
+/// Checks that `recover_instruction` succeeds without changing the wallet,
+/// nested or destination accounts
+fn assert_recover_nested_noop(setup: &RecoverNestedSetup, recover_instruction: &Instruction) {
+    let stored = |address| {
+        setup
+            .harness
+            .ctx
+            .account_store
+            .borrow()
+            .get(&address)
+            .cloned()
+    };
+    let unchanged = [setup.wallet, setup.nested_ata, setup.destination_ata]
+        .map(|address| (address, stored(address)));
+
+    setup
+        .harness
+        .ctx
+        .process_and_validate_instruction(recover_instruction, &[Check::success()]);
+
+    for (address, account) in unchanged {
+        assert_eq!(stored(address), account);
+    }
+}
+
+#[test_case(spl_token_interface::id())]
+#[test_case(spl_token_2022_interface::id())]
+fn idempotent_recovers_nested_tokens(token_program_id: Address) {
+    let setup = recover_nested_setup(token_program_id, token_program_id);
+    let recover_instruction = recover_nested_idempotent_instruction(&setup, token_program_id);
+
+    assert_recover_nested_success(setup, recover_instruction);
+}
+
+#[test_case(spl_token_interface::id())]
+#[test_case(spl_token_2022_interface::id())]
+fn idempotent_noop_without_nested_ata(token_program_id: Address) {
+    let setup = recover_nested_setup(token_program_id, token_program_id);
+    remove_nested_ata(&setup);
+    let recover_instruction = recover_nested_idempotent_instruction(&setup, token_program_id);
+
+    assert_recover_nested_noop(&setup, &recover_instruction);
+
+    // `RecoverNested` itself still fails
+    let mut recover_nested = recover_instruction;
+    recover_nested.data = vec![2];
+    setup.harness.ctx.process_and_validate_instruction(
+        &recover_nested,
+        &[Check::err(ProgramError::IllegalOwner)],
+    );
+}
+
+#[test_case(spl_token_interface::id())]
+#[test_case(spl_token_2022_interface::id())]
+fn idempotent_noop_with_empty_nested_ata(token_program_id: Address) {
+    let setup = recover_nested_setup(token_program_id, token_program_id);
+    {
+        let mut store = setup.harness.ctx.account_store.borrow_mut();
+        let nested = store.get_mut(&setup.nested_ata).unwrap();
+        // `amount` of the base account state
+        nested.data[64..72].copy_from_slice(&0u64.to_le_bytes());
+    }
+    let recover_instruction = recover_nested_idempotent_instruction(&setup, token_program_id);
+
+    assert_recover_nested_noop(&setup, &recover_instruction);
+}
+
+#[test]
+fn idempotent_without_nested_ata_requires_wallet_signature() {
+    let token_program_id = spl_token_interface::id();
+    let setup = recover_nested_setup(token_program_id, token_program_id);
+    remove_nested_ata(&setup);
+    let mut recover_instruction = recover_nested_idempotent_instruction(&setup, token_program_id);
+    recover_instruction.accounts[RECOVER_NESTED_WALLET].is_signer = false;
+
+    setup.harness.ctx.process_and_validate_instruction(
+        &recover_instruction,
+        &[Check::err(ProgramError::MissingRequiredSignature)],
+    );
+}
+
+/// `RecoverNestedWithMemo` for `setup`, with the memo program loaded
+fn recover_nested_with_memo_instruction(
+    setup: &mut RecoverNestedSetup,
+    token_program_id: Address,
+    memo: &[u8],
+) -> Instruction {
+    memo::add_program(&mut setup.harness.ctx.mollusk);
+    let mut instruction = build_recover_nested_instruction(
+        &setup.wallet,
+        &setup.owner_mint,
+        &setup.nested_mint,
+        &token_program_id,
+        &token_program_id,
+        &[],
+    );
+    instruction
+        .accounts
+        .push(AccountMeta::new_readonly(token_program_id, false));
+    instruction
+        .accounts
+        .push(AccountMeta::new_readonly(memo::ID, false));
+    let memo_len = u16::try_from(memo.len()).unwrap();
+    instruction.data = [&[16][..], &memo_len.to_le_bytes(), memo].concat();
+    instruction
+}
+
+// Reallocates the destination for the `MemoTransfer` extension and requires
+// incoming transfer memos
+fn require_destination_memos(setup: &RecoverNestedSetup) {
+    let token_program_id = spl_token_2022_interface::id();
+    let reallocate = reallocate(
+        &token_program_id,
+        &setup.destination_ata,
+        &setup.harness.payer,
+        &setup.wallet,
+        &[],
+        &[ExtensionType::MemoTransfer],
+    )
+    .unwrap();
+    let enable = enable_required_transfer_memos(
+        &token_program_id,
+        &setup.destination_ata,
+        &setup.wallet,
+        &[],
+    )
+    .unwrap();
+    setup
+        .harness
+        .ctx
+        .process_and_validate_instruction(&reallocate, &[Check::success()]);
+    setup
+        .harness
+        .ctx
+        .process_and_validate_instruction(&enable, &[Check::success()]);
+}
+
+#[test]
+fn memo_required_destination_fails_without_memo() {
+    let token_program_id = spl_token_2022_interface::id();
+    let setup = recover_nested_setup(token_program_id, token_program_id);
+    require_destination_memos(&setup);
+
+    setup.harness.ctx.process_and_validate_instruction(
+        &build_recover_nested_instruction(
+            &setup.wallet,
+            &setup.owner_mint,
+            &setup.nested_mint,
+            &token_program_id,
+            &token_program_id,
+            &[],
+        ),
+        &[Check::err(ProgramError::Custom(TokenError::NoMemo as u32))],
+    );
+}
+
+#[test]
+fn with_memo_recovers_to_memo_required_destination() {
+    let token_program_id = spl_token_2022_interface::id();
+    let mut setup = recover_nested_setup(token_program_id, token_program_id);
+    require_destination_memos(&setup);
+    let recover_instruction =
+        recover_nested_with_memo_instruction(&mut setup, token_program_id, b"recovered");
+
+    assert_recover_nested_success(setup, recover_instruction);
+}
+
+#[test_case(spl_token_interface::id())]
+#[test_case(spl_token_2022_interface::id())]
+fn with_empty_memo_recovers_like_recover_nested(token_program_id: Address) {
+    let mut setup = recover_nested_setup(token_program_id, token_program_id);
+    let recover_instruction =
+        recover_nested_with_memo_instruction(&mut setup, token_program_id, &[]);
+
+    assert_recover_nested_success(setup, recover_instruction);
+}
+
+#[test]
+fn with_memo_accepts_memo_up_to_max_len() {
+    let token_program_id = spl_token_interface::id();
+    let mut setup = recover_nested_setup(token_program_id, token_program_id);
+    let memo = [b'a'; AssociatedTokenAccountInstruction::MAX_MEMO_LEN];
+    let recover_instruction =
+        recover_nested_with_memo_instruction(&mut setup, token_program_id, &memo);
+
+    assert_recover_nested_success(setup, recover_instruction);
+}
+
+#[test]
+fn with_memo_fails_for_memo_over_max_len() {
+    let token_program_id = spl_token_interface::id();
+    let mut setup = recover_nested_setup(token_program_id, token_program_id);
+    let memo = vec![
+        b'a';
+        AssociatedTokenAccountInstruction::MAX_MEMO_LEN
+            .checked_add(1)
+            .unwrap()
+    ];
+    let recover_instruction =
+        recover_nested_with_memo_instruction(&mut setup, token_program_id, &memo);
+
+    setup.harness.ctx.process_and_validate_instruction(
+        &recover_instruction,
+        &[Check::err(ProgramError::InvalidInstructionData)],
+    );
+}
+
+#[test]
+fn with_memo_fails_for_memo_len_mismatch() {
+    let token_program_id = spl_token_interface::id();
+    let mut setup = recover_nested_setup(token_program_id, token_program_id);
+    let mut recover_instruction =
+        recover_nested_with_memo_instruction(&mut setup, token_program_id, b"recovered");
+    recover_instruction.data.pop();
+
+    setup.harness.ctx.process_and_validate_instruction(
+        &recover_instruction,
+        &[Check::err(ProgramError::InvalidInstructionData)],
+    );
+}
+
+#[test]
+fn with_memo_fails_for_wrong_memo_program() {
+    let token_program_id = spl_token_interface::id();
+    let mut setup = recover_nested_setup(token_program_id, token_program_id);
+    let mut recover_instruction =
+        recover_nested_with_memo_instruction(&mut setup, token_program_id, b"recovered");
+    recover_instruction.accounts[RECOVER_NESTED_WITH_MEMO_MEMO_PROGRAM].pubkey = token_program_id;
+
+    setup.harness.ctx.process_and_validate_instruction(
+        &recover_instruction,
+        &[Check::err(ProgramError::IncorrectProgramId)],
+    );
+}
+
+#[test]
+fn with_memo_fails_without_memo_program() {
+    let token_program_id = spl_token_interface::id();
+    let mut setup = recover_nested_setup(token_program_id, token_program_id);
+    let mut recover_instruction =
+        recover_nested_with_memo_instruction(&mut setup, token_program_id, b"recovered");
+    recover_instruction.accounts.pop();
+
+    setup.harness.ctx.process_and_validate_instruction(
+        &recover_instruction,
+        &[Check::err(ProgramError::NotEnoughAccountKeys)],
+    );
+}
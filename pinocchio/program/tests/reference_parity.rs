@@ -0,0 +1,160 @@
+use {
+    mollusk_svm_result::ProgramResult,
+    pinocchio_associated_token_account_interface::account_indices::{
+        CREATE_ASSOCIATED_TOKEN_ACCOUNT, CREATE_FUNDER, CREATE_TOKEN_PROGRAM,
+    },
+    solana_address::Address,
+    solana_instruction::AccountMeta,
+    solana_program_error::ProgramError,
+    spl_associated_token_account_interface::address::get_associated_token_address_with_program_id,
+    spl_associated_token_account_mollusk_harness::{
+        AccountBuilder, AtaProgram, AtaTestHarness, CreateAtaInstructionType,
+        build_create_ata_instruction, build_recover_nested_instruction,
+    },
+    test_case::{test_case, test_matrix},
+};
+
+#[test_matrix(
+    [spl_token_interface::id(), spl_token_2022_interface::id()],
+    [CreateAtaInstructionType::Create, CreateAtaInstructionType::CreateIdempotent],
+    [false, true]
+)]
+fn create_matches_reference(
+    token_program_id: Address,
+    instruction_type: CreateAtaInstructionType,
+    existing: bool,
+) {
+    let mut harness =
+        AtaTestHarness::new_with_ata_program(&token_program_id, AtaProgram::Pinocchio)
+            .with_wallet_and_mint(1_000_000, 6);
+    if existing {
+        harness = harness.with_ata();
+    }
+    let instruction = harness.build_create_ata_instruction(instruction_type);
+
+    harness.assert_matches_reference(&instruction);
+}
+
+#[test_matrix(
+    [spl_token_interface::id(), spl_token_2022_interface::id()],
+    [CreateAtaInstructionType::Create, CreateAtaInstructionType::CreateIdempotent]
+)]
+fn create_at_wrong_address_matches_reference(
+    token_program_id: Address,
+    instruction_type: CreateAtaInstructionType,
+) {
+    let mut harness =
+        AtaTestHarness::new_with_ata_program(&token_program_id, AtaProgram::Pinocchio)
+            .with_wallet_and_mint(1_000_000, 6);
+    let mut instruction = harness.build_create_ata_instruction(instruction_type);
+    instruction.accounts[1] = AccountMeta::new(Address::new_unique(), false);
+
+    let result = harness.assert_matches_reference(&instruction);
+    assert!(result.program_result.is_err());
+}
+
+/// A second fault of a create at the wrong address
+#[derive(Clone, Copy)]
+enum CreateFault {
+    ExistingAccount,
+    UnsignedPayer,
+    UnknownTokenProgram,
+}
+
+/// p-ATA derives the ATA address before checking the accounts, as SPL ATA
+/// does, so a create at the wrong address fails with `InvalidSeeds` under both
+/// whatever else is wrong with it
+#[test_matrix(
+    [CreateAtaInstructionType::Create, CreateAtaInstructionType::CreateIdempotent],
+    [CreateFault::ExistingAccount, CreateFault::UnsignedPayer, CreateFault::UnknownTokenProgram]
+)]
+fn create_at_wrong_address_fails_on_derivation_first(
+    instruction_type: CreateAtaInstructionType,
+    fault: CreateFault,
+) {
+    let token_program_id = spl_token_interface::id();
+    let mut harness =
+        AtaTestHarness::new_with_ata_program(&token_program_id, AtaProgram::Pinocchio)
+            .with_wallet_and_mint(1_000_000, 6);
+    let mut instruction = harness.build_create_ata_instruction(instruction_type);
+    let wrong_address = Address::new_unique();
+    instruction.accounts[CREATE_ASSOCIATED_TOKEN_ACCOUNT] = AccountMeta::new(wrong_address, false);
+    match fault {
+        CreateFault::ExistingAccount => {
+            let account = AccountBuilder::token_account(
+                &harness.mint.unwrap(),
+                &harness.wallet.unwrap(),
+                0,
+                &token_program_id,
+            );
+            harness
+                .ctx
+                .account_store
+                .borrow_mut()
+                .insert(wrong_address, account);
+        }
+        CreateFault::UnsignedPayer => {
+            instruction.accounts[CREATE_FUNDER].is_signer = false;
+        }
+        CreateFault::UnknownTokenProgram => {
+            instruction.accounts[CREATE_TOKEN_PROGRAM].pubkey = Address::new_unique();
+        }
+    }
+
+    let result = harness.assert_matches_reference(&instruction);
+    assert_eq!(
+        result.program_result,
+        ProgramResult::Failure(ProgramError::InvalidSeeds)
+    );
+}
+
+#[test_case(spl_token_interface::id())]
+#[test_case(spl_token_2022_interface::id())]
+fn recover_nested_matches_reference(token_program_id: Address) {
+    let mut harness =
+        AtaTestHarness::new_with_ata_program(&token_program_id, AtaProgram::Pinocchio)
+            .with_wallet(1_000_000);
+    let wallet = harness.wallet.unwrap();
+    let (owner_mint, _) = harness.create_mint_with_token_program(token_program_id, 0);
+    let owner_ata = harness.create_ata_for_owner_with_token_program(
+        wallet,
+        1_000_000,
+        owner_mint,
+        token_program_id,
+    );
+    let (nested_mint, nested_mint_authority) =
+        harness.create_mint_with_token_program(token_program_id, 0);
+    let nested_ata = harness.create_ata_for_owner_with_token_program(
+        owner_ata,
+        1_000_000,
+        nested_mint,
+        token_program_id,
+    );
+    harness.mint_tokens_to_with_token_program(
+        nested_mint,
+        nested_mint_authority,
+        nested_ata,
+        token_program_id,
+        100,
+    );
+    let create_destination = build_create_ata_instruction(
+        spl_associated_token_account_interface::program::id(),
+        harness.payer,
+        get_associated_token_address_with_program_id(&wallet, &nested_mint, &token_program_id),
+        wallet,
+        nested_mint,
+        token_program_id,
+        CreateAtaInstructionType::Create,
+    );
+    harness.assert_matches_reference(&create_destination);
+
+    let instruction = build_recover_nested_instruction(
+        &wallet,
+        &owner_mint,
+        &nested_mint,
+        &token_program_id,
+        &token_program_id,
+        &[],
+    );
+    harness.assert_matches_reference(&instruction);
+}
@@ -0,0 +1,186 @@
+mod common;
+
+use {
+    common::expected_bump,
+    mollusk_svm_result::ProgramResult,
+    pinocchio_associated_token_account_interface::{
+        account_indices::{CREATE_ASSOCIATED_TOKEN_ACCOUNT, CREATE_FUNDER, CREATE_TOKEN_PROGRAM},
+        instruction::CreateMode,
+    },
+    solana_address::Address,
+    solana_instruction::Instruction,
+    solana_program_error::ProgramError,
+    spl_associated_token_account_mollusk_harness::{
+        AtaProgram, AtaTestHarness, CreateAtaInstructionType,
+        token_2022_immutable_owner_account_len,
+    },
+};
+
+/// Payloads rejected while decoding the instruction data
+const DECODE_REJECT_BUDGET: u64 = 1_000;
+/// Payloads rejected by account flag, owner and id checks, before the ATA
+/// address is derived
+const ACCOUNT_CHECK_REJECT_BUDGET: u64 = 1_000;
+/// Payloads rejected while validating the ATA address
+const DERIVATION_REJECT_BUDGET: u64 = 5_000;
+/// Payloads only rejected by the token program after the account is allocated
+const ALLOCATION_REJECT_BUDGET: u64 = 15_000;
+
+fn create_with_args(
+    harness: &mut AtaTestHarness,
+    bump: Option<u8>,
+    account_len: Option<u32>,
+) -> Instruction {
+    harness.build_create_ata_instruction(CreateAtaInstructionType::CreateWithArgs {
+        mode: CreateMode::Always,
+        bump,
+        account_len,
+        rent_sysvar: false,
+    })
+}
+
+fn with_data(mut instruction: Instruction, data: &[u8]) -> Instruction {
+    instruction.data = data.to_vec();
+    instruction
+}
+
+/// Bounds how many compute units near-valid payloads burn before rejection, so
+/// griefing with malformed instructions stays cheap for the payer only
+#[test]
+fn near_valid_payloads_are_rejected_within_budget() {
+    let mut harness = AtaTestHarness::new_with_ata_program(
+        &spl_token_2022_interface::id(),
+        AtaProgram::Pinocchio,
+    )
+    .with_wallet_and_mint(1_000_000, 6);
+    let bump = expected_bump(&harness);
+    let wrong_bump = if bump > 1 {
+        bump.saturating_sub(1)
+    } else {
+        bump.saturating_add(1)
+    };
+    let account_len = token_2022_immutable_owner_account_len() as u32;
+    let valid = create_with_args(&mut harness, Some(bump), Some(account_len));
+
+    let mut trailing = valid.data.clone();
+    trailing.push(0);
+    let mut invalid_mode = valid.data.clone();
+    invalid_mode[1] = 2;
+
+    let corpus = [
+        (
+            "unknown discriminator",
+            with_data(valid.clone(), &[254]),
+            DECODE_REJECT_BUDGET,
+        ),
+        (
+            "trailing byte",
+            with_data(valid.clone(), &trailing),
+            DECODE_REJECT_BUDGET,
+        ),
+        (
+            "truncated account_len",
+            with_data(
+                valid.clone(),
+                &valid.data[..valid.data.len().saturating_sub(1)],
+            ),
+            DECODE_REJECT_BUDGET,
+        ),
+        (
+            "invalid mode",
+            with_data(valid.clone(), &invalid_mode),
+            DECODE_REJECT_BUDGET,
+        ),
+        (
+            "bump off by one",
+            create_with_args(&mut harness, Some(wrong_bump), Some(account_len)),
+            DERIVATION_REJECT_BUDGET,
+        ),
+        (
+            "account_len one byte short",
+            create_with_args(
+                &mut harness,
+                Some(bump),
+                Some(account_len.saturating_sub(1)),
+            ),
+            ALLOCATION_REJECT_BUDGET,
+        ),
+    ];
+
+    for (name, instruction, budget) in &corpus {
+        let result = harness.ctx.process_instruction(instruction);
+        assert!(
+            !matches!(result.program_result, ProgramResult::Success),
+            "{name}: unexpectedly succeeded"
+        );
+        println!("{name}: {} CUs to reject", result.compute_units_consumed);
+        assert!(
+            result.compute_units_consumed <= *budget,
+            "{name}: {} CUs to reject exceeds budget of {budget}",
+            result.compute_units_consumed
+        );
+    }
+}
+
+fn assert_rejected_within_budget(
+    harness: &AtaTestHarness,
+    name: &str,
+    instruction: &Instruction,
+    error: ProgramError,
+) {
+    let result = harness.ctx.process_instruction(instruction);
+    assert_eq!(
+        result.program_result,
+        ProgramResult::Failure(error),
+        "{name}: unexpected result"
+    );
+    println!("{name}: {} CUs to reject", result.compute_units_consumed);
+    assert!(
+        result.compute_units_consumed <= ACCOUNT_CHECK_REJECT_BUDGET,
+        "{name}: {} CUs to reject exceeds budget of {ACCOUNT_CHECK_REJECT_BUDGET}",
+        result.compute_units_consumed
+    );
+}
+
+/// The failures clients hit most often are caught by the checks the p-ATA-only
+/// creates run before the address derivation, so rejecting them costs little
+/// more than decoding the instruction. `Create` and `CreateIdempotent` derive
+/// the address first, as SPL ATA does.
+#[test]
+fn common_create_failures_are_rejected_before_derivation() {
+    let mut harness =
+        AtaTestHarness::new_with_ata_program(&spl_token_interface::id(), AtaProgram::Pinocchio)
+            .with_wallet_and_mint(1_000_000, 6);
+    let create = create_with_args(&mut harness, None, None);
+
+    let mut read_only_ata = create.clone();
+    read_only_ata.accounts[CREATE_ASSOCIATED_TOKEN_ACCOUNT].is_writable = false;
+    let mut unsigned_payer = create.clone();
+    unsigned_payer.accounts[CREATE_FUNDER].is_signer = false;
+    let mut unknown_token_program = create.clone();
+    unknown_token_program.accounts[CREATE_TOKEN_PROGRAM].pubkey = Address::new_unique();
+
+    for (name, instruction, error) in [
+        ("read-only ATA", &read_only_ata, ProgramError::Immutable),
+        (
+            "missing payer signature",
+            &unsigned_payer,
+            ProgramError::MissingRequiredSignature,
+        ),
+        (
+            "unknown token program",
+            &unknown_token_program,
+            ProgramError::IncorrectProgramId,
+        ),
+    ] {
+        assert_rejected_within_budget(&harness, name, instruction, error);
+    }
+
+    let harness = harness.with_ata();
+    assert_rejected_within_budget(
+        &harness,
+        "ATA already exists",
+        &create,
+        ProgramError::IllegalOwner,
+    );
+}
@@ -0,0 +1,49 @@
+use {
+    solana_address::Address,
+    spl_associated_token_account_interface::address::get_associated_token_address_with_program_id,
+    spl_associated_token_account_mollusk_harness::{
+        AtaProgram, AtaTestHarness, CreateAtaInstructionType, sandboxed_program_id,
+    },
+    test_case::test_case,
+};
+
+#[test]
+fn sandboxed_program_ids_are_stable_and_distinct() {
+    let name = "sandboxed_program_ids_are_stable_and_distinct";
+
+    assert_eq!(sandboxed_program_id(name), sandboxed_program_id(name));
+    assert_ne!(sandboxed_program_id(name), sandboxed_program_id("other"));
+    assert_ne!(
+        sandboxed_program_id(name),
+        spl_associated_token_account_interface::program::id()
+    );
+    // Longer than a single seed
+    assert_ne!(
+        sandboxed_program_id(&name.repeat(4)),
+        sandboxed_program_id(&name.repeat(5))
+    );
+}
+
+#[test_case(spl_token_interface::id(), AtaProgram::Legacy)]
+#[test_case(spl_token_2022_interface::id(), AtaProgram::Legacy)]
+#[test_case(spl_token_interface::id(), AtaProgram::Pinocchio)]
+#[test_case(spl_token_2022_interface::id(), AtaProgram::Pinocchio)]
+fn create_under_sandboxed_program_id(token_program_id: Address, ata_program: AtaProgram) {
+    let mut harness = AtaTestHarness::new_with_ata_program(&token_program_id, ata_program)
+        .with_sandboxed_program_id("create_under_sandboxed_program_id")
+        .with_wallet_and_mint(1_000_000, 6);
+    let (wallet, mint) = (harness.wallet.unwrap(), harness.mint.unwrap());
+
+    let ata = harness.create_ata(CreateAtaInstructionType::CreateIdempotent);
+
+    assert_eq!(
+        ata,
+        harness.derive_ata_address(&wallet, &mint, &token_program_id)
+    );
+    assert_ne!(
+        ata,
+        get_associated_token_address_with_program_id(&wallet, &mint, &token_program_id)
+    );
+    let instruction = harness.build_create_ata_instruction(CreateAtaInstructionType::Create);
+    assert_eq!(instruction.program_id, harness.ata_program_id);
+}
@@ -0,0 +1,82 @@
+use {
+    spl_associated_token_account_mollusk_harness::{
+        AtaProgram, HarnessScenario, ScenarioCorruption, ScenarioInstruction, ScenarioMint,
+        ScenarioWallet, run_scenario,
+    },
+    test_case::test_matrix,
+};
+
+#[test_matrix(
+    [ScenarioWallet::System, ScenarioWallet::OffCurve, ScenarioWallet::TokenAccount],
+    [
+        ScenarioMint::Token,
+        ScenarioMint::Token2022,
+        ScenarioMint::Token2022TransferFee,
+        ScenarioMint::Token2022Group,
+        ScenarioMint::Token2022GroupMember
+    ],
+    [false, true]
+)]
+fn valid_scenarios(wallet: ScenarioWallet, mint: ScenarioMint, existing: bool) {
+    for instruction in [
+        ScenarioInstruction::Create,
+        ScenarioInstruction::CreateIdempotent,
+        ScenarioInstruction::CreateWithArgs {
+            idempotent: true,
+            bump: true,
+            account_len: true,
+            rent_sysvar: true,
+        },
+        ScenarioInstruction::CreateCompact {
+            idempotent: false,
+            bump: false,
+            account_len: false,
+            rent_sysvar: false,
+        },
+    ] {
+        run_scenario(
+            AtaProgram::Pinocchio,
+            &HarnessScenario {
+                wallet,
+                mint,
+                existing,
+                instruction,
+                corruption: ScenarioCorruption::None,
+            },
+        );
+    }
+}
+
+#[test_matrix(
+    [
+        ScenarioCorruption::ReadOnlyPayer,
+        ScenarioCorruption::ReadOnlyAta,
+        ScenarioCorruption::WrongAtaAddress,
+        ScenarioCorruption::WrongTokenProgram,
+        ScenarioCorruption::MissingAccounts
+    ],
+    [ScenarioMint::Token, ScenarioMint::Token2022],
+    [false, true]
+)]
+fn corrupted_scenarios(corruption: ScenarioCorruption, mint: ScenarioMint, existing: bool) {
+    for instruction in [
+        ScenarioInstruction::CreateIdempotent,
+        ScenarioInstruction::CreateCompact {
+            idempotent: true,
+            bump: true,
+            account_len: false,
+            rent_sysvar: false,
+        },
+    ] {
+        run_scenario(
+            AtaProgram::Pinocchio,
+            &HarnessScenario {
+                wallet: ScenarioWallet::System,
+                mint,
+                existing,
+                instruction,
+                corruption,
+            },
+        );
+    }
+}
@@ -0,0 +1,133 @@
+use {
+    mollusk_svm_result::Check,
+    pinocchio_associated_token_account_interface::instruction::CreateMode,
+    solana_address::Address,
+    solana_program_error::ProgramError,
+    spl_associated_token_account_mollusk_harness::{
+        AtaProgram, AtaTestHarness, CreateAtaInstructionType, build_recover_nested_instruction,
+    },
+    test_case::test_case,
+};
+
+/// Index of the ATA in the `Create` / `CreateWithArgs` account list
+const CREATE_ATA_INDEX: usize = 1;
+/// Index of the token program in the `Create` / `CreateWithArgs` account list
+const CREATE_TOKEN_PROGRAM_INDEX: usize = 5;
+/// Index of the token program in the `CreateCompact` account list
+const COMPACT_TOKEN_PROGRAM_INDEX: usize = 4;
+/// Index of the owner token program in the `RecoverNested` account list
+const RECOVER_OWNER_TOKEN_PROGRAM_INDEX: usize = 6;
+/// Index of the nested token program in the `RecoverNested` account list
+const RECOVER_NESTED_TOKEN_PROGRAM_INDEX: usize = 7;
+
+// Rejected before any address derivation
+const SELF_CPI_BUDGET: u64 = 500;
+
+#[test_case(
+    CreateAtaInstructionType::CreateWithArgs {
+        mode: CreateMode::Always,
+        bump: None,
+        account_len: None,
+        rent_sysvar: false,
+    },
+    CREATE_TOKEN_PROGRAM_INDEX;
+    "create_with_args"
+)]
+#[test_case(
+    CreateAtaInstructionType::CreateCompact {
+        mode: CreateMode::Idempotent,
+        bump: None,
+        account_len: None,
+        rent_sysvar: false,
+    },
+    COMPACT_TOKEN_PROGRAM_INDEX;
+    "create_compact"
+)]
+fn create_rejects_self_as_token_program(
+    instruction_type: CreateAtaInstructionType,
+    token_program_index: usize,
+) {
+    let mut harness =
+        AtaTestHarness::new_with_ata_program(&spl_token_interface::id(), AtaProgram::Pinocchio)
+            .with_wallet_and_mint(1_000_000, 6);
+    let mut instruction = harness.build_create_ata_instruction(instruction_type);
+    instruction.accounts[token_program_index].pubkey = instruction.program_id;
+
+    harness.process_and_validate_failed_create(&instruction, ProgramError::IncorrectProgramId);
+    let result = harness.ctx.process_instruction(&instruction);
+    assert!(
+        result.compute_units_consumed <= SELF_CPI_BUDGET,
+        "rejection consumed {} CUs",
+        result.compute_units_consumed
+    );
+}
+
+/// `Create` and `CreateIdempotent` derive the address first, as SPL ATA does,
+/// so the program passed as the token program fails the derivation unless the
+/// address was derived with it, and is then rejected as an unknown program
+#[test_case(CreateAtaInstructionType::Create, false, ProgramError::InvalidSeeds; "create")]
+#[test_case(CreateAtaInstructionType::CreateIdempotent, false, ProgramError::InvalidSeeds; "create_idempotent")]
+#[test_case(CreateAtaInstructionType::Create, true, ProgramError::IncorrectProgramId; "create at derived address")]
+#[test_case(CreateAtaInstructionType::CreateIdempotent, true, ProgramError::IncorrectProgramId; "create_idempotent at derived address")]
+fn create_rejects_self_as_token_program_after_derivation(
+    instruction_type: CreateAtaInstructionType,
+    derive_with_self: bool,
+    expected: ProgramError,
+) {
+    let mut harness =
+        AtaTestHarness::new_with_ata_program(&spl_token_interface::id(), AtaProgram::Pinocchio)
+            .with_wallet_and_mint(1_000_000, 6);
+    let mut instruction = harness.build_create_ata_instruction(instruction_type);
+    instruction.accounts[CREATE_TOKEN_PROGRAM_INDEX].pubkey = instruction.program_id;
+    if derive_with_self {
+        instruction.accounts[CREATE_ATA_INDEX].pubkey = harness.derive_ata_address(
+            &harness.wallet.unwrap(),
+            &harness.mint.unwrap(),
+            &instruction.program_id,
+        );
+    }
+
+    harness.process_and_validate_failed_create(&instruction, expected);
+}
+
+/// Swapping a token program for this one fails the derivation of the ATA
+/// addresses, as in SPL ATA. Addresses derived with it pass, and the program
+/// is rejected before any mint or account check.
+#[test_case(RECOVER_OWNER_TOKEN_PROGRAM_INDEX, false, ProgramError::InvalidSeeds; "owner token program")]
+#[test_case(RECOVER_NESTED_TOKEN_PROGRAM_INDEX, false, ProgramError::InvalidSeeds; "nested token program")]
+#[test_case(RECOVER_OWNER_TOKEN_PROGRAM_INDEX, true, ProgramError::IncorrectProgramId; "owner token program at derived addresses")]
+#[test_case(RECOVER_NESTED_TOKEN_PROGRAM_INDEX, true, ProgramError::IncorrectProgramId; "nested token program at derived addresses")]
+fn recover_nested_rejects_self_as_token_program(
+    token_program_index: usize,
+    derive_with_self: bool,
+    expected: ProgramError,
+) {
+    let token_program_id = spl_token_interface::id();
+    let ata_program_id = spl_associated_token_account_interface::program::id();
+    let harness = AtaTestHarness::new_with_ata_program(&token_program_id, AtaProgram::Pinocchio)
+        .with_wallet(1_000_000);
+    let token_program_for = |index: usize, program_id: Address| {
+        if derive_with_self && index == token_program_index {
+            ata_program_id
+        } else {
+            program_id
+        }
+    };
+    let mut instruction = build_recover_nested_instruction(
+        &harness.wallet.unwrap(),
+        &Address::new_unique(),
+        &Address::new_unique(),
+        &token_program_for(RECOVER_OWNER_TOKEN_PROGRAM_INDEX, token_program_id),
+        // Always include the nested token program account
+        &token_program_for(
+            RECOVER_NESTED_TOKEN_PROGRAM_INDEX,
+            spl_token_2022_interface::id(),
+        ),
+        &[],
+    );
+    instruction.accounts[token_program_index].pubkey = instruction.program_id;
+
+    harness
+        .ctx
+        .process_and_validate_instruction(&instruction, &[Check::err(expected)]);
+}
@@ -13,8 +13,10 @@ use {
         extension::{
             BaseStateWithExtensionsMut, ExtensionType, StateWithExtensionsMut,
             account_len::try_calculate_account_len_from_mint_data,
-            mint_close_authority::MintCloseAuthority, non_transferable::NonTransferable,
-            pausable::PausableConfig, transfer_fee::TransferFeeConfig, transfer_hook::TransferHook,
+            interest_bearing_mint::InterestBearingConfig, mint_close_authority::MintCloseAuthority,
+            non_transferable::NonTransferable, pausable::PausableConfig,
+            scaled_ui_amount::ScaledUiAmountConfig, transfer_fee::TransferFeeConfig,
+            transfer_hook::TransferHook,
         },
         state::{Account as Token2022Account, Mint},
     },
@@ -22,7 +24,9 @@ use {
 };
 
 const CREATE_FAST_PATH_INNER_IX_COUNT: usize = 2; // `CreateAccountAllowPrefund` and Batch
-const FAILED_SIZE_CPI_FALLBACK_INNER_IX_COUNT: usize = 1; // `GetAccountDataSize`
+
+// The default build has no `GetAccountDataSize` fallback and rejects before any CPI
+const INVALID_MINT_INNER_IX_COUNT: usize = 0;
 
 fn token_2022_raw_mint_harness(mint_extensions: &[ExtensionType]) -> (AtaTestHarness, usize) {
     let mint_space = ExtensionType::try_calculate_account_len::<Mint>(mint_extensions).unwrap();
@@ -46,6 +50,12 @@ fn token_2022_raw_mint_harness(mint_extensions: &[ExtensionType]) -> (AtaTestHar
             ExtensionType::MintCloseAuthority => {
                 state.init_extension::<MintCloseAuthority>(true).unwrap();
             }
+            ExtensionType::ScaledUiAmount => {
+                state.init_extension::<ScaledUiAmountConfig>(true).unwrap();
+            }
+            ExtensionType::InterestBearingConfig => {
+                state.init_extension::<InterestBearingConfig>(true).unwrap();
+            }
             _ => panic!("unsupported raw mint extension for this test"),
         }
     }
@@ -129,6 +139,48 @@ fn mint_with_extensions_uses_fast_path(mint_extensions: &[ExtensionType]) {
     assert_create_uses_fast_path(harness, CreateAtaInstructionType::Create, account_len);
 }
 
+// Neither extension adds account-side data, but both have to be stepped over
+// to reach the extensions that do
+#[test_case(&[ExtensionType::ScaledUiAmount]; "scaled ui amount")]
+#[test_case(&[ExtensionType::InterestBearingConfig]; "interest bearing")]
+#[test_case(&[
+    ExtensionType::ScaledUiAmount,
+    ExtensionType::TransferFeeConfig,
+    ExtensionType::Pausable,
+]; "scaled ui amount before account-side extension")]
+#[test_case(&[
+    ExtensionType::TransferFeeConfig,
+    ExtensionType::ScaledUiAmount,
+    ExtensionType::TransferHook,
+]; "scaled ui amount between account-side extensions")]
+#[test_case(&[
+    ExtensionType::InterestBearingConfig,
+    ExtensionType::NonTransferable,
+    ExtensionType::TransferFeeConfig,
+]; "interest bearing before account-side extensions")]
+#[test_case(&[
+    ExtensionType::TransferHook,
+    ExtensionType::MintCloseAuthority,
+    ExtensionType::InterestBearingConfig,
+]; "interest bearing last")]
+fn mint_with_ui_amount_extensions_uses_fast_path(mint_extensions: &[ExtensionType]) {
+    let (harness, account_len) = token_2022_raw_mint_harness(mint_extensions);
+    let expected_account_extensions =
+        ExtensionType::get_required_init_account_extensions(mint_extensions);
+    assert_eq!(
+        account_len,
+        ExtensionType::try_calculate_account_len::<Token2022Account>(
+            &[
+                &[ExtensionType::ImmutableOwner],
+                expected_account_extensions.as_slice()
+            ]
+            .concat()
+        )
+        .unwrap()
+    );
+    assert_create_uses_fast_path(harness, CreateAtaInstructionType::Create, account_len);
+}
+
 #[test]
 fn invalid_mint_extension_data_falls_back_to_cpi() {
     let mint_space =
@@ -166,8 +218,5 @@ fn invalid_mint_extension_data_falls_back_to_cpi() {
     let result = harness.ctx.process_instruction(&instruction);
 
     assert!(result.raw_result.is_err());
-    assert_eq!(
-        result.inner_instructions.len(),
-        FAILED_SIZE_CPI_FALLBACK_INNER_IX_COUNT
-    );
+    assert_eq!(result.inner_instructions.len(), INVALID_MINT_INNER_IX_COUNT);
 }
@@ -0,0 +1,79 @@
+use {
+    mollusk_svm_result::Check,
+    solana_address::Address,
+    spl_associated_token_account_mollusk_harness::{
+        AtaProgram, AtaTestHarness, CreateAtaInstructionType, build_recover_nested_instruction,
+    },
+    test_case::{test_case, test_matrix},
+};
+
+#[test_matrix(
+    [spl_token_interface::id(), spl_token_2022_interface::id()],
+    [CreateAtaInstructionType::Create, CreateAtaInstructionType::CreateIdempotent]
+)]
+fn create_leaves_read_only_accounts_untouched(
+    token_program_id: Address,
+    instruction_type: CreateAtaInstructionType,
+) {
+    let mut harness =
+        AtaTestHarness::new_with_ata_program(&token_program_id, AtaProgram::Pinocchio)
+            .with_wallet_and_mint(1_000_000, 6);
+    let instruction = harness.build_create_ata_instruction(instruction_type);
+
+    harness.process_and_assert_untouched(
+        &instruction,
+        &[Check::success()],
+        &[harness.wallet.unwrap(), harness.mint.unwrap()],
+    );
+}
+
+#[test_case(spl_token_interface::id())]
+#[test_case(spl_token_2022_interface::id())]
+fn recover_nested_leaves_read_only_accounts_untouched(token_program_id: Address) {
+    let mut harness =
+        AtaTestHarness::new_with_ata_program(&token_program_id, AtaProgram::Pinocchio)
+            .with_wallet(1_000_000);
+    let wallet = harness.wallet.unwrap();
+    let (owner_mint, _) = harness.create_mint_with_token_program(token_program_id, 0);
+    let owner_ata = harness.create_ata_for_owner_with_token_program(
+        wallet,
+        1_000_000,
+        owner_mint,
+        token_program_id,
+    );
+    let (nested_mint, nested_mint_authority) =
+        harness.create_mint_with_token_program(token_program_id, 0);
+    let nested_ata = harness.create_ata_for_owner_with_token_program(
+        owner_ata,
+        1_000_000,
+        nested_mint,
+        token_program_id,
+    );
+    harness.mint_tokens_to_with_token_program(
+        nested_mint,
+        nested_mint_authority,
+        nested_ata,
+        token_program_id,
+        100,
+    );
+    harness.create_ata_for_owner_with_token_program(
+        wallet,
+        1_000_000,
+        nested_mint,
+        token_program_id,
+    );
+
+    let instruction = build_recover_nested_instruction(
+        &wallet,
+        &owner_mint,
+        &nested_mint,
+        &token_program_id,
+        &token_program_id,
+        &[],
+    );
+    harness.process_and_assert_untouched(
+        &instruction,
+        &[Check::success()],
+        &[owner_ata, owner_mint, nested_mint],
+    );
+}
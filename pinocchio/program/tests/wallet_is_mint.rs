@@ -0,0 +1,79 @@
+use {
+    pinocchio_associated_token_account_interface::instruction::CreateMode,
+    solana_address::Address,
+    spl_associated_token_account_mollusk_harness::{
+        AtaProgram, AtaTestHarness, CreateAtaInstructionType, TokenChecks,
+    },
+    spl_token_2022_interface::extension::ExtensionType,
+    spl_token_interface::state::AccountState,
+    test_case::test_case,
+};
+
+// The mint doubles as the wallet. Both keys are still valid seeds, so the ATA
+// owned by the mint account itself is created as it is by the legacy program.
+fn wallet_is_mint_harness(token_program_id: Address, extended_mint: bool) -> AtaTestHarness {
+    let harness = AtaTestHarness::new_with_ata_program(&token_program_id, AtaProgram::Pinocchio);
+    let mut harness = if extended_mint {
+        harness
+            .with_mint_with_extensions(&[ExtensionType::TransferFeeConfig])
+            .initialize_transfer_fee(1_000, 100)
+            .initialize_mint(6)
+    } else {
+        harness.with_mint(6)
+    };
+    harness.wallet = harness.mint;
+    harness
+}
+
+#[test_case(spl_token_interface::id(), false; "spl-token")]
+#[test_case(spl_token_2022_interface::id(), false; "token-2022")]
+#[test_case(spl_token_2022_interface::id(), true; "token-2022 extended mint")]
+fn create_matches_legacy(token_program_id: Address, extended_mint: bool) {
+    for instruction_type in [
+        CreateAtaInstructionType::Create,
+        CreateAtaInstructionType::CreateIdempotent,
+    ] {
+        let mut harness = wallet_is_mint_harness(token_program_id, extended_mint);
+        let instruction = harness.build_create_ata_instruction(instruction_type);
+
+        harness.assert_no_regression_against(&instruction, AtaProgram::Legacy);
+    }
+}
+
+#[test_case(spl_token_interface::id(), false; "spl-token")]
+#[test_case(spl_token_2022_interface::id(), false; "token-2022")]
+#[test_case(spl_token_2022_interface::id(), true; "token-2022 extended mint")]
+fn create_idempotent_existing_matches_legacy(token_program_id: Address, extended_mint: bool) {
+    let mut harness = wallet_is_mint_harness(token_program_id, extended_mint).with_ata();
+    let instruction =
+        harness.build_create_ata_instruction(CreateAtaInstructionType::CreateIdempotent);
+
+    harness.assert_no_regression_against(&instruction, AtaProgram::Legacy);
+}
+
+#[test_case(spl_token_interface::id(), false, false; "spl-token")]
+#[test_case(spl_token_2022_interface::id(), false, false; "token-2022")]
+#[test_case(spl_token_2022_interface::id(), true, false; "token-2022 extended mint")]
+#[test_case(spl_token_interface::id(), false, true; "spl-token with rent sysvar")]
+#[test_case(spl_token_2022_interface::id(), true, true; "token-2022 extended mint with rent sysvar")]
+fn create_with_args_initializes_ata_owned_by_mint(
+    token_program_id: Address,
+    extended_mint: bool,
+    rent_sysvar: bool,
+) {
+    let mut harness = wallet_is_mint_harness(token_program_id, extended_mint);
+    let mint = harness.mint.unwrap();
+
+    let ata = harness.create_ata(CreateAtaInstructionType::CreateWithArgs {
+        mode: CreateMode::Always,
+        bump: None,
+        account_len: None,
+        rent_sysvar,
+    });
+
+    TokenChecks::account(ata)
+        .mint_field(mint)
+        .owner_field(mint)
+        .state(AccountState::Initialized)
+        .assert(&harness);
+}
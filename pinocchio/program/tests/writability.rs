@@ -0,0 +1,97 @@
+use {
+    mollusk_svm_result::Check,
+    solana_address::Address,
+    solana_instruction::AccountMeta,
+    solana_program_error::ProgramError,
+    spl_associated_token_account_mollusk_harness::{
+        AtaProgram, AtaTestHarness, CreateAtaInstructionType, build_recover_nested_instruction,
+    },
+    test_case::{test_case, test_matrix},
+};
+
+fn make_readonly(meta: &mut AccountMeta) {
+    meta.is_writable = false;
+}
+
+#[test_matrix(
+    [spl_token_interface::id(), spl_token_2022_interface::id()],
+    [CreateAtaInstructionType::Create, CreateAtaInstructionType::CreateIdempotent],
+    [0, 1]
+)]
+fn create_rejects_readonly_account(
+    token_program_id: Address,
+    instruction_type: CreateAtaInstructionType,
+    account_index: usize,
+) {
+    let mut harness =
+        AtaTestHarness::new_with_ata_program(&token_program_id, AtaProgram::Pinocchio)
+            .with_wallet_and_mint(1_000_000, 6);
+    let mut instruction = harness.build_create_ata_instruction(instruction_type);
+    make_readonly(&mut instruction.accounts[account_index]);
+
+    harness.process_and_validate_failed_create(&instruction, ProgramError::Immutable);
+}
+
+// SPL ATA returns before looking at either account's flags
+#[test_case(0)]
+#[test_case(1)]
+fn idempotent_noop_accepts_readonly_account(account_index: usize) {
+    let mut harness = AtaTestHarness::new_with_ata_program(
+        &spl_token_2022_interface::id(),
+        AtaProgram::Pinocchio,
+    )
+    .with_wallet_and_mint(1_000_000, 6)
+    .with_ata();
+    let mut instruction =
+        harness.build_create_ata_instruction(CreateAtaInstructionType::CreateIdempotent);
+    make_readonly(&mut instruction.accounts[account_index]);
+
+    let result = harness.assert_matches_reference(&instruction);
+    assert!(!result.program_result.is_err());
+}
+
+// nested ATA, destination ATA, wallet
+#[test_case(0)]
+#[test_case(2)]
+#[test_case(5)]
+fn recover_nested_rejects_readonly_account(account_index: usize) {
+    let token_program_id = spl_token_2022_interface::id();
+    let mut harness =
+        AtaTestHarness::new_with_ata_program(&token_program_id, AtaProgram::Pinocchio)
+            .with_wallet(1_000_000);
+    let wallet = harness.wallet.unwrap();
+    let (owner_mint, _) = harness.create_mint_with_token_program(token_program_id, 0);
+    let owner_ata = harness.create_ata_for_owner_with_token_program(
+        wallet,
+        1_000_000,
+        owner_mint,
+        token_program_id,
+    );
+    let (nested_mint, _) = harness.create_mint_with_token_program(token_program_id, 0);
+    harness.create_ata_for_owner_with_token_program(
+        owner_ata,
+        1_000_000,
+        nested_mint,
+        token_program_id,
+    );
+    harness.create_ata_for_owner_with_token_program(
+        wallet,
+        1_000_000,
+        nested_mint,
+        token_program_id,
+    );
+
+    let mut instruction = build_recover_nested_instruction(
+        &wallet,
+        &owner_mint,
+        &nested_mint,
+        &token_program_id,
+        &token_program_id,
+        &[],
+    );
+    make_readonly(&mut instruction.accounts[account_index]);
+
+    harness
+        .ctx
+        .process_and_validate_instruction(&instruction, &[Check::err(ProgramError::Immutable)]);
+}